@@ -17,11 +17,16 @@ pub enum CreateSignedSetKeysExtrinsicError {
 
 sp_api::decl_runtime_apis! {
     /// Runtime API for the author extension logic.
+    #[api_version(2)]
     pub trait AuthorExtApi<Id>
     where
         Id: Encode,
     {
         /// Create signed set_keys extrinsic.
         fn create_signed_set_keys_extrinsic(id: &Id, session_keys: Vec<u8>) -> Result<Block::Extrinsic, CreateSignedSetKeysExtrinsicError>;
+
+        /// Get the encoded session keys currently set for the given id, if any.
+        #[api_version(2)]
+        fn session_keys(id: &Id) -> Option<Vec<u8>>;
     }
 }
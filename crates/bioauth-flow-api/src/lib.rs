@@ -5,10 +5,12 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, Encode};
+use codec::{Codec, Decode, Encode};
 use scale_info::TypeInfo;
 use sp_std::prelude::*;
 
+pub mod v2;
+
 /// The bioauth status.
 #[derive(Debug, PartialEq, Eq, Decode, Encode, TypeInfo)]
 pub enum BioauthStatus<Timestamp> {
@@ -18,16 +20,60 @@ pub enum BioauthStatus<Timestamp> {
     Active {
         /// The timestamp when the authentication will expire.
         expires_at: Timestamp,
+        /// The timestamp starting from which the authentication can be renewed.
+        renewable_from: Timestamp,
     },
 }
 
+/// The active authentication.
+#[derive(Debug, PartialEq, Eq, Decode, Encode, TypeInfo)]
+pub struct ActiveAuthentication<Id, Timestamp> {
+    /// The id of the authenticated validator.
+    pub id: Id,
+    /// The timestamp when the authentication will expire.
+    pub expires_at: Timestamp,
+    /// The timestamp starting from which the authentication can be renewed.
+    pub renewable_from: Timestamp,
+}
+
+/// The bioauth configuration, along with the current usage of the bounded state.
+#[derive(Debug, PartialEq, Eq, Decode, Encode, TypeInfo)]
+pub struct BioauthConfig<Timestamp> {
+    /// The raw bytes of the robonode public keys that the auth tickets are currently accepted
+    /// from.
+    pub robonode_public_keys: Vec<Vec<u8>>,
+    /// The number of the robonode signatures required for an auth ticket to be accepted.
+    pub robonode_signatures_threshold: u32,
+    /// The amount of time after which the authentications expire.
+    pub authentications_expire_after: Timestamp,
+    /// The amount of time before the expiration during which the authentications can be renewed.
+    pub authentications_renewal_window: Timestamp,
+    /// The amount of time since the issuance after which the auth tickets expire.
+    pub auth_tickets_expire_after: Timestamp,
+    /// The current number of the active authentications.
+    pub active_authentications: u32,
+    /// The maximum number of the active authentications.
+    pub max_authentications: u32,
+    /// The current number of the consumed auth ticket nonces.
+    pub consumed_auth_ticket_nonces: u32,
+    /// The maximum number of the consumed auth ticket nonces.
+    pub max_nonces: u32,
+}
+
 sp_api::decl_runtime_apis! {
     /// Runtime API for the bioauth flow.
+    #[api_version(3)]
     pub trait BioauthFlowApi<Id, Timestamp>
     where
         Id: Encode,
         Timestamp: Decode,
     {
+        /// Determine the bioauth status for the given `id` at the current block.
+        ///
+        /// This call is intended for use in the bioauth flow, and the `id` passed is likely.
+        #[changed_in(3)]
+        fn bioauth_status(id: &Id) -> v2::BioauthStatus<Timestamp>;
+
         /// Determine the bioauth status for the given `id` at the current block.
         ///
         /// This call is intended for use in the bioauth flow, and the `id` passed is likely.
@@ -38,5 +84,36 @@ sp_api::decl_runtime_apis! {
             auth_ticket: Vec<u8>,
             auth_ticket_signature: Vec<u8>
         ) -> Block::Extrinsic;
+
+        /// Create an extrinsic for submitting auth ticket signed by multiple robonodes.
+        #[api_version(2)]
+        fn create_multisigned_authenticate_extrinsic(
+            auth_ticket: Vec<u8>,
+            auth_ticket_signatures: Vec<Vec<u8>>
+        ) -> Block::Extrinsic;
+    }
+
+    /// Runtime API for inspecting the bioauth state as a whole.
+    pub trait BioauthStateApi<Id, Timestamp, DeauthenticationReason>
+    where
+        Id: Codec,
+        Timestamp: Decode,
+        DeauthenticationReason: Decode,
+    {
+        /// Get all of the active authentications at the current block, in no particular order.
+        fn active_authentications() -> Vec<ActiveAuthentication<Id, Timestamp>>;
+
+        /// Get the bioauth configuration at the current block.
+        fn config() -> BioauthConfig<Timestamp>;
+
+        /// Get the moment of the current block, as seen by the bioauth logic.
+        fn current_moment() -> Timestamp;
+
+        /// Get the reason of the deauthentication of the given `id` if it has been deauthenticated
+        /// at the current block.
+        ///
+        /// Returns `None` if the `id` has not been deauthenticated at the current block, including
+        /// the case when its authentication has simply expired.
+        fn deauthentication_reason(id: &Id) -> Option<DeauthenticationReason>;
     }
 }
@@ -0,0 +1,16 @@
+//! The types used by the version 2 of the API and earlier.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// The bioauth status, without the renewal information.
+#[derive(Debug, PartialEq, Eq, Decode, Encode, TypeInfo)]
+pub enum BioauthStatus<Timestamp> {
+    /// No active authentication is present.
+    Inactive,
+    /// An active authentication exists.
+    Active {
+        /// The timestamp when the authentication will expire.
+        expires_at: Timestamp,
+    },
+}
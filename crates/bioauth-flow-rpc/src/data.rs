@@ -15,6 +15,9 @@ pub enum BioauthStatus<Timestamp> {
     Active {
         /// The timestamp when the authentication will expire.
         expires_at: Timestamp,
+        /// The timestamp starting from which the authentication can be renewed.
+        /// Absent if the runtime does not support the renewal.
+        renewable_from: Option<Timestamp>,
     },
 }
 
@@ -22,7 +25,134 @@ impl<T> From<bioauth_flow_api::BioauthStatus<T>> for BioauthStatus<T> {
     fn from(status: bioauth_flow_api::BioauthStatus<T>) -> Self {
         match status {
             bioauth_flow_api::BioauthStatus::Inactive => Self::Inactive,
-            bioauth_flow_api::BioauthStatus::Active { expires_at } => Self::Active { expires_at },
+            bioauth_flow_api::BioauthStatus::Active {
+                expires_at,
+                renewable_from,
+            } => Self::Active {
+                expires_at,
+                renewable_from: Some(renewable_from),
+            },
+        }
+    }
+}
+
+impl<T> From<bioauth_flow_api::v2::BioauthStatus<T>> for BioauthStatus<T> {
+    fn from(status: bioauth_flow_api::v2::BioauthStatus<T>) -> Self {
+        match status {
+            bioauth_flow_api::v2::BioauthStatus::Inactive => Self::Inactive,
+            bioauth_flow_api::v2::BioauthStatus::Active { expires_at } => Self::Active {
+                expires_at,
+                renewable_from: None,
+            },
+        }
+    }
+}
+
+/// The transition of the bioauth status of the currently used validator key, as used in the RPC.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum BioauthStatusTransition<Timestamp, DeauthenticationReason> {
+    /// The authentication has become active.
+    Activated {
+        /// The timestamp when the authentication will expire.
+        expires_at: Timestamp,
+        /// The timestamp starting from which the authentication can be renewed.
+        /// Absent if the runtime does not support the renewal.
+        renewable_from: Option<Timestamp>,
+    },
+    /// The active authentication has been renewed.
+    Renewed {
+        /// The timestamp when the renewed authentication will expire.
+        expires_at: Timestamp,
+        /// The timestamp starting from which the renewed authentication can be renewed again.
+        /// Absent if the runtime does not support the renewal.
+        renewable_from: Option<Timestamp>,
+    },
+    /// The active authentication has entered the renewal window, and is approaching
+    /// the expiration.
+    ApproachingExpiry {
+        /// The timestamp when the authentication will expire.
+        expires_at: Timestamp,
+    },
+    /// The authentication has expired.
+    Expired,
+    /// The authentication has been removed before the expiration.
+    Deauthenticated {
+        /// The reason of the deauthentication.
+        reason: DeauthenticationReason,
+    },
+    /// The authentication is no longer active, but it is unknown whether it has expired or
+    /// has been deauthenticated, as the blocks it could have been removed at were not looked
+    /// through.
+    Unknown,
+}
+
+/// The active authentication as used in the RPC.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAuthentication<ValidatorPublicKey, Timestamp> {
+    /// The public key of the authenticated validator.
+    pub id: ValidatorPublicKey,
+    /// The timestamp when the authentication will expire.
+    pub expires_at: Timestamp,
+    /// The timestamp starting from which the authentication can be renewed.
+    pub renewable_from: Timestamp,
+}
+
+impl<ValidatorPublicKey, Timestamp>
+    From<bioauth_flow_api::ActiveAuthentication<ValidatorPublicKey, Timestamp>>
+    for ActiveAuthentication<ValidatorPublicKey, Timestamp>
+{
+    fn from(
+        authentication: bioauth_flow_api::ActiveAuthentication<ValidatorPublicKey, Timestamp>,
+    ) -> Self {
+        Self {
+            id: authentication.id,
+            expires_at: authentication.expires_at,
+            renewable_from: authentication.renewable_from,
+        }
+    }
+}
+
+/// The bioauth configuration as used in the RPC.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BioauthConfig<Timestamp> {
+    /// The robonode public keys that the auth tickets are currently accepted from.
+    pub robonode_public_keys: Vec<sp_core::Bytes>,
+    /// The number of the robonode signatures required for an auth ticket to be accepted.
+    pub robonode_signatures_threshold: u32,
+    /// The amount of time after which the authentications expire.
+    pub authentications_expire_after: Timestamp,
+    /// The amount of time before the expiration during which the authentications can be renewed.
+    pub authentications_renewal_window: Timestamp,
+    /// The amount of time since the issuance after which the auth tickets expire.
+    pub auth_tickets_expire_after: Timestamp,
+    /// The current number of the active authentications.
+    pub active_authentications: u32,
+    /// The maximum number of the active authentications.
+    pub max_authentications: u32,
+    /// The current number of the consumed auth ticket nonces.
+    pub consumed_auth_ticket_nonces: u32,
+    /// The maximum number of the consumed auth ticket nonces.
+    pub max_nonces: u32,
+}
+
+impl<Timestamp> From<bioauth_flow_api::BioauthConfig<Timestamp>> for BioauthConfig<Timestamp> {
+    fn from(config: bioauth_flow_api::BioauthConfig<Timestamp>) -> Self {
+        Self {
+            robonode_public_keys: config
+                .robonode_public_keys
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            robonode_signatures_threshold: config.robonode_signatures_threshold,
+            authentications_expire_after: config.authentications_expire_after,
+            authentications_renewal_window: config.authentications_renewal_window,
+            auth_tickets_expire_after: config.auth_tickets_expire_after,
+            active_authentications: config.active_authentications,
+            max_authentications: config.max_authentications,
+            consumed_auth_ticket_nonces: config.consumed_auth_ticket_nonces,
+            max_nonces: config.max_nonces,
         }
     }
 }
@@ -31,6 +161,11 @@ impl<T> From<bioauth_flow_api::BioauthStatus<T>> for BioauthStatus<T> {
 #[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrollV2Result {
+    /// An opaque enrollment receipt, the robonode-signed evidence of this enrollment.
+    /// Absent if the robonode doesn't issue the enrollment receipts.
+    pub enrollment_receipt: Option<Box<[u8]>>,
+    /// The robonode signature for this opaque enrollment receipt.
+    pub enrollment_receipt_signature: Option<Box<[u8]>>,
     /// Scan result blob.
     pub scan_result_blob: Option<String>,
 }
@@ -46,3 +181,33 @@ pub struct AuthenticateV2Result {
     /// Scan result blob.
     pub scan_result_blob: Option<String>,
 }
+
+/// `onboard` flow result.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardResult<TxHash> {
+    /// The hash of the submitted authenticate transaction.
+    pub authenticate_tx_hash: TxHash,
+    /// The outcome of the session keys setup.
+    pub session_keys: SessionKeysStatus<TxHash>,
+}
+
+/// The outcome of the session keys setup, as used in the RPC.
+#[derive(Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SessionKeysStatus<TxHash> {
+    /// The session keys are already set, and present at the local keystore.
+    AlreadySet,
+    /// The new session keys have been generated at the local keystore, and the transaction
+    /// setting them has been submitted.
+    Submitted {
+        /// The hash of the submitted set keys transaction.
+        tx_hash: TxHash,
+        /// The generated session keys.
+        session_keys: sp_core::Bytes,
+    },
+    /// The session keys could not be set up; the authentication is not affected by this.
+    Failed {
+        /// The human-friendly message for what happened.
+        message: String,
+    },
+}
@@ -0,0 +1,179 @@
+//! The in-process robonode stand-in for the local development networks.
+//!
+//! Issues the auth tickets and the enrollment receipts for the local validator key, signed with
+//! the configured dev robonode key, without conducting any biometric checks. Intended only for
+//! the integration tests and the frontend development against the local dev networks.
+//!
+//! The FaceTec Device SDK params and the session tokens are placeholders, so that the frontend
+//! can go through the flow; the FaceScans it captures are not processed.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use primitives_auth_ticket::{current_unix_milliseconds, AuthTicket, OpaqueAuthTicket};
+use primitives_enrollment_receipt::{EnrollmentReceipt, OpaqueEnrollmentReceipt};
+use robonode_client::{AuthenticateResponse, EnrollResponse};
+use robonode_crypto::Signer;
+
+use crate::data;
+
+/// The placeholder value of the FaceTec Device SDK params and the session tokens.
+const FACETEC_PLACEHOLDER: &str = "dev-robonode";
+
+/// The in-process dev robonode.
+pub struct DevRobonode {
+    /// The dev robonode signing key.
+    signing_key: robonode_crypto::SigningKey,
+    /// The moment (in UNIX milliseconds) this dev robonode has been created at, used to keep
+    /// the authentication nonces unique across the restarts.
+    started_at: u64,
+    /// The sequence of the issued auth tickets, used to keep the authentication nonces unique.
+    sequence: AtomicU64,
+}
+
+impl DevRobonode {
+    /// Create a new dev robonode with the given signing key.
+    pub fn new(signing_key: robonode_crypto::SigningKey) -> Self {
+        Self {
+            signing_key,
+            started_at: current_unix_milliseconds(),
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// The dev robonode public key; the chain must have it configured as the robonode key
+    /// for the issued auth tickets to be accepted.
+    pub fn public_key(&self) -> robonode_crypto::PublicKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// The placeholder FaceTec Device SDK params.
+    pub fn facetec_device_sdk_params(&self) -> data::FacetecDeviceSdkParams {
+        ["deviceKeyIdentifier", "publicFaceMapEncryptionKey"]
+            .into_iter()
+            .map(|param| (param.to_owned(), FACETEC_PLACEHOLDER.into()))
+            .collect()
+    }
+
+    /// The placeholder FaceTec session token.
+    pub fn facetec_session_token(&self) -> String {
+        FACETEC_PLACEHOLDER.to_owned()
+    }
+
+    /// Enroll the given public key, issuing an enrollment receipt for it.
+    pub fn enroll(&self, public_key: &[u8]) -> EnrollResponse {
+        let enrollment_receipt = EnrollmentReceipt {
+            public_key: public_key.to_vec(),
+            enrolled_at: current_unix_milliseconds(),
+            robonode_key_id: self.public_key().as_bytes().to_vec(),
+        };
+        let opaque_enrollment_receipt = OpaqueEnrollmentReceipt::from(&enrollment_receipt);
+        let enrollment_receipt_signature = self.sign(opaque_enrollment_receipt.as_ref());
+
+        EnrollResponse {
+            enrollment_receipt: Some(opaque_enrollment_receipt.0.into()),
+            enrollment_receipt_signature: Some(enrollment_receipt_signature),
+            scan_result_blob: None,
+        }
+    }
+
+    /// Authenticate the given public key, issuing an auth ticket for it.
+    pub fn authenticate(&self, public_key: &[u8]) -> AuthenticateResponse {
+        let sequence_value = self.sequence.fetch_add(1, Ordering::Relaxed);
+
+        let auth_ticket = AuthTicket {
+            public_key: public_key.to_vec(),
+            authentication_nonce: make_authentication_nonce(self.started_at, sequence_value),
+            issued_at: current_unix_milliseconds(),
+        };
+        let opaque_auth_ticket = OpaqueAuthTicket::from(&auth_ticket);
+        let auth_ticket_signature = self.sign(opaque_auth_ticket.as_ref());
+
+        AuthenticateResponse {
+            auth_ticket: opaque_auth_ticket.0.into(),
+            auth_ticket_signature,
+            scan_result_blob: None,
+        }
+    }
+
+    /// Sign the given message with the dev robonode key.
+    fn sign(&self, message: &[u8]) -> Box<[u8]> {
+        self.signing_key.sign(message).to_bytes().into()
+    }
+}
+
+/// Make an authentication nonce.
+fn make_authentication_nonce(started_at: u64, sequence_value: u64) -> Vec<u8> {
+    let mut data = Vec::from(&started_at.to_be_bytes()[..]);
+    data.extend_from_slice(&sequence_value.to_be_bytes()[..]);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use robonode_crypto::Verifier;
+
+    use super::*;
+
+    /// The dev robonode secret key used in tests.
+    const SECRET_KEY: robonode_crypto::SecretKey = [7; 32];
+
+    #[test]
+    fn auth_tickets_are_signed_and_unique() {
+        let dev_robonode = DevRobonode::new(robonode_crypto::SigningKey::from_bytes(&SECRET_KEY));
+
+        let first = dev_robonode.authenticate(b"validator");
+        let second = dev_robonode.authenticate(b"validator");
+
+        for response in [&first, &second] {
+            let signature =
+                robonode_crypto::Signature::try_from(&response.auth_ticket_signature[..]).unwrap();
+            dev_robonode
+                .public_key()
+                .verify(&response.auth_ticket, &signature)
+                .unwrap();
+        }
+
+        let first = AuthTicket::try_from(&OpaqueAuthTicket::from(first.auth_ticket)).unwrap();
+        let second = AuthTicket::try_from(&OpaqueAuthTicket::from(second.auth_ticket)).unwrap();
+        assert_eq!(first.public_key, b"validator");
+        assert_ne!(first.authentication_nonce, second.authentication_nonce);
+    }
+
+    #[test]
+    fn facetec_placeholders() {
+        let dev_robonode = DevRobonode::new(robonode_crypto::SigningKey::from_bytes(&SECRET_KEY));
+
+        assert_eq!(
+            serde_json::Value::Object(dev_robonode.facetec_device_sdk_params()),
+            serde_json::json!({
+                "deviceKeyIdentifier": "dev-robonode",
+                "publicFaceMapEncryptionKey": "dev-robonode",
+            })
+        );
+        assert_eq!(dev_robonode.facetec_session_token(), "dev-robonode");
+    }
+
+    #[test]
+    fn enrollment_receipts_are_signed() {
+        let dev_robonode = DevRobonode::new(robonode_crypto::SigningKey::from_bytes(&SECRET_KEY));
+
+        let response = dev_robonode.enroll(b"validator");
+
+        let receipt = response.enrollment_receipt.unwrap();
+        let signature = robonode_crypto::Signature::try_from(
+            &response.enrollment_receipt_signature.unwrap()[..],
+        )
+        .unwrap();
+        dev_robonode
+            .public_key()
+            .verify(&receipt, &signature)
+            .unwrap();
+
+        let receipt = EnrollmentReceipt::try_from(&OpaqueEnrollmentReceipt::from(receipt)).unwrap();
+        assert_eq!(receipt.public_key, b"validator");
+        assert_eq!(
+            receipt.robonode_key_id,
+            dev_robonode.public_key().as_bytes().to_vec()
+        );
+    }
+}
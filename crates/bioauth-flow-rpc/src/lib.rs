@@ -7,28 +7,36 @@
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use bioauth_flow_api::BioauthFlowApi;
+use author_ext_api::AuthorExtApi;
+use bioauth_flow_api::{BioauthFlowApi, BioauthStateApi};
 use bioauth_keys::traits::KeyExtractor as KeyExtractorT;
-use futures::StreamExt;
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use futures::{future, stream, FutureExt, StreamExt};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::SubscriptionResult, SubscriptionSink};
 use primitives_liveness_data::{LivenessData, OpaqueLivenessData};
 use robonode_client::{AuthenticateRequest, AuthenticateResponse, EnrollRequest, EnrollResponse};
 use rpc_deny_unsafe::DenyUnsafe;
+use sc_client_api::BlockchainEvents;
 use sc_transaction_pool_api::{TransactionPool as TransactionPoolT, TransactionStatus, TxHash};
-use sp_api::{BlockT, Decode, Encode, ProvideRuntimeApi};
-use sp_blockchain::HeaderBackend;
+use sp_api::{ApiExt, BlockT, Decode, Encode, ProvideRuntimeApi};
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
+use sp_core::traits::SpawnNamed;
+use sp_keystore::{KeystoreExt, KeystorePtr};
+use sp_session::SessionKeys;
 use tracing::*;
 
 pub mod data;
+pub mod dev_robonode;
 pub mod error;
 pub mod method;
 pub mod signer;
+pub mod status_tracker;
 
+pub use self::dev_robonode::DevRobonode;
 pub use self::signer::Signer;
 
 /// The API exposed via JSON-RPC.
 #[rpc(server)]
-pub trait Bioauth<Timestamp, TxHash> {
+pub trait Bioauth<ValidatorPublicKey, Timestamp, DeauthenticationReason, TxHash> {
     /// Get the configuration required for the Device SDK.
     #[method(name = "bioauth_getFacetecDeviceSdkParams")]
     async fn get_facetec_device_sdk_params(&self) -> RpcResult<data::FacetecDeviceSdkParams>;
@@ -41,6 +49,24 @@ pub trait Bioauth<Timestamp, TxHash> {
     #[method(name = "bioauth_status")]
     async fn status(&self) -> RpcResult<data::BioauthStatus<Timestamp>>;
 
+    /// Subscribe to the transitions of the bioauth status as the new best blocks are imported.
+    #[subscription(
+        name = "bioauth_subscribeStatus" => "bioauth_statusTransition",
+        unsubscribe = "bioauth_unsubscribeStatus",
+        item = data::BioauthStatusTransition<Timestamp, DeauthenticationReason>,
+    )]
+    fn subscribe_status(&self);
+
+    /// Get all of the active authentications.
+    #[method(name = "bioauth_activeAuthentications")]
+    async fn active_authentications(
+        &self,
+    ) -> RpcResult<Vec<data::ActiveAuthentication<ValidatorPublicKey, Timestamp>>>;
+
+    /// Get the bioauth configuration.
+    #[method(name = "bioauth_config")]
+    async fn config(&self) -> RpcResult<data::BioauthConfig<Timestamp>>;
+
     /// Enroll with provided liveness data.
     #[method(name = "bioauth_enroll")]
     async fn enroll(&self, liveness_data: LivenessData) -> RpcResult<()>;
@@ -59,6 +85,11 @@ pub trait Bioauth<Timestamp, TxHash> {
         &self,
         liveness_data: LivenessData,
     ) -> RpcResult<data::AuthenticateV2Result>;
+
+    /// Authenticate with provided liveness data, and set up the session keys if they are not
+    /// set yet.
+    #[method(name = "bioauth_onboard")]
+    async fn onboard(&self, liveness_data: LivenessData) -> RpcResult<data::OnboardResult<TxHash>>;
 }
 
 /// The RPC implementation.
@@ -69,10 +100,17 @@ pub struct Bioauth<
     Client,
     Block,
     Timestamp,
+    DeauthenticationReason,
     TransactionPool,
 > {
     /// The robonode client, used for fetching the FaceTec Session Token.
     robonode_client: RobonodeClient,
+    /// The clients of the additional robonodes to co-sign the auth tickets with, so that
+    /// the auth tickets meet the robonode signatures threshold.
+    cosigning_robonode_clients: Vec<RobonodeClient>,
+    /// The in-process dev robonode to issue the auth tickets and enrollment receipts with
+    /// instead of the robonode; only set at the local development networks.
+    dev_robonode: Option<Arc<DevRobonode>>,
     /// Provider of the local validator key.
     validator_key_extractor: ValidatorKeyExtractor,
     /// The type that provides signing with the validator private key.
@@ -81,10 +119,14 @@ pub struct Bioauth<
     client: Arc<Client>,
     /// The transaction pool to use.
     pool: Arc<TransactionPool>,
+    /// The keystore to generate the session keys at.
+    keystore: KeystorePtr,
     /// Whether to deny unsafe calls or not.
     deny_unsafe: DenyUnsafe,
+    /// The executor to spawn the subscription tasks with.
+    executor: Arc<dyn SpawnNamed>,
     /// The phantom types.
-    phantom_types: PhantomData<(Block, Timestamp)>,
+    phantom_types: PhantomData<(Block, Timestamp, DeauthenticationReason)>,
 }
 
 impl<
@@ -94,6 +136,7 @@ impl<
         Client,
         Block,
         Timestamp,
+        DeauthenticationReason,
         TransactionPool,
     >
     Bioauth<
@@ -103,25 +146,35 @@ impl<
         Client,
         Block,
         Timestamp,
+        DeauthenticationReason,
         TransactionPool,
     >
 {
     /// Create a new [`Bioauth`] API implementation.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         robonode_client: RobonodeClient,
+        cosigning_robonode_clients: Vec<RobonodeClient>,
+        dev_robonode: Option<Arc<DevRobonode>>,
         validator_key_extractor: ValidatorKeyExtractor,
         validator_signer_factory: ValidatorSignerFactory,
         client: Arc<Client>,
         pool: Arc<TransactionPool>,
+        keystore: KeystorePtr,
         deny_unsafe: DenyUnsafe,
+        executor: Arc<dyn SpawnNamed>,
     ) -> Self {
         Self {
             robonode_client,
+            cosigning_robonode_clients,
+            dev_robonode,
             validator_key_extractor,
             validator_signer_factory,
             client,
             pool,
+            keystore,
             deny_unsafe,
+            executor,
             phantom_types: PhantomData,
         }
     }
@@ -134,6 +187,7 @@ impl<
         Client,
         Block,
         Timestamp,
+        DeauthenticationReason,
         TransactionPool,
     >
     Bioauth<
@@ -143,6 +197,7 @@ impl<
         Client,
         Block,
         Timestamp,
+        DeauthenticationReason,
         TransactionPool,
     >
 where
@@ -185,6 +240,11 @@ where
             .await
             .map_err(error::shared::FlowBaseError::Sign)?;
 
+        if let Some(dev_robonode) = &self.dev_robonode {
+            warn!("Bioauth flow - enrolling with the dev robonode, no biometric checks are conducted");
+            return Ok(dev_robonode.enroll(public_key.as_ref()));
+        }
+
         let response = self
             .robonode_client
             .as_ref()
@@ -202,8 +262,11 @@ where
     }
 
     /// Do authenticate with provided liveness data.
-    async fn do_authenticate(&self, liveness_data: LivenessData) -> Result<
-        AuthenticateResponse,
+    ///
+    /// Optionally, collect the co-signatures of the auth ticket from the co-signing robonodes;
+    /// the robonodes that fail to co-sign are skipped.
+    async fn do_authenticate(&self, liveness_data: LivenessData, collect_cosignatures: bool) -> Result<
+        (AuthenticateResponse, Vec<Vec<u8>>),
         error::shared::FlowBaseError<robonode_client::AuthenticateError>
     > {
         info!("Bioauth flow - authentication in progress");
@@ -213,23 +276,178 @@ where
             .map_err(error::shared::FlowBaseError::KeyExtraction)?;
 
         let (opaque_liveness_data, signature) = self
-            .sign(public_key, &liveness_data)
+            .sign(public_key.clone(), &liveness_data)
             .await
             .map_err(error::shared::FlowBaseError::Sign)?;
 
+        if let Some(dev_robonode) = &self.dev_robonode {
+            warn!("Bioauth flow - authenticating with the dev robonode, no biometric checks are conducted");
+            return Ok((dev_robonode.authenticate(public_key.as_ref()), Vec::new()));
+        }
+
         let response = self
             .robonode_client
             .as_ref()
             .authenticate(AuthenticateRequest {
                 liveness_data: opaque_liveness_data.as_ref(),
                 liveness_data_signature: signature.as_ref(),
+                auth_ticket: None,
+                auth_ticket_signature: None,
             })
             .await
             .map_err(error::shared::FlowBaseError::RobonodeClient)?;
 
         info!("Bioauth flow - authentication complete");
 
-        Ok(response)
+        if !collect_cosignatures || self.cosigning_robonode_clients.is_empty() {
+            return Ok((response, Vec::new()));
+        }
+
+        info!("Bioauth flow - collecting the auth ticket co-signatures");
+
+        let results = future::join_all(self.cosigning_robonode_clients.iter().map(|robonode_client| {
+            robonode_client.as_ref().authenticate(AuthenticateRequest {
+                liveness_data: opaque_liveness_data.as_ref(),
+                liveness_data_signature: signature.as_ref(),
+                auth_ticket: Some(&response.auth_ticket),
+                auth_ticket_signature: Some(&response.auth_ticket_signature),
+            })
+        }))
+        .await;
+
+        let cosignatures = results
+            .into_iter()
+            .zip(&self.cosigning_robonode_clients)
+            .filter_map(|(result, robonode_client)| {
+                let robonode_url = &robonode_client.as_ref().base_url;
+                match result {
+                    Ok(cosigned) if cosigned.auth_ticket == response.auth_ticket => {
+                        Some(cosigned.auth_ticket_signature.into())
+                    }
+                    Ok(_) => {
+                        warn!(message = "Bioauth flow - the robonode has co-signed another auth ticket", %robonode_url);
+                        None
+                    }
+                    Err(error) => {
+                        warn!(message = "Bioauth flow - unable to co-sign the auth ticket", %robonode_url, ?error);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        info!(
+            message = "Bioauth flow - auth ticket co-signatures collected",
+            cosignatures = cosignatures.len(),
+        );
+
+        Ok((response, cosignatures))
+    }
+}
+
+impl<
+        RobonodeClient,
+        ValidatorKeyExtractor,
+        ValidatorSignerFactory,
+        Client,
+        Block,
+        Timestamp,
+        DeauthenticationReason,
+        TransactionPool,
+    >
+    Bioauth<
+        RobonodeClient,
+        ValidatorKeyExtractor,
+        ValidatorSignerFactory,
+        Client,
+        Block,
+        Timestamp,
+        DeauthenticationReason,
+        TransactionPool,
+    >
+where
+    ValidatorKeyExtractor: KeyExtractorT,
+    ValidatorKeyExtractor::PublicKeyType: Encode,
+    ValidatorKeyExtractor::Error: std::fmt::Debug,
+    Client: HeaderBackend<Block>,
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: AuthorExtApi<Block, ValidatorKeyExtractor::PublicKeyType>,
+    Client::Api: SessionKeys<Block>,
+    Block: BlockT,
+    TransactionPool: TransactionPoolT<Block = Block>,
+{
+    /// Make sure the session keys of the local validator are set, and present at the local
+    /// keystore; generate and set the new session keys otherwise.
+    async fn ensure_session_keys(
+        &self,
+    ) -> Result<
+        data::SessionKeysStatus<TxHash<TransactionPool>>,
+        method::onboard::SessionKeysError<TransactionPool::Error>,
+    > {
+        let validator_key =
+            rpc_validator_key_logic::validator_public_key(&self.validator_key_extractor)
+                .map_err(method::onboard::SessionKeysError::KeyExtraction)?;
+
+        let at = self.client.info().best_hash;
+
+        let runtime_api = self.client.runtime_api();
+
+        let api_version = runtime_api
+            .api_version::<dyn AuthorExtApi<Block, ValidatorKeyExtractor::PublicKeyType>>(at)
+            .map_err(method::onboard::SessionKeysError::RuntimeApi)?;
+
+        // The runtimes prior to the API version 2 do not provide the session keys lookup.
+        if !matches!(api_version, Some(api_version) if api_version >= 2) {
+            return Err(method::onboard::SessionKeysError::Unsupported);
+        }
+
+        let session_keys = runtime_api
+            .session_keys(at, &validator_key)
+            .map_err(method::onboard::SessionKeysError::RuntimeApi)?;
+
+        if let Some(session_keys) = session_keys {
+            let public_keys = runtime_api
+                .decode_session_keys(at, session_keys)
+                .map_err(method::onboard::SessionKeysError::RuntimeApi)?;
+
+            if public_keys.map_or(false, |public_keys| self.keystore.has_keys(&public_keys)) {
+                info!("Bioauth flow - session keys are already set");
+                return Ok(data::SessionKeysStatus::AlreadySet);
+            }
+        }
+
+        info!("Bioauth flow - generating new session keys");
+
+        let mut runtime_api = self.client.runtime_api();
+        runtime_api.register_extension(KeystoreExt::from(Arc::clone(&self.keystore)));
+
+        let session_keys = runtime_api
+            .generate_session_keys(at, None)
+            .map_err(method::onboard::SessionKeysError::RuntimeApi)?;
+
+        let ext = self
+            .client
+            .runtime_api()
+            .create_signed_set_keys_extrinsic(at, &validator_key, session_keys.clone())
+            .map_err(method::onboard::SessionKeysError::RuntimeApi)?
+            .map_err(method::onboard::SessionKeysError::ExtrinsicCreation)?;
+
+        info!("Bioauth flow - submitting set keys transaction");
+
+        let tx_hash = self
+            .pool
+            .submit_one(
+                &sp_api::BlockId::Hash(at),
+                sp_runtime::transaction_validity::TransactionSource::Local,
+                ext,
+            )
+            .await
+            .map_err(method::onboard::SessionKeysError::SetKeysTx)?;
+
+        Ok(data::SessionKeysStatus::Submitted {
+            tx_hash,
+            session_keys: session_keys.into(),
+        })
     }
 }
 
@@ -241,8 +459,15 @@ impl<
         Client,
         Block,
         Timestamp,
+        DeauthenticationReason,
         TransactionPool,
-    > BioauthServer<Timestamp, TxHash<TransactionPool>>
+    >
+    BioauthServer<
+        ValidatorKeyExtractor::PublicKeyType,
+        Timestamp,
+        DeauthenticationReason,
+        TxHash<TransactionPool>,
+    >
     for Bioauth<
         RobonodeClient,
         ValidatorKeyExtractor,
@@ -250,6 +475,7 @@ impl<
         Client,
         Block,
         Timestamp,
+        DeauthenticationReason,
         TransactionPool,
     >
 where
@@ -261,25 +487,41 @@ where
     Client: Send + Sync + 'static,
     Block: Send + Sync + 'static,
     Timestamp: Send + Sync + 'static,
+    DeauthenticationReason: Send + Sync + 'static,
     TransactionPool: Send + Sync + 'static,
 
     RobonodeClient: AsRef<robonode_client::Client>,
     ValidatorKeyExtractor: KeyExtractorT,
-    ValidatorKeyExtractor::PublicKeyType: Encode + AsRef<[u8]> + Clone,
+    ValidatorKeyExtractor::PublicKeyType: Encode + Decode + AsRef<[u8]> + Clone,
     ValidatorKeyExtractor::Error: std::fmt::Debug,
     ValidatorSignerFactory: signer::Factory<Vec<u8>, ValidatorKeyExtractor::PublicKeyType>,
     <<ValidatorSignerFactory as signer::Factory<Vec<u8>, ValidatorKeyExtractor::PublicKeyType>>::Signer as Signer<Vec<u8>>>::Error:
         std::error::Error + 'static,
     Client: HeaderBackend<Block>,
+    Client: HeaderMetadata<Block, Error = sp_blockchain::Error>,
     Client: ProvideRuntimeApi<Block>,
+    Client: BlockchainEvents<Block>,
     Client: Send + Sync + 'static,
     Client::Api:
         bioauth_flow_api::BioauthFlowApi<Block, ValidatorKeyExtractor::PublicKeyType, Timestamp>,
+    Client::Api: bioauth_flow_api::BioauthStateApi<
+        Block,
+        ValidatorKeyExtractor::PublicKeyType,
+        Timestamp,
+        DeauthenticationReason,
+    >,
+    Client::Api: AuthorExtApi<Block, ValidatorKeyExtractor::PublicKeyType>,
+    Client::Api: SessionKeys<Block>,
     Block: BlockT,
-    Timestamp: Encode + Decode,
+    Timestamp: Encode + Decode + PartialOrd + Clone + serde::Serialize,
+    DeauthenticationReason: Decode + serde::Serialize,
     TransactionPool: TransactionPoolT<Block = Block>,
 {
     async fn get_facetec_device_sdk_params(&self) -> RpcResult<data::FacetecDeviceSdkParams> {
+        if let Some(dev_robonode) = &self.dev_robonode {
+            return Ok(dev_robonode.facetec_device_sdk_params());
+        }
+
         let res = self
             .robonode_client
             .as_ref()
@@ -290,6 +532,10 @@ where
     }
 
     async fn get_facetec_session_token(&self) -> RpcResult<String> {
+        if let Some(dev_robonode) = &self.dev_robonode {
+            return Ok(dev_robonode.facetec_session_token());
+        }
+
         let res = self
             .robonode_client
             .as_ref()
@@ -309,13 +555,93 @@ where
         // Extract an id of the last imported block.
         let at = self.client.info().best_hash;
 
-        let status = self
+        let status = bioauth_status_at::<_, Block, _, _>(&*self.client, at, &own_key)
+            .map_err(method::status::Error::RuntimeApi)?;
+
+        Ok(status)
+    }
+
+    fn subscribe_status(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        let own_key = match rpc_validator_key_logic::validator_public_key(&self.validator_key_extractor) {
+            Ok(v) => v,
+            Err(err) => {
+                let err = method::subscribe_status::Error::KeyExtraction(err);
+                let _ = sink.reject(jsonrpsee::core::Error::from(err));
+                return Ok(());
+            }
+        };
+
+        // Extract an id of the last imported block.
+        let at = self.client.info().best_hash;
+
+        let initial_status = match bioauth_status_at::<_, Block, _, _>(&*self.client, at, &own_key)
+        {
+            Ok(v) => v,
+            Err(err) => {
+                let err = method::subscribe_status::Error::RuntimeApi(err);
+                let _ = sink.reject(jsonrpsee::core::Error::from(err));
+                return Ok(());
+            }
+        };
+
+        let mut tracker = status_tracker::StatusTracker::new(initial_status);
+        let mut last_seen = at;
+        let client = Arc::clone(&self.client);
+
+        let stream = self
+            .client
+            .import_notification_stream()
+            .filter(|notification| future::ready(notification.is_new_best))
+            .flat_map(move |notification| {
+                let mut transitions = Vec::new();
+                if let Err(error) = bioauth_status_transitions_to::<_, Block, _, _, _>(
+                    &*client,
+                    &mut last_seen,
+                    notification.hash,
+                    &own_key,
+                    &mut tracker,
+                    &mut transitions,
+                ) {
+                    // The blocks the tracker has not been updated with are picked up
+                    // at the next best block.
+                    error!(message = "Unable to track the bioauth status", ?error);
+                }
+                stream::iter(transitions)
+            });
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+
+        self.executor.spawn("bioauth-rpc-subscription", Some("rpc"), fut.boxed());
+
+        Ok(())
+    }
+
+    async fn active_authentications(&self) -> RpcResult<Vec<data::ActiveAuthentication<ValidatorKeyExtractor::PublicKeyType, Timestamp>>> {
+        // Extract an id of the last imported block.
+        let at = self.client.info().best_hash;
+
+        let active_authentications = self
             .client
             .runtime_api()
-            .bioauth_status(at, &own_key)
-            .map_err(method::status::Error::RuntimeApi)?;
+            .active_authentications(at)
+            .map_err(method::active_authentications::Error::RuntimeApi)?;
+
+        Ok(active_authentications.into_iter().map(Into::into).collect())
+    }
+
+    async fn config(&self) -> RpcResult<data::BioauthConfig<Timestamp>> {
+        // Extract an id of the last imported block.
+        let at = self.client.info().best_hash;
+
+        let config = self
+            .client
+            .runtime_api()
+            .config(at)
+            .map_err(method::config::Error::RuntimeApi)?;
 
-        Ok(status.into())
+        Ok(config.into())
     }
 
     async fn enroll(&self, liveness_data: LivenessData) -> RpcResult<()> {
@@ -329,12 +655,22 @@ where
     async fn enroll_v2(&self, liveness_data: LivenessData) -> RpcResult<data::EnrollV2Result> {
         self.deny_unsafe.check_if_safe()?;
 
-        let EnrollResponse { scan_result_blob } = self
+        let EnrollResponse {
+            enrollment_receipt,
+            enrollment_receipt_signature,
+            scan_result_blob,
+        } = self
             .do_enroll(liveness_data)
             .await
             .map_err(method::enroll_v2::Error)?;
 
-        Ok(data::EnrollV2Result { scan_result_blob })
+        info!(message = "We've obtained an enrollment receipt", ?enrollment_receipt);
+
+        Ok(data::EnrollV2Result {
+            enrollment_receipt,
+            enrollment_receipt_signature,
+            scan_result_blob,
+        })
     }
 
     async fn authenticate(&self, liveness_data: LivenessData) -> RpcResult<TxHash<TransactionPool>> {
@@ -342,8 +678,8 @@ where
 
         let errtype = |val: method::authenticate::Error<TransactionPool::Error>| {  val };
 
-        let response = self
-            .do_authenticate(liveness_data)
+        let (response, cosignatures) = self
+            .do_authenticate(liveness_data, true)
             .await
             .map_err(method::authenticate::Error::RobonodeRequest)
             .map_err(errtype)?;
@@ -352,15 +688,32 @@ where
 
         let at = self.client.info().best_hash;
 
-        let ext = self
-            .client
-            .runtime_api()
-            .create_authenticate_extrinsic(
+        let runtime_api = self.client.runtime_api();
+
+        let api_version = runtime_api
+            .api_version::<dyn BioauthFlowApi<Block, ValidatorKeyExtractor::PublicKeyType, Timestamp>>(at)
+            .map_err(method::authenticate::Error::RuntimeApi).map_err(errtype)?;
+
+        // The runtimes prior to the API version 2 only accept a single robonode signature.
+        let ext = if matches!(api_version, Some(api_version) if api_version >= 2) {
+            let mut auth_ticket_signatures = vec![response.auth_ticket_signature.into()];
+            auth_ticket_signatures.extend(cosignatures);
+            runtime_api.create_multisigned_authenticate_extrinsic(
+                at,
+                response.auth_ticket.into(),
+                auth_ticket_signatures,
+            )
+        } else {
+            if !cosignatures.is_empty() {
+                warn!("Bioauth flow - the runtime does not accept the co-signatures, submitting the auth ticket with a single signature");
+            }
+            runtime_api.create_authenticate_extrinsic(
                 at,
                 response.auth_ticket.into(),
                 response.auth_ticket_signature.into(),
             )
-            .map_err(method::authenticate::Error::RuntimeApi).map_err(errtype)?;
+        }
+        .map_err(method::authenticate::Error::RuntimeApi).map_err(errtype)?;
 
         info!("Bioauth flow - submitting authenticate transaction");
 
@@ -450,14 +803,167 @@ where
     ) -> RpcResult<data::AuthenticateV2Result> {
         self.deny_unsafe.check_if_safe()?;
 
-        let AuthenticateResponse {
+        let (AuthenticateResponse {
             auth_ticket,
             auth_ticket_signature,
             scan_result_blob,
-        } = self.do_authenticate(liveness_data).await.map_err(method::authenticate_v2::Error)?;
+        }, _) = self.do_authenticate(liveness_data, false).await.map_err(method::authenticate_v2::Error)?;
 
         info!(message = "We've obtained an auth ticket", auth_ticket = ?auth_ticket);
 
         Ok(data::AuthenticateV2Result { auth_ticket, auth_ticket_signature, scan_result_blob })
     }
+
+    async fn onboard(&self, liveness_data: LivenessData) -> RpcResult<data::OnboardResult<TxHash<TransactionPool>>> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let authenticate_tx_hash = self.authenticate(liveness_data).await?;
+
+        let session_keys = self.ensure_session_keys().await.unwrap_or_else(|error| {
+            error!(message = "Bioauth flow - unable to set up the session keys", %error);
+            error.into()
+        });
+
+        Ok(data::OnboardResult { authenticate_tx_hash, session_keys })
+    }
+}
+
+/// Get the bioauth status of the given validator key at the given block.
+fn bioauth_status_at<Client, Block, ValidatorPublicKey, Timestamp>(
+    client: &Client,
+    at: Block::Hash,
+    own_key: &ValidatorPublicKey,
+) -> Result<data::BioauthStatus<Timestamp>, sp_api::ApiError>
+where
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: BioauthFlowApi<Block, ValidatorPublicKey, Timestamp>,
+    Block: BlockT,
+    ValidatorPublicKey: Encode,
+    Timestamp: Decode,
+{
+    let runtime_api = client.runtime_api();
+
+    let api_version =
+        runtime_api.api_version::<dyn BioauthFlowApi<Block, ValidatorPublicKey, Timestamp>>(at)?;
+
+    // The runtimes prior to the API version 3 do not report the renewal window.
+    let status = match api_version {
+        Some(api_version) if api_version >= 3 => runtime_api.bioauth_status(at, own_key)?.into(),
+        _ => {
+            #[allow(deprecated)]
+            let status = runtime_api.bioauth_status_before_version_3(at, own_key)?;
+            status.into()
+        }
+    };
+
+    Ok(status)
+}
+
+/// The maximum number of the blocks to look through when the best block has advanced by more
+/// than one block, beyond which the removal of the authentication is reported as unknown.
+const MAX_TRACKED_BLOCKS: usize = 64;
+
+/// An error that can occur while tracking the bioauth status.
+#[derive(Debug, thiserror::Error)]
+enum StatusTrackingError {
+    /// Unable to find the route between the last seen and the new best blocks.
+    #[error("unable to find the route to the best block: {0}")]
+    TreeRoute(sp_blockchain::Error),
+    /// The runtime API call has failed.
+    #[error("runtime error: {0}")]
+    RuntimeApi(sp_api::ApiError),
+}
+
+/// Bring the status tracker from the last seen best block to the new best one, collecting
+/// the detected transitions.
+///
+/// Each of the blocks in between is looked at, so that the deauthentications are told apart
+/// from the expirations. On a reorg, or if there are too many blocks in between, only the new
+/// best block is looked at, and the removal of the authentication is reported as unknown.
+///
+/// The `last_seen` is advanced to the last block the tracker has been updated with, so in case of
+/// an error the rest of the blocks are picked up at the next call.
+fn bioauth_status_transitions_to<
+    Client,
+    Block,
+    ValidatorPublicKey,
+    Timestamp,
+    DeauthenticationReason,
+>(
+    client: &Client,
+    last_seen: &mut Block::Hash,
+    best: Block::Hash,
+    own_key: &ValidatorPublicKey,
+    tracker: &mut status_tracker::StatusTracker<Timestamp>,
+    transitions: &mut Vec<data::BioauthStatusTransition<Timestamp, DeauthenticationReason>>,
+) -> Result<(), StatusTrackingError>
+where
+    Client: HeaderMetadata<Block, Error = sp_blockchain::Error>,
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: BioauthFlowApi<Block, ValidatorPublicKey, Timestamp>,
+    Client::Api: BioauthStateApi<Block, ValidatorPublicKey, Timestamp, DeauthenticationReason>,
+    Block: BlockT,
+    ValidatorPublicKey: Encode + Decode,
+    Timestamp: Decode + PartialOrd + Clone,
+    DeauthenticationReason: Decode,
+{
+    let tree_route = sp_blockchain::tree_route::<Block, _>(client, *last_seen, best)
+        .map_err(StatusTrackingError::TreeRoute)?;
+
+    if !tree_route.retracted().is_empty() || tree_route.enacted().len() > MAX_TRACKED_BLOCKS {
+        let status = bioauth_status_at::<_, Block, _, _>(client, best, own_key)
+            .map_err(StatusTrackingError::RuntimeApi)?;
+        let now = client
+            .runtime_api()
+            .current_moment(best)
+            .map_err(StatusTrackingError::RuntimeApi)?;
+
+        transitions.extend(tracker.update_untracked(status, &now));
+        *last_seen = best;
+        return Ok(());
+    }
+
+    for block in tree_route.enacted() {
+        transitions.extend(
+            bioauth_status_transition_at::<_, Block, _, _, _>(client, block.hash, own_key, tracker)
+                .map_err(StatusTrackingError::RuntimeApi)?,
+        );
+        *last_seen = block.hash;
+    }
+
+    Ok(())
+}
+
+/// Update the status tracker with the bioauth status of the given validator key at the given
+/// block, and return the detected transition, if any.
+fn bioauth_status_transition_at<
+    Client,
+    Block,
+    ValidatorPublicKey,
+    Timestamp,
+    DeauthenticationReason,
+>(
+    client: &Client,
+    at: Block::Hash,
+    own_key: &ValidatorPublicKey,
+    tracker: &mut status_tracker::StatusTracker<Timestamp>,
+) -> Result<
+    Option<data::BioauthStatusTransition<Timestamp, DeauthenticationReason>>,
+    sp_api::ApiError,
+>
+where
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: BioauthFlowApi<Block, ValidatorPublicKey, Timestamp>,
+    Client::Api: BioauthStateApi<Block, ValidatorPublicKey, Timestamp, DeauthenticationReason>,
+    Block: BlockT,
+    ValidatorPublicKey: Encode + Decode,
+    Timestamp: Decode + PartialOrd + Clone,
+    DeauthenticationReason: Decode,
+{
+    let status = bioauth_status_at::<_, Block, _, _>(client, at, own_key)?;
+    let now = client.runtime_api().current_moment(at)?;
+
+    tracker.update(status, &now, || {
+        client.runtime_api().deauthentication_reason(at, own_key)
+    })
 }
@@ -35,6 +35,8 @@ where
                     | robonode_client::AuthenticateError::PersonNotFound(_)
                     | robonode_client::AuthenticateError::SignatureInvalidNoBlob
                     | robonode_client::AuthenticateError::SignatureInvalid(_)
+                    | robonode_client::AuthenticateError::AuthTicketMismatchNoBlob
+                    | robonode_client::AuthenticateError::AuthTicketMismatch(_)
                     | robonode_client::AuthenticateError::LogicInternalNoBlob
                     | robonode_client::AuthenticateError::LogicInternal(_)
                     | robonode_client::AuthenticateError::UnknownCode(_)
@@ -13,6 +13,7 @@ impl From<Error> for jsonrpsee::core::Error {
                 robonode_client::AuthenticateError::PersonNotFound(ref scan_result_blob)
                 | robonode_client::AuthenticateError::FaceScanRejected(ref scan_result_blob)
                 | robonode_client::AuthenticateError::SignatureInvalid(ref scan_result_blob)
+                | robonode_client::AuthenticateError::AuthTicketMismatch(ref scan_result_blob)
                 | robonode_client::AuthenticateError::LogicInternal(ref scan_result_blob) => {
                     Some(error::data::ScanResultBlob(scan_result_blob.clone()).into())
                 }
@@ -24,6 +25,7 @@ impl From<Error> for jsonrpsee::core::Error {
                 robonode_client::AuthenticateError::InvalidLivenessData
                 | robonode_client::AuthenticateError::PersonNotFoundNoBlob
                 | robonode_client::AuthenticateError::SignatureInvalidNoBlob
+                | robonode_client::AuthenticateError::AuthTicketMismatchNoBlob
                 | robonode_client::AuthenticateError::LogicInternalNoBlob
                 | robonode_client::AuthenticateError::UnknownCode(_)
                 | robonode_client::AuthenticateError::Unknown(_) => None,
@@ -1,9 +1,13 @@
 //! Module implementation details.
 
+pub mod active_authentications;
 pub mod authenticate;
 pub mod authenticate_v2;
+pub mod config;
 pub mod enroll;
 pub mod enroll_v2;
 pub mod get_facetec_device_sdk_params;
 pub mod get_facetec_session_token;
+pub mod onboard;
 pub mod status;
+pub mod subscribe_status;
@@ -0,0 +1,67 @@
+//! The `onboard` method error.
+
+use author_ext_api::CreateSignedSetKeysExtrinsicError;
+use sp_api::ApiError;
+
+use crate::data;
+
+/// The session keys setup error kinds of the `onboard` method.
+///
+/// The authenticate transaction is already submitted by the time the session keys are set up,
+/// so these errors do not fail the method, and are reported at the session keys status instead.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionKeysError<TxPoolError> {
+    /// An error that can occur during validator key extraction.
+    #[error(transparent)]
+    KeyExtraction(rpc_validator_key_logic::Error),
+    /// The runtime does not provide the session keys lookup.
+    #[error("the runtime does not support the session keys lookup")]
+    Unsupported,
+    /// An error that can occur during doing a call into runtime api.
+    #[error("runtime api call failed: {0}")]
+    RuntimeApi(ApiError),
+    /// An error that can occur during the set keys extrinsic creation.
+    #[error("unable to create the set keys transaction: {0:?}")]
+    ExtrinsicCreation(CreateSignedSetKeysExtrinsicError),
+    /// An error that can occur with transaction pool logic.
+    #[error("unable to submit the set keys transaction: {0}")]
+    SetKeysTx(TxPoolError),
+}
+
+impl<TxPoolError, TxHash> From<SessionKeysError<TxPoolError>> for data::SessionKeysStatus<TxHash>
+where
+    TxPoolError: std::fmt::Display,
+{
+    fn from(err: SessionKeysError<TxPoolError>) -> Self {
+        Self::Failed {
+            message: err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_keys_error_status() {
+        let status: data::SessionKeysStatus<()> =
+            SessionKeysError::<std::fmt::Error>::ExtrinsicCreation(
+                CreateSignedSetKeysExtrinsicError::SignedExtrinsicCreation,
+            )
+            .into();
+
+        let expected_status = "{\"Failed\":{\"message\":\"unable to create the set keys transaction: SignedExtrinsicCreation\"}}";
+        assert_eq!(expected_status, serde_json::to_string(&status).unwrap());
+    }
+
+    #[test]
+    fn session_keys_unsupported_status() {
+        let status: data::SessionKeysStatus<()> =
+            SessionKeysError::<std::fmt::Error>::Unsupported.into();
+
+        let expected_status =
+            "{\"Failed\":{\"message\":\"the runtime does not support the session keys lookup\"}}";
+        assert_eq!(expected_status, serde_json::to_string(&status).unwrap());
+    }
+}
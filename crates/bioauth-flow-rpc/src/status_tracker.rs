@@ -0,0 +1,303 @@
+//! The tracking of the bioauth status transitions across the blocks.
+
+use std::convert::Infallible;
+
+use crate::data::{BioauthStatus, BioauthStatusTransition};
+
+/// Tracks the bioauth status of the validator key and detects its transitions.
+#[derive(Debug)]
+pub struct StatusTracker<Timestamp> {
+    /// The last observed status.
+    status: BioauthStatus<Timestamp>,
+    /// Whether the approaching expiry of the current authentication has been reported already.
+    approaching_expiry_reported: bool,
+}
+
+impl<Timestamp> StatusTracker<Timestamp>
+where
+    Timestamp: PartialOrd + Clone,
+{
+    /// Start tracking the status from the given initial one.
+    ///
+    /// The initial status is not reported as a transition, but if the authentication is already
+    /// in the renewal window, the approaching expiry is reported at the first update.
+    pub fn new(initial_status: BioauthStatus<Timestamp>) -> Self {
+        Self {
+            status: initial_status,
+            approaching_expiry_reported: false,
+        }
+    }
+
+    /// Update the tracked status with the newly observed one and detect the transition.
+    ///
+    /// The `now` is the moment the newly observed status is at, and the `deauthentication_reason`
+    /// is consulted only when the authentication has been removed, to tell apart the expiration
+    /// from the deauthentication.
+    /// If it fails, the tracked status is left intact, so the update can be retried.
+    pub fn update<DeauthenticationReason, Error>(
+        &mut self,
+        status: BioauthStatus<Timestamp>,
+        now: &Timestamp,
+        deauthentication_reason: impl FnOnce() -> Result<Option<DeauthenticationReason>, Error>,
+    ) -> Result<Option<BioauthStatusTransition<Timestamp, DeauthenticationReason>>, Error> {
+        self.transition(status, now, || {
+            Ok(match deauthentication_reason()? {
+                Some(reason) => BioauthStatusTransition::Deauthenticated { reason },
+                None => BioauthStatusTransition::Expired,
+            })
+        })
+    }
+
+    /// Update the tracked status with the newly observed one, when the blocks since the last
+    /// update have not been looked through, and detect the transition.
+    ///
+    /// The authentication could have been removed at any of those blocks, so the removal is
+    /// reported as [`BioauthStatusTransition::Unknown`].
+    pub fn update_untracked<DeauthenticationReason>(
+        &mut self,
+        status: BioauthStatus<Timestamp>,
+        now: &Timestamp,
+    ) -> Option<BioauthStatusTransition<Timestamp, DeauthenticationReason>> {
+        self.transition(status, now, || {
+            Ok::<_, Infallible>(BioauthStatusTransition::Unknown)
+        })
+        .unwrap_or_else(|never| match never {})
+    }
+
+    /// Update the tracked status with the newly observed one and detect the transition, using
+    /// the given `removal` to tell how the authentication has been removed.
+    fn transition<DeauthenticationReason, Error>(
+        &mut self,
+        status: BioauthStatus<Timestamp>,
+        now: &Timestamp,
+        removal: impl FnOnce() -> Result<
+            BioauthStatusTransition<Timestamp, DeauthenticationReason>,
+            Error,
+        >,
+    ) -> Result<Option<BioauthStatusTransition<Timestamp, DeauthenticationReason>>, Error> {
+        let transition = match (&self.status, &status) {
+            (
+                BioauthStatus::Active {
+                    expires_at: previous_expires_at,
+                    ..
+                },
+                BioauthStatus::Active {
+                    expires_at,
+                    renewable_from,
+                },
+            ) => {
+                let is_renewable =
+                    matches!(renewable_from, Some(renewable_from) if now >= renewable_from);
+
+                if previous_expires_at != expires_at {
+                    self.approaching_expiry_reported = false;
+                    Some(BioauthStatusTransition::Renewed {
+                        expires_at: expires_at.clone(),
+                        renewable_from: renewable_from.clone(),
+                    })
+                } else if is_renewable && !self.approaching_expiry_reported {
+                    self.approaching_expiry_reported = true;
+                    Some(BioauthStatusTransition::ApproachingExpiry {
+                        expires_at: expires_at.clone(),
+                    })
+                } else {
+                    None
+                }
+            }
+            (
+                _,
+                BioauthStatus::Active {
+                    expires_at,
+                    renewable_from,
+                },
+            ) => {
+                self.approaching_expiry_reported = false;
+                Some(BioauthStatusTransition::Activated {
+                    expires_at: expires_at.clone(),
+                    renewable_from: renewable_from.clone(),
+                })
+            }
+            (BioauthStatus::Active { .. }, _) => Some(removal()?),
+            _ => None,
+        };
+
+        self.status = status;
+
+        Ok(transition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The deauthentication reason used in the tests.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Offence;
+
+    /// The deauthentication reason lookup result used in the tests.
+    type ReasonResult = Result<Option<Offence>, ()>;
+
+    fn active(expires_at: u64, renewable_from: u64) -> BioauthStatus<u64> {
+        BioauthStatus::Active {
+            expires_at,
+            renewable_from: Some(renewable_from),
+        }
+    }
+
+    fn no_reason() -> ReasonResult {
+        Ok(None)
+    }
+
+    #[test]
+    fn activation_and_expiration() {
+        let mut tracker = StatusTracker::new(BioauthStatus::Inactive);
+
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &1, no_reason),
+            Ok(None)
+        );
+        assert_eq!(
+            tracker.update(active(100, 90), &2, no_reason),
+            Ok(Some(BioauthStatusTransition::Activated {
+                expires_at: 100,
+                renewable_from: Some(90),
+            }))
+        );
+        assert_eq!(tracker.update(active(100, 90), &3, no_reason), Ok(None));
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &100, no_reason),
+            Ok(Some(BioauthStatusTransition::Expired))
+        );
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &101, no_reason),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn approaching_expiry_is_reported_once() {
+        let mut tracker = StatusTracker::new(active(100, 90));
+
+        assert_eq!(tracker.update(active(100, 90), &89, no_reason), Ok(None));
+        assert_eq!(
+            tracker.update(active(100, 90), &90, no_reason),
+            Ok(Some(BioauthStatusTransition::ApproachingExpiry {
+                expires_at: 100
+            }))
+        );
+        assert_eq!(tracker.update(active(100, 90), &95, no_reason), Ok(None));
+    }
+
+    #[test]
+    fn approaching_expiry_is_reported_at_the_start() {
+        let mut tracker = StatusTracker::new(active(100, 90));
+
+        assert_eq!(
+            tracker.update(active(100, 90), &95, no_reason),
+            Ok(Some(BioauthStatusTransition::ApproachingExpiry {
+                expires_at: 100
+            }))
+        );
+    }
+
+    #[test]
+    fn approaching_expiry_is_not_reported_without_renewal_window() {
+        let status = || BioauthStatus::Active {
+            expires_at: 100,
+            renewable_from: None,
+        };
+        let mut tracker = StatusTracker::new(status());
+
+        assert_eq!(tracker.update(status(), &99, no_reason), Ok(None));
+    }
+
+    #[test]
+    fn renewal_resets_approaching_expiry() {
+        let mut tracker = StatusTracker::new(active(100, 90));
+
+        assert_eq!(
+            tracker.update(active(100, 90), &91, no_reason),
+            Ok(Some(BioauthStatusTransition::ApproachingExpiry {
+                expires_at: 100
+            }))
+        );
+        assert_eq!(
+            tracker.update(active(200, 190), &92, no_reason),
+            Ok(Some(BioauthStatusTransition::Renewed {
+                expires_at: 200,
+                renewable_from: Some(190),
+            }))
+        );
+        assert_eq!(
+            tracker.update(active(200, 190), &190, no_reason),
+            Ok(Some(BioauthStatusTransition::ApproachingExpiry {
+                expires_at: 200
+            }))
+        );
+    }
+
+    #[test]
+    fn deauthentication() {
+        let mut tracker = StatusTracker::new(active(100, 90));
+
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &50, || Ok(Some(Offence))),
+            Ok(Some(
+                BioauthStatusTransition::<_, Offence>::Deauthenticated { reason: Offence }
+            ))
+        );
+    }
+
+    #[test]
+    fn deauthentication_reason_is_only_consulted_on_removal() {
+        let mut tracker = StatusTracker::new(BioauthStatus::Inactive);
+
+        let unexpected_call = || -> ReasonResult { panic!("unexpected call") };
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &1, unexpected_call),
+            Ok(None)
+        );
+        assert_eq!(
+            tracker.update(active(100, 90), &2, unexpected_call),
+            Ok(Some(BioauthStatusTransition::Activated {
+                expires_at: 100,
+                renewable_from: Some(90),
+            }))
+        );
+    }
+
+    #[test]
+    fn untracked_removal_is_unknown() {
+        let mut tracker = StatusTracker::new(active(100, 90));
+
+        assert_eq!(
+            tracker.update_untracked::<Offence>(active(100, 90), &95),
+            Some(BioauthStatusTransition::ApproachingExpiry { expires_at: 100 })
+        );
+        assert_eq!(
+            tracker.update_untracked::<Offence>(BioauthStatus::Inactive, &96),
+            Some(BioauthStatusTransition::Unknown)
+        );
+        assert_eq!(
+            tracker.update_untracked::<Offence>(BioauthStatus::Inactive, &97),
+            None
+        );
+    }
+
+    #[test]
+    fn failed_deauthentication_reason_lookup_can_be_retried() {
+        let mut tracker = StatusTracker::new(active(100, 90));
+
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &50, || -> ReasonResult { Err(()) }),
+            Err(())
+        );
+        assert_eq!(
+            tracker.update(BioauthStatus::Inactive, &51, || Ok(Some(Offence))),
+            Ok(Some(BioauthStatusTransition::Deauthenticated {
+                reason: Offence
+            }))
+        );
+    }
+}
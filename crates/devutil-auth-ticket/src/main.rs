@@ -8,14 +8,31 @@ fn read_hex_env(key: &'static str) -> Vec<u8> {
     decode(val).unwrap()
 }
 
+/// Read the issuance moment in UNIX milliseconds from an env var, or use the current moment if
+/// the var is not set.
+fn read_issued_at_env(key: &'static str) -> u64 {
+    match std::env::var(key) {
+        Ok(val) => val.parse().unwrap(),
+        Err(std::env::VarError::NotPresent) => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            .try_into()
+            .unwrap(),
+        Err(err) => panic!("{err}"),
+    }
+}
+
 fn main() {
     let robonode_secret_key = read_hex_env("ROBONODE_SECRET_KEY");
     let public_key = read_hex_env("AUTH_TICKET_PUBLIC_KEY");
     let authentication_nonce = read_hex_env("AUTH_TICKET_AUTHENTICATION_NONCE");
+    let issued_at = read_issued_at_env("AUTH_TICKET_ISSUED_AT");
 
     let auth_ticket = AuthTicket {
         public_key,
         authentication_nonce,
+        issued_at,
     };
 
     let output = make(Input {
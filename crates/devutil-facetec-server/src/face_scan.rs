@@ -0,0 +1,81 @@
+//! The synthetic FaceScans.
+//!
+//! The simulator does not deal with the real FaceScans; instead, every FaceScan is a plain text
+//! token that deterministically describes the capture:
+//!
+//! - `<person>` or `<person>#<capture>` - a live capture of the `<person>`; the captures of
+//!   the same person match each other regardless of the `<capture>` part, which allows telling
+//!   the captures apart;
+//! - `spoof:<person>` or `spoof:<person>#<capture>` - a capture of the `<person>` that fails
+//!   the liveness check.
+
+/// The prefix of the FaceScans that fail the liveness check.
+pub const SPOOF_PREFIX: &str = "spoof:";
+
+/// The separator between the person and the capture parts of the FaceScan.
+pub const CAPTURE_SEPARATOR: char = '#';
+
+/// A parsed synthetic FaceScan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaceScan {
+    /// The person captured at the FaceScan.
+    pub person: String,
+    /// Whether the FaceScan passes the liveness check.
+    pub live: bool,
+}
+
+impl FaceScan {
+    /// Parse the synthetic FaceScan token.
+    pub fn parse(token: &str) -> Self {
+        let (live, rest) = match token.strip_prefix(SPOOF_PREFIX) {
+            Some(rest) => (false, rest),
+            None => (true, token),
+        };
+        let person = rest
+            .split_once(CAPTURE_SEPARATOR)
+            .map_or(rest, |(person, _capture)| person);
+        Self {
+            person: person.to_owned(),
+            live,
+        }
+    }
+
+    /// Whether the FaceScans capture the same person.
+    pub fn matches(&self, other: &Self) -> bool {
+        self.person == other.person
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tokens() {
+        let cases = [
+            ("alice", "alice", true),
+            ("alice#2", "alice", true),
+            ("spoof:alice", "alice", false),
+            ("spoof:alice#2", "alice", false),
+            ("", "", true),
+        ];
+
+        for (token, person, live) in cases {
+            assert_eq!(
+                FaceScan::parse(token),
+                FaceScan {
+                    person: person.to_owned(),
+                    live,
+                },
+                "{token}"
+            );
+        }
+    }
+
+    #[test]
+    fn captures_of_the_same_person_match() {
+        assert!(FaceScan::parse("alice#1").matches(&FaceScan::parse("alice#2")));
+        assert!(FaceScan::parse("alice").matches(&FaceScan::parse("spoof:alice")));
+        assert!(!FaceScan::parse("alice").matches(&FaceScan::parse("bob")));
+    }
+}
@@ -0,0 +1,276 @@
+//! A local simulator of the FaceTec Server, for running the robonode end-to-end without
+//! the real FaceTec Server.
+//!
+//! The simulator keeps the 3D-DB in memory, and operates on the synthetic FaceScans,
+//! see [`face_scan`] for the format.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde::Deserialize;
+use warp::{Filter, Rejection, Reply};
+
+pub mod face_scan;
+pub mod state;
+
+pub use state::State;
+
+/// The simulator state shared across the requests.
+pub type SharedState = Arc<Mutex<State>>;
+
+/// The scan result blob the simulator returns.
+pub const SCAN_RESULT_BLOB: &str = "simulated scan result blob";
+
+/// Input data for the `/enrollment-3d` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Enrollment3dRequest {
+    /// The ID to associate the FaceScan with.
+    #[serde(rename = "externalDatabaseRefID")]
+    external_database_ref_id: String,
+    /// The synthetic FaceScan.
+    face_scan: String,
+}
+
+/// Input data for the `/3d-db/search` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DbSearchRequest {
+    /// The ID of the FaceScan to search with.
+    #[serde(rename = "externalDatabaseRefID")]
+    external_database_ref_id: String,
+    /// The name of the group to search at.
+    group_name: String,
+    /// The minimal matching level to accept into the search result.
+    min_match_level: i64,
+}
+
+/// Input data for the `/3d-db/enroll` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DbEnrollRequest {
+    /// The ID of the FaceScan to enroll.
+    #[serde(rename = "externalDatabaseRefID")]
+    external_database_ref_id: String,
+    /// The name of the group to enroll the FaceScan at.
+    group_name: String,
+}
+
+/// Input data for the `/3d-db/delete` request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DbDeleteRequest {
+    /// The ID of the entry to delete.
+    identifier: String,
+    /// The name of the group to delete the entry from.
+    group_name: String,
+}
+
+/// Input data for the `/3d-db/list` and `/3d-db/count` requests.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DbGroupRequest {
+    /// The name of the group to inspect.
+    group_name: String,
+}
+
+/// Input data for the `/match-3d-3d` request.
+#[derive(Debug, Deserialize)]
+struct Match3d3dRequest {
+    /// The ID of the FaceScan to match.
+    #[serde(rename = "externalDatabaseRefID")]
+    external_database_ref_id: String,
+    /// The ID of the FaceScan to match against.
+    #[serde(rename = "externalDatabaseRefIDToMatch")]
+    external_database_ref_id_to_match: String,
+}
+
+/// Lock the state, ignoring the poisoning since the state is always left consistent.
+fn lock(state: &SharedState) -> std::sync::MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Reply with the successful response with the given fields.
+fn success(mut body: serde_json::Value) -> warp::reply::Json {
+    body["error"] = false.into();
+    warp::reply::json(&body)
+}
+
+/// Reply with the server error.
+fn server_error(error_message: &str) -> warp::reply::Json {
+    warp::reply::json(&serde_json::json!({
+        "error": true,
+        "errorMessage": error_message,
+        "success": false,
+    }))
+}
+
+/// Provide the state to the handlers.
+fn with_state(
+    state: SharedState,
+) -> impl Filter<Extract = (SharedState,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || Arc::clone(&state))
+}
+
+/// The routes of the simulated FaceTec Server.
+pub fn routes(
+    state: SharedState,
+) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    let session_token = warp::get()
+        .and(warp::path!("session-token"))
+        .and(with_state(Arc::clone(&state)))
+        .map(|state: SharedState| {
+            success(serde_json::json!({
+                "sessionToken": lock(&state).session_token(),
+                "success": true,
+            }))
+        });
+
+    let enrollment_3d = warp::post()
+        .and(warp::path!("enrollment-3d"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: Enrollment3dRequest| {
+            let live =
+                match lock(&state).enrollment_3d(&req.external_database_ref_id, &req.face_scan) {
+                    Ok(live) => live,
+                    Err(error_message) => return server_error(error_message),
+                };
+            success(serde_json::json!({
+                "externalDatabaseRefID": req.external_database_ref_id,
+                "faceScanSecurityChecks": {
+                    "auditTrailVerificationCheckSucceeded": true,
+                    "faceScanLivenessCheckSucceeded": live,
+                    "replayCheckSucceeded": true,
+                    "sessionTokenCheckSucceeded": true,
+                },
+                "scanResultBlob": SCAN_RESULT_BLOB,
+                "success": live,
+            }))
+        });
+
+    let db_search = warp::post()
+        .and(warp::path!("3d-db" / "search"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: DbSearchRequest| {
+            let results = match lock(&state).db_search(
+                &req.external_database_ref_id,
+                &req.group_name,
+                req.min_match_level,
+            ) {
+                Ok(results) => results,
+                Err(error_message) => return server_error(error_message),
+            };
+            let results = results
+                .into_iter()
+                .map(|(identifier, match_level)| {
+                    serde_json::json!({
+                        "identifier": identifier,
+                        "matchLevel": match_level,
+                    })
+                })
+                .collect::<Vec<_>>();
+            success(serde_json::json!({
+                "results": results,
+                "success": true,
+            }))
+        });
+
+    let db_enroll = warp::post()
+        .and(warp::path!("3d-db" / "enroll"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: DbEnrollRequest| {
+            match lock(&state).db_enroll(&req.external_database_ref_id, &req.group_name) {
+                Ok(()) => success(serde_json::json!({ "success": true })),
+                Err(error_message) => server_error(error_message),
+            }
+        });
+
+    let db_delete = warp::post()
+        .and(warp::path!("3d-db" / "delete"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: DbDeleteRequest| {
+            lock(&state).db_delete(&req.identifier, &req.group_name);
+            success(serde_json::json!({ "success": true }))
+        });
+
+    let db_list = warp::post()
+        .and(warp::path!("3d-db" / "list"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: DbGroupRequest| {
+            match lock(&state).db_list(&req.group_name) {
+                Ok(identifiers) => success(serde_json::json!({
+                    "identifiers": identifiers,
+                    "success": true,
+                })),
+                Err(error_message) => server_error(error_message),
+            }
+        });
+
+    let db_count = warp::post()
+        .and(warp::path!("3d-db" / "count"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: DbGroupRequest| {
+            match lock(&state).db_list(&req.group_name) {
+                Ok(identifiers) => success(serde_json::json!({
+                    "count": identifiers.len(),
+                    "success": true,
+                })),
+                Err(error_message) => server_error(error_message),
+            }
+        });
+
+    let match_3d_3d = warp::post()
+        .and(warp::path!("match-3d-3d"))
+        .and(with_state(Arc::clone(&state)))
+        .and(warp::body::json())
+        .map(|state: SharedState, req: Match3d3dRequest| {
+            match lock(&state).match_3d_3d(
+                &req.external_database_ref_id,
+                &req.external_database_ref_id_to_match,
+            ) {
+                Ok(match_level) => success(serde_json::json!({
+                    "matchLevel": match_level,
+                    "success": true,
+                })),
+                Err(error_message) => server_error(error_message),
+            }
+        });
+
+    let reset = warp::post()
+        .and(warp::path!("reset"))
+        .and(with_state(Arc::clone(&state)))
+        .map(|state: SharedState| {
+            lock(&state).reset();
+            success(serde_json::json!({ "success": true }))
+        });
+
+    let reset_if_small = warp::delete()
+        .and(warp::path!("delete-database-if-less-than-10-records"))
+        .and(with_state(state))
+        .map(|state: SharedState| {
+            let did_delete_database = lock(&state).reset_if_small();
+            success(serde_json::json!({
+                "didDeleteDatabase": did_delete_database,
+                "success": true,
+            }))
+        });
+
+    session_token
+        .or(enrollment_3d)
+        .or(db_search)
+        .or(db_enroll)
+        .or(db_delete)
+        .or(db_list)
+        .or(db_count)
+        .or(match_3d_3d)
+        .or(reset)
+        .or(reset_if_small)
+}
+
+#[cfg(test)]
+mod tests;
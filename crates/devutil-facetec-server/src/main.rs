@@ -0,0 +1,30 @@
+//! The entrypoint to the FaceTec Server simulator.
+
+use std::net::SocketAddr;
+
+use clap::Parser;
+
+/// The FaceTec Server simulator.
+#[derive(Debug, Parser)]
+#[command(about = "Simulated FaceTec Server for the robonode testing")]
+struct Cli {
+    /// The address to listen at.
+    #[arg(long, env = "ADDR", default_value = "127.0.0.1:5113")]
+    addr: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    let state = devutil_facetec_server::SharedState::default();
+    let routes = devutil_facetec_server::routes(state);
+
+    let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(cli.addr, async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install CTRL+C signal handler");
+    });
+    println!("Simulated FaceTec Server is listening on http://{addr}");
+    server.await;
+}
@@ -0,0 +1,252 @@
+//! The in-memory state of the simulated FaceTec Server.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::face_scan::FaceScan;
+
+/// The match level the FaceScans of the same person match with.
+pub const MATCH_LEVEL: i64 = 15;
+
+/// The error message for an attempt to reuse the `externalDatabaseRefID` at `/enrollment-3d`.
+/// The same message the real FaceTec Server returns.
+pub const EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE: &str =
+    "An enrollment already exists for this externalDatabaseRefID.";
+
+/// The error message for an unknown `externalDatabaseRefID`.
+pub const NO_ENTRY_ERROR_MESSAGE: &str =
+    "No entry found in the database for this externalDatabaseRefID.";
+
+/// The error message for a search at the group that doesn't exist.
+pub const NO_GROUP_ERROR_MESSAGE: &str =
+    "Tried to search a groupName when that groupName does not exist.";
+
+/// The maximum number of the 3D-DB entries the database is reset at.
+pub const RESET_MAX_ENTRIES: usize = 10;
+
+/// The simulated FaceTec Server state.
+#[derive(Debug, Default)]
+pub struct State {
+    /// The number of the issued session tokens.
+    session_tokens_issued: u64,
+    /// The FaceScans that have passed the `/enrollment-3d`, by the external database ref ID.
+    enrollments: HashMap<String, FaceScan>,
+    /// The 3D-DB groups, with the external database ref IDs enrolled into them.
+    groups: HashMap<String, BTreeSet<String>>,
+}
+
+impl State {
+    /// Issue a new session token.
+    pub fn session_token(&mut self) -> String {
+        self.session_tokens_issued = self.session_tokens_issued.saturating_add(1);
+        format!("session-token-{}", self.session_tokens_issued)
+    }
+
+    /// Conduct the liveness check of the FaceScan, and, if it passes, enroll the FaceScan under
+    /// the external database ref ID.
+    ///
+    /// Returns whether the liveness check has passed.
+    pub fn enrollment_3d(
+        &mut self,
+        external_database_ref_id: &str,
+        face_scan: &str,
+    ) -> Result<bool, &'static str> {
+        if self.enrollments.contains_key(external_database_ref_id) {
+            return Err(EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE);
+        }
+
+        let face_scan = FaceScan::parse(face_scan);
+        if !face_scan.live {
+            return Ok(false);
+        }
+
+        self.enrollments
+            .insert(external_database_ref_id.to_owned(), face_scan);
+        Ok(true)
+    }
+
+    /// Search the group for the entries matching the FaceScan enrolled under the external
+    /// database ref ID.
+    pub fn db_search(
+        &self,
+        external_database_ref_id: &str,
+        group_name: &str,
+        min_match_level: i64,
+    ) -> Result<Vec<(String, i64)>, &'static str> {
+        let face_scan = self
+            .enrollments
+            .get(external_database_ref_id)
+            .ok_or(NO_ENTRY_ERROR_MESSAGE)?;
+        let group = self.groups.get(group_name).ok_or(NO_GROUP_ERROR_MESSAGE)?;
+
+        if MATCH_LEVEL < min_match_level {
+            return Ok(vec![]);
+        }
+
+        let results = group
+            .iter()
+            .filter(|identifier| {
+                self.enrollments
+                    .get(*identifier)
+                    .is_some_and(|enrolled| enrolled.matches(face_scan))
+            })
+            .map(|identifier| (identifier.clone(), MATCH_LEVEL))
+            .collect();
+        Ok(results)
+    }
+
+    /// Enroll the FaceScan under the external database ref ID into the group, creating
+    /// the group if it doesn't exist.
+    pub fn db_enroll(
+        &mut self,
+        external_database_ref_id: &str,
+        group_name: &str,
+    ) -> Result<(), &'static str> {
+        if !self.enrollments.contains_key(external_database_ref_id) {
+            return Err(NO_ENTRY_ERROR_MESSAGE);
+        }
+
+        self.groups
+            .entry(group_name.to_owned())
+            .or_default()
+            .insert(external_database_ref_id.to_owned());
+        Ok(())
+    }
+
+    /// Delete the entry from the group.
+    ///
+    /// Returns whether the entry was present.
+    pub fn db_delete(&mut self, identifier: &str, group_name: &str) -> bool {
+        self.groups
+            .get_mut(group_name)
+            .is_some_and(|group| group.remove(identifier))
+    }
+
+    /// The entries enrolled into the existing group.
+    pub fn db_list(&self, group_name: &str) -> Result<Vec<String>, &'static str> {
+        let group = self.groups.get(group_name).ok_or(NO_GROUP_ERROR_MESSAGE)?;
+        Ok(group.iter().cloned().collect())
+    }
+
+    /// Match the FaceScans enrolled under the two external database ref IDs.
+    ///
+    /// Returns the match level.
+    pub fn match_3d_3d(
+        &self,
+        external_database_ref_id: &str,
+        external_database_ref_id_to_match: &str,
+    ) -> Result<i64, &'static str> {
+        let face_scan = self
+            .enrollments
+            .get(external_database_ref_id)
+            .ok_or(NO_ENTRY_ERROR_MESSAGE)?;
+        let face_scan_to_match = self
+            .enrollments
+            .get(external_database_ref_id_to_match)
+            .ok_or(NO_ENTRY_ERROR_MESSAGE)?;
+        Ok(if face_scan.matches(face_scan_to_match) {
+            MATCH_LEVEL
+        } else {
+            0
+        })
+    }
+
+    /// The entries enrolled into the group.
+    pub fn group(&self, group_name: &str) -> Vec<String> {
+        self.groups
+            .get(group_name)
+            .map(|group| group.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget everything.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Forget everything if there are less than [`RESET_MAX_ENTRIES`] entries at the 3D-DB,
+    /// like the real FaceTec testing server does.
+    ///
+    /// Returns whether the state was reset.
+    pub fn reset_if_small(&mut self) -> bool {
+        let entries = self.groups.values().map(BTreeSet::len).sum::<usize>();
+        if entries >= RESET_MAX_ENTRIES {
+            return false;
+        }
+        self.reset();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_same_person() {
+        let mut state = State::default();
+
+        assert!(state.enrollment_3d("a", "alice#1").unwrap());
+        assert_eq!(
+            state.db_search("a", "humanode", 10),
+            Err(NO_GROUP_ERROR_MESSAGE)
+        );
+        state.db_enroll("a", "humanode").unwrap();
+
+        assert!(state.enrollment_3d("b", "bob").unwrap());
+        assert_eq!(state.db_search("b", "humanode", 10), Ok(vec![]));
+
+        assert!(state.enrollment_3d("tmp", "alice#2").unwrap());
+        assert_eq!(
+            state.db_search("tmp", "humanode", 10),
+            Ok(vec![("a".to_owned(), MATCH_LEVEL)])
+        );
+        assert_eq!(
+            state.db_search("tmp", "humanode", MATCH_LEVEL + 1),
+            Ok(vec![])
+        );
+
+        assert_eq!(state.db_list("humanode"), Ok(vec!["a".to_owned()]));
+        assert_eq!(state.db_list("other"), Err(NO_GROUP_ERROR_MESSAGE));
+        assert_eq!(state.match_3d_3d("tmp", "a"), Ok(MATCH_LEVEL));
+        assert_eq!(state.match_3d_3d("tmp", "b"), Ok(0));
+        assert_eq!(state.match_3d_3d("tmp", "c"), Err(NO_ENTRY_ERROR_MESSAGE));
+
+        assert!(state.db_delete("a", "humanode"));
+        assert!(!state.db_delete("a", "humanode"));
+        assert_eq!(state.db_search("tmp", "humanode", 10), Ok(vec![]));
+    }
+
+    #[test]
+    fn rejects_spoofs_and_reused_ids() {
+        let mut state = State::default();
+
+        assert!(!state.enrollment_3d("a", "spoof:alice").unwrap());
+        assert_eq!(
+            state.db_enroll("a", "humanode"),
+            Err(NO_ENTRY_ERROR_MESSAGE)
+        );
+
+        assert!(state.enrollment_3d("a", "alice").unwrap());
+        assert_eq!(
+            state.enrollment_3d("a", "alice"),
+            Err(EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE)
+        );
+    }
+
+    #[test]
+    fn resets_small_databases_only() {
+        let mut state = State::default();
+        for i in 0..RESET_MAX_ENTRIES {
+            let id = i.to_string();
+            state.enrollment_3d(&id, &id).unwrap();
+            state.db_enroll(&id, "humanode").unwrap();
+        }
+
+        assert!(!state.reset_if_small());
+        assert_eq!(state.group("humanode").len(), RESET_MAX_ENTRIES);
+
+        assert!(state.db_delete("0", "humanode"));
+        assert!(state.reset_if_small());
+        assert!(state.group("humanode").is_empty());
+    }
+}
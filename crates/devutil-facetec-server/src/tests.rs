@@ -0,0 +1,237 @@
+use facetec_api_client as ft;
+
+use super::*;
+use crate::state::{MATCH_LEVEL, NO_ENTRY_ERROR_MESSAGE, NO_GROUP_ERROR_MESSAGE};
+
+/// The group to use in tests.
+const GROUP_NAME: &str = "humanode";
+
+/// Start the simulator and make the client for it.
+fn start() -> (
+    SharedState,
+    ft::Client<ft::response_body_error::NoopInspector>,
+) {
+    let state = SharedState::default();
+    let (addr, server) =
+        warp::serve(routes(Arc::clone(&state))).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    let client = ft::Client {
+        reqwest: reqwest::Client::new(),
+        base_url: format!("http://{addr}"),
+        device_key_identifier: "device key identifier".to_owned(),
+        injected_ip_address: None,
+        response_body_error_inspector: ft::response_body_error::NoopInspector,
+        policy: Default::default(),
+    };
+    (state, client)
+}
+
+/// Conduct the `/enrollment-3d` with the given FaceScan.
+async fn enrollment_3d(
+    client: &ft::Client<ft::response_body_error::NoopInspector>,
+    external_database_ref_id: &str,
+    face_scan: &str,
+) -> Result<ft::enrollment3d::Response, ft::Error> {
+    client
+        .enrollment_3d(ft::enrollment3d::Request {
+            external_database_ref_id,
+            face_scan,
+            audit_trail_image: "audit trail image",
+            low_quality_audit_trail_image: "low quality audit trail image",
+        })
+        .await
+}
+
+/// Search the test group with the given FaceScan.
+async fn db_search(
+    client: &ft::Client<ft::response_body_error::NoopInspector>,
+    external_database_ref_id: &str,
+) -> Result<ft::db_search::Response, ft::Error> {
+    client
+        .db_search(ft::db_search::Request {
+            external_database_ref_id,
+            group_name: GROUP_NAME,
+            min_match_level: 10,
+        })
+        .await
+}
+
+/// Enroll the FaceScan into the test group.
+async fn db_enroll(
+    client: &ft::Client<ft::response_body_error::NoopInspector>,
+    external_database_ref_id: &str,
+) -> Result<ft::db_enroll::Response, ft::Error> {
+    client
+        .db_enroll(ft::db_enroll::Request {
+            external_database_ref_id,
+            group_name: GROUP_NAME,
+        })
+        .await
+}
+
+/// Assert the result is the server error with the given message.
+fn assert_server_error<T: std::fmt::Debug>(res: Result<T, ft::Error>, expected_message: &str) {
+    match res {
+        Err(ft::Error::Server(ft::ServerError { error_message })) => {
+            assert_eq!(error_message, expected_message)
+        }
+        res => panic!("unexpected result: {res:?}"),
+    }
+}
+
+#[tokio::test]
+async fn session_tokens_are_unique() {
+    let (_state, client) = start();
+
+    let first = client.session_token().await.unwrap();
+    let second = client.session_token().await.unwrap();
+
+    assert!(first.success);
+    assert_ne!(first.session_token, second.session_token);
+}
+
+#[tokio::test]
+async fn enroll_and_search() {
+    let (state, client) = start();
+
+    // Enroll the person.
+    let res = enrollment_3d(&client, "alice-key", "alice#1")
+        .await
+        .unwrap();
+    assert!(res.success);
+    assert_eq!(res.scan_result_blob, SCAN_RESULT_BLOB);
+    assert_server_error(
+        db_search(&client, "alice-key").await,
+        NO_GROUP_ERROR_MESSAGE,
+    );
+    assert!(db_enroll(&client, "alice-key").await.unwrap().success);
+
+    // The same person is found under another key.
+    let res = enrollment_3d(&client, "other-key", "alice#2")
+        .await
+        .unwrap();
+    assert!(res.success);
+    let res = db_search(&client, "other-key").await.unwrap();
+    assert_eq!(
+        res.results,
+        vec![ft::db_search::ResponseResult {
+            identifier: "alice-key".to_owned(),
+            match_level: MATCH_LEVEL,
+        }]
+    );
+
+    // The other person is not.
+    enrollment_3d(&client, "bob-key", "bob").await.unwrap();
+    assert!(db_search(&client, "bob-key")
+        .await
+        .unwrap()
+        .results
+        .is_empty());
+
+    // The keys can't be reused.
+    assert_server_error(
+        enrollment_3d(&client, "alice-key", "alice#3").await,
+        state::EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE,
+    );
+
+    assert_eq!(lock(&state).group(GROUP_NAME), vec!["alice-key".to_owned()]);
+}
+
+#[tokio::test]
+async fn inspect_the_db() {
+    let (_state, client) = start();
+    let group = ft::db_list::Request {
+        group_name: GROUP_NAME,
+    };
+
+    assert_server_error(client.db_list(group).await, NO_GROUP_ERROR_MESSAGE);
+
+    enrollment_3d(&client, "alice-key", "alice#1")
+        .await
+        .unwrap();
+    db_enroll(&client, "alice-key").await.unwrap();
+    enrollment_3d(&client, "tmp", "alice#2").await.unwrap();
+    enrollment_3d(&client, "bob-key", "bob").await.unwrap();
+
+    let res = client
+        .db_list(ft::db_list::Request {
+            group_name: GROUP_NAME,
+        })
+        .await
+        .unwrap();
+    assert_eq!(res.identifiers, vec!["alice-key".to_owned()]);
+
+    let res = client
+        .db_count(ft::db_count::Request {
+            group_name: GROUP_NAME,
+        })
+        .await
+        .unwrap();
+    assert_eq!(res.count, 1);
+
+    let match_3d_3d = |external_database_ref_id_to_match| {
+        client.match_3d_3d(ft::match_3d_3d::Request {
+            external_database_ref_id: "tmp",
+            external_database_ref_id_to_match,
+        })
+    };
+    assert_eq!(
+        match_3d_3d("alice-key").await.unwrap().match_level,
+        MATCH_LEVEL
+    );
+    assert_eq!(match_3d_3d("bob-key").await.unwrap().match_level, 0);
+    assert_server_error(match_3d_3d("carol-key").await, NO_ENTRY_ERROR_MESSAGE);
+}
+
+#[tokio::test]
+async fn spoofs_are_rejected() {
+    let (_state, client) = start();
+
+    let res = enrollment_3d(&client, "alice-key", "spoof:alice")
+        .await
+        .unwrap();
+    assert!(!res.success);
+    assert!(!res
+        .face_scan
+        .face_scan_security_checks
+        .all_checks_succeeded());
+
+    assert_server_error(
+        db_enroll(&client, "alice-key").await,
+        NO_ENTRY_ERROR_MESSAGE,
+    );
+}
+
+#[tokio::test]
+async fn delete_and_reset() {
+    let (state, client) = start();
+
+    enrollment_3d(&client, "alice-key", "alice").await.unwrap();
+    db_enroll(&client, "alice-key").await.unwrap();
+    enrollment_3d(&client, "tmp", "alice").await.unwrap();
+
+    let res = client
+        .db_delete(ft::db_delete::Request {
+            identifier: "alice-key",
+            group_name: GROUP_NAME,
+        })
+        .await
+        .unwrap();
+    assert!(res.success);
+    assert!(db_search(&client, "tmp").await.unwrap().results.is_empty());
+
+    let res = client.reset().await.unwrap();
+    assert!(res.did_delete_database);
+    assert_server_error(db_search(&client, "tmp").await, NO_ENTRY_ERROR_MESSAGE);
+
+    enrollment_3d(&client, "alice-key", "alice").await.unwrap();
+    db_enroll(&client, "alice-key").await.unwrap();
+    let res = reqwest::Client::new()
+        .post(format!("{}/reset", client.base_url))
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+    assert!(lock(&state).group(GROUP_NAME).is_empty());
+}
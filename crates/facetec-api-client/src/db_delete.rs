@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::Client;
+use super::{Client, Idempotency};
 
 impl<RBEI> Client<RBEI>
 where
@@ -10,8 +10,10 @@ where
 {
     /// Perform the `/3d-db/delete` call to the server.
     pub async fn db_delete(&self, req: Request<'_>) -> Result<Response, crate::Error> {
-        let res = self.build_post("/3d-db/delete", &req).send().await?;
-        self.parse_response(res).await
+        self.call(Idempotency::NonIdempotent, || {
+            self.build_post("/3d-db/delete", &req)
+        })
+        .await
     }
 }
 
@@ -0,0 +1,190 @@
+//! POST `/3d-db/list`
+
+use serde::{Deserialize, Serialize};
+
+use super::{Client, Idempotency};
+
+impl<RBEI> Client<RBEI>
+where
+    RBEI: crate::response_body_error::Inspector,
+{
+    /// Perform the `/3d-db/list` call to the server.
+    pub async fn db_list(&self, req: Request<'_>) -> Result<Response, crate::Error> {
+        self.call(Idempotency::Idempotent, || {
+            self.build_post("/3d-db/list", &req)
+        })
+        .await
+    }
+}
+
+/// Input data for the `/3d-db/list` request.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Request<'a> {
+    /// The name of the group to inspect.
+    pub group_name: &'a str,
+}
+
+/// The response from `/3d-db/list`.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// Whether the request was successful.
+    pub success: bool,
+    /// The external database IDs of the entries enrolled at the group.
+    pub identifiers: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::{tests::test_client, ResponseBodyError, ServerError};
+
+    #[test]
+    fn request_serialization() {
+        let expected_request = serde_json::json!({
+            "groupName": "humanode"
+        });
+
+        let actual_request = serde_json::to_value(&Request {
+            group_name: "humanode",
+        })
+        .unwrap();
+
+        assert_eq!(expected_request, actual_request);
+    }
+
+    #[test]
+    fn response_deserialization() {
+        let sample_response = serde_json::json!({
+            "additionalSessionData": {
+                "isAdditionalDataPartiallyIncomplete": true
+            },
+            "callData": {
+                "tid": "0haAzpKGLfc4fa345-ee26-11eb-86b0-0232fd4aba88",
+                "path": "/3d-db/list",
+                "date": "Jul 26, 2021 15:34:37 PM",
+                "epochSecond": 1627313677,
+                "requestMethod": "POST"
+            },
+            "error": false,
+            "serverInfo": {
+                "version": "9.3.1-dev-2021070201",
+                "mode": "Development Only",
+                "notice": "You should only be reading this if you are in server-side code.  Please make sure you do not allow the FaceTec Server to be called from the public internet."
+            },
+            "identifiers": ["first", "second"],
+            "success": true
+        });
+
+        let response: Response = serde_json::from_value(sample_response).unwrap();
+        assert_eq!(
+            response,
+            Response {
+                success: true,
+                identifiers: vec!["first".to_owned(), "second".to_owned()],
+            }
+        )
+    }
+
+    #[tokio::test]
+    async fn mock_success() {
+        let mock_server = MockServer::start().await;
+
+        let sample_request = Request {
+            group_name: "humanode",
+        };
+        let sample_response = serde_json::json!({
+            "additionalSessionData": {
+                "isAdditionalDataPartiallyIncomplete": true
+            },
+            "callData": {
+                "tid": "0haAzpKGLfc4fa345-ee26-11eb-86b0-0232fd4aba88",
+                "path": "/3d-db/list",
+                "date": "Jul 26, 2021 15:34:37 PM",
+                "epochSecond": 1627313677,
+                "requestMethod": "POST"
+            },
+            "error": false,
+            "serverInfo": {
+                "version": "9.3.1-dev-2021070201",
+                "mode": "Development Only",
+                "notice": "You should only be reading this if you are in server-side code.  Please make sure you do not allow the FaceTec Server to be called from the public internet."
+            },
+            "identifiers": ["first", "second"],
+            "success": true
+        });
+
+        let expected_response: Response = serde_json::from_value(sample_response.clone()).unwrap();
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/3d-db/list"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(mock_server.uri());
+
+        let actual_response = client.db_list(sample_request).await.unwrap();
+        assert_eq!(actual_response, expected_response);
+    }
+
+    #[tokio::test]
+    async fn mock_error_unknown() {
+        let mock_server = MockServer::start().await;
+
+        let sample_request = Request {
+            group_name: "humanode",
+        };
+        let sample_response = "Some error text";
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/3d-db/list"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(500).set_body_string(sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(mock_server.uri());
+
+        let actual_error = client.db_list(sample_request).await.unwrap_err();
+        assert_matches!(
+            actual_error,
+            crate::Error::ResponseBody(ResponseBodyError::Json{body, ..}) if body == sample_response
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_error_bad_request() {
+        let mock_server = MockServer::start().await;
+
+        let sample_request = Request {
+            group_name: "humanode",
+        };
+        let sample_response = serde_json::json!({
+            "error": true,
+            "errorMessage": "Tried to search a groupName when that groupName does not exist.",
+            "success": false
+        });
+
+        let expected_error = "Tried to search a groupName when that groupName does not exist.";
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/3d-db/list"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(400).set_body_json(&sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(mock_server.uri());
+
+        let actual_error = client.db_list(sample_request).await.unwrap_err();
+        assert_matches!(
+            actual_error,
+            crate::Error::Server(ServerError {error_message}) if error_message == expected_error
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::Client;
+use super::{Client, Idempotency};
 use crate::MatchLevel;
 
 impl<RBEI> Client<RBEI>
@@ -11,8 +11,10 @@ where
 {
     /// Perform the `/3d-db/search` call to the server.
     pub async fn db_search(&self, req: Request<'_>) -> Result<Response, crate::Error> {
-        let res = self.build_post("/3d-db/search", &req).send().await?;
-        self.parse_response(res).await
+        self.call(Idempotency::Idempotent, || {
+            self.build_post("/3d-db/search", &req)
+        })
+        .await
     }
 }
 
@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::Client;
+use super::{Client, Idempotency};
 use crate::OpaqueBase64DataRef;
 
 impl<RBEI> Client<RBEI>
@@ -11,8 +11,10 @@ where
 {
     /// Perform the `/enrollment-3d` call to the server.
     pub async fn enrollment_3d(&self, req: Request<'_>) -> Result<Response, crate::Error> {
-        let res = self.build_post("/enrollment-3d", &req).send().await?;
-        self.parse_response(res).await
+        self.call(Idempotency::NonIdempotent, || {
+            self.build_post("/enrollment-3d", &req)
+        })
+        .await
     }
 }
 
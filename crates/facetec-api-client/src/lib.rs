@@ -9,11 +9,15 @@ use reqwest::{RequestBuilder, Response};
 use serde::{de::DeserializeOwned, Deserialize};
 use thiserror::Error;
 
+pub mod db_count;
 pub mod db_delete;
 pub mod db_enroll;
+pub mod db_list;
 pub mod db_search;
 pub mod enrollment3d;
 pub mod facetec_response;
+pub mod match_3d_3d;
+pub mod policy;
 pub mod reset;
 pub mod response_body_error;
 pub mod session_token;
@@ -23,6 +27,7 @@ mod types;
 #[cfg(test)]
 mod tests;
 
+pub use policy::Policy;
 pub use response_body_error::ResponseBodyError;
 pub use types::*;
 
@@ -38,6 +43,35 @@ pub enum Error {
     /// An error coming from the underlying reqwest layer.
     #[error("reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
+    /// The call did not complete within the timeout set by the [`Policy`].
+    #[error("timeout: {0}")]
+    Timeout(#[source] reqwest::Error),
+    /// The call was not made since the circuit breaker is open, i.e. the FaceTec Server
+    /// appears to be down.
+    #[error("circuit breaker is open")]
+    CircuitOpen,
+}
+
+impl Error {
+    /// Convert the reqwest error, telling the timeouts apart.
+    fn from_reqwest(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            Self::Timeout(err)
+        } else {
+            Self::Reqwest(err)
+        }
+    }
+
+    /// Whether the error is a transport-level failure, i.e. the call might have not reached
+    /// the server or the response might have not reached us.
+    pub fn is_transport(&self) -> bool {
+        matches!(
+            self,
+            Self::Reqwest(_)
+                | Self::Timeout(_)
+                | Self::ResponseBody(ResponseBodyError::BodyRead(_))
+        )
+    }
 }
 
 /// An error response originating from the FaceTec Server itself.
@@ -62,6 +96,17 @@ pub struct Client<RBEI> {
     pub injected_ip_address: Option<String>,
     /// The inspector for the response body.
     pub response_body_error_inspector: RBEI,
+    /// The policy of conducting the calls.
+    pub policy: Policy,
+}
+
+/// Whether the call can be safely repeated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Idempotency {
+    /// Repeating the call has no extra effect, so it can be retried.
+    Idempotent,
+    /// Repeating the call might have an extra effect, so it must be made once.
+    NonIdempotent,
 }
 
 impl<RBEI> Client<RBEI> {
@@ -141,4 +186,68 @@ where
         let body: FacetecResponse<T> = self.parse_json(res).await?;
         Ok(body.into_inner()?)
     }
+
+    /// Make a single call attempt, respecting the timeout and the circuit breaker.
+    async fn attempt<T>(&self, req: RequestBuilder) -> Result<T, crate::Error>
+    where
+        T: for<'de> Deserialize<'de> + std::fmt::Debug,
+    {
+        let circuit_breaker = self.policy.circuit_breaker.as_ref();
+        if let Some(circuit_breaker) = circuit_breaker {
+            if !circuit_breaker.allow() {
+                return Err(Error::CircuitOpen);
+            }
+        }
+
+        let req = match self.policy.timeout {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
+        };
+        let result = match req.send().await {
+            Ok(res) => self.parse_response(res).await.map_err(|err| match err {
+                Error::ResponseBody(ResponseBodyError::BodyRead(err)) if err.is_timeout() => {
+                    Error::Timeout(err)
+                }
+                err => err,
+            }),
+            Err(err) => Err(Error::from_reqwest(err)),
+        };
+
+        if let Some(circuit_breaker) = circuit_breaker {
+            match &result {
+                Err(err) if err.is_transport() => circuit_breaker.record_failure(),
+                _ => circuit_breaker.record_success(),
+            }
+        }
+
+        result
+    }
+
+    /// Make the call according to the [`Policy`], retrying the transport-level failures of
+    /// the idempotent calls.
+    async fn call<T, F>(
+        &self,
+        idempotency: Idempotency,
+        build_request: F,
+    ) -> Result<T, crate::Error>
+    where
+        T: for<'de> Deserialize<'de> + std::fmt::Debug,
+        F: Fn() -> RequestBuilder,
+    {
+        let max_retries = match idempotency {
+            Idempotency::Idempotent => self.policy.retry.max_retries,
+            Idempotency::NonIdempotent => 0,
+        };
+
+        let mut retry = 0;
+        loop {
+            match self.attempt(build_request()).await {
+                Err(err) if err.is_transport() && retry < max_retries => {
+                    tokio::time::sleep(self.policy.retry.backoff(retry)).await;
+                    retry = retry.saturating_add(1);
+                }
+                result => return result,
+            }
+        }
+    }
 }
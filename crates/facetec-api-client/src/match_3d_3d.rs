@@ -0,0 +1,200 @@
+//! POST `/match-3d-3d`
+
+use serde::{Deserialize, Serialize};
+
+use super::{Client, Idempotency};
+use crate::MatchLevel;
+
+impl<RBEI> Client<RBEI>
+where
+    RBEI: crate::response_body_error::Inspector,
+{
+    /// Perform the `/match-3d-3d` call to the server.
+    pub async fn match_3d_3d(&self, req: Request<'_>) -> Result<Response, crate::Error> {
+        self.call(Idempotency::Idempotent, || {
+            self.build_post("/match-3d-3d", &req)
+        })
+        .await
+    }
+}
+
+/// Input data for the `/match-3d-3d` request.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Request<'a> {
+    /// The ID of the pre-enrolled FaceMap to match.
+    #[serde(rename = "externalDatabaseRefID")]
+    pub external_database_ref_id: &'a str,
+    /// The ID of the pre-enrolled FaceMap to match against.
+    #[serde(rename = "externalDatabaseRefIDToMatch")]
+    pub external_database_ref_id_to_match: &'a str,
+}
+
+/// The response from `/match-3d-3d`.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// Whether the request was successful.
+    pub success: bool,
+    /// The level of matching between the two FaceMaps.
+    pub match_level: MatchLevel,
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::{tests::test_client, ResponseBodyError, ServerError};
+
+    #[test]
+    fn request_serialization() {
+        let expected_request = serde_json::json!({
+            "externalDatabaseRefID": "my_test_id",
+            "externalDatabaseRefIDToMatch": "my_other_test_id"
+        });
+
+        let actual_request = serde_json::to_value(&Request {
+            external_database_ref_id: "my_test_id",
+            external_database_ref_id_to_match: "my_other_test_id",
+        })
+        .unwrap();
+
+        assert_eq!(expected_request, actual_request);
+    }
+
+    #[test]
+    fn response_deserialization() {
+        let sample_response = serde_json::json!({
+            "additionalSessionData": {
+                "isAdditionalDataPartiallyIncomplete": true
+            },
+            "callData": {
+                "tid": "0haAzpKGLfc4fa345-ee26-11eb-86b0-0232fd4aba88",
+                "path": "/match-3d-3d",
+                "date": "Jul 26, 2021 15:34:37 PM",
+                "epochSecond": 1627313677,
+                "requestMethod": "POST"
+            },
+            "error": false,
+            "serverInfo": {
+                "version": "9.3.1-dev-2021070201",
+                "mode": "Development Only",
+                "notice": "You should only be reading this if you are in server-side code.  Please make sure you do not allow the FaceTec Server to be called from the public internet."
+            },
+            "matchLevel": 15,
+            "success": true
+        });
+
+        let response: Response = serde_json::from_value(sample_response).unwrap();
+        assert_eq!(
+            response,
+            Response {
+                success: true,
+                match_level: 15,
+            }
+        )
+    }
+
+    #[tokio::test]
+    async fn mock_success() {
+        let mock_server = MockServer::start().await;
+
+        let sample_request = Request {
+            external_database_ref_id: "my_test_id",
+            external_database_ref_id_to_match: "my_other_test_id",
+        };
+        let sample_response = serde_json::json!({
+            "additionalSessionData": {
+                "isAdditionalDataPartiallyIncomplete": true
+            },
+            "callData": {
+                "tid": "0haAzpKGLfc4fa345-ee26-11eb-86b0-0232fd4aba88",
+                "path": "/match-3d-3d",
+                "date": "Jul 26, 2021 15:34:37 PM",
+                "epochSecond": 1627313677,
+                "requestMethod": "POST"
+            },
+            "error": false,
+            "serverInfo": {
+                "version": "9.3.1-dev-2021070201",
+                "mode": "Development Only",
+                "notice": "You should only be reading this if you are in server-side code.  Please make sure you do not allow the FaceTec Server to be called from the public internet."
+            },
+            "matchLevel": 15,
+            "success": true
+        });
+
+        let expected_response: Response = serde_json::from_value(sample_response.clone()).unwrap();
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/match-3d-3d"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(mock_server.uri());
+
+        let actual_response = client.match_3d_3d(sample_request).await.unwrap();
+        assert_eq!(actual_response, expected_response);
+    }
+
+    #[tokio::test]
+    async fn mock_error_unknown() {
+        let mock_server = MockServer::start().await;
+
+        let sample_request = Request {
+            external_database_ref_id: "my_test_id",
+            external_database_ref_id_to_match: "my_other_test_id",
+        };
+        let sample_response = "Some error text";
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/match-3d-3d"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(500).set_body_string(sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(mock_server.uri());
+
+        let actual_error = client.match_3d_3d(sample_request).await.unwrap_err();
+        assert_matches!(
+            actual_error,
+            crate::Error::ResponseBody(ResponseBodyError::Json{body, ..}) if body == sample_response
+        );
+    }
+
+    #[tokio::test]
+    async fn mock_error_bad_request() {
+        let mock_server = MockServer::start().await;
+
+        let sample_request = Request {
+            external_database_ref_id: "my_test_id",
+            external_database_ref_id_to_match: "my_other_test_id",
+        };
+        let sample_response = serde_json::json!({
+            "error": true,
+            "errorMessage": "No entry found in the database for this externalDatabaseRefID.",
+            "success": false
+        });
+
+        let expected_error = "No entry found in the database for this externalDatabaseRefID.";
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/match-3d-3d"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(400).set_body_json(&sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = test_client(mock_server.uri());
+
+        let actual_error = client.match_3d_3d(sample_request).await.unwrap_err();
+        assert_matches!(
+            actual_error,
+            crate::Error::Server(ServerError {error_message}) if error_message == expected_error
+        );
+    }
+}
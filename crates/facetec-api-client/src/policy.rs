@@ -0,0 +1,183 @@
+//! The policy of conducting the calls: timeouts, retries and circuit breaking.
+
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// The policy to conduct the FaceTec Server calls with.
+///
+/// The default policy makes every call exactly once, without a timeout and without
+/// circuit breaking.
+#[derive(Debug, Default)]
+pub struct Policy {
+    /// The timeout of a single call attempt; no timeout if not set.
+    pub timeout: Option<Duration>,
+    /// The retry policy for the idempotent calls.
+    pub retry: RetryPolicy,
+    /// The circuit breaker; no circuit breaking if not set.
+    pub circuit_breaker: Option<CircuitBreaker>,
+}
+
+/// The policy of retrying the idempotent calls that have failed at the transport level.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry; doubled with every next retry.
+    pub initial_backoff: Duration,
+    /// The upper bound of the delay between the retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// The delay before the retry with the given zero-based index.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// The circuit breaker parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// The number of consecutive failures that opens the circuit.
+    pub failure_threshold: u32,
+    /// For how long the circuit stays open before a trial call is let through.
+    pub open_for: Duration,
+}
+
+/// The circuit breaker that fails the calls fast while the FaceTec Server appears to be down.
+///
+/// The circuit opens after [`CircuitBreakerConfig::failure_threshold`] consecutive
+/// transport-level failures. While open, the calls are rejected without reaching the server.
+/// Once [`CircuitBreakerConfig::open_for`] has passed, a single trial call is let through:
+/// its success closes the circuit, and its failure keeps the circuit open for another period.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// The circuit breaker parameters.
+    config: CircuitBreakerConfig,
+    /// The current state of the circuit.
+    state: Mutex<CircuitState>,
+}
+
+/// The state of the circuit.
+#[derive(Debug, Default)]
+struct CircuitState {
+    /// The number of consecutive failures observed.
+    consecutive_failures: u32,
+    /// The moment the current open period has started at, if the circuit is open.
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Create a new closed circuit breaker.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CircuitState::default()),
+        }
+    }
+
+    /// Lock the state, ignoring the poisoning since the state is always left consistent.
+    fn lock(&self) -> std::sync::MutexGuard<'_, CircuitState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether the circuit is currently open.
+    pub fn is_open(&self) -> bool {
+        self.lock().opened_at.is_some()
+    }
+
+    /// Check whether a call is allowed to proceed.
+    ///
+    /// When the open period is over, the call is allowed as a trial, and the open period is
+    /// restarted to hold the other calls off until the trial call completes.
+    pub fn allow(&self) -> bool {
+        let mut state = self.lock();
+        let Some(opened_at) = state.opened_at else {
+            return true;
+        };
+        if opened_at.elapsed() < self.config.open_for {
+            return false;
+        }
+        state.opened_at = Some(Instant::now());
+        true
+    }
+
+    /// Record a call that has reached the server.
+    pub fn record_success(&self) {
+        *self.lock() = CircuitState::default();
+    }
+
+    /// Record a call that has failed at the transport level.
+    pub fn record_failure(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.opened_at.is_some() || state.consecutive_failures >= self.config.failure_threshold
+        {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_limit() {
+        let retry = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+        };
+
+        assert_eq!(retry.backoff(0), Duration::from_millis(100));
+        assert_eq!(retry.backoff(1), Duration::from_millis(200));
+        assert_eq!(retry.backoff(3), Duration::from_millis(800));
+        assert_eq!(retry.backoff(4), Duration::from_millis(1000));
+        assert_eq!(retry.backoff(u32::MAX), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn circuit_opens_after_consecutive_failures() {
+        let circuit_breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 2,
+            open_for: Duration::from_secs(3600),
+        });
+
+        circuit_breaker.record_failure();
+        circuit_breaker.record_success();
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.allow());
+        assert!(!circuit_breaker.is_open());
+
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.is_open());
+        assert!(!circuit_breaker.allow());
+    }
+
+    #[test]
+    fn circuit_lets_a_single_trial_call_through() {
+        let circuit_breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_for: Duration::ZERO,
+        });
+
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.is_open());
+
+        // The trial call fails, and the circuit stays open.
+        assert!(circuit_breaker.allow());
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.is_open());
+
+        // The trial call succeeds, and the circuit closes.
+        assert!(circuit_breaker.allow());
+        circuit_breaker.record_success();
+        assert!(!circuit_breaker.is_open());
+    }
+}
@@ -2,7 +2,7 @@
 
 use serde::Deserialize;
 
-use super::Client;
+use super::{Client, Idempotency};
 
 impl<RBEI> Client<RBEI>
 where
@@ -10,14 +10,13 @@ where
 {
     /// Perform the `/delete-database-if-less-than-10-records` call to the server.
     pub async fn reset(&self) -> Result<Response, crate::Error> {
-        let res = self
-            .build("/delete-database-if-less-than-10-records", |url| {
+        self.call(Idempotency::NonIdempotent, || {
+            self.build("/delete-database-if-less-than-10-records", |url| {
                 self.reqwest.delete(url)
             })
             .body(&b"1"[..])
-            .send()
-            .await?;
-        self.parse_response(res).await
+        })
+        .await
     }
 }
 
@@ -2,7 +2,7 @@
 
 use serde::Deserialize;
 
-use super::Client;
+use super::{Client, Idempotency};
 
 impl<RBEI> Client<RBEI>
 where
@@ -10,8 +10,8 @@ where
 {
     /// Perform the `/session-token` call to the server.
     pub async fn session_token(&self) -> Result<Response, crate::Error> {
-        let res = self.build_get("/session-token").send().await?;
-        self.parse_response(res).await
+        self.call(Idempotency::Idempotent, || self.build_get("/session-token"))
+            .await
     }
 }
 
@@ -8,5 +8,174 @@ pub fn test_client(base_url: String) -> Client<NoopInspector> {
         device_key_identifier: "my device key identifier".into(),
         injected_ip_address: None,
         response_body_error_inspector: crate::response_body_error::NoopInspector,
+        policy: Default::default(),
+    }
+}
+
+mod policy {
+    use std::time::Duration;
+
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    use super::test_client;
+    use crate::{
+        enrollment3d,
+        policy::{CircuitBreaker, CircuitBreakerConfig, RetryPolicy},
+        Error, Policy,
+    };
+
+    /// The timeout to use in tests.
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    /// The delay of the responses that exceeds the [`TIMEOUT`].
+    const SLOW_RESPONSE_DELAY: Duration = Duration::from_millis(1000);
+
+    /// A successful `/session-token` response.
+    fn session_token_response() -> serde_json::Value {
+        serde_json::json!({
+            "error": false,
+            "sessionToken": "the session token",
+            "success": true
+        })
+    }
+
+    /// A policy with the [`TIMEOUT`] and the given number of retries.
+    fn policy(max_retries: u32) -> Policy {
+        Policy {
+            timeout: Some(TIMEOUT),
+            retry: RetryPolicy {
+                max_retries,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(10),
+            },
+            circuit_breaker: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn idempotent_call_is_retried_after_timeout() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/session-token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(session_token_response())
+                    .set_delay(SLOW_RESPONSE_DELAY),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/session-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(session_token_response()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(mock_server.uri());
+        client.policy = policy(1);
+
+        let res = client.session_token().await.unwrap();
+        assert_eq!(res.session_token, "the session token");
+    }
+
+    #[tokio::test]
+    async fn retries_are_bounded() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/session-token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(session_token_response())
+                    .set_delay(SLOW_RESPONSE_DELAY),
+            )
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(mock_server.uri());
+        client.policy = policy(2);
+
+        let res = client.session_token().await;
+        assert_matches!(res, Err(Error::Timeout(_)));
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_call_is_not_retried() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/enrollment-3d"))
+            .respond_with(ResponseTemplate::new(500).set_delay(SLOW_RESPONSE_DELAY))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(mock_server.uri());
+        client.policy = policy(3);
+
+        let res = client
+            .enrollment_3d(enrollment3d::Request {
+                external_database_ref_id: "my_test_id",
+                face_scan: "123",
+                audit_trail_image: "456",
+                low_quality_audit_trail_image: "789",
+            })
+            .await;
+        assert_matches!(res, Err(Error::Timeout(_)));
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_not_retried() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/session-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": true,
+                "errorMessage": "the error message",
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(mock_server.uri());
+        client.policy = policy(3);
+
+        let res = client.session_token().await;
+        assert_matches!(res, Err(Error::Server(_)));
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker_fails_fast() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/session-token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(session_token_response())
+                    .set_delay(SLOW_RESPONSE_DELAY),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let mut client = test_client(mock_server.uri());
+        client.policy = Policy {
+            circuit_breaker: Some(CircuitBreaker::new(CircuitBreakerConfig {
+                failure_threshold: 2,
+                open_for: Duration::from_secs(3600),
+            })),
+            ..policy(0)
+        };
+
+        assert_matches!(client.session_token().await, Err(Error::Timeout(_)));
+        assert_matches!(client.session_token().await, Err(Error::Timeout(_)));
+        assert_matches!(client.session_token().await, Err(Error::CircuitOpen));
     }
 }
@@ -0,0 +1,466 @@
+//! The reminder about the upcoming expiration of the local validator bioauth authentication.
+
+use std::{sync::Arc, time::Duration};
+
+use bioauth_flow_api::{BioauthFlowApi, BioauthStateApi};
+use bioauth_keys::traits::KeyExtractor as KeyExtractorT;
+use futures::StreamExt;
+use humanode_runtime::{opaque::Block, DeauthenticationReason, UnixMilliseconds};
+use sc_client_api::BlockchainEvents;
+use sp_api::{ApiExt, Decode, Encode, ProvideRuntimeApi};
+use sp_consensus::SyncOracle;
+use sp_runtime::traits::Block as BlockT;
+use substrate_prometheus_endpoint::{register, Gauge, PrometheusError, Registry, I64};
+use tracing::*;
+
+use crate::{configuration, qrcode::WebApp};
+
+/// The stage of the local validator authentication, as tracked by the reminder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// There is no active authentication, and it has not been observed to expire.
+    Inactive,
+    /// The authentication is active, and the expiration is not near yet.
+    Active,
+    /// The authentication expires within the lead time.
+    Expiring,
+    /// The authentication expires within a quarter of the lead time.
+    ExpiringSoon,
+    /// The authentication has been observed to expire.
+    Expired,
+    /// The authentication has been observed to be removed before the expiration.
+    Deauthenticated,
+}
+
+impl Stage {
+    /// Detect the stage from the authentication expiration moment, if the authentication is
+    /// active, whether it has been deauthenticated at the observed block, and the current moment.
+    pub fn detect(
+        previous: Self,
+        expires_at: Option<UnixMilliseconds>,
+        deauthenticated: bool,
+        now: UnixMilliseconds,
+        lead_time: Duration,
+    ) -> Self {
+        let expires_at = match expires_at {
+            Some(expires_at) => expires_at,
+            None => {
+                return match previous {
+                    Self::Active | Self::Expiring | Self::ExpiringSoon if deauthenticated => {
+                        Self::Deauthenticated
+                    }
+                    Self::Active | Self::Expiring | Self::ExpiringSoon => Self::Expired,
+                    Self::Inactive | Self::Expired | Self::Deauthenticated => previous,
+                }
+            }
+        };
+
+        let lead_time: UnixMilliseconds = lead_time.as_millis().try_into().unwrap_or(u64::MAX);
+        let time_left = expires_at.saturating_sub(now);
+
+        if time_left <= lead_time / 4 {
+            Self::ExpiringSoon
+        } else if time_left <= lead_time {
+            Self::Expiring
+        } else {
+            Self::Active
+        }
+    }
+
+    /// The stage name, as passed to the hook command.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Inactive => "inactive",
+            Self::Active => "active",
+            Self::Expiring => "expiring",
+            Self::ExpiringSoon => "expiring-soon",
+            Self::Expired => "expired",
+            Self::Deauthenticated => "deauthenticated",
+        }
+    }
+
+    /// Whether the operator should be reminded to re-authenticate at this stage.
+    fn needs_reminder(&self) -> bool {
+        matches!(
+            self,
+            Self::Expiring | Self::ExpiringSoon | Self::Expired | Self::Deauthenticated
+        )
+    }
+}
+
+/// The reminder metrics.
+pub struct Metrics {
+    /// The number of seconds left until the authentication expires, zero if inactive.
+    seconds_until_expiry: Gauge<I64>,
+}
+
+impl Metrics {
+    /// Register.
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            seconds_until_expiry: register(
+                Gauge::new(
+                    "bioauth_seconds_until_expiry",
+                    "Number of seconds until the local validator bioauth authentication expires.",
+                )?,
+                registry,
+            )?,
+        })
+    }
+}
+
+/// The reminder about the upcoming expiration of the local validator bioauth authentication.
+pub struct Reminder {
+    /// The reminder configuration.
+    config: configuration::BioauthReminder,
+    /// The Web App QR Code to re-print, if available.
+    webapp_qrcode: Option<WebApp>,
+    /// The metrics, if enabled.
+    metrics: Option<Metrics>,
+}
+
+impl Reminder {
+    /// Create a new [`Reminder`].
+    pub fn new(
+        config: configuration::BioauthReminder,
+        webapp_qrcode: Option<WebApp>,
+        metrics: Option<Metrics>,
+    ) -> Self {
+        Self {
+            config,
+            webapp_qrcode,
+            metrics,
+        }
+    }
+
+    /// Track the authentication of the local validator at the new best blocks, and remind about
+    /// its expiration.
+    pub async fn run<Client, KeyExtractor, SyncOracleT>(
+        self,
+        client: Arc<Client>,
+        key_extractor: KeyExtractor,
+        sync_oracle: SyncOracleT,
+    ) where
+        Client: ProvideRuntimeApi<Block> + BlockchainEvents<Block>,
+        Client::Api: BioauthFlowApi<Block, KeyExtractor::PublicKeyType, UnixMilliseconds>,
+        Client::Api: BioauthStateApi<
+            Block,
+            KeyExtractor::PublicKeyType,
+            UnixMilliseconds,
+            DeauthenticationReason,
+        >,
+        KeyExtractor: KeyExtractorT,
+        KeyExtractor::PublicKeyType: Encode + Decode,
+        KeyExtractor::Error: std::fmt::Debug,
+        SyncOracleT: SyncOracle,
+    {
+        let mut stage = Stage::Inactive;
+        let mut import_notifications = client.import_notification_stream();
+
+        while let Some(notification) = import_notifications.next().await {
+            // The historical blocks are of no interest.
+            if !notification.is_new_best || sync_oracle.is_major_syncing() {
+                continue;
+            }
+
+            let Observation {
+                expires_at,
+                now,
+                deauthentication_reason,
+            } = match Self::observe(client.as_ref(), &key_extractor, notification.hash) {
+                Ok(Some(observation)) => observation,
+                Ok(None) => {
+                    // Do not keep reporting the stale value.
+                    if let Some(metrics) = &self.metrics {
+                        metrics.seconds_until_expiry.set(0);
+                    }
+                    continue;
+                }
+                Err(error) => {
+                    error!(
+                        message = "Bioauth reminder - unable to observe the authentication",
+                        ?error
+                    );
+                    continue;
+                }
+            };
+
+            let seconds_left = expires_at.map_or(0, |expires_at| {
+                Duration::from_millis(expires_at.saturating_sub(now)).as_secs()
+            });
+            if let Some(metrics) = &self.metrics {
+                metrics
+                    .seconds_until_expiry
+                    .set(seconds_left.try_into().unwrap_or(i64::MAX));
+            }
+
+            let new_stage = Stage::detect(
+                stage,
+                expires_at,
+                deauthentication_reason.is_some(),
+                now,
+                self.config.lead_time,
+            );
+            if new_stage != stage {
+                self.on_stage_change(
+                    stage,
+                    new_stage,
+                    expires_at,
+                    deauthentication_reason.as_ref(),
+                    seconds_left,
+                );
+                stage = new_stage;
+            }
+        }
+    }
+
+    /// Observe the authentication of the local validator at the given block.
+    ///
+    /// Returns `None` if there is nothing to observe.
+    fn observe<Client, KeyExtractor>(
+        client: &Client,
+        key_extractor: &KeyExtractor,
+        at: <Block as BlockT>::Hash,
+    ) -> Result<Option<Observation>, ObserveError<KeyExtractor::Error>>
+    where
+        Client: ProvideRuntimeApi<Block>,
+        Client::Api: BioauthFlowApi<Block, KeyExtractor::PublicKeyType, UnixMilliseconds>,
+        Client::Api: BioauthStateApi<
+            Block,
+            KeyExtractor::PublicKeyType,
+            UnixMilliseconds,
+            DeauthenticationReason,
+        >,
+        KeyExtractor: KeyExtractorT,
+        KeyExtractor::PublicKeyType: Encode + Decode,
+    {
+        let Some(own_key) = key_extractor
+            .extract_key()
+            .map_err(ObserveError::KeyExtraction)?
+        else {
+            return Ok(None);
+        };
+
+        let runtime_api = client.runtime_api();
+
+        // The runtimes without the bioauth state API do not report the current moment.
+        let has_state_api = runtime_api
+            .has_api::<dyn BioauthStateApi<
+                Block,
+                KeyExtractor::PublicKeyType,
+                UnixMilliseconds,
+                DeauthenticationReason,
+            >>(at)
+            .map_err(ObserveError::RuntimeApi)?;
+        if !has_state_api {
+            return Ok(None);
+        }
+
+        let expires_at = match runtime_api
+            .bioauth_status(at, &own_key)
+            .map_err(ObserveError::RuntimeApi)?
+        {
+            bioauth_flow_api::BioauthStatus::Inactive => None,
+            bioauth_flow_api::BioauthStatus::Active { expires_at, .. } => Some(expires_at),
+        };
+        let now = runtime_api
+            .current_moment(at)
+            .map_err(ObserveError::RuntimeApi)?;
+        let deauthentication_reason = match expires_at {
+            Some(_) => None,
+            None => runtime_api
+                .deauthentication_reason(at, &own_key)
+                .map_err(ObserveError::RuntimeApi)?,
+        };
+
+        Ok(Some(Observation {
+            expires_at,
+            now,
+            deauthentication_reason,
+        }))
+    }
+
+    /// Notify the operator about the stage change.
+    fn on_stage_change(
+        &self,
+        previous_stage: Stage,
+        stage: Stage,
+        expires_at: Option<UnixMilliseconds>,
+        deauthentication_reason: Option<&DeauthenticationReason>,
+        seconds_left: u64,
+    ) {
+        match stage {
+            Stage::Inactive => {}
+            Stage::Active
+                if matches!(
+                    previous_stage,
+                    Stage::Inactive | Stage::Expired | Stage::Deauthenticated
+                ) =>
+            {
+                info!(
+                    message = "Bioauth reminder - the authentication is active",
+                    ?expires_at,
+                    seconds_left
+                );
+            }
+            Stage::Active => {
+                info!(
+                    message = "Bioauth reminder - the authentication has been renewed",
+                    ?expires_at,
+                    seconds_left
+                );
+            }
+            Stage::Expiring => {
+                warn!(
+                    message = "Bioauth reminder - the authentication is about to expire",
+                    ?expires_at,
+                    seconds_left
+                );
+            }
+            Stage::ExpiringSoon => {
+                error!(
+                    message = "Bioauth reminder - the authentication expires very soon",
+                    ?expires_at,
+                    seconds_left
+                );
+            }
+            Stage::Expired => {
+                error!("Bioauth reminder - the authentication has expired");
+            }
+            Stage::Deauthenticated => {
+                error!(
+                    message = "Bioauth reminder - the authentication has been deauthenticated",
+                    ?deauthentication_reason
+                );
+            }
+        }
+
+        if !stage.needs_reminder() {
+            return;
+        }
+
+        // Printing the QR Code also prompts to proceed with the re-authentication.
+        match &self.webapp_qrcode {
+            Some(webapp_qrcode) => webapp_qrcode.print(),
+            None => warn!("Bioauth reminder - please re-authenticate to remain a validator"),
+        }
+
+        if let Some(hook) = &self.config.hook {
+            run_hook(hook, stage, expires_at, seconds_left);
+        }
+    }
+}
+
+/// The authentication of the local validator, as observed at a block.
+struct Observation {
+    /// The authentication expiration moment, if it is active.
+    expires_at: Option<UnixMilliseconds>,
+    /// The current moment.
+    now: UnixMilliseconds,
+    /// The reason of the deauthentication, if it has been deauthenticated at the block.
+    deauthentication_reason: Option<DeauthenticationReason>,
+}
+
+/// An error that can occur during the authentication observation.
+#[derive(Debug)]
+enum ObserveError<KeyExtractionError> {
+    /// The validator key extraction has failed.
+    KeyExtraction(KeyExtractionError),
+    /// A call into the runtime API has failed.
+    RuntimeApi(sp_api::ApiError),
+}
+
+/// Run the hook command in the background, passing the reminder details via the environment
+/// variables.
+fn run_hook(hook: &str, stage: Stage, expires_at: Option<UnixMilliseconds>, seconds_left: u64) {
+    let mut command = tokio::process::Command::new("sh");
+    command
+        .arg("-c")
+        .arg(hook)
+        .env("BIOAUTH_REMINDER_STAGE", stage.as_str())
+        .env("BIOAUTH_SECONDS_LEFT", seconds_left.to_string());
+    if let Some(expires_at) = expires_at {
+        command.env("BIOAUTH_EXPIRES_AT", expires_at.to_string());
+    }
+
+    tokio::spawn(async move {
+        match command.status().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                warn!(message = "Bioauth reminder - the hook command has failed", %status);
+            }
+            Err(error) => {
+                error!(message = "Bioauth reminder - unable to run the hook command", %error);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The lead time used in the tests.
+    const LEAD_TIME: Duration = Duration::from_secs(100);
+
+    #[test]
+    fn stage_detection() {
+        let cases = [
+            (Stage::Inactive, None, Stage::Inactive),
+            (Stage::Inactive, Some(1_000_000), Stage::Active),
+            (Stage::Inactive, Some(100_000), Stage::Expiring),
+            (Stage::Active, Some(100_001), Stage::Active),
+            (Stage::Active, Some(100_000), Stage::Expiring),
+            (Stage::Expiring, Some(25_001), Stage::Expiring),
+            (Stage::Expiring, Some(25_000), Stage::ExpiringSoon),
+            (Stage::ExpiringSoon, Some(0), Stage::ExpiringSoon),
+            (Stage::ExpiringSoon, None, Stage::Expired),
+            (Stage::Active, None, Stage::Expired),
+            (Stage::Expired, None, Stage::Expired),
+            (Stage::Expired, Some(1_000_000), Stage::Active),
+            (Stage::ExpiringSoon, Some(1_000_000), Stage::Active),
+        ];
+
+        for (previous, expires_at, expected) in cases {
+            assert_eq!(
+                Stage::detect(previous, expires_at, false, 0, LEAD_TIME),
+                expected,
+                "{previous:?} with {expires_at:?} ms left"
+            );
+        }
+    }
+
+    #[test]
+    fn deauthentication_detection() {
+        let cases = [
+            (Stage::Inactive, Stage::Inactive),
+            (Stage::Active, Stage::Deauthenticated),
+            (Stage::Expiring, Stage::Deauthenticated),
+            (Stage::ExpiringSoon, Stage::Deauthenticated),
+            (Stage::Expired, Stage::Expired),
+            (Stage::Deauthenticated, Stage::Deauthenticated),
+        ];
+
+        for (previous, expected) in cases {
+            assert_eq!(
+                Stage::detect(previous, None, true, 0, LEAD_TIME),
+                expected,
+                "{previous:?}"
+            );
+        }
+        assert_eq!(
+            Stage::detect(Stage::Deauthenticated, Some(1_000_000), false, 0, LEAD_TIME),
+            Stage::Active
+        );
+    }
+
+    #[test]
+    fn reminder_stages() {
+        assert!(!Stage::Inactive.needs_reminder());
+        assert!(!Stage::Active.needs_reminder());
+        assert!(Stage::Expiring.needs_reminder());
+        assert!(Stage::ExpiringSoon.needs_reminder());
+        assert!(Stage::Expired.needs_reminder());
+        assert!(Stage::Deauthenticated.needs_reminder());
+    }
+}
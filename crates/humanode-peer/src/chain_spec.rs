@@ -91,12 +91,28 @@ fn evm_genesis_account(init_balance: Balance) -> fp_evm::GenesisAccount {
     }
 }
 
+/// Provide the robonode public key for the local chains.
+///
+/// The public key of the in-process dev robonode, if set, takes precedence, so that the auth
+/// tickets it issues are accepted by the chain.
+fn local_robonode_public_key(
+    dev_robonode_public_key_override: Option<robonode_crypto::PublicKey>,
+) -> Result<robonode::PublicKey, String> {
+    match dev_robonode_public_key_override {
+        Some(val) => robonode::PublicKey::from_bytes(val.as_bytes())
+            .map_err(|err| format!("unable to parse robonode public key: {err:?}")),
+        None => dev_robonode_public_key(&DEFAULT_DEV_ROBONODE_PUBLIC_KEY),
+    }
+}
+
 /// A configuration for local testnet.
-pub fn local_testnet_config() -> Result<ChainSpec, String> {
+pub fn local_testnet_config(
+    dev_robonode_public_key_override: Option<robonode_crypto::PublicKey>,
+) -> Result<ChainSpec, String> {
     let wasm_binary =
         WASM_BINARY.ok_or_else(|| "Development wasm binary not available".to_string())?;
 
-    let robonode_public_key = dev_robonode_public_key(&DEFAULT_DEV_ROBONODE_PUBLIC_KEY)?;
+    let robonode_public_key = local_robonode_public_key(dev_robonode_public_key_override)?;
 
     Ok(ChainSpec::from_genesis(
         // Name
@@ -150,10 +166,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 }
 
 /// A configuration for dev.
-pub fn development_config() -> Result<ChainSpec, String> {
+pub fn development_config(
+    dev_robonode_public_key_override: Option<robonode_crypto::PublicKey>,
+) -> Result<ChainSpec, String> {
     let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
-    let robonode_public_key = dev_robonode_public_key(&DEFAULT_DEV_ROBONODE_PUBLIC_KEY)?;
+    let robonode_public_key = local_robonode_public_key(dev_robonode_public_key_override)?;
 
     Ok(ChainSpec::from_genesis(
         // Name
@@ -423,16 +441,31 @@ mod tests {
 
     #[test]
     fn local_testnet_config_works() {
-        let storage = assert_genesis_config(local_testnet_config());
+        let storage = assert_genesis_config(local_testnet_config(None));
         assert_balanced_currency_swap(storage);
     }
 
     #[test]
     fn development_config_works() {
-        let storage = assert_genesis_config(development_config());
+        let storage = assert_genesis_config(development_config(None));
         assert_balanced_currency_swap(storage);
     }
 
+    #[test]
+    fn development_config_trusts_dev_robonode() {
+        let dev_robonode_public_key =
+            robonode_crypto::SigningKey::from_bytes(&[7; 32]).verifying_key();
+        let storage = assert_genesis_config(development_config(Some(dev_robonode_public_key)));
+        Into::<sp_io::TestExternalities>::into(storage).execute_with(move || {
+            let robonode_public_keys = humanode_runtime::Bioauth::robonode_public_keys();
+            assert_eq!(robonode_public_keys.len(), 1);
+            assert_eq!(
+                robonode_public_keys[0].public_key,
+                robonode::PublicKey::from_bytes(dev_robonode_public_key.as_bytes()).unwrap()
+            );
+        });
+    }
+
     #[test]
     fn benchmark_config_works() {
         let storage = assert_genesis_config(benchmark_config());
@@ -1,12 +1,15 @@
 //! Machinery to populate the configuration from the CLI arguments.
 
+use std::time::Duration;
+
 use sc_chain_spec::get_extension;
+use sc_service::ChainType;
 
 use super::{params, BioauthFlowParams, RpcUrlSchemePreference};
 use crate::{
     chain_spec::Extensions,
     configuration::{self, Configuration},
-    rpc_url::RpcUrl,
+    rpc_url::{RpcUrl, TunnelProvider},
     time_warp::{current_timestamp, TimeWarp, DEFAULT_WARP_FACTOR},
 };
 
@@ -28,21 +31,45 @@ pub trait CliConfigurationExt: SubstrateCliConfigurationProvider {
             .cloned()
             .unwrap_or_default();
 
-        let bioauth_flow = self.bioauth_params().map(|params| {
-            let rpc_port = substrate.rpc_addr.map(|v| v.port());
-            let rpc_url = rpc_url_from_params(params, rpc_port);
-
-            configuration::BioauthFlow {
-                rpc_url_resolver: Default::default(),
-                robonode_url: params
-                    .robonode_url
-                    .clone()
-                    .or(extensions.robonode_url)
-                    .unwrap_or_else(|| "http://127.0.0.1:3033".into()),
-                webapp_url: params.webapp_url.clone().or(extensions.webapp_url),
-                rpc_url,
-            }
-        });
+        let bioauth_flow = self
+            .bioauth_params()
+            .map(|params| {
+                if params.dev_robonode_secret_key.is_some()
+                    && !matches!(
+                        substrate.chain_spec.chain_type(),
+                        ChainType::Development | ChainType::Local
+                    )
+                {
+                    return Err(sc_cli::Error::Input(
+                        "the dev robonode is only allowed at the development and local chains"
+                            .into(),
+                    ));
+                }
+
+                let rpc_port = substrate.rpc_addr.map(|v| v.port());
+                let rpc_url = rpc_url_from_params(params, rpc_port);
+
+                Ok(configuration::BioauthFlow {
+                    rpc_url_resolver: Default::default(),
+                    robonode_url: params
+                        .robonode_url
+                        .clone()
+                        .or(extensions.robonode_url)
+                        .unwrap_or_else(|| "http://127.0.0.1:3033".into()),
+                    cosigning_robonode_urls: params.cosigning_robonode_urls.clone(),
+                    webapp_url: params.webapp_url.clone().or(extensions.webapp_url),
+                    rpc_url,
+                    dev_robonode_secret_key: params.dev_robonode_secret_key,
+                })
+            })
+            .transpose()?;
+
+        let bioauth_reminder =
+            self.bioauth_reminder_params()
+                .map(|params| configuration::BioauthReminder {
+                    lead_time: Duration::from_secs(params.bioauth_reminder_lead_time),
+                    hook: params.bioauth_reminder_hook.clone(),
+                });
 
         let ethereum_rpc = self
             .ethereum_rpc_params()
@@ -83,6 +110,7 @@ pub trait CliConfigurationExt: SubstrateCliConfigurationProvider {
         Ok(Configuration {
             substrate,
             bioauth_flow,
+            bioauth_reminder,
             ethereum_rpc,
             frontier_backend,
             time_warp,
@@ -94,6 +122,11 @@ pub trait CliConfigurationExt: SubstrateCliConfigurationProvider {
         None
     }
 
+    /// Provide the bioauth reminder params, if available.
+    fn bioauth_reminder_params(&self) -> Option<&params::BioauthReminderParams> {
+        None
+    }
+
     /// Provide the Ethereum RPC params.
     fn ethereum_rpc_params(&self) -> Option<&params::EthereumRpcParams> {
         None
@@ -141,10 +174,28 @@ fn rpc_url_from_params(params: &BioauthFlowParams, rpc_port: Option<u16>) -> Rpc
             RpcUrlSchemePreference::NoPreference | RpcUrlSchemePreference::Ws => rpc_port,
             RpcUrlSchemePreference::Http => None,
         };
-        return RpcUrl::DetectFromNgrok {
+        return RpcUrl::DetectFromTunnel(TunnelProvider::Ngrok {
             tunnel_name: params.rpc_url_ngrok_detect_from.clone(),
             ws_rpc_endpoint_port,
-        };
+        });
+    }
+    if params.rpc_url_cloudflared_detect {
+        return RpcUrl::DetectFromTunnel(TunnelProvider::Cloudflared {
+            metrics_url: params.rpc_url_cloudflared_detect_from.clone(),
+            // If there's no preference - use WebSocket, cloudflared tunnels it along with HTTP.
+            ws: !matches!(
+                params.rpc_url_scheme_preference,
+                RpcUrlSchemePreference::Http
+            ),
+        });
+    }
+    if let Some(path) = &params.rpc_url_from_file {
+        return RpcUrl::DetectFromTunnel(TunnelProvider::File { path: path.clone() });
+    }
+    if let Some(command) = &params.rpc_url_from_command {
+        return RpcUrl::DetectFromTunnel(TunnelProvider::Command {
+            command: command.clone(),
+        });
     }
 
     match (&params.rpc_url_scheme_preference, rpc_port) {
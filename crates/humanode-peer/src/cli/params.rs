@@ -1,5 +1,7 @@
 //! Shared CLI parameters.
 
+use std::path::PathBuf;
+
 use crate::configuration::{EthTracingMode, FrontierBackendType};
 
 /// Possible RPC URL scheme preference options.
@@ -23,7 +25,7 @@ pub struct BioauthFlowParams {
 
     /// The URL to pass to the web app to connect to the node RPC.
     /// If not passed, a URL with WebSocket scheme and `localhost` path will be used.
-    #[arg(long, value_name = "RPC_URL", conflicts_with_all = &["rpc_url_scheme_preference", "rpc_url_ngrok_detect", "rpc_url_unset"])]
+    #[arg(long, value_name = "RPC_URL", conflicts_with_all = &["rpc_url_scheme_preference", "rpc_url_ngrok_detect", "rpc_url_cloudflared_detect", "rpc_url_from_file", "rpc_url_from_command", "rpc_url_unset"])]
     pub rpc_url: Option<String>,
 
     /// What RPC URL scheme to prefer.
@@ -40,17 +42,93 @@ pub struct BioauthFlowParams {
     #[arg(long, conflicts_with_all = &["rpc_url", "rpc_url_unset"])]
     pub rpc_url_ngrok_detect: bool,
 
+    /// Detect RPC URL from the cloudflared quick tunnel.
+    #[arg(long, conflicts_with_all = &["rpc_url", "rpc_url_ngrok_detect", "rpc_url_unset"])]
+    pub rpc_url_cloudflared_detect: bool,
+
+    /// Read RPC URL from the first line of a file, waiting for the file to appear.
+    /// Intended for the tunnels that write their public URL into a file.
+    #[arg(long, value_name = "PATH", conflicts_with_all = &["rpc_url", "rpc_url_ngrok_detect", "rpc_url_cloudflared_detect", "rpc_url_unset"])]
+    pub rpc_url_from_file: Option<PathBuf>,
+
+    /// Read RPC URL from the first line of the output of a shell command.
+    /// The command is expected to wait for the tunnel to start on its own.
+    #[arg(long, value_name = "COMMAND", conflicts_with_all = &["rpc_url", "rpc_url_ngrok_detect", "rpc_url_cloudflared_detect", "rpc_url_from_file", "rpc_url_unset"])]
+    pub rpc_url_from_command: Option<String>,
+
     /// Explicitly unset the RPC URL.
-    #[arg(long, conflicts_with_all = &["rpc_url", "rpc_url_scheme_preference", "rpc_url_ngrok_detect"])]
+    #[arg(long, conflicts_with_all = &["rpc_url", "rpc_url_scheme_preference", "rpc_url_ngrok_detect", "rpc_url_cloudflared_detect", "rpc_url_from_file", "rpc_url_from_command"])]
     pub rpc_url_unset: bool,
 
     /// The tunnel name at ngrok to detect RPC URL from, if ngrok is used to detect the RPC URL.
     #[arg(long, value_name = "TUNNEL_NAME", default_value = "command_line")]
     pub rpc_url_ngrok_detect_from: String,
 
+    /// The URL of the cloudflared metrics server to detect RPC URL from, if cloudflared is used
+    /// to detect the RPC URL.
+    #[arg(
+        long,
+        value_name = "METRICS_URL",
+        default_value = "http://127.0.0.1:20241"
+    )]
+    pub rpc_url_cloudflared_detect_from: String,
+
     /// The URL of robonode to authenticate with.
     #[arg(long, value_name = "ROBONODE_URL")]
     pub robonode_url: Option<String>,
+
+    /// The URL of a robonode to co-sign the auth tickets with, in addition to the robonode that
+    /// issues them; can be given multiple times to meet the robonode signatures threshold.
+    #[arg(
+        long = "cosigning-robonode-url",
+        value_name = "ROBONODE_URL",
+        conflicts_with = "dev_robonode_secret_key"
+    )]
+    pub cosigning_robonode_urls: Vec<String>,
+
+    /// The HEX-encoded secret key of the dev robonode to issue the auth tickets with in-process,
+    /// instead of using the robonode. No biometric checks are conducted.
+    /// Only allowed at the development and local chains, which are set up to trust this key.
+    #[arg(
+        long,
+        value_name = "SECRET_KEY",
+        value_parser = parse_dev_robonode_secret_key,
+        conflicts_with = "robonode_url"
+    )]
+    pub dev_robonode_secret_key: Option<robonode_crypto::SecretKey>,
+}
+
+impl BioauthFlowParams {
+    /// The public key of the dev robonode, if set.
+    pub fn dev_robonode_public_key(&self) -> Option<robonode_crypto::PublicKey> {
+        self.dev_robonode_secret_key
+            .map(|secret_key| robonode_crypto::SigningKey::from_bytes(&secret_key).verifying_key())
+    }
+}
+
+/// Parse the HEX-encoded dev robonode secret key.
+fn parse_dev_robonode_secret_key(val: &str) -> Result<robonode_crypto::SecretKey, String> {
+    let bytes =
+        hex::decode(val).map_err(|err| format!("secret key is not in hex format: {err}"))?;
+    bytes
+        .try_into()
+        .map_err(|_| "secret key must be 32 bytes long".to_owned())
+}
+
+/// Shared CLI parameters used to configure the bioauth authentication expiration reminder.
+#[derive(Debug, clap::Parser, Clone)]
+pub struct BioauthReminderParams {
+    /// How long before the bioauth authentication expiration to start reminding about
+    /// the re-authentication, in seconds.
+    #[arg(long, value_name = "SECONDS", default_value = "7200")]
+    pub bioauth_reminder_lead_time: u64,
+
+    /// The command to run when the bioauth authentication is about to expire, has expired or has
+    /// been deauthenticated.
+    /// Executed with `sh -c`, with the details passed via the `BIOAUTH_REMINDER_STAGE`,
+    /// `BIOAUTH_SECONDS_LEFT` and `BIOAUTH_EXPIRES_AT` environment variables.
+    #[arg(long, value_name = "COMMAND")]
+    pub bioauth_reminder_hook: Option<String>,
 }
 
 /// Shared CLI parameters used to configure Ethereum RPC.
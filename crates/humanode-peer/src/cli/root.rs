@@ -43,9 +43,10 @@ impl SubstrateCli for Root {
     }
 
     fn load_spec(&self, id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
+        let dev_robonode_public_key = self.run.bioauth_flow_params.dev_robonode_public_key();
         Ok(match id {
-            "dev" => Box::new(chain_spec::development_config()?),
-            "" | "local" => Box::new(chain_spec::local_testnet_config()?),
+            "dev" => Box::new(chain_spec::development_config(dev_robonode_public_key)?),
+            "" | "local" => Box::new(chain_spec::local_testnet_config(dev_robonode_public_key)?),
             "benchmark" => Box::new(chain_spec::benchmark_config()?),
             path => Box::new(chain_spec::ChainSpec::from_json_file(
                 std::path::PathBuf::from(path),
@@ -134,7 +134,45 @@ pub async fn run() -> sc_cli::Result<()> {
                 })
                 .await
         }
+        Some(Subcommand::Bioauth(bioauth::BioauthCmd::Key(bioauth::key::KeyCmd::Export(cmd)))) => {
+            let runner = root.create_humanode_runner(cmd)?;
+            runner
+                .run_tasks(|config| async move {
+                    let keystore_path = bioauth::key::keystore_path(&config)?;
+                    let (keystore_container, task_manager) = service::keystore_container(&config)?;
+                    Ok((cmd.run(keystore_container, keystore_path), task_manager))
+                })
+                .await
+        }
+        Some(Subcommand::Bioauth(bioauth::BioauthCmd::Key(bioauth::key::KeyCmd::Import(cmd)))) => {
+            let runner = root.create_humanode_runner(cmd)?;
+            runner
+                .run_tasks(|config| async move {
+                    let (keystore_container, task_manager) = service::keystore_container(&config)?;
+                    Ok((cmd.run(keystore_container), task_manager))
+                })
+                .await
+        }
+        Some(Subcommand::Bioauth(bioauth::BioauthCmd::Key(bioauth::key::KeyCmd::Remove(cmd)))) => {
+            let runner = root.create_humanode_runner(cmd)?;
+            runner
+                .run_tasks(|config| async move {
+                    let keystore_path = bioauth::key::keystore_path(&config)?;
+                    let PartialComponents {
+                        client,
+                        keystore_container,
+                        task_manager,
+                        ..
+                    } = service::new_partial(&config)?;
+                    Ok((
+                        cmd.run(client, keystore_container, keystore_path),
+                        task_manager,
+                    ))
+                })
+                .await
+        }
         Some(Subcommand::Bioauth(bioauth::BioauthCmd::ApiVersions(cmd))) => cmd.run().await,
+        Some(Subcommand::Bioauth(bioauth::BioauthCmd::Status(cmd))) => cmd.run().await,
         Some(Subcommand::Bioauth(bioauth::BioauthCmd::AuthUrl(cmd))) => {
             let runner = root.create_humanode_runner(cmd)?;
             runner
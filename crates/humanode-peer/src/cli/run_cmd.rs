@@ -15,6 +15,10 @@ pub struct RunCmd {
     #[command(flatten)]
     pub bioauth_flow_params: params::BioauthFlowParams,
 
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub bioauth_reminder_params: params::BioauthReminderParams,
+
     #[allow(missing_docs, clippy::missing_docs_in_private_items)]
     #[command(flatten)]
     pub ethereum_rpc_params: params::EthereumRpcParams,
@@ -37,6 +41,10 @@ impl CliConfigurationExt for RunCmd {
         Some(&self.bioauth_flow_params)
     }
 
+    fn bioauth_reminder_params(&self) -> Option<&params::BioauthReminderParams> {
+        Some(&self.bioauth_reminder_params)
+    }
+
     fn ethereum_rpc_params(&self) -> Option<&params::EthereumRpcParams> {
         Some(&self.ethereum_rpc_params)
     }
@@ -0,0 +1,109 @@
+//! Bioauth key export subcommand logic.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use sc_cli::{CliConfiguration, KeystoreParams, SharedParams};
+use sc_service::KeystoreContainer;
+use zeroize::Zeroizing;
+
+use super::{bioauth_key_file_path, keyfile, read_passphrase, KeystoreBioauthId};
+use crate::cli::{utils::application_error, CliConfigurationExt};
+
+/// The `bioauth key export` command.
+#[derive(Debug, clap::Parser)]
+pub struct ExportKeyCmd {
+    /// The path to write the encrypted JSON keyfile to; must not exist yet.
+    #[arg(long, short = 'o', value_name = "PATH")]
+    pub output: PathBuf,
+
+    /// The path to the file with the passphrase to encrypt the keyfile with.
+    /// If not passed, the passphrase is prompted for.
+    #[arg(long, value_name = "PATH")]
+    pub passphrase_file: Option<PathBuf>,
+
+    /// The name of the key to put into the keyfile.
+    #[arg(long, value_name = "NAME")]
+    pub name: Option<String>,
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub keystore_params: KeystoreParams,
+}
+
+impl ExportKeyCmd {
+    /// Run the export command.
+    pub async fn run(
+        &self,
+        keystore_container: KeystoreContainer,
+        keystore_path: PathBuf,
+    ) -> sc_cli::Result<()> {
+        let keystore = keystore_container.keystore();
+        let key =
+            crate::validator_key::AppCryptoPublic::<KeystoreBioauthId>::list(keystore.as_ref())
+                .await
+                .map_err(|err| sc_cli::Error::Service(sc_service::Error::Other(err.to_string())))?
+                .next()
+                .ok_or_else(|| application_error("no bioauth key at the keystore"))?;
+
+        let suri = read_suri(&bioauth_key_file_path(&keystore_path, key.as_ref()))?;
+
+        let passphrase = read_passphrase(self.passphrase_file.as_deref(), true)?;
+        if passphrase.is_empty() {
+            return Err(sc_cli::Error::Input(
+                "the passphrase must not be empty".into(),
+            ));
+        }
+
+        let when_created = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .and_then(|since_epoch| since_epoch.as_millis().try_into().ok());
+        let keyfile = keyfile::encrypt(
+            &suri,
+            &passphrase,
+            keyfile::Meta {
+                name: self.name.clone(),
+                when_created,
+            },
+        )
+        .map_err(|err| sc_cli::Error::Application(Box::new(err)))?;
+        let json = serde_json::to_vec_pretty(&keyfile)
+            .map_err(|err| sc_cli::Error::Application(Box::new(err)))?;
+
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&self.output)?
+            .write_all(&json)?;
+
+        println!("{key}");
+        Ok(())
+    }
+}
+
+/// Read the secret key URI from the keystore key file.
+fn read_suri(path: &Path) -> sc_cli::Result<Zeroizing<String>> {
+    let file = std::fs::File::open(path)?;
+    let suri =
+        serde_json::from_reader(file).map_err(|err| sc_cli::Error::Application(Box::new(err)))?;
+    Ok(Zeroizing::new(suri))
+}
+
+impl CliConfiguration for ExportKeyCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+impl CliConfigurationExt for ExportKeyCmd {}
@@ -0,0 +1,71 @@
+//! Bioauth key import subcommand logic.
+
+use std::{path::PathBuf, sync::Arc};
+
+use sc_cli::{CliConfiguration, KeystoreParams, SharedParams};
+use sc_service::KeystoreContainer;
+
+use super::{
+    insert::{ensure_bioauth_key_absent, insert_bioauth_key},
+    keyfile, read_passphrase, KeystoreBioauthId,
+};
+use crate::cli::CliConfigurationExt;
+
+/// The `bioauth key import` command.
+#[derive(Debug, clap::Parser)]
+pub struct ImportKeyCmd {
+    /// The path to the encrypted JSON keyfile to import the key from.
+    ///
+    /// Only the keyfiles exported with `bioauth key export` can be imported. The keyfiles
+    /// exported from polkadot.js carry the PKCS8 key pair only, which can not be loaded into
+    /// the keystore; insert such keys with their mnemonic via `bioauth key insert` instead.
+    #[arg(value_name = "PATH")]
+    pub keyfile: PathBuf,
+
+    /// The path to the file with the passphrase to decrypt the keyfile with.
+    /// If not passed, the passphrase is prompted for.
+    #[arg(long, value_name = "PATH")]
+    pub passphrase_file: Option<PathBuf>,
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub keystore_params: KeystoreParams,
+}
+
+impl ImportKeyCmd {
+    /// Run the import command.
+    pub async fn run(&self, keystore_container: KeystoreContainer) -> sc_cli::Result<()> {
+        let keystore = keystore_container.keystore();
+
+        ensure_bioauth_key_absent::<KeystoreBioauthId>(Arc::clone(&keystore))
+            .await
+            .map_err(|err| sc_cli::Error::Service(sc_service::Error::Other(err.to_string())))?;
+
+        let keyfile: keyfile::Keyfile = serde_json::from_slice(&std::fs::read(&self.keyfile)?)
+            .map_err(|err| sc_cli::Error::Input(err.to_string()))?;
+        let passphrase = read_passphrase(self.passphrase_file.as_deref(), false)?;
+        let suri = keyfile::decrypt(&keyfile, &passphrase)
+            .map_err(|err| sc_cli::Error::Input(err.to_string()))?;
+
+        insert_bioauth_key::<KeystoreBioauthId>(&suri, keystore).await?;
+
+        println!("{}", keyfile.address);
+        Ok(())
+    }
+}
+
+impl CliConfiguration for ImportKeyCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+impl CliConfigurationExt for ImportKeyCmd {}
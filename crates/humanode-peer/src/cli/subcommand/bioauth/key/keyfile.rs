@@ -0,0 +1,423 @@
+//! The encrypted JSON keyfile of the bioauth key.
+//!
+//! The keyfile follows the polkadot.js keyfile format (version 3): the key pair is stored as
+//! PKCS8, encrypted with the scrypt-derived key using xsalsa20-poly1305, so the exported key
+//! can be imported into the polkadot.js tooling.
+//!
+//! The keystore keeps the secret key URIs rather than the key pairs, and loads the keys back with
+//! [`Pair::from_string`], which only takes the 32 byte mini secret key (seed) as a raw `0x` secret.
+//! The polkadot.js format stores the 64 byte expanded secret key, and the mini secret key can not be
+//! recovered from it, so the `0x`-encoded expanded secret key is rejected as a secret key URI.
+//! So the keyfile additionally carries the secret key URI, encrypted in the same way, for the key
+//! to be imported back into the keystore; the keyfiles exported from polkadot.js are still
+//! decrypted and checked, but can not be imported.
+
+use base64::Engine as _;
+use crypto_secretbox::{
+    aead::{Aead, KeyInit},
+    Key, Nonce, XSalsa20Poly1305,
+};
+use serde::{Deserialize, Serialize};
+use sp_core::{crypto::Ss58Codec, sr25519, Pair};
+use zeroize::Zeroizing;
+
+/// The PKCS8 header that goes before the secret key.
+const PKCS8_HEADER: [u8; 16] = [48, 83, 2, 1, 1, 48, 5, 6, 3, 43, 101, 112, 4, 34, 4, 32];
+/// The PKCS8 divider that goes between the secret key and the public key.
+const PKCS8_DIVIDER: [u8; 5] = [161, 35, 3, 33, 0];
+/// The length of the sr25519 secret key, in the ed25519-expanded form.
+const SECRET_KEY_LENGTH: usize = 64;
+/// The length of the sr25519 public key.
+const PUBLIC_KEY_LENGTH: usize = 32;
+
+/// The length of the scrypt salt.
+const SALT_LENGTH: usize = 32;
+/// The length of the xsalsa20-poly1305 nonce.
+const NONCE_LENGTH: usize = 24;
+/// The length of the key derived with scrypt.
+const DERIVED_KEY_LENGTH: usize = 64;
+/// The length of the xsalsa20-poly1305 key, taken from the start of the derived key.
+const ENCRYPTION_KEY_LENGTH: usize = 32;
+
+/// The scrypt cost parameter (log2 of N) used for the exported keyfiles.
+const SCRYPT_LOG_N: u8 = 15;
+/// The scrypt block size parameter.
+const SCRYPT_R: u32 = 8;
+/// The scrypt parallelization parameter used for the exported keyfiles.
+const SCRYPT_P: u32 = 1;
+/// The maximum scrypt cost parameter (log2 of N) accepted from the imported keyfiles.
+const MAX_SCRYPT_LOG_N: u8 = 20;
+/// The maximum scrypt parallelization parameter accepted from the imported keyfiles.
+const MAX_SCRYPT_P: u32 = 16;
+
+/// The keyfile encoding version.
+const ENCODING_VERSION: &str = "3";
+/// The keyfile content type.
+const ENCODING_CONTENT: [&str; 2] = ["pkcs8", "sr25519"];
+/// The keyfile encryption type.
+const ENCODING_TYPE: [&str; 2] = ["scrypt", "xsalsa20-poly1305"];
+
+/// The encrypted JSON keyfile.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keyfile {
+    /// The encrypted PKCS8 key pair, base64-encoded.
+    pub encoded: String,
+    /// The encoding of the key pair.
+    pub encoding: Encoding,
+    /// The SS58 address of the key.
+    pub address: String,
+    /// The encrypted secret key URI, base64-encoded; not present at the polkadot.js keyfiles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoded_suri: Option<String>,
+    /// The key metadata.
+    #[serde(default)]
+    pub meta: Meta,
+}
+
+/// The keyfile encoding description.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Encoding {
+    /// The content type.
+    pub content: Vec<String>,
+    /// The encryption type.
+    #[serde(rename = "type")]
+    pub kind: Vec<String>,
+    /// The encoding version.
+    pub version: String,
+}
+
+/// The key metadata.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    /// The name of the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The moment (in UNIX milliseconds) at which the keyfile has been created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when_created: Option<u64>,
+}
+
+/// An error that occurred while encrypting or decrypting the keyfile.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The secret key URI is invalid.
+    #[error("invalid secret key URI")]
+    InvalidSuri,
+    /// The keyfile encoding is not supported.
+    #[error("unsupported keyfile encoding")]
+    UnsupportedEncoding,
+    /// The keyfile encrypted data is not properly base64-encoded.
+    #[error("keyfile encrypted data is not in base64 format: {0}")]
+    Base64(base64::DecodeError),
+    /// The keyfile encrypted data is malformed.
+    #[error("keyfile encrypted data is malformed")]
+    Malformed,
+    /// The scrypt parameters of the keyfile are not supported.
+    #[error("unsupported keyfile scrypt parameters")]
+    UnsupportedScryptParams,
+    /// Encryption has failed.
+    #[error("unable to encrypt the keyfile")]
+    Encryption,
+    /// Decryption has failed, most likely due to the wrong passphrase.
+    #[error("unable to decrypt the keyfile, the passphrase is likely wrong")]
+    Decryption,
+    /// The secret key at the keyfile is invalid.
+    #[error("invalid secret key")]
+    InvalidSecretKey,
+    /// The keys at the keyfile do not match each other.
+    #[error("the keyfile keys do not match each other")]
+    KeyMismatch,
+    /// The keyfile does not carry the secret key URI.
+    #[error(
+        "the keyfile does not carry the secret key URI, so it can not be imported into the keystore; \
+        insert the key with its mnemonic instead"
+    )]
+    MissingSuri,
+}
+
+/// Encrypt the key with the given secret key URI into a keyfile.
+pub fn encrypt(suri: &str, passphrase: &str, meta: Meta) -> Result<Keyfile, Error> {
+    encrypt_with(suri, passphrase, meta, SCRYPT_LOG_N)
+}
+
+/// Encrypt the key with the given secret key URI into a keyfile, using the given scrypt cost.
+fn encrypt_with(suri: &str, passphrase: &str, meta: Meta, log_n: u8) -> Result<Keyfile, Error> {
+    let pair = sr25519::Pair::from_string(suri, None).map_err(|_| Error::InvalidSuri)?;
+    let secret_key = schnorrkel::SecretKey::from_bytes(&Zeroizing::new(pair.to_raw_vec()))
+        .map_err(|_| Error::InvalidSecretKey)?;
+
+    let mut pkcs8 = Zeroizing::new(Vec::new());
+    pkcs8.extend_from_slice(&PKCS8_HEADER);
+    pkcs8.extend_from_slice(&Zeroizing::new(secret_key.to_ed25519_bytes())[..]);
+    pkcs8.extend_from_slice(&PKCS8_DIVIDER);
+    pkcs8.extend_from_slice(pair.public().as_ref());
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    Ok(Keyfile {
+        encoded: engine.encode(seal(&pkcs8, passphrase, log_n)?),
+        encoding: Encoding {
+            content: ENCODING_CONTENT.map(String::from).into(),
+            kind: ENCODING_TYPE.map(String::from).into(),
+            version: ENCODING_VERSION.into(),
+        },
+        address: pair.public().to_ss58check(),
+        encoded_suri: Some(engine.encode(seal(suri.as_bytes(), passphrase, log_n)?)),
+        meta,
+    })
+}
+
+/// Decrypt the keyfile, obtaining the secret key URI of the key.
+pub fn decrypt(keyfile: &Keyfile, passphrase: &str) -> Result<Zeroizing<String>, Error> {
+    let Encoding {
+        content,
+        kind,
+        version,
+    } = &keyfile.encoding;
+    if version != ENCODING_VERSION || content != &ENCODING_CONTENT || kind != &ENCODING_TYPE {
+        return Err(Error::UnsupportedEncoding);
+    }
+
+    let engine = base64::engine::general_purpose::STANDARD;
+
+    let encoded = engine.decode(&keyfile.encoded).map_err(Error::Base64)?;
+    let pkcs8 = open(&encoded, passphrase)?;
+    let public_key = parse_pkcs8(&pkcs8)?;
+
+    let encoded_suri = keyfile.encoded_suri.as_ref().ok_or(Error::MissingSuri)?;
+    let encoded_suri = engine.decode(encoded_suri).map_err(Error::Base64)?;
+    let suri = open(&encoded_suri, passphrase)?;
+    let suri = Zeroizing::new(String::from_utf8(suri.to_vec()).map_err(|_| Error::InvalidSuri)?);
+
+    let pair = sr25519::Pair::from_string(&suri, None).map_err(|_| Error::InvalidSuri)?;
+    if pair.public().as_ref() != public_key {
+        return Err(Error::KeyMismatch);
+    }
+
+    Ok(suri)
+}
+
+/// Parse the PKCS8 key pair, checking the keys match each other, and return the public key.
+fn parse_pkcs8(mut pkcs8: &[u8]) -> Result<&[u8], Error> {
+    if take(&mut pkcs8, PKCS8_HEADER.len())? != PKCS8_HEADER {
+        return Err(Error::Malformed);
+    }
+    let secret_key = take(&mut pkcs8, SECRET_KEY_LENGTH)?;
+    if take(&mut pkcs8, PKCS8_DIVIDER.len())? != PKCS8_DIVIDER {
+        return Err(Error::Malformed);
+    }
+    let public_key = take(&mut pkcs8, PUBLIC_KEY_LENGTH)?;
+    if !pkcs8.is_empty() {
+        return Err(Error::Malformed);
+    }
+
+    let secret_key = schnorrkel::SecretKey::from_ed25519_bytes(secret_key)
+        .map_err(|_| Error::InvalidSecretKey)?;
+    if secret_key.to_public().to_bytes() != public_key {
+        return Err(Error::KeyMismatch);
+    }
+
+    Ok(public_key)
+}
+
+/// Encrypt the data with the passphrase, prepending the scrypt parameters and the nonce.
+fn seal(data: &[u8], passphrase: &str, log_n: u8) -> Result<Vec<u8>, Error> {
+    let salt: [u8; SALT_LENGTH] = rand::random();
+    let nonce: [u8; NONCE_LENGTH] = rand::random();
+    let n = 2u32
+        .checked_pow(log_n.into())
+        .ok_or(Error::UnsupportedScryptParams)?;
+
+    let key = derive_key(passphrase, &salt, log_n, SCRYPT_R, SCRYPT_P)?;
+    let encrypted = XSalsa20Poly1305::new(Key::from_slice(&key[..ENCRYPTION_KEY_LENGTH]))
+        .encrypt(Nonce::from_slice(&nonce), data)
+        .map_err(|_| Error::Encryption)?;
+
+    let mut sealed = Vec::from(salt);
+    sealed.extend_from_slice(&n.to_le_bytes());
+    sealed.extend_from_slice(&SCRYPT_P.to_le_bytes());
+    sealed.extend_from_slice(&SCRYPT_R.to_le_bytes());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&encrypted);
+    Ok(sealed)
+}
+
+/// Decrypt the data sealed with the passphrase.
+fn open(mut sealed: &[u8], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let salt = take(&mut sealed, SALT_LENGTH)?;
+    let n = take_u32(&mut sealed)?;
+    let p = take_u32(&mut sealed)?;
+    let r = take_u32(&mut sealed)?;
+    let nonce = take(&mut sealed, NONCE_LENGTH)?;
+
+    if !n.is_power_of_two() || r != SCRYPT_R || p == 0 || p > MAX_SCRYPT_P {
+        return Err(Error::UnsupportedScryptParams);
+    }
+    let log_n = u8::try_from(n.trailing_zeros()).map_err(|_| Error::UnsupportedScryptParams)?;
+    if log_n == 0 || log_n > MAX_SCRYPT_LOG_N {
+        return Err(Error::UnsupportedScryptParams);
+    }
+
+    let key = derive_key(passphrase, salt, log_n, r, p)?;
+    XSalsa20Poly1305::new(Key::from_slice(&key[..ENCRYPTION_KEY_LENGTH]))
+        .decrypt(Nonce::from_slice(nonce), sealed)
+        .map(Zeroizing::new)
+        .map_err(|_| Error::Decryption)
+}
+
+/// Derive the encryption key from the passphrase with scrypt.
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+) -> Result<Zeroizing<[u8; DERIVED_KEY_LENGTH]>, Error> {
+    let params = scrypt::Params::new(log_n, r, p, DERIVED_KEY_LENGTH)
+        .map_err(|_| Error::UnsupportedScryptParams)?;
+    let mut key = Zeroizing::new([0; DERIVED_KEY_LENGTH]);
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key[..])
+        .map_err(|_| Error::UnsupportedScryptParams)?;
+    Ok(key)
+}
+
+/// Take the given number of bytes from the start of the data.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if data.len() < len {
+        return Err(Error::Malformed);
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+/// Take a little-endian `u32` from the start of the data.
+fn take_u32(data: &mut &[u8]) -> Result<u32, Error> {
+    let bytes = take(data, 4)?.try_into().map_err(|_| Error::Malformed)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::crypto::DEV_PHRASE;
+
+    use super::*;
+
+    /// A scrypt cost low enough to keep the tests fast.
+    const TEST_SCRYPT_LOG_N: u8 = 10;
+
+    #[test]
+    fn round_trip() {
+        let keyfile =
+            encrypt_with(DEV_PHRASE, "passphrase", Meta::default(), TEST_SCRYPT_LOG_N).unwrap();
+
+        let suri = decrypt(&keyfile, "passphrase").unwrap();
+
+        assert_eq!(suri.as_str(), DEV_PHRASE);
+        assert_eq!(
+            keyfile.address,
+            sr25519::Pair::from_string(DEV_PHRASE, None)
+                .unwrap()
+                .public()
+                .to_ss58check()
+        );
+    }
+
+    #[test]
+    fn polkadot_js_layout() {
+        let keyfile = encrypt_with(
+            "//Alice",
+            "passphrase",
+            Meta {
+                name: Some("bioauth".into()),
+                when_created: Some(1),
+            },
+            TEST_SCRYPT_LOG_N,
+        )
+        .unwrap();
+
+        let value = serde_json::to_value(&keyfile).unwrap();
+        assert_eq!(
+            value["encoding"],
+            serde_json::json!({
+                "content": ["pkcs8", "sr25519"],
+                "type": ["scrypt", "xsalsa20-poly1305"],
+                "version": "3",
+            })
+        );
+        assert_eq!(
+            value["meta"],
+            serde_json::json!({ "name": "bioauth", "whenCreated": 1 })
+        );
+
+        let encoded = base64::engine::general_purpose::STANDARD
+            .decode(&keyfile.encoded)
+            .unwrap();
+        // Salt (32), scrypt params (12), nonce (24), poly1305 tag (16) and
+        // the PKCS8 key pair (117).
+        assert_eq!(encoded.len(), 201);
+        assert_eq!(encoded[32..36], 1024u32.to_le_bytes());
+        assert_eq!(encoded[36..40], 1u32.to_le_bytes());
+        assert_eq!(encoded[40..44], 8u32.to_le_bytes());
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let keyfile =
+            encrypt_with(DEV_PHRASE, "passphrase", Meta::default(), TEST_SCRYPT_LOG_N).unwrap();
+
+        assert_eq!(decrypt(&keyfile, "wrong").unwrap_err(), Error::Decryption);
+    }
+
+    #[test]
+    fn missing_suri() {
+        let mut keyfile =
+            encrypt_with(DEV_PHRASE, "passphrase", Meta::default(), TEST_SCRYPT_LOG_N).unwrap();
+        keyfile.encoded_suri = None;
+
+        assert_eq!(
+            decrypt(&keyfile, "passphrase").unwrap_err(),
+            Error::MissingSuri
+        );
+        assert_eq!(decrypt(&keyfile, "wrong").unwrap_err(), Error::Decryption);
+    }
+
+    #[test]
+    fn expanded_secret_key_is_not_a_suri() {
+        let pair = sr25519::Pair::from_string(DEV_PHRASE, None).unwrap();
+        let secret_key = schnorrkel::SecretKey::from_bytes(&pair.to_raw_vec()).unwrap();
+        let suri = format!("0x{}", hex::encode(secret_key.to_bytes()));
+
+        assert!(sr25519::Pair::from_string(&suri, None).is_err());
+    }
+
+    #[test]
+    fn mismatching_suri() {
+        let mut keyfile =
+            encrypt_with(DEV_PHRASE, "passphrase", Meta::default(), TEST_SCRYPT_LOG_N).unwrap();
+        let other =
+            encrypt_with("//Bob", "passphrase", Meta::default(), TEST_SCRYPT_LOG_N).unwrap();
+        keyfile.encoded_suri = other.encoded_suri;
+
+        assert_eq!(
+            decrypt(&keyfile, "passphrase").unwrap_err(),
+            Error::KeyMismatch
+        );
+    }
+
+    #[test]
+    fn excessive_scrypt_cost() {
+        let mut keyfile =
+            encrypt_with(DEV_PHRASE, "passphrase", Meta::default(), TEST_SCRYPT_LOG_N).unwrap();
+        let engine = base64::engine::general_purpose::STANDARD;
+        let mut encoded = engine.decode(&keyfile.encoded).unwrap();
+        encoded[32..36].copy_from_slice(&1_073_741_824u32.to_le_bytes());
+        keyfile.encoded = engine.encode(encoded);
+
+        assert_eq!(
+            decrypt(&keyfile, "passphrase").unwrap_err(),
+            Error::UnsupportedScryptParams
+        );
+    }
+}
@@ -1,11 +1,21 @@
 //! Bioauth key management subcommands.
 
-use crate::service::KeystoreBioauthId;
+use std::path::{Path, PathBuf};
 
+use sc_service::config::KeystoreConfig;
+use sp_application_crypto::AppCrypto;
+use zeroize::Zeroizing;
+
+use crate::{configuration::Configuration, service::KeystoreBioauthId};
+
+pub mod export;
 pub mod generate;
+pub mod import;
 pub mod insert;
 pub mod inspect;
+pub mod keyfile;
 pub mod list;
+pub mod remove;
 
 /// Keystore bioauth key pair scheme type used at the keystore.
 pub type KeystoreBioauthPair = <<KeystoreBioauthId as sp_application_crypto::CryptoType>::Pair as sp_application_crypto::AppPair>::Generic;
@@ -21,4 +31,48 @@ pub enum KeyCmd {
     Insert(insert::InsertKeyCmd),
     /// List the bioauth keys.
     List(list::ListKeysCmd),
+    /// Export the bioauth key to an encrypted JSON keyfile.
+    Export(export::ExportKeyCmd),
+    /// Import the bioauth key from an encrypted JSON keyfile.
+    Import(import::ImportKeyCmd),
+    /// Remove the bioauth key.
+    Remove(remove::RemoveKeyCmd),
+}
+
+/// Obtain the path of the keystore directory.
+pub fn keystore_path(config: &Configuration) -> sc_cli::Result<PathBuf> {
+    match &config.substrate.keystore {
+        KeystoreConfig::Path { path, .. } => Ok(path.clone()),
+        KeystoreConfig::InMemory => Err(sc_cli::Error::Input(
+            "the keystore is not stored on disk".into(),
+        )),
+    }
+}
+
+/// The path of the file the keystore keeps the given bioauth key at.
+pub fn bioauth_key_file_path(keystore_path: &Path, public_key: &[u8]) -> PathBuf {
+    keystore_path.join(format!(
+        "{}{}",
+        hex::encode(KeystoreBioauthId::ID.0),
+        hex::encode(public_key)
+    ))
+}
+
+/// Read the keyfile passphrase from the given file, or prompt for it if no file is given.
+pub fn read_passphrase(path: Option<&Path>, confirm: bool) -> sc_cli::Result<Zeroizing<String>> {
+    if let Some(path) = path {
+        let passphrase = Zeroizing::new(std::fs::read_to_string(path)?);
+        return Ok(Zeroizing::new(
+            passphrase.trim_end_matches(&['\r', '\n'][..]).to_owned(),
+        ));
+    }
+
+    let passphrase = Zeroizing::new(rpassword::prompt_password("Keyfile passphrase: ")?);
+    if confirm {
+        let repeated = Zeroizing::new(rpassword::prompt_password("Repeat the passphrase: ")?);
+        if *passphrase != *repeated {
+            return Err(sc_cli::Error::Input("the passphrases do not match".into()));
+        }
+    }
+    Ok(passphrase)
 }
@@ -0,0 +1,82 @@
+//! Bioauth key remove subcommand logic.
+
+use std::{path::PathBuf, sync::Arc};
+
+use bioauth_flow_api::BioauthFlowApi;
+use sc_cli::{CliConfiguration, KeystoreParams, SharedParams};
+use sc_service::KeystoreContainer;
+use sp_api::ProvideRuntimeApi;
+
+use super::{bioauth_key_file_path, KeystoreBioauthId};
+use crate::{
+    cli::{utils::application_error, CliConfigurationExt},
+    service::FullClient,
+};
+
+/// The `bioauth key remove` command.
+#[derive(Debug, clap::Parser)]
+pub struct RemoveKeyCmd {
+    /// Remove the key even if it has an active authentication.
+    #[arg(long)]
+    pub force: bool,
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub shared_params: SharedParams,
+
+    #[allow(missing_docs, clippy::missing_docs_in_private_items)]
+    #[command(flatten)]
+    pub keystore_params: KeystoreParams,
+}
+
+impl RemoveKeyCmd {
+    /// Run the remove command.
+    pub async fn run(
+        &self,
+        client: Arc<FullClient>,
+        keystore_container: KeystoreContainer,
+        keystore_path: PathBuf,
+    ) -> sc_cli::Result<()> {
+        let keystore = keystore_container.keystore();
+        let key =
+            crate::validator_key::AppCryptoPublic::<KeystoreBioauthId>::list(keystore.as_ref())
+                .await
+                .map_err(|err| sc_cli::Error::Service(sc_service::Error::Other(err.to_string())))?
+                .next()
+                .ok_or_else(|| application_error("no bioauth key at the keystore"))?;
+
+        if !self.force {
+            let at = client.chain_info().best_hash;
+            let active_authentications = client
+                .runtime_api()
+                .active_authentications(at)
+                .map_err(|err| sc_cli::Error::Application(Box::new(err)))?;
+
+            if active_authentications
+                .iter()
+                .any(|authentication| authentication.id == key.0)
+            {
+                return Err(application_error(
+                    "the bioauth key has an active authentication, pass --force to remove it anyway",
+                ));
+            }
+        }
+
+        std::fs::remove_file(bioauth_key_file_path(&keystore_path, key.as_ref()))?;
+
+        println!("{key}");
+        Ok(())
+    }
+}
+
+impl CliConfiguration for RemoveKeyCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+impl CliConfigurationExt for RemoveKeyCmd {}
@@ -3,6 +3,7 @@
 pub mod api_versions;
 pub mod authurl;
 pub mod key;
+pub mod status;
 
 /// Subcommands for the `bioauth` command.
 #[derive(Debug, clap::Subcommand)]
@@ -14,4 +15,6 @@ pub enum BioauthCmd {
     AuthUrl(authurl::AuthUrlCmd),
     /// API versions print.
     ApiVersions(api_versions::ApiVersionsCmd),
+    /// Validator status report of a running node.
+    Status(status::StatusCmd),
 }
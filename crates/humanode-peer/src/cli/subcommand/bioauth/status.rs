@@ -0,0 +1,705 @@
+//! Bioauth status subcommand logic.
+//!
+//! Connects to the RPC of a running node and reports everything that affects whether the node
+//! is validating: the bioauth key, the authentication, the session membership, the ban,
+//! the session keys and the im-online heartbeats.
+//!
+//! The im-online heartbeats are recorded on-chain without the moment they were sent at, so
+//! the last heartbeat the node has sent is read from the node offchain storage, where im-online
+//! keeps track of it. The offchain storage RPC is unsafe and might be denied by the node, so
+//! the heartbeat state is reported as unavailable rather than failing the whole report.
+
+use std::collections::BTreeSet;
+
+use bioauth_flow_rpc::data::BioauthStatus;
+use chrono::{TimeZone, Utc};
+use codec::{Decode, Encode};
+use frame_support::{traits::PalletInfoAccess, StorageHasher, Twox64Concat};
+use humanode_runtime::{AccountId, BlockNumber, Runtime, UnixMilliseconds};
+use pallet_humanode_session::{Identification, IdentificationFor};
+use serde::de::DeserializeOwned;
+use sp_core::Bytes;
+
+use crate::cli::utils::application_error;
+
+/// The session index type.
+type SessionIndex = u32;
+
+/// The `bioauth status` command.
+#[derive(Debug, clap::Parser)]
+pub struct StatusCmd {
+    /// The HTTP RPC URL of the running node to inspect.
+    #[arg(long, value_name = "URL", default_value = "http://127.0.0.1:9944")]
+    pub rpc_url: String,
+}
+
+impl StatusCmd {
+    /// Run the status command.
+    pub async fn run(&self) -> sc_cli::Result<()> {
+        let rpc = RpcClient {
+            url: self.rpc_url.clone(),
+            http: reqwest::Client::new(),
+        };
+        let report = Report::collect(&rpc).await.map_err(application_error)?;
+        print!("{report}");
+        Ok(())
+    }
+}
+
+/// An error that can occur while collecting the report.
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    /// The node could not be reached.
+    #[error("unable to reach the node: {0}")]
+    Transport(#[from] reqwest::Error),
+    /// The node has responded with an error.
+    #[error("the node responded to {method} with error {code}: {message}")]
+    Rpc {
+        /// The RPC method that has been called.
+        method: &'static str,
+        /// The error code.
+        code: i64,
+        /// The error message.
+        message: String,
+    },
+    /// The node has responded without a result.
+    #[error("the node responded to {method} without a result: {source}")]
+    UnexpectedResponse {
+        /// The RPC method that has been called.
+        method: &'static str,
+        /// The deserialization error.
+        source: serde_json::Error,
+    },
+    /// The node has responded with an unexpected value.
+    #[error("unable to decode the {what}: {source}")]
+    Decode {
+        /// What has been decoded.
+        what: &'static str,
+        /// The decoding error.
+        source: codec::Error,
+    },
+}
+
+impl Error {
+    /// Whether the error is the node reporting that it has no bioauth key.
+    fn is_missing_validator_key(&self) -> bool {
+        matches!(
+            self,
+            Self::Rpc { code, .. }
+                if *code == i64::from(rpc_validator_key_logic::api_error_code::MISSING_VALIDATOR_KEY)
+        )
+    }
+}
+
+/// A minimal JSON-RPC client for the node HTTP RPC.
+struct RpcClient {
+    /// The RPC URL.
+    url: String,
+    /// The HTTP client.
+    http: reqwest::Client,
+}
+
+/// The JSON-RPC response.
+#[derive(Debug, serde::Deserialize)]
+struct RpcResponse<T> {
+    /// The result, present on success.
+    result: Option<T>,
+    /// The error, present on failure.
+    error: Option<RpcResponseError>,
+}
+
+/// The JSON-RPC response error.
+#[derive(Debug, serde::Deserialize)]
+struct RpcResponseError {
+    /// The error code.
+    code: i64,
+    /// The error message.
+    message: String,
+}
+
+impl RpcClient {
+    /// Call the given RPC method.
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: serde_json::Value,
+    ) -> Result<T, Error> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let res: RpcResponse<T> = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        match (res.result, res.error) {
+            (_, Some(RpcResponseError { code, message })) => Err(Error::Rpc {
+                method,
+                code,
+                message,
+            }),
+            (Some(result), None) => Ok(result),
+            // The `null` result is indistinguishable from the absent one.
+            (None, None) => serde_json::from_value(serde_json::Value::Null)
+                .map_err(|source| Error::UnexpectedResponse { method, source }),
+        }
+    }
+
+    /// Read the raw storage value under the given key at the best block.
+    async fn raw_storage(&self, key: &[u8]) -> Result<Option<Bytes>, Error> {
+        self.call("state_getStorage", serde_json::json!([Bytes(key.to_vec())]))
+            .await
+    }
+
+    /// Read and decode the storage value under the given key at the best block.
+    async fn storage<T: Decode>(&self, what: &'static str, key: &[u8]) -> Result<Option<T>, Error> {
+        self.raw_storage(key)
+            .await?
+            .map(|value| T::decode(&mut &value[..]))
+            .transpose()
+            .map_err(|source| Error::Decode { what, source })
+    }
+
+    /// Read and decode the value under the given key at the node persistent offchain storage.
+    async fn offchain_storage<T: Decode>(
+        &self,
+        what: &'static str,
+        key: &[u8],
+    ) -> Result<Option<T>, Error> {
+        let value: Option<Bytes> = self
+            .call(
+                "offchain_localStorageGet",
+                serde_json::json!(["PERSISTENT", Bytes(key.to_vec())]),
+            )
+            .await?;
+        value
+            .map(|value| T::decode(&mut &value[..]))
+            .transpose()
+            .map_err(|source| Error::Decode { what, source })
+    }
+}
+
+/// The last im-online heartbeat sent by the node.
+///
+/// Mirrors the heartbeat status im-online keeps at the offchain storage, which is not public.
+#[derive(Debug, PartialEq, Eq, Decode)]
+struct SentHeartbeat {
+    /// The session the heartbeat has been sent in.
+    session_index: SessionIndex,
+    /// The block at which the heartbeat has been sent.
+    sent_at: BlockNumber,
+}
+
+/// The im-online heartbeat state of the node.
+#[derive(Debug, PartialEq, Eq)]
+enum Heartbeat {
+    /// The node is not a validator of the current session, so no heartbeat is expected.
+    NotExpected,
+    /// The heartbeat has been received in the current session.
+    Received {
+        /// The last heartbeat sent by the node, if any is recorded.
+        last_sent: Option<SentHeartbeat>,
+    },
+    /// The heartbeat has not yet been received in the current session.
+    Pending {
+        /// The block after which the heartbeats are sent.
+        after: BlockNumber,
+        /// The last heartbeat sent by the node, if any is recorded.
+        last_sent: Option<SentHeartbeat>,
+    },
+}
+
+/// The session keys state of the node.
+#[derive(Debug, PartialEq, Eq)]
+enum SessionKeys {
+    /// The session keys are not set on-chain.
+    NotSet,
+    /// The session keys are set on-chain, but the local keystore does not hold them.
+    MissingFromKeystore,
+    /// The session keys are set on-chain and held by the local keystore.
+    Set,
+}
+
+/// The part of the report that concerns the bioauth key of the node.
+#[derive(Debug)]
+struct ValidatorReport {
+    /// The bioauth key of the node.
+    key: AccountId,
+    /// The bioauth status of the key.
+    bioauth_status: BioauthStatus<UnixMilliseconds>,
+    /// The identification of the key in the current session, if it is a validator there.
+    current_session_identity: Option<IdentificationFor<Runtime>>,
+    /// The identification of the key in the next session, if it is a validator there.
+    next_session_identity: Option<IdentificationFor<Runtime>>,
+    /// Whether the key is banned from being a validator.
+    banned: bool,
+    /// The session keys state.
+    session_keys: SessionKeys,
+    /// The im-online heartbeat state, or the error that prevented obtaining it.
+    heartbeat: Result<Heartbeat, Error>,
+}
+
+/// The consolidated validator status report.
+#[derive(Debug)]
+struct Report {
+    /// The current session index.
+    session_index: SessionIndex,
+    /// The report on the bioauth key, if the node has one.
+    validator: Option<ValidatorReport>,
+}
+
+impl Report {
+    /// Collect the report from the node.
+    async fn collect(rpc: &RpcClient) -> Result<Self, Error> {
+        let session_index: SessionIndex = rpc
+            .storage(
+                "current session index",
+                &pallet_session::CurrentIndex::<Runtime>::hashed_key(),
+            )
+            .await?
+            .unwrap_or_default();
+
+        // The key absence is reported as an error.
+        let key: AccountId = match rpc
+            .call("authorExt_getValidatorPublicKey", serde_json::json!([]))
+            .await
+        {
+            Ok(key) => key,
+            Err(err) if err.is_missing_validator_key() => {
+                return Ok(Self {
+                    session_index,
+                    validator: None,
+                })
+            }
+            Err(err) => return Err(err),
+        };
+
+        let bioauth_status = rpc.call("bioauth_status", serde_json::json!([])).await?;
+
+        let current_session_identity = rpc
+            .storage(
+                "current session identity",
+                &pallet_humanode_session::SessionIdentities::<Runtime>::hashed_key_for(
+                    session_index,
+                    &key,
+                ),
+            )
+            .await?;
+        let next_session_identity = match session_index.checked_add(1) {
+            Some(next_session_index) => {
+                rpc.storage(
+                    "next session identity",
+                    &pallet_humanode_session::SessionIdentities::<Runtime>::hashed_key_for(
+                        next_session_index,
+                        &key,
+                    ),
+                )
+                .await?
+            }
+            None => None,
+        };
+
+        let banned_accounts: BTreeSet<AccountId> = rpc
+            .storage(
+                "banned accounts",
+                &pallet_humanode_session::BannedAccounts::<Runtime>::hashed_key(),
+            )
+            .await?
+            .unwrap_or_default();
+
+        // The keys are checked against the local keystore in their encoded form, as stored.
+        let session_keys = match rpc
+            .raw_storage(&pallet_session::NextKeys::<Runtime>::hashed_key_for(&key))
+            .await?
+        {
+            None => SessionKeys::NotSet,
+            Some(encoded_keys) => {
+                let in_keystore: bool = rpc
+                    .call("author_hasSessionKeys", serde_json::json!([encoded_keys]))
+                    .await?;
+                if in_keystore {
+                    SessionKeys::Set
+                } else {
+                    SessionKeys::MissingFromKeystore
+                }
+            }
+        };
+
+        let heartbeat = Heartbeat::collect(rpc, session_index, &key).await;
+
+        Ok(Self {
+            session_index,
+            validator: Some(ValidatorReport {
+                banned: banned_accounts.contains(&key),
+                key,
+                bioauth_status,
+                current_session_identity,
+                next_session_identity,
+                session_keys,
+                heartbeat,
+            }),
+        })
+    }
+}
+
+impl Heartbeat {
+    /// Collect the im-online heartbeat state of the given key from the node.
+    async fn collect(
+        rpc: &RpcClient,
+        session_index: SessionIndex,
+        key: &AccountId,
+    ) -> Result<Self, Error> {
+        let validators: Vec<AccountId> = rpc
+            .storage(
+                "session validators",
+                &pallet_session::Validators::<Runtime>::hashed_key(),
+            )
+            .await?
+            .unwrap_or_default();
+        let Some(auth_index) = validators
+            .iter()
+            .position(|validator| validator == key)
+            .and_then(|auth_index| u32::try_from(auth_index).ok())
+        else {
+            return Ok(Self::NotExpected);
+        };
+
+        let last_sent = rpc
+            .offchain_storage("last sent heartbeat", &sent_heartbeat_key(auth_index))
+            .await?;
+        let received = rpc
+            .raw_storage(&received_heartbeats_key(session_index, auth_index))
+            .await?;
+        Ok(match received {
+            Some(_) => Self::Received { last_sent },
+            None => Self::Pending {
+                after: rpc
+                    .storage("heartbeat after", &heartbeat_after_key())
+                    .await?
+                    .unwrap_or_default(),
+                last_sent,
+            },
+        })
+    }
+}
+
+/// The storage key of the im-online received heartbeat for the given session and authority.
+///
+/// The im-online storage is not public, so the key is computed by hand.
+fn received_heartbeats_key(session_index: SessionIndex, auth_index: u32) -> Vec<u8> {
+    let mut key = frame_support::storage::storage_prefix(
+        humanode_runtime::ImOnline::name().as_bytes(),
+        b"ReceivedHeartbeats",
+    )
+    .to_vec();
+    key.extend(Twox64Concat::hash(&session_index.encode()));
+    key.extend(Twox64Concat::hash(&auth_index.encode()));
+    key
+}
+
+/// The storage key of the im-online block after which the heartbeats are sent.
+///
+/// The im-online storage is not public, so the key is computed by hand.
+fn heartbeat_after_key() -> [u8; 32] {
+    frame_support::storage::storage_prefix(
+        humanode_runtime::ImOnline::name().as_bytes(),
+        b"HeartbeatAfter",
+    )
+}
+
+/// The offchain storage key of the last heartbeat sent by the node for the given authority.
+///
+/// The im-online offchain storage layout is not public, so the key is computed by hand.
+fn sent_heartbeat_key(auth_index: u32) -> Vec<u8> {
+    let mut key = b"parity/im-online-heartbeat/".to_vec();
+    key.extend(auth_index.encode());
+    key
+}
+
+/// Format the given moment for display.
+fn display_moment(moment: UnixMilliseconds) -> String {
+    i64::try_from(moment)
+        .ok()
+        .and_then(|moment| Utc.timestamp_millis_opt(moment).single())
+        .map_or_else(
+            || format!("[invalid milliseconds {moment}]"),
+            |moment| moment.to_string(),
+        )
+}
+
+/// Describe the given session identity.
+fn display_identity(identity: Option<&IdentificationFor<Runtime>>) -> &'static str {
+    match identity {
+        None => "no",
+        Some(Identification::Bootnode(_)) => "yes (bootnode)",
+        Some(Identification::Bioauth(_)) => "yes (bioauth)",
+        Some(Identification::FixedValidatorsSet(_)) => "yes (fixed validators set)",
+    }
+}
+
+/// Describe the given flag.
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(validator) = &self.validator else {
+            writeln!(f, "Bioauth key: absent")?;
+            writeln!(
+                f,
+                "Hint: generate or insert the key with the `bioauth key` subcommands"
+            )?;
+            return Ok(());
+        };
+
+        writeln!(f, "Bioauth key: {}", validator.key)?;
+
+        match validator.bioauth_status {
+            BioauthStatus::Unknown => writeln!(f, "Authentication: unknown")?,
+            BioauthStatus::Inactive => writeln!(f, "Authentication: inactive")?,
+            BioauthStatus::Active { expires_at, .. } => writeln!(
+                f,
+                "Authentication: active, expires at {}",
+                display_moment(expires_at)
+            )?,
+        }
+
+        writeln!(
+            f,
+            "Validator in the current session #{}: {}",
+            self.session_index,
+            display_identity(validator.current_session_identity.as_ref())
+        )?;
+        match self.session_index.checked_add(1) {
+            Some(next_session_index) => writeln!(
+                f,
+                "Validator in the next session #{next_session_index}: {}",
+                display_identity(validator.next_session_identity.as_ref())
+            )?,
+            None => writeln!(f, "Validator in the next session: no")?,
+        }
+
+        writeln!(f, "Banned: {}", yes_no(validator.banned))?;
+        match validator.session_keys {
+            SessionKeys::NotSet => writeln!(f, "Session keys: not set")?,
+            SessionKeys::MissingFromKeystore => {
+                writeln!(f, "Session keys: set, but missing from the local keystore")?
+            }
+            SessionKeys::Set => writeln!(f, "Session keys: set")?,
+        }
+
+        let last_sent = match &validator.heartbeat {
+            Err(err) => return writeln!(f, "Im-online heartbeat: unavailable, {err}"),
+            Ok(Heartbeat::NotExpected) => {
+                return writeln!(
+                    f,
+                    "Im-online heartbeat: not expected, not a validator in the current session"
+                );
+            }
+            Ok(Heartbeat::Received { last_sent }) => {
+                writeln!(f, "Im-online heartbeat: received in the current session")?;
+                last_sent
+            }
+            Ok(Heartbeat::Pending { after, last_sent }) => {
+                writeln!(
+                    f,
+                    "Im-online heartbeat: not yet received, due after block #{after}"
+                )?;
+                last_sent
+            }
+        };
+        match last_sent {
+            Some(SentHeartbeat {
+                session_index,
+                sent_at,
+            }) => writeln!(
+                f,
+                "Last im-online heartbeat sent: at block #{sent_at} in session #{session_index}"
+            )?,
+            None => writeln!(f, "Last im-online heartbeat sent: none recorded")?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::formatdoc;
+
+    use super::*;
+
+    #[test]
+    fn missing_validator_key_error() {
+        let rpc_error = |code| Error::Rpc {
+            method: "authorExt_getValidatorPublicKey",
+            code,
+            message: "error".into(),
+        };
+
+        assert!(rpc_error(500).is_missing_validator_key());
+        // Method not found.
+        assert!(!rpc_error(-32601).is_missing_validator_key());
+        // Unable to extract own key.
+        assert!(!rpc_error(600).is_missing_validator_key());
+    }
+
+    #[test]
+    fn sent_heartbeat_decoding() {
+        let encoded = (3u32, 1200u32).encode();
+
+        assert_eq!(
+            SentHeartbeat::decode(&mut &encoded[..]).unwrap(),
+            SentHeartbeat {
+                session_index: 3,
+                sent_at: 1200,
+            }
+        );
+    }
+
+    #[test]
+    fn report_without_key() {
+        let report = Report {
+            session_index: 3,
+            validator: None,
+        };
+
+        assert_eq!(
+            report.to_string(),
+            formatdoc!(
+                "
+                Bioauth key: absent
+                Hint: generate or insert the key with the `bioauth key` subcommands
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn report_with_key() {
+        let key = AccountId::new([1; 32]);
+        let report = Report {
+            session_index: 3,
+            validator: Some(ValidatorReport {
+                key: key.clone(),
+                bioauth_status: BioauthStatus::Active {
+                    expires_at: 1637521860001,
+                    renewable_from: None,
+                },
+                current_session_identity: None,
+                next_session_identity: Some(Identification::Bioauth(
+                    pallet_bioauth::Authentication {
+                        public_key: key.clone(),
+                        expires_at: 1637521860001,
+                    },
+                )),
+                banned: false,
+                session_keys: SessionKeys::Set,
+                heartbeat: Ok(Heartbeat::NotExpected),
+            }),
+        };
+
+        assert_eq!(
+            report.to_string(),
+            formatdoc!(
+                "
+                Bioauth key: {key}
+                Authentication: active, expires at 2021-11-21 19:11:00.001 UTC
+                Validator in the current session #3: no
+                Validator in the next session #4: yes (bioauth)
+                Banned: no
+                Session keys: set
+                Im-online heartbeat: not expected, not a validator in the current session
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn report_with_pending_heartbeat() {
+        let key = AccountId::new([1; 32]);
+        let report = Report {
+            session_index: 3,
+            validator: Some(ValidatorReport {
+                key: key.clone(),
+                bioauth_status: BioauthStatus::Inactive,
+                current_session_identity: Some(Identification::Bootnode(key.clone())),
+                next_session_identity: None,
+                banned: true,
+                session_keys: SessionKeys::MissingFromKeystore,
+                heartbeat: Ok(Heartbeat::Pending {
+                    after: 1250,
+                    last_sent: Some(SentHeartbeat {
+                        session_index: 2,
+                        sent_at: 1030,
+                    }),
+                }),
+            }),
+        };
+
+        assert_eq!(
+            report.to_string(),
+            formatdoc!(
+                "
+                Bioauth key: {key}
+                Authentication: inactive
+                Validator in the current session #3: yes (bootnode)
+                Validator in the next session #4: no
+                Banned: yes
+                Session keys: set, but missing from the local keystore
+                Im-online heartbeat: not yet received, due after block #1250
+                Last im-online heartbeat sent: at block #1030 in session #2
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn report_with_unavailable_heartbeat() {
+        let key = AccountId::new([1; 32]);
+        let report = Report {
+            session_index: 3,
+            validator: Some(ValidatorReport {
+                key: key.clone(),
+                bioauth_status: BioauthStatus::Inactive,
+                current_session_identity: Some(Identification::Bootnode(key.clone())),
+                next_session_identity: None,
+                banned: false,
+                session_keys: SessionKeys::Set,
+                heartbeat: Err(Error::Rpc {
+                    method: "offchain_localStorageGet",
+                    code: -32601,
+                    message: "RPC call is unsafe to be called externally".into(),
+                }),
+            }),
+        };
+
+        assert_eq!(
+            report.to_string(),
+            formatdoc!(
+                "
+                Bioauth key: {key}
+                Authentication: inactive
+                Validator in the current session #3: yes (bootnode)
+                Validator in the next session #4: no
+                Banned: no
+                Session keys: set
+                Im-online heartbeat: unavailable, the node responded to offchain_localStorageGet with error -32601: RPC call is unsafe to be called externally
+                "
+            )
+        );
+    }
+}
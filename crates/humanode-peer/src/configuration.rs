@@ -1,6 +1,6 @@
 //! Humanode peer configuration.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 use crate::{
     rpc_url::{RpcUrl, RpcUrlResolver},
@@ -18,6 +18,10 @@ pub struct Configuration {
     /// always required.
     pub bioauth_flow: Option<BioauthFlow>,
 
+    /// Bioauth authentication expiration reminder configuration.
+    /// If not defined, the reminder is not run.
+    pub bioauth_reminder: Option<BioauthReminder>,
+
     /// Ethereum RPC configuration.
     pub ethereum_rpc: Option<EthereumRpc>,
 
@@ -47,6 +51,13 @@ pub struct BioauthFlow {
 
     /// The URL of robonode to authenticate with.
     pub robonode_url: String,
+
+    /// The URLs of the robonodes to co-sign the auth tickets with.
+    pub cosigning_robonode_urls: Vec<String>,
+
+    /// The secret key of the in-process dev robonode to use instead of the robonode.
+    /// Only set at the development and local chains.
+    pub dev_robonode_secret_key: Option<robonode_crypto::SecretKey>,
 }
 
 impl BioauthFlow {
@@ -58,6 +69,15 @@ impl BioauthFlow {
     }
 }
 
+/// Bioauth authentication expiration reminder configuration parameters.
+pub struct BioauthReminder {
+    /// How long before the expiration to start reminding about the re-authentication.
+    pub lead_time: Duration,
+
+    /// The command to run when the authentication is about to expire or has expired.
+    pub hook: Option<String>,
+}
+
 /// Ethereum RPC configuration parameters.
 pub struct EthereumRpc {
     /// Maximum number of blocks to keep the log information available
@@ -1,6 +1,6 @@
 //! The RPC URL configuration logic.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, future::Future, path::PathBuf};
 
 /// An RPC URL, represents the configuration parameters allowing to resolve the RPC URL.
 #[derive(Debug, Clone)]
@@ -18,20 +18,59 @@ pub enum RpcUrl {
         /// The scheme to use for the RPC URL.
         scheme: &'static str,
     },
-    /// Detect the RPC URL from ngrok.
-    DetectFromNgrok {
+    /// Detect the RPC URL from a tunnel provider.
+    DetectFromTunnel(TunnelProvider),
+}
+
+/// A tunnel provider, represents the configuration parameters allowing to detect the public URL
+/// of the tunnel to the RPC endpoint.
+#[derive(Debug, Clone)]
+pub enum TunnelProvider {
+    /// Detect the URL from ngrok.
+    Ngrok {
         /// The tunnel name to get the public URL from.
         tunnel_name: String,
         /// The WebSocket port to match against, and switch protocol to WebSocket if the tunnel
         /// address has this port.
         ws_rpc_endpoint_port: Option<u16>,
     },
+    /// Detect the URL of the cloudflared quick tunnel from the cloudflared metrics server.
+    Cloudflared {
+        /// The base URL of the cloudflared metrics server.
+        metrics_url: String,
+        /// Whether to use the WebSocket protocol for the URL.
+        ws: bool,
+    },
+    /// Read the URL from a file, for the tunnels that write their public URL into a file.
+    File {
+        /// The path to the file.
+        path: PathBuf,
+    },
+    /// Read the URL from the output of a shell command.
+    Command {
+        /// The shell command to run.
+        command: String,
+    },
+}
+
+impl TunnelProvider {
+    /// The human-readable name of the tunnel provider.
+    fn name(&self) -> &'static str {
+        match self {
+            TunnelProvider::Ngrok { .. } => "ngrok",
+            TunnelProvider::Cloudflared { .. } => "cloudflared",
+            TunnelProvider::File { .. } => "the file",
+            TunnelProvider::Command { .. } => "the command",
+        }
+    }
 }
 
 /// An RPC URL resolver provides necessary runtime components to perform RPC URL resolution.
 pub struct RpcUrlResolver {
     /// The `ngrok` agent API client.
     pub ngrok_client: ngrok_api::client::Client,
+    /// The HTTP client to query the other tunnel providers with.
+    pub http_client: reqwest::Client,
 }
 
 impl Default for RpcUrlResolver {
@@ -42,10 +81,28 @@ impl Default for RpcUrlResolver {
                 ngrok_api::client::Client::standard_base_url(),
             )
             .expect("standard base URL should work"),
+            http_client: reqwest::Client::default(),
         }
     }
 }
 
+/// The outcome of a single attempt to detect the RPC URL from a tunnel provider.
+enum Attempt {
+    /// The URL has been detected.
+    Detected(String),
+    /// The tunnel is not ready yet, and the detection is to be retried.
+    NotReady(String),
+    /// The detection has failed.
+    Failed(String),
+}
+
+/// The cloudflared quick tunnel info, as reported by the cloudflared metrics server.
+#[derive(Debug, serde::Deserialize)]
+struct CloudflaredQuickTunnel {
+    /// The hostname of the quick tunnel; empty until the tunnel is started.
+    hostname: String,
+}
+
 impl RpcUrlResolver {
     /// Performs the RPC URL resolution according to the passed settings.
     /// Returns an error if the RPC URL is unset, or if we were unable to
@@ -58,13 +115,34 @@ impl RpcUrlResolver {
                 rpc_endpoint_port,
                 scheme,
             } => Ok(format!("{scheme}://localhost:{rpc_endpoint_port}").into()),
-            RpcUrl::DetectFromNgrok {
+            RpcUrl::DetectFromTunnel(tunnel_provider) => {
+                Ok(self.detect_from_tunnel(tunnel_provider).await?.into())
+            }
+        }
+    }
+
+    /// Detect the RPC URL from the tunnel provider, waiting for the tunnel to start.
+    async fn detect_from_tunnel(&self, tunnel_provider: &TunnelProvider) -> Result<String, String> {
+        match tunnel_provider {
+            TunnelProvider::Ngrok {
                 tunnel_name,
                 ws_rpc_endpoint_port,
-            } => Ok(self
-                .detect_from_ngrok(tunnel_name, *ws_rpc_endpoint_port)
-                .await?
-                .into()),
+            } => {
+                retry_until_ready(tunnel_provider, || {
+                    self.detect_from_ngrok(tunnel_name, *ws_rpc_endpoint_port)
+                })
+                .await
+            }
+            TunnelProvider::Cloudflared { metrics_url, ws } => {
+                retry_until_ready(tunnel_provider, || {
+                    self.detect_from_cloudflared(metrics_url, *ws)
+                })
+                .await
+            }
+            TunnelProvider::File { path } => {
+                retry_until_ready(tunnel_provider, || detect_from_file(path)).await
+            }
+            TunnelProvider::Command { command } => detect_from_command(command).await,
         }
     }
 
@@ -75,42 +153,34 @@ impl RpcUrlResolver {
         &self,
         tunnel_name: &str,
         ws_rpc_endpoint_port: Option<u16>,
-    ) -> Result<String, String> {
-        let mut attempts_left = 100_i32;
-        let res = loop {
-            let tunnel_name = std::borrow::Cow::Owned(tunnel_name.to_owned());
-            let result = self
-                .ngrok_client
-                .call(&ngrok_api::data::request::TunnelInfo, (tunnel_name,))
-                .await;
-
-            let err = match result {
-                Ok(res) => break res,
-                Err(err) => match err {
-                    ngrok_api::client::Error::BadStatus(status) if status == 404 => err,
-                    ngrok_api::client::Error::Reqwest(ref reqwest_error)
-                        if reqwest_error.is_redirect()
-                            || reqwest_error.is_status()
-                            || reqwest_error.is_timeout()
-                            || reqwest_error.is_request()
-                            || reqwest_error.is_connect()
-                            || reqwest_error.is_body()
-                            || reqwest_error.is_decode() =>
-                    {
-                        err
-                    }
-                    err => return Err(format!("unable to detect the RPC URL from ngrok: {err}")),
-                },
-            };
+    ) -> Attempt {
+        let tunnel_name = std::borrow::Cow::Owned(tunnel_name.to_owned());
+        let result = self
+            .ngrok_client
+            .call(&ngrok_api::data::request::TunnelInfo, (tunnel_name,))
+            .await;
 
-            if attempts_left <= 0 {
-                return Err(format!("ngrok did not start the tunnel in time: {err}"));
-            }
-            attempts_left = attempts_left
-                .checked_sub(1)
-                .expect("internal number of left attempts is valid; qed");
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        let res = match result {
+            Ok(res) => res,
+            Err(err) => match err {
+                ngrok_api::client::Error::BadStatus(status) if status == 404 => {
+                    return Attempt::NotReady(err.to_string())
+                }
+                ngrok_api::client::Error::Reqwest(ref reqwest_error)
+                    if reqwest_error.is_redirect()
+                        || reqwest_error.is_status()
+                        || reqwest_error.is_timeout()
+                        || reqwest_error.is_request()
+                        || reqwest_error.is_connect()
+                        || reqwest_error.is_body()
+                        || reqwest_error.is_decode() =>
+                {
+                    return Attempt::NotReady(err.to_string())
+                }
+                err => return Attempt::Failed(err.to_string()),
+            },
         };
+
         let mut public_url = res.public_url;
         if let Some(ws_rpc_endpoint_port) = ws_rpc_endpoint_port {
             if res
@@ -121,6 +191,268 @@ impl RpcUrlResolver {
                 public_url = public_url.replacen("https", "wss", 1);
             }
         }
-        Ok(public_url)
+        Attempt::Detected(public_url)
+    }
+
+    /// Detect the RPC URL from the cloudflared metrics server.
+    /// Returns the public URL of the quick tunnel.
+    /// Assumes that the tunnel points to the RPC endpoint.
+    async fn detect_from_cloudflared(&self, metrics_url: &str, ws: bool) -> Attempt {
+        let url = format!("{}/quicktunnel", metrics_url.trim_end_matches('/'));
+        let result = async {
+            self.http_client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<CloudflaredQuickTunnel>()
+                .await
+        }
+        .await;
+
+        match result {
+            // The metrics server is up before the tunnel is.
+            Ok(quick_tunnel) if quick_tunnel.hostname.is_empty() => {
+                Attempt::NotReady("the quick tunnel has no hostname yet".to_owned())
+            }
+            Ok(quick_tunnel) => {
+                let scheme = if ws { "wss" } else { "https" };
+                Attempt::Detected(format!("{scheme}://{}", quick_tunnel.hostname))
+            }
+            Err(err) if err.is_connect() || err.is_timeout() || err.is_status() => {
+                Attempt::NotReady(err.to_string())
+            }
+            Err(err) => Attempt::Failed(err.to_string()),
+        }
+    }
+}
+
+/// Detect the RPC URL from a file.
+/// Returns the first line of the file.
+async fn detect_from_file(path: &std::path::Path) -> Attempt {
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => match first_line(&contents) {
+            Some(url) => Attempt::Detected(url),
+            // The file is created before the URL is written into it.
+            None => Attempt::NotReady(format!("{} is empty", path.display())),
+        },
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            Attempt::NotReady(format!("{}: {err}", path.display()))
+        }
+        Err(err) => Attempt::Failed(format!("{}: {err}", path.display())),
+    }
+}
+
+/// Detect the RPC URL from the output of a shell command.
+/// Returns the first line of the command output; the command is expected to wait for the tunnel
+/// on its own.
+async fn detect_from_command(command: &str) -> Result<String, String> {
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .await
+        .map_err(|err| format!("unable to run the RPC URL command: {err}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "the RPC URL command has failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    first_line(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "the RPC URL command has printed no URL".to_owned())
+}
+
+/// Get the first non-empty line of the text.
+fn first_line(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Make the detection attempts until the tunnel is ready, or until the attempts run out.
+async fn retry_until_ready<F, Fut>(
+    tunnel_provider: &TunnelProvider,
+    mut attempt: F,
+) -> Result<String, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Attempt>,
+{
+    let name = tunnel_provider.name();
+    let mut attempts_left = 100_i32;
+    loop {
+        let err = match attempt().await {
+            Attempt::Detected(url) => return Ok(url),
+            Attempt::NotReady(err) => err,
+            Attempt::Failed(err) => {
+                return Err(format!("unable to detect the RPC URL from {name}: {err}"))
+            }
+        };
+
+        if attempts_left <= 0 {
+            return Err(format!("{name} did not start the tunnel in time: {err}"));
+        }
+        attempts_left = attempts_left
+            .checked_sub(1)
+            .expect("internal number of left attempts is valid; qed");
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    /// Create a resolver with the ngrok client pointing to the given mock server.
+    fn resolver(mock_server: &MockServer) -> RpcUrlResolver {
+        RpcUrlResolver {
+            ngrok_client: ngrok_api::client::Client::new(
+                reqwest::Client::new(),
+                format!("{}/api", mock_server.uri()).parse().unwrap(),
+            )
+            .unwrap(),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn detects_from_ngrok() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/api/tunnels/command_line"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "uri": "/api/tunnels/command_line",
+                "name": "command_line",
+                "public_url": "https://example.ngrok.io",
+                "proto": "https",
+                "config": {
+                    "addr": "http://localhost:9944",
+                    "inspect": true,
+                },
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let detect = |ws_rpc_endpoint_port| {
+            RpcUrl::DetectFromTunnel(TunnelProvider::Ngrok {
+                tunnel_name: "command_line".to_owned(),
+                ws_rpc_endpoint_port,
+            })
+        };
+        let resolver = resolver(&mock_server);
+
+        assert_eq!(
+            resolver.resolve(&detect(Some(9944))).await.unwrap(),
+            "wss://example.ngrok.io"
+        );
+        assert_eq!(
+            resolver.resolve(&detect(None)).await.unwrap(),
+            "https://example.ngrok.io"
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_from_cloudflared() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/quicktunnel"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "hostname": "example.trycloudflare.com",
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let detect = |ws| {
+            RpcUrl::DetectFromTunnel(TunnelProvider::Cloudflared {
+                metrics_url: mock_server.uri(),
+                ws,
+            })
+        };
+        let resolver = resolver(&mock_server);
+
+        assert_eq!(
+            resolver.resolve(&detect(true)).await.unwrap(),
+            "wss://example.trycloudflare.com"
+        );
+        assert_eq!(
+            resolver.resolve(&detect(false)).await.unwrap(),
+            "https://example.trycloudflare.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn cloudflared_fails_on_unexpected_response() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/quicktunnel"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not a tunnel"))
+            .mount(&mock_server)
+            .await;
+
+        let err = resolver(&mock_server)
+            .resolve(&RpcUrl::DetectFromTunnel(TunnelProvider::Cloudflared {
+                metrics_url: mock_server.uri(),
+                ws: true,
+            }))
+            .await
+            .unwrap_err();
+
+        assert!(err.starts_with("unable to detect the RPC URL from cloudflared: "));
+    }
+
+    #[tokio::test]
+    async fn detects_from_file() {
+        let mock_server = MockServer::start().await;
+
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::write(&path, "\nhttps://example.com\nhttps://other.example.com\n").unwrap();
+
+        let result = resolver(&mock_server)
+            .resolve(&RpcUrl::DetectFromTunnel(TunnelProvider::File {
+                path: path.to_path_buf(),
+            }))
+            .await;
+
+        assert_eq!(result.unwrap(), "https://example.com");
+    }
+
+    #[tokio::test]
+    async fn detects_from_command() {
+        let mock_server = MockServer::start().await;
+
+        let detect = |command: &str| {
+            RpcUrl::DetectFromTunnel(TunnelProvider::Command {
+                command: command.to_owned(),
+            })
+        };
+        let resolver = resolver(&mock_server);
+
+        assert_eq!(
+            resolver
+                .resolve(&detect("echo https://example.com"))
+                .await
+                .unwrap(),
+            "https://example.com"
+        );
+        assert_eq!(
+            resolver.resolve(&detect("true")).await.unwrap_err(),
+            "the RPC URL command has printed no URL"
+        );
+        assert!(resolver
+            .resolve(&detect("exit 3"))
+            .await
+            .unwrap_err()
+            .starts_with("the RPC URL command has failed with "));
     }
 }
@@ -235,6 +235,7 @@ pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError
     let Configuration {
         substrate: config,
         bioauth_flow: bioauth_flow_config,
+        bioauth_reminder: bioauth_reminder_config,
         ethereum_rpc: ethereum_rpc_config,
         ..
     } = config;
@@ -272,6 +273,11 @@ pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError
     let force_authoring = config.force_authoring;
     let backoff_authoring_blocks: Option<()> = None;
     let prometheus_registry = config.prometheus_registry().cloned();
+    let bioauth_reminder_metrics = prometheus_registry
+        .as_ref()
+        .map(crate::bioauth_reminder::Metrics::register)
+        .transpose()
+        .map_err(|err| ServiceError::Other(err.to_string()))?;
     let eth_filter_pool: Option<FilterPool> = Some(Arc::new(Mutex::new(BTreeMap::new())));
     let eth_fee_history_cache: FeeHistoryCache = Arc::new(Mutex::new(BTreeMap::new()));
     let eth_fee_history_limit = ethereum_rpc_config.fee_history_limit;
@@ -356,6 +362,7 @@ pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError
             block_announce_validator_builder: None,
             warp_sync_params: Some(WarpSyncParams::WithProvider(warp_sync)),
         })?;
+    let bioauth_reminder_sync_oracle = Arc::clone(&sync_service);
 
     if config.offchain_worker.enabled {
         sc_service::build_offchain_workers(
@@ -371,10 +378,35 @@ pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError
         reqwest: reqwest::Client::new(),
     });
 
+    let cosigning_robonode_clients = bioauth_flow_config
+        .cosigning_robonode_urls
+        .iter()
+        .map(|robonode_url| {
+            Arc::new(robonode_client::Client {
+                base_url: robonode_url.clone(),
+                reqwest: reqwest::Client::new(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let dev_robonode = bioauth_flow_config
+        .dev_robonode_secret_key
+        .map(|secret_key| {
+            let dev_robonode = bioauth_flow_rpc::DevRobonode::new(
+                robonode_crypto::SigningKey::from_bytes(&secret_key),
+            );
+            warn!(
+                message = "Using the dev robonode, auth tickets are issued without biometric checks",
+                public_key = %hex::encode(dev_robonode.public_key().as_bytes()),
+            );
+            Arc::new(dev_robonode)
+        });
+
     let (rpc_extensions_builder, grandpa_shared_voter_state_cloned) = {
         let client = Arc::clone(&client);
         let pool = Arc::clone(&transaction_pool);
         let robonode_client = Arc::clone(&robonode_client);
+        let cosigning_robonode_clients = cosigning_robonode_clients.clone();
         let is_authority = role.is_authority();
         let bioauth_validator_key_extractor = Arc::clone(&account_validator_key_extractor);
         let bioauth_validator_signer_factory = {
@@ -443,8 +475,11 @@ pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError
                 is_authority,
                 bioauth: humanode_rpc::BioauthDeps {
                     robonode_client: Arc::clone(&robonode_client),
+                    cosigning_robonode_clients: cosigning_robonode_clients.clone(),
+                    dev_robonode: dev_robonode.clone(),
                     bioauth_validator_signer_factory: Arc::clone(&bioauth_validator_signer_factory),
                     bioauth_validator_key_extractor: Arc::clone(&bioauth_validator_key_extractor),
+                    keystore: Arc::clone(&keystore),
                 },
                 babe: humanode_rpc::BabeDeps {
                     babe_worker_handle: babe_worker_handle.clone(),
@@ -650,6 +685,23 @@ pub async fn new_full(config: Configuration) -> Result<TaskManager, ServiceError
         }
     };
 
+    if let Some(bioauth_reminder_config) = bioauth_reminder_config {
+        let bioauth_reminder = crate::bioauth_reminder::Reminder::new(
+            bioauth_reminder_config,
+            webapp_qrcode.ok(),
+            bioauth_reminder_metrics,
+        );
+        task_manager.spawn_handle().spawn(
+            "bioauth-reminder",
+            Some("bioauth"),
+            bioauth_reminder.run(
+                client,
+                account_validator_key_extractor,
+                bioauth_reminder_sync_oracle,
+            ),
+        );
+    }
+
     Ok(task_manager)
 }
 
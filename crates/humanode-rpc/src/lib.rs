@@ -4,7 +4,7 @@ use std::{collections::BTreeMap, sync::Arc};
 
 use author_ext_api::AuthorExtApi;
 use author_ext_rpc::{AuthorExt, AuthorExtServer};
-use bioauth_flow_rpc::{signer, Bioauth, BioauthServer, Signer};
+use bioauth_flow_rpc::{signer, Bioauth, BioauthServer, DevRobonode, Signer};
 use bioauth_keys::traits::KeyExtractor as KeyExtractorT;
 use evm_tracing_rpc::{debug::core::DebugServer, trace::core::TraceServer};
 use fc_rpc::{
@@ -14,7 +14,8 @@ use fc_rpc::{
 use fc_rpc_core::types::{FeeHistoryCache, FilterPool};
 use fc_storage::OverrideHandle;
 use humanode_runtime::{
-    opaque::Block, AccountId, Balance, BlockNumber, Hash, Index, UnixMilliseconds,
+    opaque::Block, AccountId, Balance, BlockNumber, DeauthenticationReason, Hash, Index,
+    UnixMilliseconds,
 };
 use jsonrpsee::RpcModule;
 use sc_client_api::{
@@ -34,7 +35,7 @@ pub use sc_rpc_api::DenyUnsafe;
 use sc_rpc_spec_v2::chain_spec::{ChainSpec, ChainSpecApiServer};
 use sc_transaction_pool::{ChainApi, Pool};
 use sc_transaction_pool_api::TransactionPool;
-use sp_api::{CallApiAt, Encode, ProvideRuntimeApi};
+use sp_api::{CallApiAt, Decode, Encode, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::SelectChain;
@@ -52,10 +53,16 @@ pub struct AuthorExtDeps<VKE> {
 pub struct BioauthDeps<VKE, VSF> {
     /// An ready robonode API client to tunnel the calls to.
     pub robonode_client: Arc<robonode_client::Client>,
+    /// The API clients of the robonodes to co-sign the auth tickets with.
+    pub cosigning_robonode_clients: Vec<Arc<robonode_client::Client>>,
+    /// The in-process dev robonode to use instead of the robonode, if any.
+    pub dev_robonode: Option<Arc<DevRobonode>>,
     /// Extracts the currently used bioauth validator key.
     pub bioauth_validator_key_extractor: VKE,
     /// A factory for making signers by the bioauth validator public keys.
     pub bioauth_validator_signer_factory: VSF,
+    /// The keystore to generate the session keys at during the onboarding.
+    pub keystore: KeystorePtr,
 }
 
 /// Extra dependencies for BABE.
@@ -170,14 +177,21 @@ where
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: bioauth_flow_api::BioauthFlowApi<Block, VKE::PublicKeyType, UnixMilliseconds>,
+    C::Api: bioauth_flow_api::BioauthStateApi<
+        Block,
+        VKE::PublicKeyType,
+        UnixMilliseconds,
+        DeauthenticationReason,
+    >,
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
     C::Api: AuthorExtApi<Block, VKE::PublicKeyType>,
+    C::Api: sp_session::SessionKeys<Block>,
     C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
     C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
     P: TransactionPool<Block = Block> + 'static,
     VKE: KeyExtractorT + Send + Sync + 'static,
-    VKE::PublicKeyType: Encode + AsRef<[u8]> + Clone + Send + Sync + sp_runtime::Serialize,
+    VKE::PublicKeyType: Encode + Decode + AsRef<[u8]> + Clone + Send + Sync + sp_runtime::Serialize,
     VKE::Error: std::fmt::Debug,
     VSF: signer::Factory<Vec<u8>, VKE::PublicKeyType> + Send + Sync + 'static,
     VSF::Signer: Send + Sync + 'static,
@@ -216,8 +230,11 @@ where
 
     let BioauthDeps {
         robonode_client,
+        cosigning_robonode_clients,
+        dev_robonode,
         bioauth_validator_key_extractor,
         bioauth_validator_signer_factory,
+        keystore: bioauth_keystore,
     } = bioauth;
 
     let BabeDeps {
@@ -296,11 +313,15 @@ where
     io.merge(
         Bioauth::new(
             robonode_client,
+            cosigning_robonode_clients,
+            dev_robonode,
             bioauth_validator_key_extractor,
             bioauth_validator_signer_factory,
             Arc::clone(&client),
             Arc::clone(&pool),
+            bioauth_keystore,
             deny_unsafe,
+            Arc::clone(&subscription_task_executor),
         )
         .into_rpc(),
     )?;
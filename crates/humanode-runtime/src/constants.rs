@@ -38,7 +38,13 @@ pub mod bioauth {
 
     pub const MAX_AUTHENTICATIONS: u32 = 3 * 1024;
     pub const MAX_NONCES: u32 = 10000 * MAX_AUTHENTICATIONS;
+    pub const MAX_NONCE_EXPIRATIONS_PER_BLOCK: u32 = 256;
+    pub const MAX_ROBONODE_PUBLIC_KEYS: u32 = 4;
     pub const AUTHENTICATIONS_EXPIRE_AFTER: UnixMilliseconds = 7 * super::timestamp::TIMESTAMP_DAY;
+    pub const AUTH_TICKETS_EXPIRE_AFTER: UnixMilliseconds = super::timestamp::TIMESTAMP_HOUR;
+    pub const AUTH_TICKETS_MAX_CLOCK_DRIFT: UnixMilliseconds =
+        5 * super::timestamp::TIMESTAMP_MINUTE;
+    pub const AUTHENTICATIONS_RENEWAL_WINDOW: UnixMilliseconds = super::timestamp::TIMESTAMP_DAY;
 }
 
 /// Babe constants.
@@ -3,10 +3,13 @@
 
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
 use sp_std::prelude::*;
 
 /// Define a possible deauthentication reason.
 #[derive(Clone, PartialEq, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum DeauthenticationReason {
     /// Some offence has been received.
     Offence,
@@ -103,13 +103,17 @@ pub mod utils;
 
 pub use constants::{
     babe::{BABE_GENESIS_EPOCH_CONFIG, EPOCH_DURATION_IN_SLOTS, MAX_AUTHORITIES, SLOT_DURATION},
-    bioauth::{AUTHENTICATIONS_EXPIRE_AFTER, MAX_AUTHENTICATIONS, MAX_NONCES},
+    bioauth::{
+        AUTHENTICATIONS_EXPIRE_AFTER, AUTHENTICATIONS_RENEWAL_WINDOW, AUTH_TICKETS_EXPIRE_AFTER,
+        AUTH_TICKETS_MAX_CLOCK_DRIFT, MAX_AUTHENTICATIONS, MAX_NONCES,
+        MAX_NONCE_EXPIRATIONS_PER_BLOCK, MAX_ROBONODE_PUBLIC_KEYS,
+    },
     block_time::MILLISECS_PER_BLOCK,
     equivocation::REPORT_LONGEVITY,
     ethereum::EXTRA_DATA_LENGTH,
     im_online::{MAX_KEYS, MAX_PEER_DATA_ENCODING_SIZE, MAX_PEER_IN_HEARTBEATS},
 };
-use deauthentication_reason::DeauthenticationReason;
+pub use deauthentication_reason::DeauthenticationReason;
 use static_assertions::const_assert;
 
 /// An index to a block.
@@ -220,10 +224,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 131,
+    spec_version: 132,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
     state_version: 1,
 };
 
@@ -488,22 +492,26 @@ impl pallet_sudo::Config for Runtime {
 pub struct PrimitiveAuthTicketConverter;
 
 pub enum PrimitiveAuthTicketConverterError {
-    Ticket(codec::Error),
+    Ticket(primitives_auth_ticket::DecodeError),
     PublicKey(()),
 }
 
-impl pallet_bioauth::TryConvert<OpaqueAuthTicket, pallet_bioauth::AuthTicket<BioauthId>>
-    for PrimitiveAuthTicketConverter
+impl
+    pallet_bioauth::TryConvert<
+        OpaqueAuthTicket,
+        pallet_bioauth::AuthTicket<BioauthId, UnixMilliseconds>,
+    > for PrimitiveAuthTicketConverter
 {
     type Error = PrimitiveAuthTicketConverterError;
 
     fn try_convert(
         value: OpaqueAuthTicket,
-    ) -> Result<pallet_bioauth::AuthTicket<BioauthId>, Self::Error> {
+    ) -> Result<pallet_bioauth::AuthTicket<BioauthId, UnixMilliseconds>, Self::Error> {
         #[allow(clippy::needless_borrow)]
         let primitives_auth_ticket::AuthTicket {
             public_key,
             authentication_nonce: nonce,
+            issued_at,
         } = (&value)
             .try_into()
             .map_err(PrimitiveAuthTicketConverterError::Ticket)?;
@@ -513,7 +521,11 @@ impl pallet_bioauth::TryConvert<OpaqueAuthTicket, pallet_bioauth::AuthTicket<Bio
             .try_into()
             .map_err(PrimitiveAuthTicketConverterError::PublicKey)?;
 
-        Ok(AuthTicket { public_key, nonce })
+        Ok(AuthTicket {
+            public_key,
+            nonce,
+            issued_at,
+        })
     }
 }
 
@@ -537,9 +549,14 @@ impl pallet_bioauth::Config for Runtime {
     type DisplayMoment = display_moment::DisplayMoment;
     type CurrentMoment = CurrentMoment;
     type AuthenticationsExpireAfter = ConstU64<AUTHENTICATIONS_EXPIRE_AFTER>;
+    type AuthTicketsExpireAfter = ConstU64<AUTH_TICKETS_EXPIRE_AFTER>;
+    type AuthTicketsMaxClockDrift = ConstU64<AUTH_TICKETS_MAX_CLOCK_DRIFT>;
+    type AuthenticationsRenewalWindow = ConstU64<AUTHENTICATIONS_RENEWAL_WINDOW>;
     type WeightInfo = weights::pallet_bioauth::WeightInfo<Runtime>;
     type MaxAuthentications = ConstU32<MAX_AUTHENTICATIONS>;
     type MaxNonces = ConstU32<MAX_NONCES>;
+    type MaxNonceExpirationsPerBlock = ConstU32<MAX_NONCE_EXPIRATIONS_PER_BLOCK>;
+    type MaxRobonodePublicKeys = ConstU32<MAX_ROBONODE_PUBLIC_KEYS>;
     type BeforeAuthHook = ();
     type AfterAuthHook = ();
     type DeauthenticationReason = DeauthenticationReason;
@@ -550,10 +567,12 @@ impl pallet_bioauth::benchmarking::AuthTicketBuilder for Runtime {
     fn build(
         public_key: Vec<u8>,
         authentication_nonce: Vec<u8>,
+        issued_at: UnixMilliseconds,
     ) -> <Self as pallet_bioauth::Config>::OpaqueAuthTicket {
         OpaqueAuthTicket::from(&primitives_auth_ticket::AuthTicket {
             public_key,
             authentication_nonce,
+            issued_at,
         })
     }
 }
@@ -907,7 +926,9 @@ pub type Executive = frame_executive::Executive<
     Runtime,
     AllPalletsWithSystem,
     (
-        pallet_bioauth::migrations::consumed_auth_ticket_nonces_cleaner::ConsumedAuthTicketNoncesCleaner<Runtime>,
+        pallet_bioauth::migrations::v1::MigrationToV1<Runtime>,
+        pallet_bioauth::migrations::v2::MigrationToV2<Runtime>,
+        pallet_bioauth::migrations::v3::MigrationToV3<Runtime>,
         evm_nonces_recovery::MigrationBrokenNoncesRecovery<
             Runtime,
             ConstU32<1000>,
@@ -1123,6 +1144,7 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl author_ext_api::AuthorExtApi<Block, KeystoreBioauthAccountId> for Runtime {
         fn create_signed_set_keys_extrinsic(
             id: &KeystoreBioauthAccountId,
@@ -1150,22 +1172,28 @@ impl_runtime_apis! {
 
             Ok(<Block as BlockT>::Extrinsic::new_signed(call, address, signature, extra))
         }
+
+        fn session_keys(id: &KeystoreBioauthAccountId) -> Option<Vec<u8>> {
+            let account_id =
+                AccountId::new(
+                    <<KeystoreBioauthAccountId as sp_application_crypto::AppCrypto>::Public as sp_application_crypto::AppPublic>::Generic::from(id.clone()).0
+                );
+            pallet_session::NextKeys::<Runtime>::get(account_id).map(|keys| keys.encode())
+        }
     }
 
+    #[api_version(3)]
     impl bioauth_flow_api::BioauthFlowApi<Block, KeystoreBioauthAccountId, UnixMilliseconds> for Runtime {
         fn bioauth_status(id: &KeystoreBioauthAccountId) -> bioauth_flow_api::BioauthStatus<UnixMilliseconds> {
             let id =
                 AccountId::new(
                     <<KeystoreBioauthAccountId as sp_application_crypto::AppCrypto>::Public as sp_application_crypto::AppPublic>::Generic::from(id.clone()).0
                 );
-            let active_authentications = Bioauth::active_authentications().into_inner();
-            let maybe_active_authentication = active_authentications
-                .iter()
-                .find(|stored_public_key| stored_public_key.public_key == id);
-            match maybe_active_authentication {
+            match Bioauth::active_authentication(&id) {
                 None => bioauth_flow_api::BioauthStatus::Inactive,
                 Some(v) => bioauth_flow_api::BioauthStatus::Active {
                     expires_at: v.expires_at,
+                    renewable_from: Bioauth::authentication_renewable_from(&v),
                 },
             }
         }
@@ -1176,7 +1204,21 @@ impl_runtime_apis! {
         ) -> <Block as BlockT>::Extrinsic {
             let authenticate = pallet_bioauth::Authenticate {
                 ticket: auth_ticket.into(),
-                ticket_signature: auth_ticket_signature,
+                ticket_signatures: vec![auth_ticket_signature],
+            };
+
+            let call = pallet_bioauth::Call::authenticate { req: authenticate };
+
+            <Block as BlockT>::Extrinsic::new_unsigned(call.into())
+        }
+
+        fn create_multisigned_authenticate_extrinsic(
+            auth_ticket: Vec<u8>,
+            auth_ticket_signatures: Vec<Vec<u8>>
+        ) -> <Block as BlockT>::Extrinsic {
+            let authenticate = pallet_bioauth::Authenticate {
+                ticket: auth_ticket.into(),
+                ticket_signatures: auth_ticket_signatures,
             };
 
             let call = pallet_bioauth::Call::authenticate { req: authenticate };
@@ -1185,6 +1227,56 @@ impl_runtime_apis! {
         }
     }
 
+    impl bioauth_flow_api::BioauthStateApi<Block, KeystoreBioauthAccountId, UnixMilliseconds, DeauthenticationReason> for Runtime {
+        fn active_authentications() -> Vec<bioauth_flow_api::ActiveAuthentication<KeystoreBioauthAccountId, UnixMilliseconds>> {
+            Bioauth::active_authentications()
+                .into_iter()
+                .map(|authentication| bioauth_flow_api::ActiveAuthentication {
+                    id: KeystoreBioauthAccountId::from(sp_core::sr25519::Public::from_raw(
+                        authentication.public_key.clone().into(),
+                    )),
+                    expires_at: authentication.expires_at,
+                    renewable_from: Bioauth::authentication_renewable_from(&authentication),
+                })
+                .collect()
+        }
+
+        fn config() -> bioauth_flow_api::BioauthConfig<UnixMilliseconds> {
+            bioauth_flow_api::BioauthConfig {
+                // The robonode public key encodes as its raw bytes.
+                robonode_public_keys: Bioauth::active_robonode_public_keys()
+                    .into_iter()
+                    .map(|robonode_public_key| robonode_public_key.encode())
+                    .collect(),
+                robonode_signatures_threshold: Bioauth::robonode_signatures_threshold(),
+                authentications_expire_after: AUTHENTICATIONS_EXPIRE_AFTER,
+                authentications_renewal_window: AUTHENTICATIONS_RENEWAL_WINDOW,
+                auth_tickets_expire_after: AUTH_TICKETS_EXPIRE_AFTER,
+                active_authentications: pallet_bioauth::ActiveAuthentications::<Runtime>::count(),
+                max_authentications: MAX_AUTHENTICATIONS,
+                consumed_auth_ticket_nonces: pallet_bioauth::ConsumedAuthTicketNonces::<Runtime>::count(),
+                max_nonces: MAX_NONCES,
+            }
+        }
+
+        fn current_moment() -> UnixMilliseconds {
+            <CurrentMoment as pallet_bioauth::CurrentMoment<UnixMilliseconds>>::now()
+        }
+
+        fn deauthentication_reason(id: &KeystoreBioauthAccountId) -> Option<DeauthenticationReason> {
+            let id =
+                AccountId::new(
+                    <<KeystoreBioauthAccountId as sp_application_crypto::AppCrypto>::Public as sp_application_crypto::AppPublic>::Generic::from(id.clone()).0
+                );
+            // The deauthentications are only recorded in the events of the block they happen at.
+            System::read_events_no_consensus().find_map(|record| match record.event {
+                RuntimeEvent::Bioauth(pallet_bioauth::Event::AuthenticationsRemoved { removed, reason })
+                    if removed.iter().any(|authentication| authentication.public_key == id) => Some(reason),
+                _ => None,
+            })
+        }
+    }
+
     impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
         fn convert_transaction(transaction: EthereumTransaction) -> <Block as BlockT>::Extrinsic {
             UncheckedExtrinsic::new_unsigned(
@@ -91,6 +91,11 @@ mod benchmarks {
                         "0000000000000000000000000000000000000000000000000000000000000000"
                     ))
                 }
+                pallet_bioauth::benchmarking::RobonodePublicKeyBuilderValue::Indexed(idx) => {
+                    // Derive a valid key, so that the signatures are actually checked against it.
+                    let signing_key = robonode_crypto::SigningKey::from_bytes(&[idx; 32]);
+                    robonode::PublicKey(signing_key.verifying_key().to_bytes())
+                }
             }
         }
     }
@@ -1,5 +1,21 @@
-// DO NOT EDIT!
-//! Autogenerated weights for `pallet_bioauth`
+//! Estimated weights for `pallet_bioauth`.
+//!
+//! NOT generated by the benchmarks: the pallet calls and storage layout have changed since
+//! the weights were last generated, and the benchmarks have not been re-run on the reference
+//! hardware yet. Regenerate this file with `utils/weights/benchmark-all` to replace
+//! the estimates below.
+//!
+//! The estimates are derived as follows:
+//!
+//! - The database reads and writes are counted from the worst case code paths.
+//! - The execution times are scaled from the last generated weights, where
+//!   the single-signature `authenticate` took 123 µs, and the signature verification is
+//!   assumed to take at most half of that.
+//! - The per-entry execution time of the storage map scans and removals is assumed to be 1 µs,
+//!   which is about 30 times the per-entry time of the list scans in the last generated
+//!   `on_initialize`, to cover the map keys decoding.
+//! - The proof sizes are zero, as for the rest of the runtime weights, since the chain does not
+//!   meter the proof size.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -11,38 +27,77 @@ use sp_std::marker::PhantomData;
 /// Weight functions for `pallet_bioauth`.
 pub struct WeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> pallet_bioauth::WeightInfo for WeightInfo<T> {
-  /// The range of component `n` is `[0, 30719999]`.
-  /// The range of component `a` is `[0, 3071]`.
-  fn authenticate(a: u32, n: u32, ) -> Weight {
-    // Proof Size summary in bytes:
-    //  Measured:  `183 + a * (40 ±0) + n * (19 ±0)`
-    //  Estimated: `0`
-    // Minimum execution time: 123_000_000 picoseconds.
-    Weight::from_parts(123_000_000, 0)
-      // Standard Error: 96_713_286
-      .saturating_add(Weight::from_parts(27_114_099, 0).saturating_mul(a.into()))
-      // Standard Error: 9_668
-      .saturating_add(Weight::from_parts(122_564, 0).saturating_mul(n.into()))
-      .saturating_add(T::DbWeight::get().reads(4))
-      .saturating_add(T::DbWeight::get().writes(2))
+  /// The range of component `s` is `[1, 4]`.
+  fn authenticate(s: u32, ) -> Weight {
+    // The measured single-signature call without the signature verification, plus the worst
+    // case of each signature being checked against each of the 4 accepted robonode public keys;
+    // the 4 signatures case matches the 16 verifications estimate.
+    Weight::from_parts(68_000_000, 0)
+      .saturating_add(Weight::from_parts(208_000_000, 0).saturating_mul(s.into()))
+      // The threshold, the robonode public keys, the current moment, the consumed nonce and
+      // its counter, the pending consumed nonces, the active authentication, its counter and
+      // next expiration.
+      .saturating_add(T::DbWeight::get().reads(10))
+      // The consumed nonce, its counter and expiration index entry, the active authentication,
+      // its counter and next expiration.
+      .saturating_add(T::DbWeight::get().writes(6))
   }
   /// The range of component `a` is `[0, 3072]`.
-  fn set_robonode_public_key(_a: u32, ) -> Weight {
-    // Proof Size summary in bytes:
-    //  Measured:  `0`
-    //  Estimated: `0`
-    // Minimum execution time: 3_000_000 picoseconds.
+  fn set_robonode_public_key(a: u32, ) -> Weight {
+    // The measured call, plus the removal of each active authentication.
+    Weight::from_parts(4_000_000, 0)
+      .saturating_add(Weight::from_parts(1_000_000, 0).saturating_mul(a.into()))
+      // The robonode public keys, the threshold and the current moment.
+      .saturating_add(T::DbWeight::get().reads(3))
+      // The robonode public keys, the threshold, the active authentications counter and next
+      // expiration, and the events.
+      .saturating_add(T::DbWeight::get().writes(5))
+      .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
+  }
+  fn add_robonode_public_key() -> Weight {
+    // The same as the measured `set_robonode_public_key` with no authentications, plus
+    // the scan of at most 4 robonode public keys.
+    Weight::from_parts(8_000_000, 0)
+      // The robonode public keys and the current moment.
+      .saturating_add(T::DbWeight::get().reads(2))
+      // The robonode public keys and the event.
+      .saturating_add(T::DbWeight::get().writes(2))
+  }
+  fn retire_robonode_public_key() -> Weight {
+    // The same as `add_robonode_public_key`, plus the immediate retirement.
+    Weight::from_parts(12_000_000, 0)
+      // The robonode public keys (twice), the threshold and the current moment.
+      .saturating_add(T::DbWeight::get().reads(4))
+      // The robonode public keys (twice) and the event.
+      .saturating_add(T::DbWeight::get().writes(3))
+  }
+  fn set_robonode_signatures_threshold() -> Weight {
+    // The same as the measured `set_robonode_public_key` with no authentications.
     Weight::from_parts(4_000_000, 0)
+      // The robonode public keys.
+      .saturating_add(T::DbWeight::get().reads(1))
+      // The threshold and the event.
       .saturating_add(T::DbWeight::get().writes(2))
   }
   /// The range of component `a` is `[0, 3072]`.
-  fn on_initialize(_a: u32, ) -> Weight {
-    // Proof Size summary in bytes:
-    //  Measured:  `141 + a * (40 ±0)`
-    //  Estimated: `0`
-    // Minimum execution time: 6_000_000 picoseconds.
-    Weight::from_parts(99_000_000, 0)
-      .saturating_add(T::DbWeight::get().reads(2))
-      .saturating_add(T::DbWeight::get().writes(1))
+  /// The range of component `n` is `[0, 256]`.
+  fn on_initialize(a: u32, n: u32, ) -> Weight {
+    // The measured call with no authentications, plus the scan of each authentication, and
+    // the removal of each expired nonce.
+    Weight::from_parts(6_000_000, 0)
+      .saturating_add(Weight::from_parts(1_000_000, 0).saturating_mul(a.into()))
+      .saturating_add(Weight::from_parts(1_000_000, 0).saturating_mul(n.into()))
+      // The current moment, the robonode public keys, the next authentications expiration,
+      // the authentications counter, the first nonce expiration index entry and the pending
+      // consumed nonces.
+      .saturating_add(T::DbWeight::get().reads(6))
+      .saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+      // The nonce expiration index entry and the nonce itself.
+      .saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+      // The robonode public keys, the next authentications expiration, the counters, the pending
+      // consumed nonces and the events.
+      .saturating_add(T::DbWeight::get().writes(6))
+      .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(a.into())))
+      .saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
   }
 }
@@ -18,6 +18,9 @@ pub enum RobonodePublicKeyBuilderValue {
     A,
     /// Variant B.
     B,
+    /// A variant with the given index, for the benchmarks that need more distinct keys.
+    /// Must not match the key the [`AuthTicketSigner`] signs with.
+    Indexed(u8),
 }
 
 /// Provides the robonode public key to the benchmarks.
@@ -26,6 +29,16 @@ pub trait RobonodePublicKeyBuilder: pallet::Config {
     fn build(value: RobonodePublicKeyBuilderValue) -> <Self as pallet::Config>::RobonodePublicKey;
 }
 
+/// Accept the given robonode public key, activating it right away.
+fn accept_robonode_public_key<T: RobonodePublicKeyBuilder>(value: RobonodePublicKeyBuilderValue) {
+    Bioauth::<T>::add_robonode_public_key(
+        RawOrigin::Root.into(),
+        <T as RobonodePublicKeyBuilder>::build(value),
+        T::CurrentMoment::now(),
+    )
+    .unwrap();
+}
+
 /// Generate 32 bytes pubkey with prefix of our choice.
 /// NOTE: `prefix` and `idx` must be strictly under 32 bytes
 fn make_pubkey(prefix: &str, idx: u32) -> Vec<u8> {
@@ -51,8 +64,12 @@ fn make_nonce(prefix: &str, idx: u32) -> Vec<u8> {
 
 /// Enables construction of [`AuthTicket`]s deterministically.
 pub trait AuthTicketBuilder: pallet::Config {
-    /// Make `AuthTicket` with predetermined 32 bytes public key and nonce.
-    fn build(public_key: Vec<u8>, nonce: Vec<u8>) -> <Self as pallet::Config>::OpaqueAuthTicket;
+    /// Make `AuthTicket` with predetermined 32 bytes public key, nonce and issuance moment.
+    fn build(
+        public_key: Vec<u8>,
+        nonce: Vec<u8>,
+        issued_at: <Self as pallet::Config>::Moment,
+    ) -> <Self as pallet::Config>::OpaqueAuthTicket;
 }
 
 /// Enables generation of signature with robonode private key provided at runtime.
@@ -96,21 +113,40 @@ where
         expiry,
     );
 
-    let bounded_active_auths =
-        BoundedVec::<_, Runtime::MaxAuthentications>::try_from(active_auths).unwrap();
-    ActiveAuthentications::<Runtime>::put(bounded_active_auths);
+    for active_auth in &active_auths {
+        Bioauth::<Runtime>::insert_active_authentication(active_auth);
+    }
+}
+
+/// Convenient function to generate a [`ConsumedAuthTicketNonce`] struct in bulk with custom
+/// prefix for nonce.
+fn make_consumed_auth_ticket_nonces<Moment: Copy>(
+    prefix: &str,
+    count: u32,
+    expires_at: Moment,
+) -> Vec<ConsumedAuthTicketNonce<Moment>> {
+    (0..count)
+        .map(|i| ConsumedAuthTicketNonce {
+            nonce: BoundedAuthTicketNonce::try_from(make_nonce(prefix, i)).unwrap(),
+            expires_at,
+        })
+        .collect()
 }
 
 /// Populate the [`ConsumedAuthTicketNonces`] storage with generated data.
-fn populate_consumed_auth_ticket_nonces<Runtime: pallet::Config>(count: u32) {
-    let mut consumed_nonces: Vec<_> = vec![];
-    for i in 0..count {
-        let nonce = make_nonce("consumed_nonce", i);
-        consumed_nonces.push(BoundedAuthTicketNonce::try_from(nonce).unwrap());
+fn populate_consumed_auth_ticket_nonces<Runtime: pallet::Config>(count: u32)
+where
+    <Runtime as pallet::Config>::Moment: From<u64>,
+{
+    let expiry = Runtime::CurrentMoment::now() + (10u64).into();
+    let consumed_nonces = make_consumed_auth_ticket_nonces("consumed_nonce", count, expiry);
+
+    for consumed_nonce in &consumed_nonces {
+        Bioauth::<Runtime>::insert_consumed_auth_ticket_nonce(
+            &consumed_nonce.nonce,
+            consumed_nonce.expires_at,
+        );
     }
-    let bounded_consumed_nonces =
-        BoundedVec::<_, Runtime::MaxNonces>::try_from(consumed_nonces).unwrap();
-    ConsumedAuthTicketNonces::<Runtime>::put(bounded_consumed_nonces);
 }
 
 benchmarks! {
@@ -123,33 +159,59 @@ benchmarks! {
     }
 
     authenticate {
-        // Vary the amount of pre-populated active authentications and consumed nonces.
-        // Leave one space spare for the payload to be inserted in this call.
-        let a in 0 .. (T::MaxAuthentications::get() - 1) =>  populate_active_authentications::<T>(a);
-        let n in 0 .. (T::MaxNonces::get() - 1) => populate_consumed_auth_ticket_nonces::<T>(n);
+        // Vary the amount of the signatures.
+        let s in 1 .. (T::MaxRobonodePublicKeys::get());
 
-        // Create `authenticate` extrinsic payload.
+        // Pre-populate a constant, yet non-zero amount of active authentications and consumed
+        // nonces, as the lookups are not supposed to depend on their amount.
+        populate_active_authentications::<T>(10);
+        populate_consumed_auth_ticket_nonces::<T>(10);
+
+        // Fill up the robonode public keys with the keys that have not signed anything, and
+        // move the signing key to the end, so that every signature is checked against every key.
+        let other_robonode_public_keys = T::MaxRobonodePublicKeys::get() - u32::try_from(RobonodePublicKeys::<T>::get().len()).unwrap();
+        for idx in 0..other_robonode_public_keys {
+            accept_robonode_public_key::<T>(RobonodePublicKeyBuilderValue::Indexed(idx.try_into().unwrap()));
+        }
+        RobonodePublicKeys::<T>::mutate(|robonode_public_keys| {
+            let mut rotated = robonode_public_keys.clone().into_inner();
+            rotated.rotate_left(1);
+            *robonode_public_keys = rotated.try_into().unwrap();
+        });
+
+        // Create `authenticate` extrinsic payload, with the only valid signature coming last,
+        // after the signatures of the other auth tickets.
         let public_key = make_pubkey("new", T::MaxAuthentications::get());
         let nonce = make_nonce("nonce", T::MaxNonces::get());
-        let ticket = <T as AuthTicketBuilder>::build(public_key.clone(), nonce);
-        let ticket_signature = <T as AuthTicketSigner>::sign(&ticket);
+        let ticket = <T as AuthTicketBuilder>::build(public_key.clone(), nonce, T::CurrentMoment::now());
+        let mut ticket_signatures: Vec<_> = (1..s)
+            .map(|idx| {
+                let other_ticket = <T as AuthTicketBuilder>::build(
+                    public_key.clone(),
+                    make_nonce("other_nonce", idx),
+                    T::CurrentMoment::now(),
+                );
+                <T as AuthTicketSigner>::sign(&other_ticket)
+            })
+            .collect();
+        ticket_signatures.push(<T as AuthTicketSigner>::sign(&ticket));
         let req = Authenticate {
             ticket,
-            ticket_signature,
+            ticket_signatures,
         };
 
         // Capture some data used during the verification.
-        let active_authentications_before_len = ActiveAuthentications::<T>::get().len();
-        let consumed_auth_ticket_nonces_before_len = ConsumedAuthTicketNonces::<T>::get().len();
+        let active_authentications_before_len = ActiveAuthentications::<T>::count();
+        let consumed_auth_ticket_nonces_before_len = ConsumedAuthTicketNonces::<T>::count();
 
     }: _(RawOrigin::None, req)
     verify {
         // Verify that exactly one active authentication was added.
-        let active_authentications_after_len = ActiveAuthentications::<T>::get().len();
+        let active_authentications_after_len = ActiveAuthentications::<T>::count();
         assert_eq!(active_authentications_after_len - active_authentications_before_len, 1);
 
         // Verify that exactly one consumed auth ticket nonce was added.
-        let consumed_auth_ticket_nonces_after_len = ConsumedAuthTicketNonces::<T>::get().len();
+        let consumed_auth_ticket_nonces_after_len = ConsumedAuthTicketNonces::<T>::count();
         assert_eq!(consumed_auth_ticket_nonces_after_len - consumed_auth_ticket_nonces_before_len, 1);
     }
 
@@ -162,29 +224,73 @@ benchmarks! {
         populate_consumed_auth_ticket_nonces::<T>(10);
 
         // Capture this state for comparison.
-        let robonode_public_key_before = RobonodePublicKey::<T>::get();
-        let active_authentications_before = ActiveAuthentications::<T>::get();
-        let consumed_auth_ticket_nonces_before = ConsumedAuthTicketNonces::<T>::get();
+        let robonode_public_keys_before = Bioauth::<T>::active_robonode_public_keys();
+        let consumed_auth_ticket_nonces_before: Vec<_> = ConsumedAuthTicketNonces::<T>::iter().collect();
 
         // Prepare the [`set_robonode_public_key`] extrinsic argument.
         let new_robonode_public_key = <T as RobonodePublicKeyBuilder>::build(RobonodePublicKeyBuilderValue::B);
 
         // Self-check that the new key is different from the old one.
-        assert_ne!(robonode_public_key_before, new_robonode_public_key);
+        assert!(!robonode_public_keys_before.contains(&new_robonode_public_key));
 
     }: _(RawOrigin::Root, new_robonode_public_key.clone())
     verify {
-        let robonode_public_key_after = RobonodePublicKey::<T>::get();
-        let active_authentications_after = ActiveAuthentications::<T>::get();
-        let consumed_auth_ticket_nonces_after = ConsumedAuthTicketNonces::<T>::get();
+        let robonode_public_keys_after = Bioauth::<T>::active_robonode_public_keys();
+        let consumed_auth_ticket_nonces_after: Vec<_> = ConsumedAuthTicketNonces::<T>::iter().collect();
 
-        assert_eq!(robonode_public_key_after, new_robonode_public_key);
-        assert!(active_authentications_after == vec![]);
+        assert_eq!(robonode_public_keys_after, vec![new_robonode_public_key]);
+        assert_eq!(ActiveAuthentications::<T>::count(), 0);
+        assert!(ActiveAuthentications::<T>::iter().next().is_none());
         assert!(consumed_auth_ticket_nonces_after == consumed_auth_ticket_nonces_before);
     }
 
+    add_robonode_public_key {
+        // Prepare the [`add_robonode_public_key`] extrinsic arguments.
+        let new_robonode_public_key = <T as RobonodePublicKeyBuilder>::build(RobonodePublicKeyBuilderValue::B);
+        let activates_at = T::CurrentMoment::now() + (10u64).into();
+
+        // Capture this state for comparison.
+        let robonode_public_keys_before_len = RobonodePublicKeys::<T>::get().len();
+
+    }: _(RawOrigin::Root, new_robonode_public_key.clone(), activates_at)
+    verify {
+        let robonode_public_keys_after = RobonodePublicKeys::<T>::get();
+        assert_eq!(robonode_public_keys_after.len() - robonode_public_keys_before_len, 1);
+        assert!(robonode_public_keys_after.iter().any(|accepted_robonode_public_key| {
+            accepted_robonode_public_key.public_key == new_robonode_public_key
+                && !accepted_robonode_public_key.is_active
+        }));
+    }
+
+    retire_robonode_public_key {
+        // Accept one more robonode public key, so that the threshold is still met after
+        // the retirement.
+        accept_robonode_public_key::<T>(RobonodePublicKeyBuilderValue::B);
+
+        // Retire the default robonode public key right away, as it is the heavier path.
+        let robonode_public_key = <T as RobonodePublicKeyBuilder>::build(RobonodePublicKeyBuilderValue::A);
+        let retires_at = T::CurrentMoment::now();
+
+        // Self-check that the key is actually active.
+        assert!(Bioauth::<T>::active_robonode_public_keys().contains(&robonode_public_key));
+
+    }: _(RawOrigin::Root, robonode_public_key.clone(), retires_at)
+    verify {
+        assert!(!Bioauth::<T>::active_robonode_public_keys().contains(&robonode_public_key));
+    }
+
+    set_robonode_signatures_threshold {
+        // Require the signatures from all of the robonode public keys we can build.
+        accept_robonode_public_key::<T>(RobonodePublicKeyBuilderValue::B);
+        let threshold = 2;
+    }: _(RawOrigin::Root, threshold)
+    verify {
+        assert_eq!(RobonodeSignaturesThreshold::<T>::get(), threshold);
+    }
+
     on_initialize {
         let a in 0 .. (T::MaxAuthentications::get());
+        let n in 0 .. (T::MaxNonceExpirationsPerBlock::get());
         let active_auth_count: u32 = a / 2;
         let expiring_auth_count: u32 = a - active_auth_count;
 
@@ -198,17 +304,39 @@ benchmarks! {
         let mut active_auths = make_authentications("active", active_auth_count as usize, future_expiry);
         auths.append(&mut active_auths);
 
-        let bounded_auths = BoundedVec::try_from(auths).unwrap();
-        ActiveAuthentications::<T>::put(bounded_auths);
+        for auth in &auths {
+            Bioauth::<T>::insert_active_authentication(auth);
+        }
+
+        // The active nonces are not scanned, so only the expiring ones count towards `n`.
+        let active_nonce_count: u32 = n;
+        let expiring_nonce_count: u32 = n;
+
+        let mut nonces: Vec<ConsumedAuthTicketNonce<T::Moment>> = vec![];
+        // Populate with expired nonces.
+        let mut expiring_nonces = make_consumed_auth_ticket_nonces("expired", expiring_nonce_count, T::CurrentMoment::now());
+        nonces.append(&mut expiring_nonces);
+
+        // Also, populate with active nonces.
+        let mut active_nonces = make_consumed_auth_ticket_nonces("active", active_nonce_count, future_expiry);
+        nonces.append(&mut active_nonces);
+
+        for nonce in &nonces {
+            Bioauth::<T>::insert_consumed_auth_ticket_nonce(&nonce.nonce, nonce.expires_at);
+        }
 
         // Capture this state for comparison.
-        let active_authentications_before_len = ActiveAuthentications::<T>::get().len();
+        let active_authentications_before_len = ActiveAuthentications::<T>::count();
+        let consumed_auth_ticket_nonces_before_len = ConsumedAuthTicketNonces::<T>::count();
     }: {
         Bioauth::<T>::on_initialize(100u32.into());
     }
     verify {
-        let active_authentications_after_len = ActiveAuthentications::<T>::get().len();
-        assert_eq!(active_authentications_before_len - active_authentications_after_len, expiring_auth_count as usize);
+        let active_authentications_after_len = ActiveAuthentications::<T>::count();
+        assert_eq!(active_authentications_before_len - active_authentications_after_len, expiring_auth_count);
+
+        let consumed_auth_ticket_nonces_after_len = ConsumedAuthTicketNonces::<T>::count();
+        assert_eq!(consumed_auth_ticket_nonces_before_len - consumed_auth_ticket_nonces_after_len, expiring_nonce_count);
     }
 
     impl_benchmark_test_suite!(Pallet, crate::mock::benchmarking::new_benchmark_ext(), crate::mock::benchmarking::Benchmark);
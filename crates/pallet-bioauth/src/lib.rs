@@ -60,28 +60,16 @@ pub trait TryConvert<A, B> {
 /// Provides the capability to update the current validators set.
 pub trait ValidatorSetUpdater<T> {
     /// Provide an up-to-date the validators set for the of consensus.
-    fn update_validators_set<'a, I: Iterator<Item = &'a T> + 'a>(validator_public_keys: I)
-    where
-        T: 'a;
+    fn update_validators_set<I: Iterator<Item = T>>(validator_public_keys: I);
 
     /// Provide an initial validators set for the of consensus at genesis.
-    fn init_validators_set<'a, I: Iterator<Item = &'a T> + 'a>(validator_public_keys: I)
-    where
-        T: 'a;
+    fn init_validators_set<I: Iterator<Item = T>>(validator_public_keys: I);
 }
 
 impl<T> ValidatorSetUpdater<T> for () {
-    fn update_validators_set<'a, I: Iterator<Item = &'a T> + 'a>(_validator_public_keys: I)
-    where
-        T: 'a,
-    {
-    }
+    fn update_validators_set<I: Iterator<Item = T>>(_validator_public_keys: I) {}
 
-    fn init_validators_set<'a, I: Iterator<Item = &'a T> + 'a>(_validator_public_keys: I)
-    where
-        T: 'a,
-    {
-    }
+    fn init_validators_set<I: Iterator<Item = T>>(_validator_public_keys: I) {}
 }
 
 /// Provides the capability to get current moment.
@@ -96,8 +84,8 @@ pub trait CurrentMoment<Moment> {
 pub struct Authenticate<OpaqueAuthTicket, Commitment> {
     /// An auth ticket.
     pub ticket: OpaqueAuthTicket,
-    /// The robonode signatrure for the opaque auth ticket.
-    pub ticket_signature: Commitment,
+    /// The robonode signatures for the opaque auth ticket, one per each signing robonode.
+    pub ticket_signatures: Vec<Commitment>,
 }
 
 /// The maximum length of a single nonce (in bytes).
@@ -112,11 +100,27 @@ pub type BoundedAuthTicketNonce = BoundedVec<u8, ConstU32<AUTH_TICKET_NONCE_MAX_
 /// The auth ticket passed to us from the robonode.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(PartialEq, Eq, Default, Clone, Encode, Decode, Hash, Debug, TypeInfo)]
-pub struct AuthTicket<PublicKey> {
+pub struct AuthTicket<PublicKey, Moment> {
     /// The public key of a validator that was authorized by a robonode.
     pub public_key: PublicKey,
     /// The nonce that the robonode has provided.
     pub nonce: AuthTicketNonce,
+    /// The moment at which the robonode has issued the auth ticket.
+    pub issued_at: Moment,
+}
+
+/// The big-endian encoded moment, that sorts in the order of the moments.
+pub type ExpirationKey = [u8; 8];
+
+/// The state that we keep in the blockchain for a consumed auth ticket nonce.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(PartialEq, Eq, Default, Clone, Encode, Decode, Hash, Debug, TypeInfo, MaxEncodedLen)]
+pub struct ConsumedAuthTicketNonce<Moment> {
+    /// The nonce of the consumed auth ticket.
+    pub nonce: BoundedAuthTicketNonce,
+    /// The moment at which the auth ticket becomes expired, and thus the nonce can no longer be
+    /// replayed and is safe to forget.
+    pub expires_at: Moment,
 }
 
 /// The state that we keep in the blockchain for an active authentication.
@@ -129,8 +133,23 @@ pub struct Authentication<PublicKey, Moment> {
     pub expires_at: Moment,
 }
 
+/// The state that we keep in the blockchain for an accepted robonode public key.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(PartialEq, Eq, Default, Clone, Encode, Decode, Hash, Debug, TypeInfo, MaxEncodedLen)]
+pub struct AcceptedRobonodePublicKey<PublicKey, Moment> {
+    /// The public key of a robonode.
+    pub public_key: PublicKey,
+    /// The moment starting from which the auth tickets signed by this key are accepted.
+    pub activates_at: Moment,
+    /// The moment starting from which the auth tickets signed by this key are no longer
+    /// accepted, if the retirement has been planned.
+    pub retires_at: Option<Moment>,
+    /// Whether the key has already been activated.
+    pub is_active: bool,
+}
+
 /// The current storage version.
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 /// Custom invalid transaction error codes.
 #[repr(u8)]
@@ -192,7 +211,7 @@ pub mod pallet {
     use frame_system::pallet_prelude::*;
     use sp_runtime::{
         app_crypto::MaybeHash,
-        traits::{AtLeast32Bit, CheckedAdd},
+        traits::{AtLeast32Bit, CheckedAdd, Hash as HashT, SaturatedConversion, Saturating},
         DispatchError,
     };
 
@@ -229,7 +248,7 @@ pub mod pallet {
         /// A converter from an opaque to a transparent auth ticket.
         type AuthTicketConverter: TryConvert<
             Self::OpaqueAuthTicket,
-            AuthTicket<Self::ValidatorPublicKey>,
+            AuthTicket<Self::ValidatorPublicKey, Self::Moment>,
         >;
 
         /// Type used for expressing timestamp.
@@ -249,6 +268,18 @@ pub mod pallet {
         /// The amount of time (in moments) after which the authentications expire.
         type AuthenticationsExpireAfter: Get<Self::Moment>;
 
+        /// The amount of time (in moments) since the issuance after which the auth tickets expire.
+        type AuthTicketsExpireAfter: Get<Self::Moment>;
+
+        /// The amount of time (in moments) the auth ticket issuance moment is allowed to be ahead
+        /// of the current moment, to tolerate the clock drift between the robonode and the chain.
+        type AuthTicketsMaxClockDrift: Get<Self::Moment>;
+
+        /// The amount of time (in moments) before the authentication expiration during which
+        /// the authentication can be renewed with a fresh auth ticket.
+        /// Should not exceed the [`Config::AuthenticationsExpireAfter`].
+        type AuthenticationsRenewalWindow: Get<Self::Moment>;
+
         /// The validator set updater to invoke at auth the ticket acceptace.
         type ValidatorSetUpdater: ValidatorSetUpdater<Self::ValidatorPublicKey>;
 
@@ -261,6 +292,15 @@ pub mod pallet {
         /// The maximum number of nonces.
         type MaxNonces: Get<u32>;
 
+        /// The maximum number of the consumed nonces to remove, or to move into the map after
+        /// the migration to version 2, at a single block; the rest of the nonces are handled at
+        /// the following blocks.
+        type MaxNonceExpirationsPerBlock: Get<u32>;
+
+        /// The maximum number of the robonode public keys that can be accepted at the same time,
+        /// including the ones that are not active yet.
+        type MaxRobonodePublicKeys: Get<u32>;
+
         /// Before authentication hook.
         type BeforeAuthHook: BeforeAuthHook<Self::ValidatorPublicKey, Self::Moment>;
 
@@ -277,31 +317,75 @@ pub mod pallet {
     #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
-    /// The public key of the robonode.
-    #[pallet::storage]
-    #[pallet::getter(fn robonode_public_key)]
-    pub type RobonodePublicKey<T> = StorageValue<_, <T as Config>::RobonodePublicKey, ValueQuery>;
+    /// The default number of the robonode signatures required for an auth ticket to be accepted.
+    #[pallet::type_value]
+    pub fn DefaultRobonodeSignaturesThreshold() -> u32 {
+        1
+    }
 
-    /// A list of all consumed nonces.
+    /// The number of the distinct active robonode public keys that have to sign an auth ticket
+    /// for it to be accepted.
     #[pallet::storage]
-    #[pallet::getter(fn consumed_auth_ticket_nonces)]
-    pub type ConsumedAuthTicketNonces<T: Config> =
-        StorageValue<_, BoundedVec<BoundedAuthTicketNonce, T::MaxNonces>, ValueQuery>;
+    #[pallet::getter(fn robonode_signatures_threshold)]
+    pub type RobonodeSignaturesThreshold<T> =
+        StorageValue<_, u32, ValueQuery, DefaultRobonodeSignaturesThreshold>;
 
-    /// A list of all active authentications.
+    /// The robonode public keys that are accepted now or are going to be accepted in the future.
     #[pallet::storage]
-    #[pallet::getter(fn active_authentications)]
-    pub type ActiveAuthentications<T: Config> = StorageValue<
+    #[pallet::getter(fn robonode_public_keys)]
+    pub type RobonodePublicKeys<T: Config> = StorageValue<
         _,
-        BoundedVec<Authentication<T::ValidatorPublicKey, T::Moment>, T::MaxAuthentications>,
+        BoundedVec<
+            AcceptedRobonodePublicKey<T::RobonodePublicKey, T::Moment>,
+            T::MaxRobonodePublicKeys,
+        >,
         ValueQuery,
     >;
 
+    /// The consumed nonces of the auth tickets that have not expired yet, keyed by the nonce hash,
+    /// with the moments at which the corresponding auth tickets expire.
+    #[pallet::storage]
+    pub type ConsumedAuthTicketNonces<T: Config> =
+        CountedStorageMap<_, Identity, T::Hash, T::Moment, OptionQuery>;
+
+    /// The consumed nonce hashes, keyed by the moments at which the corresponding auth tickets
+    /// expire.
+    ///
+    /// The keys are not hashed, and the moments are encoded in big-endian, so the entries are
+    /// iterated in the order of expiration, and only the ones that are due have to be touched.
+    #[pallet::storage]
+    pub type ConsumedAuthTicketNoncesExpirations<T: Config> =
+        StorageMap<_, Identity, (ExpirationKey, T::Hash), (), OptionQuery>;
+
+    /// The consumed auth ticket nonces left over by the migration to version 2, that are yet to
+    /// be moved into the [`ConsumedAuthTicketNonces`].
+    ///
+    /// The nonces are moved in batches at the beginning of the blocks, and are checked against
+    /// until then.
+    #[pallet::storage]
+    pub type PendingConsumedAuthTicketNonces<T: Config> =
+        StorageValue<_, BoundedVec<ConsumedAuthTicketNonce<T::Moment>, T::MaxNonces>, ValueQuery>;
+
+    /// The active authentications, keyed by the validator public key, with the moments at which
+    /// the authentications expire.
+    #[pallet::storage]
+    pub type ActiveAuthentications<T: Config> =
+        CountedStorageMap<_, Twox64Concat, T::ValidatorPublicKey, T::Moment, OptionQuery>;
+
+    /// The earliest moment at which some of the active authentications might expire.
+    ///
+    /// Allows skipping the scan of the active authentications at the blocks where none of them
+    /// expire.
+    #[pallet::storage]
+    pub type ActiveAuthenticationsNextExpiration<T: Config> =
+        StorageValue<_, T::Moment, OptionQuery>;
+
     #[pallet::genesis_config]
     #[derive(frame_support::DefaultNoBound)]
     pub struct GenesisConfig<T: Config> {
         pub robonode_public_key: T::RobonodePublicKey,
-        pub consumed_auth_ticket_nonces: BoundedVec<BoundedAuthTicketNonce, T::MaxNonces>,
+        pub consumed_auth_ticket_nonces:
+            BoundedVec<ConsumedAuthTicketNonce<T::Moment>, T::MaxNonces>,
         pub active_authentications:
             BoundedVec<Authentication<T::ValidatorPublicKey, T::Moment>, T::MaxAuthentications>,
     }
@@ -310,11 +394,30 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
         fn build(&self) {
-            <RobonodePublicKey<T>>::put(&self.robonode_public_key);
-            <ConsumedAuthTicketNonces<T>>::put(self.consumed_auth_ticket_nonces.clone());
-            <ActiveAuthentications<T>>::put(self.active_authentications.clone());
+            // The genesis robonode public key is accepted from the very start.
+            <RobonodePublicKeys<T>>::put(BoundedVec::truncate_from(vec![
+                AcceptedRobonodePublicKey {
+                    public_key: self.robonode_public_key.clone(),
+                    activates_at: Default::default(),
+                    retires_at: None,
+                    is_active: true,
+                },
+            ]));
+            for consumed_auth_ticket_nonce in &self.consumed_auth_ticket_nonces {
+                <Pallet<T>>::insert_consumed_auth_ticket_nonce(
+                    &consumed_auth_ticket_nonce.nonce,
+                    consumed_auth_ticket_nonce.expires_at,
+                );
+            }
+            for authentication in &self.active_authentications {
+                <Pallet<T>>::insert_active_authentication(authentication);
+            }
 
-            <Pallet<T>>::issue_validators_set_init(&self.active_authentications);
+            T::ValidatorSetUpdater::init_validators_set(
+                self.active_authentications
+                    .iter()
+                    .map(|authentication| authentication.public_key.clone()),
+            );
         }
     }
 
@@ -326,6 +429,11 @@ pub mod pallet {
         NewAuthentication {
             authentication: Authentication<T::ValidatorPublicKey, T::Moment>,
         },
+        /// The active authentication was renewed with a fresh auth ticket before it has expired.
+        AuthenticationRenewed {
+            authentication: Authentication<T::ValidatorPublicKey, T::Moment>,
+            previous_expires_at: T::Moment,
+        },
         /// The authentications has been expired.
         AuthenticationsExpired {
             expired: Vec<Authentication<T::ValidatorPublicKey, T::Moment>>,
@@ -335,6 +443,14 @@ pub mod pallet {
             removed: Vec<Authentication<T::ValidatorPublicKey, T::Moment>>,
             reason: T::DeauthenticationReason,
         },
+        /// The robonode public key has been activated, and the auth tickets signed by it are
+        /// now accepted.
+        RobonodePublicKeyActivated { public_key: T::RobonodePublicKey },
+        /// The robonode public key has been retired, and the auth tickets signed by it are
+        /// no longer accepted.
+        RobonodePublicKeyRetired { public_key: T::RobonodePublicKey },
+        /// The number of the robonode signatures required for an auth ticket has been changed.
+        RobonodeSignaturesThresholdSet { threshold: u32 },
     }
 
     /// Possible error conditions during `authenticate` call processing.
@@ -351,34 +467,72 @@ pub mod pallet {
         NonceAlreadyUsed,
         /// This public key has already been used.
         PublicKeyAlreadyUsed,
-        /// The ConsumedAuthTicketNonces storage has reached the limit as BoundedVec.
+        /// The ConsumedAuthTicketNonces storage has reached the limit.
         TooManyNonces,
         /// The number of bytes at the nonce has reached the bounded limit.
         TooManyBytesInNonce,
-        /// The ActiveAuthentications storage has reached the limit as BoundedVec.
+        /// The ActiveAuthentications storage has reached the limit.
         TooManyAuthentications,
+        /// The auth ticket has been issued too long ago.
+        AuthTicketExpired,
+        /// The auth ticket has been issued too far in the future.
+        AuthTicketIssuedInFuture,
+        /// The robonode public key is already accepted.
+        RobonodePublicKeyAlreadyAccepted,
+        /// The robonode public key is not accepted.
+        UnknownRobonodePublicKey,
+        /// The RobonodePublicKeys storage has reached the limit.
+        TooManyRobonodePublicKeys,
+        /// The robonode signatures threshold is either zero or above the number of the robonode
+        /// public keys that stay active.
+        InvalidRobonodeSignaturesThreshold,
+        /// Retiring the robonode public key would leave fewer active robonode public keys than
+        /// the robonode signatures threshold.
+        NotEnoughRobonodePublicKeys,
     }
 
     #[derive(Debug)]
     enum AuthenticationAttemptValidationError {
         NonceConflict,
         AlreadyAuthenticated,
+        AuthTicketExpired,
+        AuthTicketIssuedInFuture,
     }
 
     /// Validate the incloming authentication attempt, checking the auth ticket data against
-    /// the passed input.
-    fn validate_authentication_attempt<'a, T: Config>(
-        consumed_auth_ticket_nonces: &'a [BoundedAuthTicketNonce],
-        active_authentications: &'a [Authentication<T::ValidatorPublicKey, T::Moment>],
-        auth_ticket: &AuthTicket<T::ValidatorPublicKey>,
-    ) -> Result<(), AuthenticationAttemptValidationError> {
-        for consumed_auth_ticket_nonce in consumed_auth_ticket_nonces {
-            if consumed_auth_ticket_nonce == &auth_ticket.nonce {
-                return Err(AuthenticationAttemptValidationError::NonceConflict);
-            }
+    /// the current state.
+    ///
+    /// Returns the expiration moment of the active authentication for the same public key,
+    /// if there is one; whether it can be renewed depends on the current moment and has to be
+    /// checked with [`validate_authentication_renewal`].
+    fn validate_authentication_attempt<T: Config>(
+        auth_ticket: &AuthTicket<T::ValidatorPublicKey, T::Moment>,
+    ) -> Result<Option<T::Moment>, AuthenticationAttemptValidationError> {
+        let nonce_hash = Pallet::<T>::auth_ticket_nonce_hash(&auth_ticket.nonce);
+        if ConsumedAuthTicketNonces::<T>::contains_key(nonce_hash) {
+            return Err(AuthenticationAttemptValidationError::NonceConflict);
+        }
+
+        // The nonces left over by the migration to version 2 are only stored in the list until
+        // they are moved into the map.
+        if PendingConsumedAuthTicketNonces::<T>::get()
+            .iter()
+            .any(|consumed| consumed.nonce.as_slice() == auth_ticket.nonce.as_slice())
+        {
+            return Err(AuthenticationAttemptValidationError::NonceConflict);
         }
-        for active_authentication in active_authentications {
-            if active_authentication.public_key == auth_ticket.public_key {
+
+        Ok(ActiveAuthentications::<T>::get(&auth_ticket.public_key))
+    }
+
+    /// Validate that the active authentication, if any, is within its renewal window at
+    /// the current moment.
+    fn validate_authentication_renewal<T: Config>(
+        active_expires_at: Option<T::Moment>,
+        current_moment: T::Moment,
+    ) -> Result<(), AuthenticationAttemptValidationError> {
+        if let Some(active_expires_at) = active_expires_at {
+            if current_moment < renewable_from::<T>(active_expires_at) {
                 return Err(AuthenticationAttemptValidationError::AlreadyAuthenticated);
             }
         }
@@ -386,12 +540,108 @@ pub mod pallet {
         Ok(())
     }
 
+    /// Compute the moment starting from which the authentication can be renewed.
+    fn renewable_from<T: Config>(expires_at: T::Moment) -> T::Moment {
+        expires_at.saturating_sub(T::AuthenticationsRenewalWindow::get())
+    }
+
+    /// Validate that the auth ticket has not expired yet at the current moment, and that it was
+    /// not issued further in the future than the clock drift allows.
+    fn validate_auth_ticket_expiration<T: Config>(
+        auth_ticket: &AuthTicket<T::ValidatorPublicKey, T::Moment>,
+        current_moment: T::Moment,
+    ) -> Result<(), AuthenticationAttemptValidationError> {
+        if auth_ticket.issued_at > current_moment.saturating_add(T::AuthTicketsMaxClockDrift::get())
+        {
+            return Err(AuthenticationAttemptValidationError::AuthTicketIssuedInFuture);
+        }
+
+        if auth_ticket_expires_at::<T>(auth_ticket) <= current_moment {
+            return Err(AuthenticationAttemptValidationError::AuthTicketExpired);
+        }
+
+        Ok(())
+    }
+
+    /// Compute the moment at which the auth ticket becomes expired.
+    fn auth_ticket_expires_at<T: Config>(
+        auth_ticket: &AuthTicket<T::ValidatorPublicKey, T::Moment>,
+    ) -> T::Moment {
+        auth_ticket
+            .issued_at
+            .saturating_add(T::AuthTicketsExpireAfter::get())
+    }
+
+    /// Compute the key under which the expiration moment is stored at
+    /// the [`ConsumedAuthTicketNoncesExpirations`].
+    fn expiration_key<T: Config>(moment: T::Moment) -> ExpirationKey {
+        moment.saturated_into::<u64>().to_be_bytes()
+    }
+
+    /// Compute the earliest of the currently tracked next expiration moment and a new one.
+    fn earliest_expiration<Moment: Ord>(
+        next_expiration: Option<Moment>,
+        expires_at: Moment,
+    ) -> Moment {
+        match next_expiration {
+            Some(next_expiration) => next_expiration.min(expires_at),
+            None => expires_at,
+        }
+    }
+
     /// Public API the pallet exposes to the runtime.
     impl<T: Config> Pallet<T> {
         pub fn is_authenticated(public_key: &<T as Config>::ValidatorPublicKey) -> bool {
-            ActiveAuthentications::<T>::get()
-                .iter()
-                .any(|authentication| &authentication.public_key == public_key)
+            ActiveAuthentications::<T>::contains_key(public_key)
+        }
+
+        /// Get the robonode public keys that auth tickets are currently accepted from.
+        pub fn active_robonode_public_keys() -> Vec<<T as Config>::RobonodePublicKey> {
+            RobonodePublicKeys::<T>::get()
+                .into_iter()
+                .filter(|accepted_robonode_public_key| accepted_robonode_public_key.is_active)
+                .map(|accepted_robonode_public_key| accepted_robonode_public_key.public_key)
+                .collect()
+        }
+
+        /// Get the active authentication for a given validator public key, if any.
+        pub fn active_authentication(
+            public_key: &<T as Config>::ValidatorPublicKey,
+        ) -> Option<Authentication<<T as Config>::ValidatorPublicKey, <T as Config>::Moment>>
+        {
+            ActiveAuthentications::<T>::get(public_key).map(|expires_at| Authentication {
+                public_key: public_key.clone(),
+                expires_at,
+            })
+        }
+
+        /// Get the moment starting from which the given authentication can be renewed.
+        pub fn authentication_renewable_from(
+            authentication: &Authentication<
+                <T as Config>::ValidatorPublicKey,
+                <T as Config>::Moment,
+            >,
+        ) -> <T as Config>::Moment {
+            renewable_from::<T>(authentication.expires_at)
+        }
+
+        /// Get all of the active authentications, in no particular order.
+        ///
+        /// Reads the whole [`ActiveAuthentications`] map, so avoid calling this in the hot paths.
+        pub fn active_authentications(
+        ) -> Vec<Authentication<<T as Config>::ValidatorPublicKey, <T as Config>::Moment>> {
+            ActiveAuthentications::<T>::iter()
+                .map(|(public_key, expires_at)| Authentication {
+                    public_key,
+                    expires_at,
+                })
+                .collect()
+        }
+
+        /// Compute the key under which the auth ticket nonce is stored at
+        /// the [`ConsumedAuthTicketNonces`].
+        pub fn auth_ticket_nonce_hash(nonce: &[u8]) -> T::Hash {
+            T::Hashing::hash(nonce)
         }
 
         pub fn deauthenticate(
@@ -401,15 +651,18 @@ pub mod pallet {
             reason: <T as Config>::DeauthenticationReason,
         ) -> Vec<Authentication<<T as Config>::ValidatorPublicKey, <T as Config>::Moment>> {
             let mut removed_authentications = Vec::with_capacity(authentications.len());
-            ActiveAuthentications::<T>::mutate(|active_authentications| {
-                active_authentications.retain(|authentication| {
-                    if authentications.contains(authentication) {
-                        removed_authentications.push(authentication.clone());
-                        return false;
-                    }
-                    true
+            for authentication in authentications {
+                // Match on the public key alone, as the authentication might have been renewed
+                // since, and report the expiration moment it actually had.
+                let Some(expires_at) = ActiveAuthentications::<T>::take(&authentication.public_key)
+                else {
+                    continue;
+                };
+                removed_authentications.push(Authentication {
+                    public_key: authentication.public_key,
+                    expires_at,
                 });
-            });
+            }
             if !removed_authentications.is_empty() {
                 // Emit an event.
                 Self::deposit_event(Event::AuthenticationsRemoved {
@@ -424,14 +677,16 @@ pub mod pallet {
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// ### Complexity
-        /// `O(M + N)` where `M` is the number of authentications and `N` is the number of nonces
-        /// Cost incurred from decoding vec of length M or N. Charged as maximum.
+        /// `O(S * K)` where S is the number of the signatures and K is the number of the robonode
+        /// public keys, as each signature can be checked against each key; the nonces and
+        /// the authentications are looked up by their keys.
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::authenticate(
-            <ActiveAuthentications<T>>::get().len().try_into()
-            .expect("u32 is big enough for this overflow to be practically impossible"),
-            <ConsumedAuthTicketNonces<T>>::get().len().try_into()
-            .expect("u32 is big enough for this overflow to be practically impossible")))]
+            req.ticket_signatures
+                .len()
+                .saturated_into::<u32>()
+                .min(T::MaxRobonodePublicKeys::get())
+        ))]
         pub fn authenticate(
             origin: OriginFor<T>,
             req: Authenticate<T::OpaqueAuthTicket, T::RobonodeSignature>,
@@ -448,81 +703,230 @@ pub mod pallet {
                     }
                     AuthTicketExtractionError::UnableToParse => Error::<T>::UnableToParseAuthTicket,
                 })?;
-            let public_key = auth_ticket.public_key.clone();
+
+            let map_validation_error = |err| match err {
+                AuthenticationAttemptValidationError::NonceConflict => Error::<T>::NonceAlreadyUsed,
+                AuthenticationAttemptValidationError::AlreadyAuthenticated => {
+                    Error::<T>::PublicKeyAlreadyUsed
+                }
+                AuthenticationAttemptValidationError::AuthTicketExpired => {
+                    Error::<T>::AuthTicketExpired
+                }
+                AuthenticationAttemptValidationError::AuthTicketIssuedInFuture => {
+                    Error::<T>::AuthTicketIssuedInFuture
+                }
+            };
+
+            let active_expires_at =
+                validate_authentication_attempt::<T>(&auth_ticket).map_err(map_validation_error)?;
+
+            let current_moment = T::CurrentMoment::now();
+
+            validate_authentication_renewal::<T>(active_expires_at, current_moment)
+                .map_err(map_validation_error)?;
+            validate_auth_ticket_expiration::<T>(&auth_ticket, current_moment)
+                .map_err(map_validation_error)?;
+            let auth_ticket_expires_at = auth_ticket_expires_at::<T>(&auth_ticket);
+
+            let nonce = BoundedAuthTicketNonce::try_from(auth_ticket.nonce)
+                .map_err(|_| Error::<T>::TooManyBytesInNonce)?;
+            if ConsumedAuthTicketNonces::<T>::count() >= T::MaxNonces::get() {
+                return Err(Error::<T>::TooManyNonces.into());
+            }
+
+            let authentication = Authentication {
+                public_key: auth_ticket.public_key,
+                expires_at: current_moment
+                    .checked_add(&T::AuthenticationsExpireAfter::get())
+                    .expect(
+                        "32 bits should be enough for this overflow to be practically impossible",
+                    ),
+            };
+
+            // Run the before hook, abort if needed.
+            let before_hook_data = <T as Config>::BeforeAuthHook::hook(&authentication)?;
+
+            // The renewal extends the authentication in place, so it does not take a new slot.
+            if active_expires_at.is_none()
+                && ActiveAuthentications::<T>::count() >= T::MaxAuthentications::get()
+            {
+                return Err(Error::<T>::TooManyAuthentications.into());
+            }
 
             // Update storage.
-            <ConsumedAuthTicketNonces<T>>::try_mutate::<_, DispatchError, _>(
-                move |consumed_auth_ticket_nonces| {
-                    <ActiveAuthentications<T>>::try_mutate::<_, DispatchError, _>(
-                        move |active_authentications| {
-                            validate_authentication_attempt::<T>(
-                                consumed_auth_ticket_nonces,
-                                active_authentications,
-                                &auth_ticket,
-                            )
-                            .map_err(|err| match err {
-                                AuthenticationAttemptValidationError::NonceConflict => {
-                                    Error::<T>::NonceAlreadyUsed
-                                }
-                                AuthenticationAttemptValidationError::AlreadyAuthenticated => {
-                                    Error::<T>::PublicKeyAlreadyUsed
-                                }
-                            })?;
-
-                            // Update internal state.
-                            let current_moment = T::CurrentMoment::now();
-
-                            consumed_auth_ticket_nonces
-                                .try_push(
-                                    BoundedAuthTicketNonce::try_from(auth_ticket.nonce)
-                                        .map_err(|_| Error::<T>::TooManyBytesInNonce)?,
-                                )
-                                .map_err(|_| Error::<T>::TooManyNonces)?;
-
-                            let authentication = Authentication {
-                                public_key,
-                                expires_at: current_moment
-                                    .checked_add(&T::AuthenticationsExpireAfter::get())
-                                    .expect("32 bits should be enough for this overflow to be practically impossible"),
-                            };
-
-                            // Run the before hook, abort if needed.
-                            let before_hook_data =
-                                <T as Config>::BeforeAuthHook::hook(&authentication)?;
-
-                            active_authentications
-                                .try_push(authentication.clone())
-                                .map_err(|_| Error::<T>::TooManyAuthentications)?;
-
-                            // Issue an update to the external validators set.
-                            Self::issue_validators_set_update(active_authentications.as_slice());
-
-                            // Run the after hook.
-                            <T as Config>::AfterAuthHook::hook(before_hook_data);
-
-                            // Emit an event.
-                            Self::deposit_event(Event::NewAuthentication { authentication });
-                            Ok(())
-                        },
-                    )?;
-                    Ok(())
-                },
-            )?;
+            Self::insert_consumed_auth_ticket_nonce(&nonce, auth_ticket_expires_at);
+            Self::insert_active_authentication(&authentication);
+
+            // Issue an update to the external validators set, unless the set stays the same.
+            if active_expires_at.is_none() {
+                Self::issue_validators_set_update();
+            }
+
+            // Run the after hook.
+            <T as Config>::AfterAuthHook::hook(before_hook_data);
+
+            // Emit an event.
+            match active_expires_at {
+                None => Self::deposit_event(Event::NewAuthentication { authentication }),
+                Some(previous_expires_at) => Self::deposit_event(Event::AuthenticationRenewed {
+                    authentication,
+                    previous_expires_at,
+                }),
+            }
             Ok(())
         }
 
+        /// Replace all of the accepted robonode public keys with the given one at once, and
+        /// drop all of the active authentications.
+        ///
+        /// Meant for the emergencies, like the robonode key compromise; use
+        /// [`Call::add_robonode_public_key`] and [`Call::retire_robonode_public_key`] for
+        /// the planned rotations.
+        ///
+        /// The robonode signatures threshold is reset to one, as only a single key is left.
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::set_robonode_public_key(
-            <ActiveAuthentications<T>>::get().len().try_into()
-            .expect("u32 is big enough for this overflow to be practically impossible")
+            <ActiveAuthentications<T>>::count()
         ))]
         pub fn set_robonode_public_key(
             origin: OriginFor<T>,
             robonode_public_key: T::RobonodePublicKey,
         ) -> DispatchResult {
             ensure_root(origin)?;
-            <RobonodePublicKey<T>>::put(&robonode_public_key);
-            <ActiveAuthentications<T>>::put(BoundedVec::default());
+            let current_moment = T::CurrentMoment::now();
+            let retired_robonode_public_keys = <RobonodePublicKeys<T>>::get();
+            <RobonodePublicKeys<T>>::put(BoundedVec::truncate_from(vec![
+                AcceptedRobonodePublicKey {
+                    public_key: robonode_public_key.clone(),
+                    activates_at: current_moment,
+                    retires_at: None,
+                    is_active: true,
+                },
+            ]));
+            let _ = <ActiveAuthentications<T>>::clear(u32::MAX, None);
+            <ActiveAuthenticationsNextExpiration<T>>::kill();
+            let reset_threshold = <RobonodeSignaturesThreshold<T>>::get() != 1;
+            if reset_threshold {
+                <RobonodeSignaturesThreshold<T>>::put(1);
+            }
+
+            for retired_robonode_public_key in retired_robonode_public_keys {
+                if retired_robonode_public_key.is_active {
+                    Self::deposit_event(Event::RobonodePublicKeyRetired {
+                        public_key: retired_robonode_public_key.public_key,
+                    });
+                }
+            }
+            Self::deposit_event(Event::RobonodePublicKeyActivated {
+                public_key: robonode_public_key,
+            });
+            if reset_threshold {
+                Self::deposit_event(Event::RobonodeSignaturesThresholdSet { threshold: 1 });
+            }
+            Ok(())
+        }
+
+        /// Start accepting the auth tickets signed by the given robonode public key from
+        /// the given moment on.
+        ///
+        /// The key is activated right away if the moment has already come.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::add_robonode_public_key())]
+        pub fn add_robonode_public_key(
+            origin: OriginFor<T>,
+            robonode_public_key: T::RobonodePublicKey,
+            activates_at: T::Moment,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let is_active = activates_at <= T::CurrentMoment::now();
+            <RobonodePublicKeys<T>>::try_mutate::<_, DispatchError, _>(|robonode_public_keys| {
+                if robonode_public_keys
+                    .iter()
+                    .any(|accepted_robonode_public_key| {
+                        accepted_robonode_public_key.public_key == robonode_public_key
+                    })
+                {
+                    return Err(Error::<T>::RobonodePublicKeyAlreadyAccepted.into());
+                }
+                robonode_public_keys
+                    .try_push(AcceptedRobonodePublicKey {
+                        public_key: robonode_public_key.clone(),
+                        activates_at,
+                        retires_at: None,
+                        is_active,
+                    })
+                    .map_err(|_| Error::<T>::TooManyRobonodePublicKeys)?;
+                Ok(())
+            })?;
+            if is_active {
+                Self::deposit_event(Event::RobonodePublicKeyActivated {
+                    public_key: robonode_public_key,
+                });
+            }
+            Ok(())
+        }
+
+        /// Stop accepting the auth tickets signed by the given robonode public key from
+        /// the given moment on.
+        ///
+        /// The key is retired right away if the moment has already come.
+        /// The active authentications are kept intact.
+        ///
+        /// The retirement is denied if it would leave fewer active robonode public keys than
+        /// the robonode signatures threshold at any moment.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::retire_robonode_public_key())]
+        pub fn retire_robonode_public_key(
+            origin: OriginFor<T>,
+            robonode_public_key: T::RobonodePublicKey,
+            retires_at: T::Moment,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let current_moment = T::CurrentMoment::now();
+            <RobonodePublicKeys<T>>::try_mutate::<_, DispatchError, _>(|robonode_public_keys| {
+                let accepted_robonode_public_key = robonode_public_keys
+                    .iter_mut()
+                    .find(|accepted_robonode_public_key| {
+                        accepted_robonode_public_key.public_key == robonode_public_key
+                    })
+                    .ok_or(Error::<T>::UnknownRobonodePublicKey)?;
+                accepted_robonode_public_key.retires_at = Some(retires_at);
+                ensure!(
+                    Self::min_active_robonode_public_keys(robonode_public_keys, current_moment)
+                        >= RobonodeSignaturesThreshold::<T>::get(),
+                    Error::<T>::NotEnoughRobonodePublicKeys
+                );
+                Ok(())
+            })?;
+            if retires_at <= current_moment {
+                Self::update_robonode_public_keys(current_moment);
+            }
+            Ok(())
+        }
+
+        /// Set the number of the distinct active robonode public keys that have to sign an auth
+        /// ticket for it to be accepted.
+        ///
+        /// The threshold can not exceed the number of the robonode public keys that stay active,
+        /// taking the planned retirements into account.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::set_robonode_signatures_threshold())]
+        pub fn set_robonode_signatures_threshold(
+            origin: OriginFor<T>,
+            threshold: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                threshold > 0
+                    && threshold
+                        <= Self::min_active_robonode_public_keys(
+                            &RobonodePublicKeys::<T>::get(),
+                            T::CurrentMoment::now(),
+                        ),
+                Error::<T>::InvalidRobonodeSignaturesThreshold
+            );
+            <RobonodeSignaturesThreshold<T>>::put(threshold);
+            Self::deposit_event(Event::RobonodeSignaturesThresholdSet { threshold });
             Ok(())
         }
     }
@@ -530,48 +934,31 @@ pub mod pallet {
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
         fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
-            // Remove expired authentications.
             let current_moment = T::CurrentMoment::now();
-            let possibly_expired_authentications = <ActiveAuthentications<T>>::get();
-            let possibly_expired_authentications_len = possibly_expired_authentications.len();
-            let mut expired_authentications =
-                Vec::with_capacity(possibly_expired_authentications_len);
-            let mut active_authentications =
-                Vec::with_capacity(possibly_expired_authentications_len);
-            for possibly_expired_authentication in possibly_expired_authentications {
-                if possibly_expired_authentication.expires_at > current_moment {
-                    // Still active.
-                    active_authentications.push(possibly_expired_authentication);
-                } else {
-                    // Expired!
-                    expired_authentications.push(possibly_expired_authentication)
-                }
-            }
 
-            let update_required =
-                possibly_expired_authentications_len != active_authentications.len();
-            if update_required {
-                // We use truncate_from as a resulted active authentications Vec
-                // can't become bigger than it was. Just filtering was done before.
-                let bounded_active_authentications =
-                    BoundedVec::<_, T::MaxAuthentications>::truncate_from(
-                        active_authentications.clone(),
-                    );
-                Self::issue_validators_set_update(active_authentications.as_slice());
-                <ActiveAuthentications<T>>::put(bounded_active_authentications);
-
-                Self::deposit_event(Event::AuthenticationsExpired {
-                    expired: expired_authentications,
-                });
-            }
+            // Activate and retire the robonode public keys.
+            Self::update_robonode_public_keys(current_moment);
 
-            // Weight: O(M) where M is the number of auths.
-            // Cost incurred from decoding vec of length M. Charged as maximum.
+            // Remove expired authentications.
+            let scanned_authentications = Self::expire_active_authentications(current_moment);
+
+            // Remove the nonces of the expired auth tickets, as these tickets can no longer be
+            // accepted anyway.
+            let expired_nonces = Self::expire_consumed_auth_ticket_nonces(current_moment);
+
+            // Move the nonces left over by the migration to version 2 into the map, within what
+            // remains of the per-block limit.
+            let moved_nonces = Self::move_pending_consumed_auth_ticket_nonces(
+                T::MaxNonceExpirationsPerBlock::get().saturating_sub(expired_nonces),
+            );
+
+            // Weight: O(M + N) where M is the number of auths scanned and N is the number of
+            // nonces removed or moved; the auths are only scanned at the blocks where some are
+            // due to expire, and the nonces are looked up in the order of expiration and capped
+            // by the `MaxNonceExpirationsPerBlock`.
             T::WeightInfo::on_initialize(
-                <ActiveAuthentications<T>>::get()
-                    .len()
-                    .try_into()
-                    .expect("u32 is big enough for this overflow to be practically impossible"),
+                scanned_authentications,
+                expired_nonces.saturating_add(moved_nonces),
             )
         }
     }
@@ -586,14 +973,52 @@ pub mod pallet {
     impl<T: Config> Pallet<T> {
         fn extract_auth_ticket_checked(
             req: Authenticate<T::OpaqueAuthTicket, T::RobonodeSignature>,
-        ) -> Result<AuthTicket<T::ValidatorPublicKey>, AuthTicketExtractionError> {
-            let robonode_public_key = RobonodePublicKey::<T>::get();
+        ) -> Result<AuthTicket<T::ValidatorPublicKey, T::Moment>, AuthTicketExtractionError>
+        {
+            let threshold = RobonodeSignaturesThreshold::<T>::get();
+
+            // Bound the amount of work, as there can not be more valid signatures than keys.
+            let too_many_signatures = u32::try_from(req.ticket_signatures.len())
+                .map_or(true, |signatures| {
+                    signatures > T::MaxRobonodePublicKeys::get()
+                });
+            if too_many_signatures {
+                return Err(AuthTicketExtractionError::SignatureInvalid);
+            }
 
-            let signature_valid = robonode_public_key
-                .verify(&req.ticket, req.ticket_signature)
-                .map_err(|_| AuthTicketExtractionError::UnableToValidateSignature)?;
+            // Count the distinct active robonode public keys that have signed the auth ticket.
+            // Each signature is taken out of the set once matched, so it is never counted twice.
+            let mut remaining_signatures = req.ticket_signatures;
+            let mut verification_failed = false;
+            let mut signers: u32 = 0;
+            for accepted_robonode_public_key in RobonodePublicKeys::<T>::get()
+                .into_iter()
+                .filter(|accepted_robonode_public_key| accepted_robonode_public_key.is_active)
+            {
+                if signers >= threshold {
+                    break;
+                }
+                // A signature that fails to verify with this key may still be valid for
+                // the next one, so carry on with the rest of the signatures.
+                let matched = remaining_signatures.iter().position(|signature| {
+                    accepted_robonode_public_key
+                        .public_key
+                        .verify(&req.ticket, signature.clone())
+                        .unwrap_or_else(|_| {
+                            verification_failed = true;
+                            false
+                        })
+                });
+                if let Some(index) = matched {
+                    remaining_signatures.swap_remove(index);
+                    signers = signers.saturating_add(1);
+                }
+            }
 
-            if !signature_valid {
+            if signers < threshold {
+                if verification_failed {
+                    return Err(AuthTicketExtractionError::UnableToValidateSignature);
+                }
                 return Err(AuthTicketExtractionError::SignatureInvalid);
             }
 
@@ -621,15 +1046,14 @@ pub mod pallet {
                     })
                 })?;
 
-            let consumed_auth_ticket_nonces = ConsumedAuthTicketNonces::<T>::get();
-            let active_authentications = ActiveAuthentications::<T>::get();
+            let validation_result =
+                validate_authentication_attempt::<T>(&auth_ticket).and_then(|active_expires_at| {
+                    let current_moment = T::CurrentMoment::now();
+                    validate_authentication_renewal::<T>(active_expires_at, current_moment)?;
+                    validate_auth_ticket_expiration::<T>(&auth_ticket, current_moment)
+                });
 
-            validate_authentication_attempt::<T>(
-                &consumed_auth_ticket_nonces,
-                &active_authentications,
-                &auth_ticket,
-            )
-            .map_err(|err| {
+            validation_result.map_err(|err| {
                 log::error!("Authentication attempt failed: {err:?}");
 
                 TransactionValidityError::Invalid(match err {
@@ -644,8 +1068,18 @@ pub mod pallet {
                         // Technically, we can't know if the transaction is from the future, but we
                         // know for sure it's not a replay, since the nonce didn't conflict;
                         // The way it usually observed to happen is when someone authenticates
-                        // again while already having an active authentication - so this means this
-                        // transaction would've been valid if sent in the future.
+                        // again before the renewal window of their active authentication - so this
+                        // means this transaction would've been valid if sent in the future.
+                        InvalidTransaction::Future
+                    }
+                    AuthenticationAttemptValidationError::AuthTicketExpired => {
+                        // The auth ticket was issued too long ago, and will never become valid
+                        // again.
+                        InvalidTransaction::Stale
+                    }
+                    AuthenticationAttemptValidationError::AuthTicketIssuedInFuture => {
+                        // The auth ticket was issued ahead of the current moment by more than
+                        // the clock drift allows, so it might only become valid in the future.
                         InvalidTransaction::Future
                     }
                 })
@@ -660,28 +1094,219 @@ pub mod pallet {
                 .build()
         }
 
-        fn map_active_authentications_to_validators_set(
-            active_authentications: &[Authentication<T::ValidatorPublicKey, T::Moment>],
-        ) -> impl Iterator<Item = &T::ValidatorPublicKey> {
-            active_authentications
-                .iter()
-                .map(|active_authentication| &active_authentication.public_key)
+        /// Add the consumed nonce to the state, indexing it by the expiration moment.
+        /// Does not check the limits.
+        pub(crate) fn insert_consumed_auth_ticket_nonce(nonce: &[u8], expires_at: T::Moment) {
+            let nonce_hash = Self::auth_ticket_nonce_hash(nonce);
+            ConsumedAuthTicketNonces::<T>::insert(nonce_hash, expires_at);
+            ConsumedAuthTicketNoncesExpirations::<T>::insert(
+                (expiration_key::<T>(expires_at), nonce_hash),
+                (),
+            );
         }
 
-        fn issue_validators_set_update(
-            active_authentications: &[Authentication<T::ValidatorPublicKey, T::Moment>],
+        /// Add the authentication to the state, keeping track of the next authentications
+        /// expiration.
+        /// Does not check the limits.
+        pub(crate) fn insert_active_authentication(
+            authentication: &Authentication<T::ValidatorPublicKey, T::Moment>,
         ) {
-            let validator_public_keys =
-                Self::map_active_authentications_to_validators_set(active_authentications);
-            T::ValidatorSetUpdater::update_validators_set(validator_public_keys);
+            ActiveAuthentications::<T>::insert(
+                &authentication.public_key,
+                authentication.expires_at,
+            );
+            ActiveAuthenticationsNextExpiration::<T>::mutate(|next_expiration| {
+                *next_expiration = Some(earliest_expiration(
+                    *next_expiration,
+                    authentication.expires_at,
+                ));
+            });
         }
 
-        fn issue_validators_set_init(
-            active_authentications: &[Authentication<T::ValidatorPublicKey, T::Moment>],
-        ) {
-            let validator_public_keys =
-                Self::map_active_authentications_to_validators_set(active_authentications);
-            T::ValidatorSetUpdater::init_validators_set(validator_public_keys);
+        /// Remove the authentications that have expired at the current moment.
+        ///
+        /// Returns the number of the scanned authentications.
+        fn expire_active_authentications(current_moment: T::Moment) -> u32 {
+            match ActiveAuthenticationsNextExpiration::<T>::get() {
+                Some(next_expiration) if next_expiration <= current_moment => {}
+                // Nothing is due to expire yet.
+                _ => return 0,
+            }
+
+            let mut scanned: u32 = 0;
+            let mut expired_authentications = Vec::new();
+            let mut next_expiration = None;
+            for (public_key, expires_at) in ActiveAuthentications::<T>::iter() {
+                scanned = scanned.saturating_add(1);
+                if expires_at > current_moment {
+                    // Still active.
+                    next_expiration = Some(earliest_expiration(next_expiration, expires_at));
+                } else {
+                    // Expired!
+                    expired_authentications.push(Authentication {
+                        public_key,
+                        expires_at,
+                    });
+                }
+            }
+            ActiveAuthenticationsNextExpiration::<T>::set(next_expiration);
+
+            if !expired_authentications.is_empty() {
+                // Remove the entries after the iteration is over, as altering the map while
+                // iterating over it is not allowed.
+                for expired_authentication in &expired_authentications {
+                    ActiveAuthentications::<T>::remove(&expired_authentication.public_key);
+                }
+
+                Self::issue_validators_set_update();
+
+                Self::deposit_event(Event::AuthenticationsExpired {
+                    expired: expired_authentications,
+                });
+            }
+
+            scanned
+        }
+
+        /// Remove the consumed nonces of the auth tickets that have expired at the current
+        /// moment, up to [`Config::MaxNonceExpirationsPerBlock`] of them.
+        ///
+        /// Returns the number of the removed nonces; the ones that are not due yet are not
+        /// scanned, and the due ones over the limit stay at the expiration index to be removed
+        /// at the following blocks.
+        pub(crate) fn expire_consumed_auth_ticket_nonces(current_moment: T::Moment) -> u32 {
+            let current_key = expiration_key::<T>(current_moment);
+
+            // Remove the entries after the iteration is over, as altering the map while
+            // iterating over it is not allowed.
+            let expired: Vec<_> = ConsumedAuthTicketNoncesExpirations::<T>::iter_keys()
+                .take_while(|(expires_at, _)| *expires_at <= current_key)
+                .take(T::MaxNonceExpirationsPerBlock::get().saturated_into::<usize>())
+                .collect();
+
+            let mut removed: u32 = 0;
+            for (expires_at, nonce_hash) in expired {
+                ConsumedAuthTicketNoncesExpirations::<T>::remove((expires_at, nonce_hash));
+                ConsumedAuthTicketNonces::<T>::remove(nonce_hash);
+                removed = removed.saturating_add(1);
+            }
+
+            removed
+        }
+
+        /// Move up to `limit` of the consumed nonces left over by the migration to version 2
+        /// into the [`ConsumedAuthTicketNonces`].
+        ///
+        /// Returns the number of the moved nonces.
+        pub(crate) fn move_pending_consumed_auth_ticket_nonces(limit: u32) -> u32 {
+            if limit == 0 || !PendingConsumedAuthTicketNonces::<T>::exists() {
+                return 0;
+            }
+
+            let mut pending = PendingConsumedAuthTicketNonces::<T>::take().into_inner();
+            let batch_start = pending
+                .len()
+                .saturating_sub(limit.saturated_into::<usize>());
+            let batch = pending.split_off(batch_start);
+            if !pending.is_empty() {
+                // We use truncate_from as the list has only become shorter.
+                PendingConsumedAuthTicketNonces::<T>::put(BoundedVec::truncate_from(pending));
+            }
+
+            let mut moved: u32 = 0;
+            for consumed_auth_ticket_nonce in batch {
+                Self::insert_consumed_auth_ticket_nonce(
+                    &consumed_auth_ticket_nonce.nonce,
+                    consumed_auth_ticket_nonce.expires_at,
+                );
+                moved = moved.saturating_add(1);
+            }
+
+            moved
+        }
+
+        /// Activate the robonode public keys which activation moment has come, and retire
+        /// the ones which retirement moment has come.
+        fn update_robonode_public_keys(current_moment: T::Moment) {
+            let robonode_public_keys = RobonodePublicKeys::<T>::get();
+            let mut changed = false;
+            let mut activated = Vec::new();
+            let mut retired = Vec::new();
+
+            let mut updated_robonode_public_keys = Vec::with_capacity(robonode_public_keys.len());
+            for mut accepted_robonode_public_key in robonode_public_keys {
+                let should_retire = accepted_robonode_public_key
+                    .retires_at
+                    .map(|retires_at| retires_at <= current_moment)
+                    .unwrap_or(false);
+                if should_retire {
+                    changed = true;
+                    if accepted_robonode_public_key.is_active {
+                        retired.push(accepted_robonode_public_key.public_key);
+                    }
+                    continue;
+                }
+                if !accepted_robonode_public_key.is_active
+                    && accepted_robonode_public_key.activates_at <= current_moment
+                {
+                    changed = true;
+                    accepted_robonode_public_key.is_active = true;
+                    activated.push(accepted_robonode_public_key.public_key.clone());
+                }
+                updated_robonode_public_keys.push(accepted_robonode_public_key);
+            }
+
+            if !changed {
+                return;
+            }
+
+            // The number of keys can only decrease here, so nothing gets truncated.
+            RobonodePublicKeys::<T>::put(BoundedVec::truncate_from(updated_robonode_public_keys));
+
+            for public_key in activated {
+                Self::deposit_event(Event::RobonodePublicKeyActivated { public_key });
+            }
+            for public_key in retired {
+                Self::deposit_event(Event::RobonodePublicKeyRetired { public_key });
+            }
+        }
+
+        /// The smallest number of the robonode public keys that are active at any moment
+        /// starting from the current one, given the planned activations and retirements.
+        ///
+        /// The number can only drop when a key retires, so it is enough to check right after
+        /// the current moment and each of the planned retirements.
+        fn min_active_robonode_public_keys(
+            robonode_public_keys: &[AcceptedRobonodePublicKey<T::RobonodePublicKey, T::Moment>],
+            current_moment: T::Moment,
+        ) -> u32 {
+            let active_at = |moment: T::Moment| -> u32 {
+                let active = robonode_public_keys
+                    .iter()
+                    .filter(|accepted_robonode_public_key| {
+                        (accepted_robonode_public_key.is_active
+                            || accepted_robonode_public_key.activates_at <= moment)
+                            && accepted_robonode_public_key
+                                .retires_at
+                                .map(|retires_at| retires_at > moment)
+                                .unwrap_or(true)
+                    })
+                    .count();
+                active
+                    .try_into()
+                    .expect("the number of the keys is bounded by a u32 limit")
+            };
+
+            robonode_public_keys
+                .iter()
+                .filter_map(|accepted_robonode_public_key| accepted_robonode_public_key.retires_at)
+                .filter(|retires_at| *retires_at > current_moment)
+                .map(active_at)
+                .fold(active_at(current_moment), core::cmp::min)
+        }
+
+        fn issue_validators_set_update() {
+            T::ValidatorSetUpdater::update_validators_set(ActiveAuthentications::<T>::iter_keys());
         }
     }
 
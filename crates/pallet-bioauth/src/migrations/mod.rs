@@ -1,3 +1,5 @@
 //! Storage migrations.
 
-pub mod consumed_auth_ticket_nonces_cleaner;
+pub mod v1;
+pub mod v2;
+pub mod v3;
@@ -0,0 +1,158 @@
+//! Migration to Version 1.
+
+use frame_support::{
+    log::{error, info},
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade},
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::{sp_runtime::TryRuntimeError, sp_std::vec::Vec};
+use sp_runtime::traits::Saturating;
+
+use self::storage::ConsumedAuthTicketNonces;
+use crate::{BoundedAuthTicketNonce, Config, ConsumedAuthTicketNonce, CurrentMoment, Pallet};
+
+/// The Version 0 storage types.
+#[cfg(feature = "try-runtime")]
+mod v0 {
+    use frame_support::{pallet_prelude::*, storage_alias};
+
+    use crate::{BoundedAuthTicketNonce, Config, Pallet};
+
+    /// The Version 0 consumed auth ticket nonces storage, without the expiration moments.
+    #[storage_alias]
+    pub type ConsumedAuthTicketNonces<T: Config> = StorageValue<
+        Pallet<T>,
+        BoundedVec<BoundedAuthTicketNonce, <T as Config>::MaxNonces>,
+        ValueQuery,
+    >;
+}
+
+/// The Version 1 storage types.
+pub(crate) mod storage {
+    use frame_support::{pallet_prelude::*, storage_alias};
+
+    use crate::{Authentication, Config, ConsumedAuthTicketNonce, Pallet};
+
+    /// The Version 1 consumed auth ticket nonces storage, as a single list.
+    #[storage_alias]
+    pub type ConsumedAuthTicketNonces<T: Config> = StorageValue<
+        Pallet<T>,
+        BoundedVec<ConsumedAuthTicketNonce<<T as Config>::Moment>, <T as Config>::MaxNonces>,
+        ValueQuery,
+    >;
+
+    /// The Version 1 active authentications storage, as a single list.
+    #[storage_alias]
+    pub type ActiveAuthentications<T: Config> = StorageValue<
+        Pallet<T>,
+        BoundedVec<
+            Authentication<<T as Config>::ValidatorPublicKey, <T as Config>::Moment>,
+            <T as Config>::MaxAuthentications,
+        >,
+        ValueQuery,
+    >;
+}
+
+/// Execute migration to version 1, assigning expiration moments to the consumed auth ticket nonces.
+pub struct MigrationToV1<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrationToV1<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let pallet_name = Pallet::<T>::name();
+        let onchain = Pallet::<T>::on_chain_storage_version();
+
+        // Read the onchain version.
+        let mut weight = T::DbWeight::get().reads(1);
+
+        if onchain >= 1 {
+            info!("{pallet_name}: Already at version 1, nothing to do");
+            return weight;
+        }
+
+        info!("{pallet_name}: Running migration to v1 from {onchain:?}");
+
+        // We don't know when the auth tickets of the already consumed nonces were issued, so we
+        // conservatively assume they were issued right now.
+        let expires_at = T::CurrentMoment::now().saturating_add(T::AuthTicketsExpireAfter::get());
+
+        let translation_result = ConsumedAuthTicketNonces::<T>::translate(
+            |maybe_consumed_auth_ticket_nonces: Option<
+                BoundedVec<BoundedAuthTicketNonce, T::MaxNonces>,
+            >| {
+                maybe_consumed_auth_ticket_nonces.map(|consumed_auth_ticket_nonces| {
+                    // We use truncate_from as the resulted vec has exactly the same length.
+                    BoundedVec::truncate_from(
+                        consumed_auth_ticket_nonces
+                            .into_iter()
+                            .map(|nonce| ConsumedAuthTicketNonce { nonce, expires_at })
+                            .collect(),
+                    )
+                })
+            },
+        );
+        // Read the old nonces and write the new ones.
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+
+        match translation_result {
+            Ok(Some(consumed_auth_ticket_nonces)) => info!(
+                "{pallet_name}: Migrated {} consumed auth ticket nonces",
+                consumed_auth_ticket_nonces.len()
+            ),
+            Ok(None) => info!("{pallet_name}: Nothing to migrate"),
+            Err(()) => error!(
+                "{pallet_name}: Unable to decode the consumed auth ticket nonces, they were dropped"
+            ),
+        }
+
+        // Set new version.
+        StorageVersion::new(1).put::<Pallet<T>>();
+
+        // Write the onchain version.
+        weight = weight.saturating_add(T::DbWeight::get().writes(1));
+
+        // Done.
+        weight
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+        let onchain = Pallet::<T>::on_chain_storage_version();
+
+        // Disable the check for newer versions by returning an empty state.
+        if onchain >= 1 {
+            return Ok(Vec::new());
+        }
+
+        // Record the consumed auth ticket nonces.
+        Ok(v0::ConsumedAuthTicketNonces::<T>::get().encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+        // Empty state means that the check is disabled.
+        if state.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            Pallet::<T>::on_chain_storage_version() == 1,
+            "The onchain storage version should be updated to 1"
+        );
+
+        let consumed_auth_ticket_nonces_before: Vec<BoundedAuthTicketNonce> =
+            Decode::decode(&mut &*state).map_err(|_| "Unable to decode the pre-upgrade state")?;
+        let consumed_auth_ticket_nonces_after = ConsumedAuthTicketNonces::<T>::get();
+
+        ensure!(
+            consumed_auth_ticket_nonces_before
+                .into_iter()
+                .eq(consumed_auth_ticket_nonces_after
+                    .into_iter()
+                    .map(|consumed_auth_ticket_nonce| consumed_auth_ticket_nonce.nonce)),
+            "The consumed auth ticket nonces should remain the same and in the same order"
+        );
+
+        Ok(())
+    }
+}
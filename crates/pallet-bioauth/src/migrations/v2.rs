@@ -0,0 +1,160 @@
+//! Migration to Version 2.
+
+#[cfg(feature = "try-runtime")]
+use frame_support::sp_runtime::TryRuntimeError;
+use frame_support::{
+    log::info,
+    pallet_prelude::*,
+    sp_std::vec::Vec,
+    traits::{GetStorageVersion, OnRuntimeUpgrade},
+};
+use sp_runtime::traits::Saturating;
+
+use super::v1::storage as v1;
+#[cfg(feature = "try-runtime")]
+use crate::{ActiveAuthentications, Authentication, ConsumedAuthTicketNonce};
+use crate::{Config, CurrentMoment, Pallet, PendingConsumedAuthTicketNonces};
+
+/// Execute migration to version 2, moving the active authentications from the list into
+/// the map, and dropping the expired consumed auth ticket nonces.
+///
+/// The rest of the consumed auth ticket nonces are left at
+/// the [`PendingConsumedAuthTicketNonces`], to be moved into the map in batches at
+/// the following blocks, as there might be too many of them to move at once.
+pub struct MigrationToV2<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrationToV2<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let pallet_name = Pallet::<T>::name();
+        let onchain = Pallet::<T>::on_chain_storage_version();
+
+        // Read the onchain version.
+        let mut weight = T::DbWeight::get().reads(1);
+
+        if onchain >= 2 {
+            info!("{pallet_name}: Already at version 2, nothing to do");
+            return weight;
+        }
+
+        info!("{pallet_name}: Running migration to v2 from {onchain:?}");
+
+        // The lists are stored under the same prefixes as the maps, so we have to take them out
+        // before populating the maps.
+        let consumed_auth_ticket_nonces = v1::ConsumedAuthTicketNonces::<T>::take();
+        let active_authentications = v1::ActiveAuthentications::<T>::take();
+        // Read and remove the lists.
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+
+        // The nonces of the expired auth tickets are not needed anymore.
+        let current_moment = T::CurrentMoment::now();
+        let consumed_auth_ticket_nonces_len = consumed_auth_ticket_nonces.len();
+        let pending_consumed_auth_ticket_nonces: Vec<_> = consumed_auth_ticket_nonces
+            .into_iter()
+            .filter(|consumed_auth_ticket_nonce| {
+                consumed_auth_ticket_nonce.expires_at > current_moment
+            })
+            .collect();
+        let pending_consumed_auth_ticket_nonces_len = pending_consumed_auth_ticket_nonces.len();
+        let dropped_consumed_auth_ticket_nonces_len =
+            consumed_auth_ticket_nonces_len.saturating_sub(pending_consumed_auth_ticket_nonces_len);
+        if !pending_consumed_auth_ticket_nonces.is_empty() {
+            // We use truncate_from as the list has only become shorter.
+            PendingConsumedAuthTicketNonces::<T>::put(BoundedVec::truncate_from(
+                pending_consumed_auth_ticket_nonces,
+            ));
+        }
+        // Read the current moment and write the pending nonces.
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+        info!(
+            "{pallet_name}: Dropped {dropped_consumed_auth_ticket_nonces_len} expired consumed auth ticket nonces, {pending_consumed_auth_ticket_nonces_len} are pending to be moved"
+        );
+
+        let active_authentications_len: u64 = active_authentications
+            .len()
+            .try_into()
+            .expect("u64 is big enough for this overflow to be practically impossible");
+        for active_authentication in active_authentications {
+            Pallet::<T>::insert_active_authentication(&active_authentication);
+        }
+        info!("{pallet_name}: Migrated {active_authentications_len} active authentications");
+
+        // Each insertion reads and writes the entry, the counter and the expiration tracking.
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(
+            active_authentications_len.saturating_mul(3),
+            active_authentications_len.saturating_mul(3),
+        ));
+
+        // Set new version.
+        StorageVersion::new(2).put::<Pallet<T>>();
+
+        // Write the onchain version.
+        weight = weight.saturating_add(T::DbWeight::get().writes(1));
+
+        // Done.
+        weight
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+        let onchain = Pallet::<T>::on_chain_storage_version();
+
+        // Disable the check for newer versions by returning an empty state.
+        if onchain >= 2 {
+            return Ok(Vec::new());
+        }
+
+        // Record the consumed auth ticket nonces and the active authentications.
+        Ok((
+            v1::ConsumedAuthTicketNonces::<T>::get().into_inner(),
+            v1::ActiveAuthentications::<T>::get().into_inner(),
+        )
+            .encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+        // Empty state means that the check is disabled.
+        if state.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            Pallet::<T>::on_chain_storage_version() == 2,
+            "The onchain storage version should be updated to 2"
+        );
+
+        let (consumed_auth_ticket_nonces_before, active_authentications_before): (
+            Vec<ConsumedAuthTicketNonce<T::Moment>>,
+            Vec<Authentication<T::ValidatorPublicKey, T::Moment>>,
+        ) = Decode::decode(&mut &*state).map_err(|_| "Unable to decode the pre-upgrade state")?;
+
+        let current_moment = T::CurrentMoment::now();
+        let expected_pending_consumed_auth_ticket_nonces: Vec<_> =
+            consumed_auth_ticket_nonces_before
+                .into_iter()
+                .filter(|consumed_auth_ticket_nonce| {
+                    consumed_auth_ticket_nonce.expires_at > current_moment
+                })
+                .collect();
+        ensure!(
+            PendingConsumedAuthTicketNonces::<T>::get().into_inner()
+                == expected_pending_consumed_auth_ticket_nonces,
+            "The consumed auth ticket nonces that have not expired should be pending to be moved"
+        );
+
+        ensure!(
+            u32::try_from(active_authentications_before.len()).ok()
+                == Some(ActiveAuthentications::<T>::count()),
+            "The number of the active authentications should remain the same"
+        );
+        for active_authentication in active_authentications_before {
+            ensure!(
+                ActiveAuthentications::<T>::get(&active_authentication.public_key)
+                    == Some(active_authentication.expires_at),
+                "The active authentications should remain the same"
+            );
+        }
+
+        Ok(())
+    }
+}
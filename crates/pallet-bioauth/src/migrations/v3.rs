@@ -0,0 +1,105 @@
+//! Migration to Version 3.
+
+use frame_support::{
+    log::info,
+    pallet_prelude::*,
+    traits::{GetStorageVersion, OnRuntimeUpgrade},
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::{sp_runtime::TryRuntimeError, sp_std::vec::Vec};
+use sp_runtime::traits::Saturating;
+
+use self::storage::RobonodePublicKey;
+use crate::{AcceptedRobonodePublicKey, Config, Pallet, RobonodePublicKeys};
+
+/// The Version 2 storage types.
+pub(crate) mod storage {
+    use frame_support::{pallet_prelude::*, storage_alias};
+
+    use crate::{Config, Pallet};
+
+    /// The Version 2 robonode public key storage, as a single key.
+    #[storage_alias]
+    pub type RobonodePublicKey<T: Config> =
+        StorageValue<Pallet<T>, <T as Config>::RobonodePublicKey, ValueQuery>;
+}
+
+/// Execute migration to version 3, moving the single robonode public key into the set of
+/// the accepted robonode public keys.
+pub struct MigrationToV3<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrationToV3<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let pallet_name = Pallet::<T>::name();
+        let onchain = Pallet::<T>::on_chain_storage_version();
+
+        // Read the onchain version.
+        let mut weight = T::DbWeight::get().reads(1);
+
+        if onchain >= 3 {
+            info!("{pallet_name}: Already at version 3, nothing to do");
+            return weight;
+        }
+
+        info!("{pallet_name}: Running migration to v3 from {onchain:?}");
+
+        // The key has been accepted all along, so it is active from the very start.
+        let robonode_public_key = RobonodePublicKey::<T>::take();
+        RobonodePublicKeys::<T>::put(BoundedVec::truncate_from(sp_std::vec![
+            AcceptedRobonodePublicKey {
+                public_key: robonode_public_key,
+                activates_at: Default::default(),
+                retires_at: None,
+                is_active: true,
+            }
+        ]));
+
+        // Read and remove the old key, write the new set.
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+
+        // Set new version.
+        StorageVersion::new(3).put::<Pallet<T>>();
+
+        // Write the onchain version.
+        weight = weight.saturating_add(T::DbWeight::get().writes(1));
+
+        // Done.
+        weight
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+        let onchain = Pallet::<T>::on_chain_storage_version();
+
+        // Disable the check for newer versions by returning an empty state.
+        if onchain >= 3 {
+            return Ok(Vec::new());
+        }
+
+        // Record the robonode public key.
+        Ok(RobonodePublicKey::<T>::get().encode())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+        // Empty state means that the check is disabled.
+        if state.is_empty() {
+            return Ok(());
+        }
+
+        ensure!(
+            Pallet::<T>::on_chain_storage_version() == 3,
+            "The onchain storage version should be updated to 3"
+        );
+
+        let robonode_public_key_before: <T as Config>::RobonodePublicKey =
+            Decode::decode(&mut &*state).map_err(|_| "Unable to decode the pre-upgrade state")?;
+
+        ensure!(
+            Pallet::<T>::active_robonode_public_keys() == sp_std::vec![robonode_public_key_before],
+            "The robonode public key should remain the only active one"
+        );
+
+        Ok(())
+    }
+}
@@ -39,7 +39,7 @@ pub type ValidatorPublicKey = [u8; 32];
 
 #[derive(PartialEq, Eq, Default, Clone, Encode, Decode, Hash, Debug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct MockOpaqueAuthTicket(pub AuthTicket<ValidatorPublicKey>);
+pub struct MockOpaqueAuthTicket(pub AuthTicket<ValidatorPublicKey, UnixMilliseconds>);
 
 impl AsRef<[u8]> for MockOpaqueAuthTicket {
     fn as_ref(&self) -> &[u8] {
@@ -55,12 +55,14 @@ impl From<Vec<u8>> for MockOpaqueAuthTicket {
 
 pub struct MockAuthTicketConverter;
 
-impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey>> for MockAuthTicketConverter {
+impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey, UnixMilliseconds>>
+    for MockAuthTicketConverter
+{
     type Error = DeriveError;
 
     fn try_convert(
         value: MockOpaqueAuthTicket,
-    ) -> Result<AuthTicket<ValidatorPublicKey>, Self::Error> {
+    ) -> Result<AuthTicket<ValidatorPublicKey, UnixMilliseconds>, Self::Error> {
         Ok(value.0)
     }
 }
@@ -70,6 +72,7 @@ impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey>> for MockAu
 pub enum MockVerifier {
     A,
     B,
+    Indexed(u8),
 }
 
 impl Default for MockVerifier {
@@ -98,19 +101,9 @@ impl MaxEncodedLen for MockVerifier {
 pub struct MockValidatorSetUpdater;
 
 impl crate::ValidatorSetUpdater<ValidatorPublicKey> for MockValidatorSetUpdater {
-    fn update_validators_set<'a, I: Iterator<Item = &'a ValidatorPublicKey> + 'a>(
-        _validator_public_keys: I,
-    ) where
-        ValidatorPublicKey: 'a,
-    {
-    }
+    fn update_validators_set<I: Iterator<Item = ValidatorPublicKey>>(_validator_public_keys: I) {}
 
-    fn init_validators_set<'a, I: Iterator<Item = &'a ValidatorPublicKey> + 'a>(
-        _validator_public_keys: I,
-    ) where
-        ValidatorPublicKey: 'a,
-    {
-    }
+    fn init_validators_set<I: Iterator<Item = ValidatorPublicKey>>(_validator_public_keys: I) {}
 }
 
 pub struct MockCurrentMomentProvider;
@@ -152,8 +145,13 @@ const TIMESTAMP_SECOND: UnixMilliseconds = 1000;
 const TIMESTAMP_MINUTE: UnixMilliseconds = 60 * TIMESTAMP_SECOND;
 
 pub const AUTHENTICATIONS_EXPIRE_AFTER: UnixMilliseconds = TIMESTAMP_MINUTE;
+pub const AUTH_TICKETS_EXPIRE_AFTER: UnixMilliseconds = 10 * TIMESTAMP_MINUTE;
+pub const AUTH_TICKETS_MAX_CLOCK_DRIFT: UnixMilliseconds = TIMESTAMP_MINUTE;
+pub const AUTHENTICATIONS_RENEWAL_WINDOW: UnixMilliseconds = 10 * TIMESTAMP_SECOND;
 pub const MAX_AUTHENTICATIONS: u32 = 512;
 pub const MAX_NONCES: u32 = 512;
+pub const MAX_NONCE_EXPIRATIONS_PER_BLOCK: u32 = 64;
+pub const MAX_ROBONODE_PUBLIC_KEYS: u32 = 4;
 
 pub struct DisplayMoment;
 
@@ -181,9 +179,14 @@ impl pallet_bioauth::Config for Benchmark {
     type DisplayMoment = DisplayMoment;
     type CurrentMoment = MockCurrentMomentProvider;
     type AuthenticationsExpireAfter = ConstU64<AUTHENTICATIONS_EXPIRE_AFTER>;
+    type AuthTicketsExpireAfter = ConstU64<AUTH_TICKETS_EXPIRE_AFTER>;
+    type AuthTicketsMaxClockDrift = ConstU64<AUTH_TICKETS_MAX_CLOCK_DRIFT>;
+    type AuthenticationsRenewalWindow = ConstU64<AUTHENTICATIONS_RENEWAL_WINDOW>;
     type WeightInfo = ();
     type MaxAuthentications = ConstU32<MAX_AUTHENTICATIONS>;
     type MaxNonces = ConstU32<MAX_NONCES>;
+    type MaxNonceExpirationsPerBlock = ConstU32<MAX_NONCE_EXPIRATIONS_PER_BLOCK>;
+    type MaxRobonodePublicKeys = ConstU32<MAX_ROBONODE_PUBLIC_KEYS>;
     type BeforeAuthHook = ();
     type AfterAuthHook = ();
     type DeauthenticationReason = ();
@@ -201,11 +204,13 @@ impl crate::benchmarking::AuthTicketBuilder for Benchmark {
     fn build(
         public_key: Vec<u8>,
         nonce: Vec<u8>,
+        issued_at: UnixMilliseconds,
     ) -> <Self as pallet_bioauth::Config>::OpaqueAuthTicket {
         let public_key_fixed_size: [u8; 32] = public_key.try_into().unwrap();
         let opaque_auth_ticket = AuthTicket {
             public_key: public_key_fixed_size,
             nonce,
+            issued_at,
         };
         MockOpaqueAuthTicket(opaque_auth_ticket)
     }
@@ -217,6 +222,9 @@ impl crate::benchmarking::RobonodePublicKeyBuilder for Benchmark {
         match value {
             crate::benchmarking::RobonodePublicKeyBuilderValue::A => MockVerifier::A,
             crate::benchmarking::RobonodePublicKeyBuilderValue::B => MockVerifier::B,
+            crate::benchmarking::RobonodePublicKeyBuilderValue::Indexed(idx) => {
+                MockVerifier::Indexed(idx)
+            }
         }
     }
 }
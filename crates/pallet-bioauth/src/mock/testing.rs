@@ -42,7 +42,7 @@ pub type ValidatorPublicKey = [u8; 32];
 
 #[derive(PartialEq, Eq, Default, Clone, Encode, Decode, Hash, Debug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct MockOpaqueAuthTicket(pub AuthTicket<ValidatorPublicKey>);
+pub struct MockOpaqueAuthTicket(pub AuthTicket<ValidatorPublicKey, UnixMilliseconds>);
 
 impl AsRef<[u8]> for MockOpaqueAuthTicket {
     fn as_ref(&self) -> &[u8] {
@@ -51,12 +51,14 @@ impl AsRef<[u8]> for MockOpaqueAuthTicket {
 }
 pub struct MockAuthTicketConverter;
 
-impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey>> for MockAuthTicketConverter {
+impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey, UnixMilliseconds>>
+    for MockAuthTicketConverter
+{
     type Error = DeriveError;
 
     fn try_convert(
         value: MockOpaqueAuthTicket,
-    ) -> Result<AuthTicket<ValidatorPublicKey>, Self::Error> {
+    ) -> Result<AuthTicket<ValidatorPublicKey, UnixMilliseconds>, Self::Error> {
         Ok(value.0)
     }
 }
@@ -81,7 +83,10 @@ impl crate::Verifier<Vec<u8>> for MockVerifier {
     where
         D: AsRef<[u8]> + Send + 'a,
     {
-        Ok(signature.starts_with(b"should_be_valid"))
+        match self {
+            Self::A => Ok(signature.starts_with(b"should_be_valid")),
+            Self::B => Ok(signature.starts_with(b"signed_by_b")),
+        }
     }
 }
 
@@ -102,26 +107,28 @@ thread_local! {
     pub static MOCK_VALIDATOR_SET_UPDATER: RefCell<MockValidatorSetUpdater> = RefCell::new(MockValidatorSetUpdater::new());
 }
 
+/// Collect the validator public keys in a deterministic order, as the order of the storage map
+/// iteration is not meaningful.
+fn sorted_validator_public_keys<I: Iterator<Item = ValidatorPublicKey>>(
+    validator_public_keys: I,
+) -> Vec<ValidatorPublicKey> {
+    let mut validator_public_keys: Vec<_> = validator_public_keys.collect();
+    validator_public_keys.sort();
+    validator_public_keys
+}
+
 impl crate::ValidatorSetUpdater<ValidatorPublicKey> for MockValidatorSetUpdater {
-    fn update_validators_set<'a, I: Iterator<Item = &'a ValidatorPublicKey> + 'a>(
-        validator_public_keys: I,
-    ) where
-        ValidatorPublicKey: 'a,
-    {
+    fn update_validators_set<I: Iterator<Item = ValidatorPublicKey>>(validator_public_keys: I) {
         MOCK_VALIDATOR_SET_UPDATER.with(|val| {
             val.borrow_mut()
-                .update_validators_set(validator_public_keys.copied().collect())
+                .update_validators_set(sorted_validator_public_keys(validator_public_keys))
         });
     }
 
-    fn init_validators_set<'a, I: Iterator<Item = &'a ValidatorPublicKey> + 'a>(
-        validator_public_keys: I,
-    ) where
-        ValidatorPublicKey: 'a,
-    {
+    fn init_validators_set<I: Iterator<Item = ValidatorPublicKey>>(validator_public_keys: I) {
         MOCK_VALIDATOR_SET_UPDATER.with(|val| {
             val.borrow_mut()
-                .init_validators_set(validator_public_keys.copied().collect())
+                .init_validators_set(sorted_validator_public_keys(validator_public_keys))
         });
     }
 }
@@ -235,8 +242,13 @@ const TIMESTAMP_SECOND: UnixMilliseconds = 1000;
 const TIMESTAMP_MINUTE: UnixMilliseconds = 60 * TIMESTAMP_SECOND;
 
 pub const AUTHENTICATIONS_EXPIRE_AFTER: UnixMilliseconds = TIMESTAMP_MINUTE;
+pub const AUTH_TICKETS_EXPIRE_AFTER: UnixMilliseconds = 10 * TIMESTAMP_MINUTE;
+pub const AUTH_TICKETS_MAX_CLOCK_DRIFT: UnixMilliseconds = TIMESTAMP_MINUTE;
+pub const AUTHENTICATIONS_RENEWAL_WINDOW: UnixMilliseconds = 10 * TIMESTAMP_SECOND;
 pub const MAX_AUTHENTICATIONS: u32 = 512;
 pub const MAX_NONCES: u32 = 512;
+pub const MAX_NONCE_EXPIRATIONS_PER_BLOCK: u32 = 16;
+pub const MAX_ROBONODE_PUBLIC_KEYS: u32 = 4;
 
 pub struct DisplayMoment;
 
@@ -264,9 +276,14 @@ impl pallet_bioauth::Config for Test {
     type DisplayMoment = DisplayMoment;
     type CurrentMoment = MockCurrentMomentProvider;
     type AuthenticationsExpireAfter = ConstU64<AUTHENTICATIONS_EXPIRE_AFTER>;
+    type AuthTicketsExpireAfter = ConstU64<AUTH_TICKETS_EXPIRE_AFTER>;
+    type AuthTicketsMaxClockDrift = ConstU64<AUTH_TICKETS_MAX_CLOCK_DRIFT>;
+    type AuthenticationsRenewalWindow = ConstU64<AUTHENTICATIONS_RENEWAL_WINDOW>;
     type WeightInfo = ();
     type MaxAuthentications = ConstU32<MAX_AUTHENTICATIONS>;
     type MaxNonces = ConstU32<MAX_NONCES>;
+    type MaxNonceExpirationsPerBlock = ConstU32<MAX_NONCE_EXPIRATIONS_PER_BLOCK>;
+    type MaxRobonodePublicKeys = ConstU32<MAX_ROBONODE_PUBLIC_KEYS>;
     type BeforeAuthHook = MockBeforeAuthHookProvider;
     type AfterAuthHook = MockAfterAuthHookProvider;
     type DeauthenticationReason = ();
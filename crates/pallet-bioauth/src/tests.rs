@@ -4,8 +4,11 @@
 use std::ops::Div;
 
 use frame_support::{
-    assert_err, assert_noop, assert_ok, assert_storage_noop, dispatch::DispatchInfo,
-    pallet_prelude::*, traits::ConstU32, BoundedVec,
+    assert_err, assert_noop, assert_ok, assert_storage_noop,
+    dispatch::DispatchInfo,
+    pallet_prelude::*,
+    traits::{ConstU32, GetStorageVersion, OnRuntimeUpgrade},
+    BoundedVec,
 };
 use mockall::predicate;
 
@@ -21,13 +24,23 @@ fn make_input(
     public_key: ValidatorPublicKey,
     nonce: &[u8],
     signature: &[u8],
+) -> pallet_bioauth::Authenticate<MockOpaqueAuthTicket, Vec<u8>> {
+    make_input_issued_at(public_key, nonce, signature, CHAIN_START)
+}
+
+fn make_input_issued_at(
+    public_key: ValidatorPublicKey,
+    nonce: &[u8],
+    signature: &[u8],
+    issued_at: UnixMilliseconds,
 ) -> pallet_bioauth::Authenticate<MockOpaqueAuthTicket, Vec<u8>> {
     pallet_bioauth::Authenticate {
         ticket: MockOpaqueAuthTicket(AuthTicket {
             public_key,
             nonce: nonce.into(),
+            issued_at,
         }),
-        ticket_signature: signature.into(),
+        ticket_signatures: vec![signature.into()],
     }
 }
 
@@ -45,19 +58,81 @@ fn make_bounded_active_authentications(
     BoundedVec::<_, ConstU32<MAX_AUTHENTICATIONS>>::try_from(authentications).unwrap()
 }
 
+/// Make the consumed auth nonces of the tickets issued at the [`CHAIN_START`].
 fn make_bounded_consumed_auth_nonces(
     auth_nonces: Vec<Vec<u8>>,
-) -> BoundedVec<pallet_bioauth::BoundedAuthTicketNonce, ConstU32<MAX_NONCES>> {
+) -> BoundedVec<ConsumedAuthTicketNonce<UnixMilliseconds>, ConstU32<MAX_NONCES>> {
+    make_bounded_consumed_auth_nonces_expiring_at(
+        auth_nonces,
+        CHAIN_START + AUTH_TICKETS_EXPIRE_AFTER,
+    )
+}
+
+fn make_bounded_consumed_auth_nonces_expiring_at(
+    auth_nonces: Vec<Vec<u8>>,
+    expires_at: UnixMilliseconds,
+) -> BoundedVec<ConsumedAuthTicketNonce<UnixMilliseconds>, ConstU32<MAX_NONCES>> {
     BoundedVec::<_, ConstU32<MAX_NONCES>>::try_from(
         auth_nonces
             .iter()
             .cloned()
-            .map(|nonce| BoundedAuthTicketNonce::try_from(nonce).unwrap())
+            .map(|nonce| ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(nonce).unwrap(),
+                expires_at,
+            })
             .collect::<Vec<_>>(),
     )
     .unwrap()
 }
 
+/// Populate the active authentications state.
+fn populate_active_authentications(
+    authentications: impl IntoIterator<Item = Authentication<ValidatorPublicKey, UnixMilliseconds>>,
+) {
+    for authentication in authentications {
+        Bioauth::insert_active_authentication(&authentication);
+    }
+}
+
+/// Populate the consumed auth ticket nonces state.
+fn populate_consumed_auth_ticket_nonces(
+    nonces: impl IntoIterator<Item = ConsumedAuthTicketNonce<UnixMilliseconds>>,
+) {
+    for consumed_auth_ticket_nonce in nonces {
+        Bioauth::insert_consumed_auth_ticket_nonce(
+            &consumed_auth_ticket_nonce.nonce,
+            consumed_auth_ticket_nonce.expires_at,
+        );
+    }
+}
+
+/// Assert that the consumed auth ticket nonces state contains exactly the expected nonces.
+#[track_caller]
+fn assert_consumed_auth_ticket_nonces(
+    expected: impl IntoIterator<Item = ConsumedAuthTicketNonce<UnixMilliseconds>>,
+) {
+    let expected: Vec<_> = expected.into_iter().collect();
+    assert_eq!(
+        <ConsumedAuthTicketNonces<Test>>::count(),
+        u32::try_from(expected.len()).unwrap()
+    );
+    assert_eq!(
+        <ConsumedAuthTicketNoncesExpirations<Test>>::iter_keys().count(),
+        expected.len()
+    );
+    for consumed_auth_ticket_nonce in expected {
+        let nonce_hash = Bioauth::auth_ticket_nonce_hash(&consumed_auth_ticket_nonce.nonce);
+        assert_eq!(
+            <ConsumedAuthTicketNonces<Test>>::get(nonce_hash),
+            Some(consumed_auth_ticket_nonce.expires_at)
+        );
+        assert!(<ConsumedAuthTicketNoncesExpirations<Test>>::contains_key((
+            consumed_auth_ticket_nonce.expires_at.to_be_bytes(),
+            nonce_hash
+        )));
+    }
+}
+
 fn bounded(data: &[u8]) -> [u8; 32] {
     let mut bounded = [0u8; 32];
     bounded[..data.len()].copy_from_slice(data);
@@ -134,10 +209,9 @@ fn authentication_with_empty_state() {
             }]
         );
         // Ensure that the state of ConsumedAuthTicketNonces has been updated.
-        assert_eq!(
-            Bioauth::consumed_auth_ticket_nonces().into_inner(),
-            vec![b"rty".to_vec()]
-        );
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(
+            vec![b"rty".to_vec()],
+        ));
 
         System::assert_has_event(RuntimeEvent::Bioauth(Event::NewAuthentication {
             authentication: Authentication {
@@ -165,8 +239,8 @@ fn authentication_expires_exactly_at_the_moment() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"alice_auth_ticket_nonce".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations.
         with_mock_validator_set_updater(|mock| {
@@ -198,10 +272,9 @@ fn authentication_expires_exactly_at_the_moment() {
         // Ensure that authentication expires.
         assert_eq!(Bioauth::active_authentications(), vec![]);
         // Ensure that nonce didn't go anywhere as it's still listed as blocked.
-        assert_eq!(
-            Bioauth::consumed_auth_ticket_nonces().into_inner(),
-            vec![b"alice_auth_ticket_nonce".to_vec()]
-        );
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(vec![
+            b"alice_auth_ticket_nonce".to_vec(),
+        ]));
 
         System::assert_has_event(RuntimeEvent::Bioauth(Event::AuthenticationsExpired {
             expired: vec![Authentication {
@@ -230,8 +303,8 @@ fn authentication_expires_in_successive_block() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"alice_auth_ticket_nonce".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations.
         with_mock_validator_set_updater(|mock| {
@@ -263,10 +336,9 @@ fn authentication_expires_in_successive_block() {
         // Ensure that authentication expires.
         assert_eq!(Bioauth::active_authentications(), vec![]);
         // Ensure that nonce didn't go anywhere as it's still listed as blocked.
-        assert_eq!(
-            Bioauth::consumed_auth_ticket_nonces().into_inner(),
-            vec![b"alice_auth_ticket_nonce".to_vec()]
-        );
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(vec![
+            b"alice_auth_ticket_nonce".to_vec(),
+        ]));
 
         System::assert_has_event(RuntimeEvent::Bioauth(Event::AuthenticationsExpired {
             expired: vec![Authentication {
@@ -306,8 +378,8 @@ fn authentication_expiration_lifecycle() {
             mock.expect_hook().never();
         });
 
-        <ActiveAuthentications<Test>>::put(bounded_authentication);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_nonce);
+        populate_active_authentications(bounded_authentication);
+        populate_consumed_auth_ticket_nonces(bounded_nonce);
 
         loop {
             // Set up mock expectations.
@@ -323,10 +395,9 @@ fn authentication_expiration_lifecycle() {
                 vec![authentication.clone()]
             );
             // Ensure that nonce didn't go anywhere and it's still listed as blocked.
-            assert_eq!(
-                Bioauth::consumed_auth_ticket_nonces().into_inner(),
-                vec![nonce.clone()]
-            );
+            assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(vec![
+                nonce.clone()
+            ]));
 
             // Advance the block number and the current moment.
             System::set_block_number(System::block_number() + 1);
@@ -359,10 +430,7 @@ fn authentication_expiration_lifecycle() {
         // Ensure that authentication is gone.
         assert_eq!(Bioauth::active_authentications(), vec![]);
         // Ensure that nonce didn't go anywhere and it's still listed as blocked.
-        assert_eq!(
-            Bioauth::consumed_auth_ticket_nonces().into_inner(),
-            vec![nonce]
-        );
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(vec![nonce]));
 
         System::assert_has_event(RuntimeEvent::Bioauth(Event::AuthenticationsExpired {
             expired: vec![Authentication {
@@ -373,6 +441,146 @@ fn authentication_expiration_lifecycle() {
     });
 }
 
+/// This test verifies that the consumed auth ticket nonces are removed from the state once
+/// the corresponding auth tickets expire.
+#[test]
+fn consumed_auth_ticket_nonces_expire() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let current_moment = CHAIN_START + 2 * SLOT_DURATION;
+
+        let consumed_auth_ticket_nonces = BoundedVec::<_, ConstU32<MAX_NONCES>>::try_from(vec![
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"expired_nonce".to_vec()).unwrap(),
+                expires_at: current_moment,
+            },
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"active_nonce".to_vec()).unwrap(),
+                expires_at: current_moment + 1,
+            },
+        ])
+        .unwrap();
+
+        populate_consumed_auth_ticket_nonces(consumed_auth_ticket_nonces);
+
+        // Set up mock expectations.
+        with_mock_validator_set_updater(|mock| {
+            mock.expect_update_validators_set().never();
+        });
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(current_moment);
+        });
+        with_mock_before_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+        with_mock_after_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+
+        // Process the block.
+        Bioauth::on_initialize(block_to_process_moment(current_moment));
+
+        // Ensure that only the nonce of the expired auth ticket has been removed.
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces_expiring_at(
+            vec![b"active_nonce".to_vec()],
+            current_moment + 1,
+        ));
+    });
+}
+
+/// This test verifies that only the consumed auth ticket nonces that are due to expire are
+/// touched when the nonces are removed, regardless of the order they were consumed in.
+#[test]
+fn consumed_auth_ticket_nonces_expire_in_order() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let current_moment = CHAIN_START + 2 * SLOT_DURATION;
+
+        let consumed_auth_ticket_nonces = BoundedVec::<_, ConstU32<MAX_NONCES>>::try_from(vec![
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"late_nonce".to_vec()).unwrap(),
+                expires_at: current_moment + 256,
+            },
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"expired_nonce".to_vec()).unwrap(),
+                expires_at: current_moment - 1,
+            },
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"active_nonce".to_vec()).unwrap(),
+                expires_at: current_moment + 1,
+            },
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"due_nonce".to_vec()).unwrap(),
+                expires_at: current_moment,
+            },
+        ])
+        .unwrap();
+
+        populate_consumed_auth_ticket_nonces(consumed_auth_ticket_nonces);
+
+        // Remove the expired nonces.
+        let removed = Bioauth::expire_consumed_auth_ticket_nonces(current_moment);
+
+        // Ensure that only the due nonces have been touched.
+        assert_eq!(removed, 2);
+        assert_consumed_auth_ticket_nonces(vec![
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"active_nonce".to_vec()).unwrap(),
+                expires_at: current_moment + 1,
+            },
+            ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"late_nonce".to_vec()).unwrap(),
+                expires_at: current_moment + 256,
+            },
+        ]);
+
+        // Ensure that nothing is touched when nothing is due.
+        assert_eq!(
+            Bioauth::expire_consumed_auth_ticket_nonces(current_moment),
+            0
+        );
+    });
+}
+
+/// This test verifies that at most the configured number of the due consumed auth ticket nonces
+/// are removed at once, and the rest are removed later.
+#[test]
+fn consumed_auth_ticket_nonces_expire_in_batches() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let current_moment = CHAIN_START + 2 * SLOT_DURATION;
+
+        let due_nonces = (0..=MAX_NONCE_EXPIRATIONS_PER_BLOCK)
+            .map(|idx| format!("due_nonce_{idx}").into_bytes())
+            .collect();
+        populate_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces_expiring_at(
+            due_nonces,
+            current_moment,
+        ));
+        populate_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces_expiring_at(
+            vec![b"active_nonce".to_vec()],
+            current_moment + 1,
+        ));
+
+        // Remove the first batch of the expired nonces.
+        assert_eq!(
+            Bioauth::expire_consumed_auth_ticket_nonces(current_moment),
+            MAX_NONCE_EXPIRATIONS_PER_BLOCK
+        );
+        assert_eq!(<ConsumedAuthTicketNonces<Test>>::count(), 2);
+
+        // Remove the rest of the expired nonces.
+        assert_eq!(
+            Bioauth::expire_consumed_auth_ticket_nonces(current_moment),
+            1
+        );
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces_expiring_at(
+            vec![b"active_nonce".to_vec()],
+            current_moment + 1,
+        ));
+    });
+}
+
 /// This test verifies that authentication call works correctly when a previous
 /// authentication has been expired.
 #[test]
@@ -390,8 +598,8 @@ fn authentication_when_previous_one_has_been_expired() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"alice_auth_ticket_nonce".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Prepare the test input.
         let input = make_input(
@@ -472,13 +680,10 @@ fn authentication_when_previous_one_has_been_expired() {
         );
 
         // Ensure that the current state of ConsumedAuthTicketNonces has nonces from both authentications.
-        assert_eq!(
-            Bioauth::consumed_auth_ticket_nonces().into_inner(),
-            vec![
-                b"alice_auth_ticket_nonce".to_vec(),
-                b"new_alice_auth_ticket_nonce".to_vec()
-            ]
-        );
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(vec![
+            b"alice_auth_ticket_nonce".to_vec(),
+            b"new_alice_auth_ticket_nonce".to_vec(),
+        ]));
 
         System::assert_has_event(RuntimeEvent::Bioauth(Event::NewAuthentication {
             authentication: Authentication {
@@ -489,7 +694,7 @@ fn authentication_when_previous_one_has_been_expired() {
     });
 }
 
-/// This test prevents authentication when the authentications limit has been reached as `BoundedVec`.
+/// This test prevents authentication when the authentications limit has been reached.
 #[test]
 fn too_many_authentications() {
     new_test_ext().execute_with(|| {
@@ -509,7 +714,7 @@ fn too_many_authentications() {
         let bounded_active_authentications =
             make_bounded_active_authentications(active_authentications);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
+        populate_active_authentications(bounded_active_authentications);
 
         // Prepare the test input.
         let input = make_input(
@@ -552,7 +757,7 @@ fn too_many_authentications() {
 }
 
 /// This test prevents authentication when the consumed auth ticket nonces
-/// limit has been reached as `BoundedVec`.
+/// limit has been reached.
 #[test]
 fn too_many_nonces() {
     new_test_ext().execute_with(|| {
@@ -567,7 +772,7 @@ fn too_many_nonces() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(consumed_auth_ticket_nonces);
 
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Prepare the test input.
         let input = make_input(
@@ -649,6 +854,70 @@ fn authentication_with_invalid_signature() {
     });
 }
 
+/// This test prevents authentication call with an expired auth ticket.
+#[test]
+fn authentication_with_expired_auth_ticket() {
+    new_test_ext().execute_with(|| {
+        // Prepare test input.
+        let issued_at = CHAIN_START + SLOT_DURATION;
+        let input = make_input_issued_at(bounded(b"qwe"), b"rty", b"should_be_valid", issued_at);
+
+        // Set up mock expectations.
+        with_mock_validator_set_updater(|mock| {
+            mock.expect_update_validators_set().never();
+        });
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(issued_at + AUTH_TICKETS_EXPIRE_AFTER);
+        });
+        with_mock_before_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+        with_mock_after_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+
+        // Make test.
+        assert_noop!(
+            Bioauth::authenticate(RuntimeOrigin::none(), input),
+            Error::<Test>::AuthTicketExpired
+        );
+    });
+}
+
+/// This test prevents authentication call with an auth ticket issued further in the future
+/// than the clock drift allows.
+#[test]
+fn authentication_with_auth_ticket_issued_in_future() {
+    new_test_ext().execute_with(|| {
+        // Prepare test input.
+        let issued_at = CHAIN_START + AUTH_TICKETS_MAX_CLOCK_DRIFT + 1;
+        let input = make_input_issued_at(bounded(b"qwe"), b"rty", b"should_be_valid", issued_at);
+
+        // Set up mock expectations.
+        with_mock_validator_set_updater(|mock| {
+            mock.expect_update_validators_set().never();
+        });
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(CHAIN_START);
+        });
+        with_mock_before_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+        with_mock_after_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+
+        // Make test.
+        assert_noop!(
+            Bioauth::authenticate(RuntimeOrigin::none(), input),
+            Error::<Test>::AuthTicketIssuedInFuture
+        );
+    });
+}
+
 /// This test prevents authentication call with conflicting nonces.
 #[test]
 fn authentication_with_conlicting_nonce() {
@@ -665,8 +934,8 @@ fn authentication_with_conlicting_nonce() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"conflict!".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations.
         with_mock_validator_set_updater(|mock| {
@@ -693,6 +962,41 @@ fn authentication_with_conlicting_nonce() {
     });
 }
 
+/// This test prevents authentication call with a nonce that is yet to be moved into the map
+/// after the migration to v2.
+#[test]
+fn authentication_with_conlicting_pending_nonce() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test precondition.
+        <PendingConsumedAuthTicketNonces<Test>>::put(make_bounded_consumed_auth_nonces(vec![
+            b"conflict!".to_vec(),
+        ]));
+
+        // Set up mock expectations.
+        with_mock_validator_set_updater(|mock| {
+            mock.expect_update_validators_set().never();
+        });
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().never();
+        });
+        with_mock_before_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+        with_mock_after_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+
+        // Prepare test input.
+        let input = make_input(bounded(b"pk1"), b"conflict!", b"should_be_valid");
+
+        // Make test.
+        assert_noop!(
+            Bioauth::authenticate(RuntimeOrigin::none(), input),
+            Error::<Test>::NonceAlreadyUsed,
+        );
+    });
+}
+
 /// This test prevents authentication call with conflicting nonces when previous
 /// authentication has been expired.
 #[test]
@@ -710,8 +1014,8 @@ fn authentication_with_conlicting_nonce_after_expiration() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"alice_auth_ticket_nonce".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations for Bioauth::on_initialize.
         with_mock_validator_set_updater(|mock| {
@@ -777,15 +1081,16 @@ fn authentication_with_concurrent_conflicting_public_keys() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"nonce1".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations.
         with_mock_validator_set_updater(|mock| {
             mock.expect_update_validators_set().never();
         });
         with_mock_current_moment_provider(|mock| {
-            mock.expect_now().never();
+            // Before the renewal window of the active authentication.
+            mock.expect_now().once().with().return_const(CHAIN_START);
         });
         with_mock_before_auth_hook_provider(|mock| {
             mock.expect_hook().never();
@@ -805,16 +1110,23 @@ fn authentication_with_concurrent_conflicting_public_keys() {
     });
 }
 
-/// This test verifies that before auth hook can deny the authentication
-/// and the resulting state is proper.
+/// This test verifies that the active authentication is renewed in place with a fresh auth
+/// ticket within the renewal window.
 #[test]
-fn authentication_denied_by_before_hook() {
+fn authentication_renewal_within_renewal_window() {
     new_test_ext().execute_with(|| {
-        // Prepare test input.
-        let input = make_input(bounded(b"qwe"), b"rty", b"should_be_valid");
-        let current_moment = CHAIN_START + 2 * SLOT_DURATION;
+        // Prepare the test precondition.
+        let previous_expires_at = CHAIN_START + AUTHENTICATIONS_EXPIRE_AFTER;
+        let current_moment = previous_expires_at - AUTHENTICATIONS_RENEWAL_WINDOW;
         let expires_at = current_moment + AUTHENTICATIONS_EXPIRE_AFTER;
 
+        populate_active_authentications(make_bounded_active_authentications(vec![
+            Authentication {
+                public_key: bounded(b"alice_pk"),
+                expires_at: previous_expires_at,
+            },
+        ]));
+
         // Set up mock expectations.
         with_mock_validator_set_updater(|mock| {
             mock.expect_update_validators_set().never();
@@ -828,71 +1140,197 @@ fn authentication_denied_by_before_hook() {
                 .withf(move |authentication| {
                     authentication
                         == &Authentication {
-                            public_key: bounded(b"qwe"),
+                            public_key: bounded(b"alice_pk"),
                             expires_at,
                         }
                 })
-                .return_const(Err(sp_runtime::DispatchError::CannotLookup));
+                .return_const(Ok(()));
         });
         with_mock_after_auth_hook_provider(|mock| {
-            mock.expect_hook().never();
+            mock.expect_hook()
+                .once()
+                .with(predicate::eq(()))
+                .return_const(());
         });
 
-        // Ensure that authentication call is denied.
-        assert_err!(
-            Bioauth::authenticate(RuntimeOrigin::none(), input),
-            sp_runtime::DispatchError::CannotLookup
-        );
+        // Set block number to enable events.
+        System::set_block_number(1);
 
-        // Ensure that the state of ActiveAuthentications has not been updated.
-        assert_eq!(Bioauth::active_authentications(), vec![]);
+        // Prepare test input.
+        let input = make_input(bounded(b"alice_pk"), b"renewal", b"should_be_valid");
 
-        // Ensure that the state of ConsumedAuthTicketNonces has not been updated.
-        assert_eq!(Bioauth::consumed_auth_ticket_nonces(), vec![]);
+        // Make test.
+        assert_ok!(Bioauth::authenticate(RuntimeOrigin::none(), input));
+
+        // Ensure the authentication has been extended in place.
+        assert_eq!(
+            Bioauth::active_authentications(),
+            vec![Authentication {
+                public_key: bounded(b"alice_pk"),
+                expires_at,
+            }]
+        );
+        assert_eq!(ActiveAuthentications::<Test>::count(), 1);
+        assert_consumed_auth_ticket_nonces(make_bounded_consumed_auth_nonces(vec![
+            b"renewal".to_vec()
+        ]));
+
+        System::assert_has_event(RuntimeEvent::Bioauth(Event::AuthenticationRenewed {
+            authentication: Authentication {
+                public_key: bounded(b"alice_pk"),
+                expires_at,
+            },
+            previous_expires_at,
+        }));
     });
 }
 
-/// This test verifies that the [`set_robonode_public_key`] updates the robonode public key properly.
+/// This test verifies that the active authentication can not be renewed before the renewal
+/// window.
 #[test]
-fn set_robonode_public_key_updates_key() {
+fn authentication_renewal_before_renewal_window() {
     new_test_ext().execute_with(|| {
         // Prepare the test precondition.
-        let expires_at = CHAIN_START + 2 * SLOT_DURATION;
-        let bounded_active_authentications =
-            make_bounded_active_authentications(vec![Authentication {
-                public_key: bounded(b"key1"),
-                expires_at,
-            }]);
-
-        let bounded_consumed_auth_ticket_nonces =
-            make_bounded_consumed_auth_nonces(vec![b"nonce1".to_vec()]);
+        let expires_at = CHAIN_START + AUTHENTICATIONS_EXPIRE_AFTER;
+        let current_moment = expires_at - AUTHENTICATIONS_RENEWAL_WINDOW - 1;
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces.clone());
+        populate_active_authentications(make_bounded_active_authentications(vec![
+            Authentication {
+                public_key: bounded(b"alice_pk"),
+                expires_at,
+            },
+        ]));
 
-        // Check the test precondition.
-        assert_eq!(Bioauth::robonode_public_key(), MockVerifier::A);
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(current_moment);
+        });
+        with_mock_before_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+        with_mock_after_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
 
         // Prepare test input.
-        let input = MockVerifier::B;
+        let input = make_input(bounded(b"alice_pk"), b"renewal", b"should_be_valid");
 
-        // Execute the key change.
-        assert_ok!(Bioauth::set_robonode_public_key(
-            RuntimeOrigin::root(),
-            input
-        ));
+        // Make test.
+        assert_noop!(
+            Bioauth::authenticate(RuntimeOrigin::none(), input),
+            Error::<Test>::PublicKeyAlreadyUsed,
+        );
+    });
+}
+
+/// This test verifies that before auth hook can deny the authentication
+/// and the resulting state is proper.
+#[test]
+fn authentication_denied_by_before_hook() {
+    new_test_ext().execute_with(|| {
+        // Prepare test input.
+        let input = make_input(bounded(b"qwe"), b"rty", b"should_be_valid");
+        let current_moment = CHAIN_START + 2 * SLOT_DURATION;
+        let expires_at = current_moment + AUTHENTICATIONS_EXPIRE_AFTER;
+
+        // Set up mock expectations.
+        with_mock_validator_set_updater(|mock| {
+            mock.expect_update_validators_set().never();
+        });
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(current_moment);
+        });
+        with_mock_before_auth_hook_provider(|mock| {
+            mock.expect_hook()
+                .once()
+                .withf(move |authentication| {
+                    authentication
+                        == &Authentication {
+                            public_key: bounded(b"qwe"),
+                            expires_at,
+                        }
+                })
+                .return_const(Err(sp_runtime::DispatchError::CannotLookup));
+        });
+        with_mock_after_auth_hook_provider(|mock| {
+            mock.expect_hook().never();
+        });
+
+        // Ensure that authentication call is denied.
+        assert_err!(
+            Bioauth::authenticate(RuntimeOrigin::none(), input),
+            sp_runtime::DispatchError::CannotLookup
+        );
+
+        // Ensure that the state of ActiveAuthentications has not been updated.
+        assert_eq!(Bioauth::active_authentications(), vec![]);
+
+        // Ensure that the state of ConsumedAuthTicketNonces has not been updated.
+        assert_consumed_auth_ticket_nonces(vec![]);
+    });
+}
+
+/// This test verifies that the [`set_robonode_public_key`] updates the robonode public key properly.
+#[test]
+fn set_robonode_public_key_updates_key() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test precondition.
+        let expires_at = CHAIN_START + 2 * SLOT_DURATION;
+        let bounded_active_authentications =
+            make_bounded_active_authentications(vec![Authentication {
+                public_key: bounded(b"key1"),
+                expires_at,
+            }]);
+
+        let bounded_consumed_auth_ticket_nonces =
+            make_bounded_consumed_auth_nonces(vec![b"nonce1".to_vec()]);
+
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces.clone());
+
+        // Check the test precondition.
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A]
+        );
+
+        // Prepare test input.
+        let input = MockVerifier::B;
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
+        // Set block number to enable events.
+        System::set_block_number(1);
+
+        // Execute the key change.
+        assert_ok!(Bioauth::set_robonode_public_key(
+            RuntimeOrigin::root(),
+            input
+        ));
 
         // Ensure the key has changed.
-        assert_eq!(Bioauth::robonode_public_key(), MockVerifier::B);
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::B]
+        );
+        System::assert_has_event(RuntimeEvent::Bioauth(Event::RobonodePublicKeyRetired {
+            public_key: MockVerifier::A,
+        }));
+        System::assert_has_event(RuntimeEvent::Bioauth(Event::RobonodePublicKeyActivated {
+            public_key: MockVerifier::B,
+        }));
 
         // Ensure the active authentications are cleared.
-        assert_eq!(<ActiveAuthentications<Test>>::get(), vec![]);
+        assert_eq!(Bioauth::active_authentications(), vec![]);
 
         // Ensure that the auth ticket nonces are *not* cleared.
-        assert_eq!(
-            <ConsumedAuthTicketNonces<Test>>::get(),
-            bounded_consumed_auth_ticket_nonces
-        );
+        assert_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
     });
 }
 
@@ -911,11 +1349,14 @@ fn set_robonode_public_key_checks_the_origin() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"nonce1".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications.clone());
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces.clone());
+        populate_active_authentications(bounded_active_authentications.clone());
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces.clone());
 
         // Check the test precondition.
-        assert_eq!(Bioauth::robonode_public_key(), MockVerifier::A);
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A]
+        );
 
         // Prepare test input.
         let input = MockVerifier::B;
@@ -931,22 +1372,451 @@ fn set_robonode_public_key_checks_the_origin() {
         );
 
         // Ensure that the key has not changed.
-        assert_eq!(Bioauth::robonode_public_key(), MockVerifier::A);
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A]
+        );
 
         // Ensure that the active authentications are *not* cleared.
         assert_eq!(
-            <ActiveAuthentications<Test>>::get(),
-            bounded_active_authentications
+            Bioauth::active_authentications(),
+            bounded_active_authentications.into_inner()
         );
 
         // Ensure that the auth ticket nonces are *not* cleared.
+        assert_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
+    });
+}
+
+/// This test verifies that the auth tickets are accepted from both the old and the new robonode
+/// public keys during the rotation overlap window, and only from the new one after it.
+#[test]
+fn robonode_public_key_rotation_lifecycle() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let activates_at = CHAIN_START + 2 * SLOT_DURATION;
+        let retires_at = CHAIN_START + 4 * SLOT_DURATION;
+        let call_signed_by = |signature: &[u8]| -> RuntimeCall {
+            pallet_bioauth::Call::authenticate {
+                req: make_input(bounded(b"qwe"), b"rty", signature),
+            }
+            .into()
+        };
+        let validate = |call: &RuntimeCall| {
+            CheckBioauthTx::<Test>(PhantomData).validate(&1, call, &DispatchInfo::default(), 1)
+        };
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
+        // Set block number to enable events.
+        System::set_block_number(1);
+
+        // Plan the new key activation.
+        assert_ok!(Bioauth::add_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::B,
+            activates_at
+        ));
+
+        // Ensure the new key is not accepted yet.
         assert_eq!(
-            <ConsumedAuthTicketNonces<Test>>::get(),
-            bounded_consumed_auth_ticket_nonces
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A]
+        );
+        assert_eq!(
+            validate(&call_signed_by(b"signed_by_b")),
+            InvalidTransaction::BadProof.into()
+        );
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(activates_at);
+        });
+
+        // Activate the new key.
+        Bioauth::on_initialize(block_to_process_moment(activates_at));
+        System::assert_has_event(RuntimeEvent::Bioauth(Event::RobonodePublicKeyActivated {
+            public_key: MockVerifier::B,
+        }));
+
+        // Ensure both keys are accepted during the overlap window.
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A, MockVerifier::B]
+        );
+        for signature in [b"should_be_valid".as_slice(), b"signed_by_b".as_slice()] {
+            with_mock_current_moment_provider(|mock| {
+                mock.expect_now().once().with().return_const(activates_at);
+            });
+            assert!(validate(&call_signed_by(signature)).is_ok());
+        }
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(activates_at);
+        });
+
+        // Plan the old key retirement.
+        assert_ok!(Bioauth::retire_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::A,
+            retires_at
+        ));
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A, MockVerifier::B]
+        );
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(retires_at);
+        });
+
+        // Retire the old key.
+        Bioauth::on_initialize(block_to_process_moment(retires_at));
+        System::assert_has_event(RuntimeEvent::Bioauth(Event::RobonodePublicKeyRetired {
+            public_key: MockVerifier::A,
+        }));
+
+        // Ensure only the new key is accepted now.
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::B]
+        );
+        assert_eq!(
+            validate(&call_signed_by(b"should_be_valid")),
+            InvalidTransaction::BadProof.into()
+        );
+    });
+}
+
+/// This test verifies that the robonode public key that is accepted already can not be added
+/// again.
+#[test]
+fn add_robonode_public_key_denies_duplicates() {
+    new_test_ext().execute_with(|| {
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
+        // Make test.
+        assert_noop!(
+            Bioauth::add_robonode_public_key(RuntimeOrigin::root(), MockVerifier::A, CHAIN_START),
+            Error::<Test>::RobonodePublicKeyAlreadyAccepted
+        );
+    });
+}
+
+/// This test verifies that the robonode public key that is not accepted can not be retired.
+#[test]
+fn retire_robonode_public_key_denies_unknown_keys() {
+    new_test_ext().execute_with(|| {
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
+        // Make test.
+        assert_noop!(
+            Bioauth::retire_robonode_public_key(
+                RuntimeOrigin::root(),
+                MockVerifier::B,
+                CHAIN_START
+            ),
+            Error::<Test>::UnknownRobonodePublicKey
+        );
+    });
+}
+
+/// This test verifies that the robonode public key rotation calls check the origin.
+#[test]
+fn robonode_public_key_rotation_checks_the_origin() {
+    new_test_ext().execute_with(|| {
+        // Attempt key rotations with various origins.
+        for origin in [RuntimeOrigin::none(), RuntimeOrigin::signed(123)] {
+            assert_noop!(
+                Bioauth::add_robonode_public_key(origin.clone(), MockVerifier::B, CHAIN_START),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_noop!(
+                Bioauth::retire_robonode_public_key(origin, MockVerifier::A, CHAIN_START),
+                sp_runtime::DispatchError::BadOrigin
+            );
+        }
+
+        // Ensure that the keys have not changed.
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A]
+        );
+    });
+}
+
+/// This test verifies that the auth tickets are only accepted when signed by the required number
+/// of the distinct active robonode public keys.
+#[test]
+fn robonode_signatures_threshold() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let current_moment = CHAIN_START + SLOT_DURATION;
+        let call_signed_with = |signatures: &[&[u8]]| -> RuntimeCall {
+            let mut input = make_input(bounded(b"qwe"), b"rty", b"");
+            input.ticket_signatures = signatures
+                .iter()
+                .map(|signature| signature.to_vec())
+                .collect();
+            pallet_bioauth::Call::authenticate { req: input }.into()
+        };
+        let validate = |call: &RuntimeCall| {
+            CheckBioauthTx::<Test>(PhantomData).validate(&1, call, &DispatchInfo::default(), 1)
+        };
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .times(2)
+                .with()
+                .return_const(current_moment);
+        });
+
+        // Set block number to enable events.
+        System::set_block_number(1);
+
+        // Accept the second robonode public key and require both robonodes to sign.
+        assert_ok!(Bioauth::add_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::B,
+            CHAIN_START
+        ));
+        assert_ok!(Bioauth::set_robonode_signatures_threshold(
+            RuntimeOrigin::root(),
+            2
+        ));
+        System::assert_has_event(RuntimeEvent::Bioauth(
+            Event::RobonodeSignaturesThresholdSet { threshold: 2 },
+        ));
+
+        // Ensure a single signature is not enough.
+        assert_eq!(
+            validate(&call_signed_with(&[b"should_be_valid"])),
+            InvalidTransaction::BadProof.into()
+        );
+
+        // Ensure the same robonode can not meet the threshold alone.
+        assert_eq!(
+            validate(&call_signed_with(&[b"should_be_valid", b"should_be_valid"])),
+            InvalidTransaction::BadProof.into()
+        );
+
+        // Ensure the signature set exceeding the robonode public keys limit is denied.
+        assert_eq!(
+            validate(&call_signed_with(&[b"signed_by_b"; 5])),
+            InvalidTransaction::BadProof.into()
+        );
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(current_moment);
+        });
+
+        // Ensure the signatures from both robonodes are accepted in any order.
+        assert!(validate(&call_signed_with(&[b"signed_by_b", b"should_be_valid"])).is_ok());
+    });
+}
+
+/// This test verifies that the robonode signatures threshold can not be set to an invalid value.
+#[test]
+fn set_robonode_signatures_threshold_denies_invalid_values() {
+    new_test_ext().execute_with(|| {
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
+        // Make test.
+        for threshold in [0, MAX_ROBONODE_PUBLIC_KEYS + 1] {
+            assert_noop!(
+                Bioauth::set_robonode_signatures_threshold(RuntimeOrigin::root(), threshold),
+                Error::<Test>::InvalidRobonodeSignaturesThreshold
+            );
+        }
+        assert_noop!(
+            Bioauth::set_robonode_signatures_threshold(RuntimeOrigin::signed(123), 1),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // Ensure the default threshold is in place.
+        assert_eq!(Bioauth::robonode_signatures_threshold(), 1);
+    });
+}
+
+/// This test verifies that the robonode signatures threshold can not exceed the number of
+/// the robonode public keys that stay active.
+#[test]
+fn set_robonode_signatures_threshold_denies_exceeding_active_keys() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let current_moment = CHAIN_START + SLOT_DURATION;
+        let retires_at = current_moment + 2 * SLOT_DURATION;
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .times(5)
+                .with()
+                .return_const(current_moment);
+        });
+
+        // Ensure a single active key does not meet the threshold of two.
+        assert_noop!(
+            Bioauth::set_robonode_signatures_threshold(RuntimeOrigin::root(), 2),
+            Error::<Test>::InvalidRobonodeSignaturesThreshold
+        );
+
+        // Ensure the key that is not active yet does not count.
+        assert_ok!(Bioauth::add_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::B,
+            retires_at
+        ));
+        assert_noop!(
+            Bioauth::set_robonode_signatures_threshold(RuntimeOrigin::root(), 2),
+            Error::<Test>::InvalidRobonodeSignaturesThreshold
+        );
+
+        // Ensure the key that is planned to retire does not count.
+        assert_ok!(Bioauth::retire_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::A,
+            retires_at
+        ));
+        assert_noop!(
+            Bioauth::set_robonode_signatures_threshold(RuntimeOrigin::root(), 2),
+            Error::<Test>::InvalidRobonodeSignaturesThreshold
+        );
+
+        // Ensure the default threshold is in place.
+        assert_eq!(Bioauth::robonode_signatures_threshold(), 1);
+    });
+}
+
+/// This test verifies that the robonode public key retirement can not leave fewer active
+/// robonode public keys than the robonode signatures threshold.
+#[test]
+fn retire_robonode_public_key_keeps_the_threshold() {
+    new_test_ext().execute_with(|| {
+        // Prepare the test preconditions.
+        let current_moment = CHAIN_START + SLOT_DURATION;
+        let retires_at = current_moment + 2 * SLOT_DURATION;
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .times(6)
+                .with()
+                .return_const(current_moment);
+        });
+
+        // Ensure the last key can not be retired, neither right away nor later.
+        for retires_at in [current_moment, retires_at] {
+            assert_noop!(
+                Bioauth::retire_robonode_public_key(
+                    RuntimeOrigin::root(),
+                    MockVerifier::A,
+                    retires_at
+                ),
+                Error::<Test>::NotEnoughRobonodePublicKeys
+            );
+        }
+
+        // Accept the second robonode public key and require both robonodes to sign.
+        assert_ok!(Bioauth::add_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::B,
+            CHAIN_START
+        ));
+        assert_ok!(Bioauth::set_robonode_signatures_threshold(
+            RuntimeOrigin::root(),
+            2
+        ));
+
+        // Ensure neither of the keys can be retired now.
+        for robonode_public_key in [MockVerifier::A, MockVerifier::B] {
+            assert_noop!(
+                Bioauth::retire_robonode_public_key(
+                    RuntimeOrigin::root(),
+                    robonode_public_key,
+                    retires_at
+                ),
+                Error::<Test>::NotEnoughRobonodePublicKeys
+            );
+        }
+
+        // Ensure the keys are intact.
+        assert_eq!(
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A, MockVerifier::B]
         );
     });
 }
 
+/// This test verifies that the emergency robonode public key replacement resets the robonode
+/// signatures threshold, as only a single key is left.
+#[test]
+fn set_robonode_public_key_resets_the_threshold() {
+    new_test_ext().execute_with(|| {
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .times(3)
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
+        // Set block number to enable events.
+        System::set_block_number(1);
+
+        // Require both robonodes to sign.
+        assert_ok!(Bioauth::add_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::B,
+            CHAIN_START
+        ));
+        assert_ok!(Bioauth::set_robonode_signatures_threshold(
+            RuntimeOrigin::root(),
+            2
+        ));
+
+        // Replace the keys.
+        assert_ok!(Bioauth::set_robonode_public_key(
+            RuntimeOrigin::root(),
+            MockVerifier::B
+        ));
+
+        // Ensure the threshold is reset.
+        assert_eq!(Bioauth::robonode_signatures_threshold(), 1);
+        System::assert_last_event(RuntimeEvent::Bioauth(
+            Event::RobonodeSignaturesThresholdSet { threshold: 1 },
+        ));
+    });
+}
+
 /// This test verifies `SignedExt` logic for transaction processing with empty state.
 #[test]
 fn signed_ext_check_bioauth_tx_permits_empty_state() {
@@ -956,8 +1826,17 @@ fn signed_ext_check_bioauth_tx_permits_empty_state() {
         let expected_tag = AuthTicket {
             public_key: bounded(b"qwe"),
             nonce: b"rty".to_vec(),
+            issued_at: CHAIN_START,
         };
 
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(CHAIN_START + SLOT_DURATION);
+        });
+
         // Make test.
         let call = pallet_bioauth::Call::authenticate { req: input }.into();
         let info = DispatchInfo::default();
@@ -1008,8 +1887,8 @@ fn signed_ext_check_bioauth_tx_denies_conlicting_nonce() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"conflict!".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations for the precondition Bioauth::authenticate.
         with_mock_validator_set_updater(|mock| {
@@ -1055,15 +1934,16 @@ fn signed_ext_check_bioauth_tx_denies_conflicting_public_keys() {
         let bounded_consumed_auth_ticket_nonces =
             make_bounded_consumed_auth_nonces(vec![b"nonce1".to_vec()]);
 
-        <ActiveAuthentications<Test>>::put(bounded_active_authentications);
-        <ConsumedAuthTicketNonces<Test>>::put(bounded_consumed_auth_ticket_nonces);
+        populate_active_authentications(bounded_active_authentications);
+        populate_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
 
         // Set up mock expectations for Bioauth::authenticate.
         with_mock_validator_set_updater(|mock| {
             mock.expect_update_validators_set().never();
         });
         with_mock_current_moment_provider(|mock| {
-            mock.expect_now().never();
+            // Before the renewal window of the active authentication.
+            mock.expect_now().once().with().return_const(CHAIN_START);
         });
         with_mock_before_auth_hook_provider(|mock| {
             mock.expect_hook().never();
@@ -1086,14 +1966,72 @@ fn signed_ext_check_bioauth_tx_denies_conflicting_public_keys() {
     })
 }
 
+/// This test verifies `SignedExt` logic for transaction processing with an expired auth ticket.
+#[test]
+fn signed_ext_check_bioauth_tx_denies_expired_auth_ticket() {
+    new_test_ext().execute_with(|| {
+        // Prepare test input.
+        let issued_at = CHAIN_START + SLOT_DURATION;
+        let input = make_input_issued_at(bounded(b"qwe"), b"rty", b"should_be_valid", issued_at);
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now()
+                .once()
+                .with()
+                .return_const(issued_at + AUTH_TICKETS_EXPIRE_AFTER);
+        });
+
+        // Make test.
+        let call = pallet_bioauth::Call::authenticate { req: input }.into();
+        let info = DispatchInfo::default();
+
+        assert_eq!(
+            CheckBioauthTx::<Test>(PhantomData).validate(&1, &call, &info, 1),
+            InvalidTransaction::Stale.into()
+        );
+    })
+}
+
+/// This test verifies `SignedExt` logic for transaction processing with an auth ticket issued
+/// further in the future than the clock drift allows.
+#[test]
+fn signed_ext_check_bioauth_tx_denies_auth_ticket_issued_in_future() {
+    new_test_ext().execute_with(|| {
+        // Prepare test input.
+        let issued_at = CHAIN_START + AUTH_TICKETS_MAX_CLOCK_DRIFT + 1;
+        let input = make_input_issued_at(bounded(b"qwe"), b"rty", b"should_be_valid", issued_at);
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(CHAIN_START);
+        });
+
+        // Make test.
+        let call = pallet_bioauth::Call::authenticate { req: input }.into();
+        let info = DispatchInfo::default();
+
+        assert_eq!(
+            CheckBioauthTx::<Test>(PhantomData).validate(&1, &call, &info, 1),
+            InvalidTransaction::Future.into()
+        );
+    })
+}
+
 /// This test verifies that genesis initialization properly assigns the state and invokes
 /// the validators set init.
 #[test]
 fn genesis_build() {
     // Prepare some sample data and a config.
     let consumed_auth_ticket_nonces = BoundedVec::try_from(vec![
-        BoundedVec::try_from(b"nonce1".to_vec()).unwrap(),
-        BoundedVec::try_from(b"nonce2".to_vec()).unwrap(),
+        ConsumedAuthTicketNonce {
+            nonce: BoundedVec::try_from(b"nonce1".to_vec()).unwrap(),
+            expires_at: 123,
+        },
+        ConsumedAuthTicketNonce {
+            nonce: BoundedVec::try_from(b"nonce2".to_vec()).unwrap(),
+            expires_at: 456,
+        },
     ])
     .unwrap();
     let active_authentications = BoundedVec::try_from(vec![
@@ -1127,11 +2065,115 @@ fn genesis_build() {
         with_mock_validator_set_updater(|mock| mock.checkpoint());
 
         // Assert the state.
-        assert_eq!(Bioauth::robonode_public_key(), MockVerifier::A);
         assert_eq!(
-            Bioauth::consumed_auth_ticket_nonces(),
-            consumed_auth_ticket_nonces
+            Bioauth::active_robonode_public_keys(),
+            vec![MockVerifier::A]
+        );
+        assert_consumed_auth_ticket_nonces(consumed_auth_ticket_nonces);
+        let mut state_active_authentications = Bioauth::active_authentications();
+        state_active_authentications.sort_by_key(|authentication| authentication.public_key);
+        assert_eq!(
+            state_active_authentications,
+            active_authentications.into_inner()
         );
-        assert_eq!(Bioauth::active_authentications(), active_authentications);
     })
 }
+
+/// This test verifies that the migration to v2 moves the active authentications from the list
+/// into the map, drops the expired consumed auth ticket nonces, and leaves the rest of them to be
+/// moved into the map in batches.
+#[test]
+fn migration_to_v2() {
+    new_test_ext().execute_with(|| {
+        // Prepare the v1 state.
+        let expires_at = CHAIN_START + 2 * SLOT_DURATION;
+        let bounded_active_authentications = make_bounded_active_authentications(vec![
+            Authentication {
+                public_key: bounded(b"key1"),
+                expires_at,
+            },
+            Authentication {
+                public_key: bounded(b"key2"),
+                expires_at: expires_at + 1,
+            },
+        ]);
+        let bounded_consumed_auth_ticket_nonces =
+            make_bounded_consumed_auth_nonces(vec![b"nonce1".to_vec(), b"nonce2".to_vec()]);
+        let mut v1_consumed_auth_ticket_nonces = bounded_consumed_auth_ticket_nonces.clone();
+        v1_consumed_auth_ticket_nonces
+            .try_push(ConsumedAuthTicketNonce {
+                nonce: BoundedAuthTicketNonce::try_from(b"expired_nonce".to_vec()).unwrap(),
+                expires_at: CHAIN_START,
+            })
+            .unwrap();
+
+        StorageVersion::new(1).put::<Bioauth>();
+        migrations::v1::storage::ActiveAuthentications::<Test>::put(
+            bounded_active_authentications.clone(),
+        );
+        migrations::v1::storage::ConsumedAuthTicketNonces::<Test>::put(
+            v1_consumed_auth_ticket_nonces,
+        );
+
+        // Set up mock expectations.
+        with_mock_current_moment_provider(|mock| {
+            mock.expect_now().once().with().return_const(CHAIN_START);
+        });
+
+        // Run the migration.
+        migrations::v2::MigrationToV2::<Test>::on_runtime_upgrade();
+
+        // Assert the state.
+        assert_eq!(Bioauth::on_chain_storage_version(), 2);
+        let mut state_active_authentications = Bioauth::active_authentications();
+        state_active_authentications.sort_by_key(|authentication| authentication.public_key);
+        assert_eq!(
+            state_active_authentications,
+            bounded_active_authentications.into_inner()
+        );
+        assert_eq!(
+            <ActiveAuthenticationsNextExpiration<Test>>::get(),
+            Some(expires_at)
+        );
+        assert_consumed_auth_ticket_nonces(vec![]);
+        assert_eq!(
+            <PendingConsumedAuthTicketNonces<Test>>::get(),
+            bounded_consumed_auth_ticket_nonces
+        );
+
+        // Move the pending nonces into the map in batches.
+        assert_eq!(Bioauth::move_pending_consumed_auth_ticket_nonces(1), 1);
+        assert_eq!(<PendingConsumedAuthTicketNonces<Test>>::get().len(), 1);
+        assert_eq!(Bioauth::move_pending_consumed_auth_ticket_nonces(2), 1);
+        assert!(!<PendingConsumedAuthTicketNonces<Test>>::exists());
+        assert_eq!(Bioauth::move_pending_consumed_auth_ticket_nonces(2), 0);
+        assert_consumed_auth_ticket_nonces(bounded_consumed_auth_ticket_nonces);
+    });
+}
+
+/// This test verifies that the migration to v3 moves the robonode public key into the set of
+/// the accepted robonode public keys.
+#[test]
+fn migration_to_v3() {
+    new_test_ext().execute_with(|| {
+        // Prepare the v2 state.
+        StorageVersion::new(2).put::<Bioauth>();
+        <RobonodePublicKeys<Test>>::kill();
+        migrations::v3::storage::RobonodePublicKey::<Test>::put(MockVerifier::B);
+
+        // Run the migration.
+        migrations::v3::MigrationToV3::<Test>::on_runtime_upgrade();
+
+        // Assert the state.
+        assert_eq!(Bioauth::on_chain_storage_version(), 3);
+        assert_eq!(
+            Bioauth::robonode_public_keys().into_inner(),
+            vec![AcceptedRobonodePublicKey {
+                public_key: MockVerifier::B,
+                activates_at: 0,
+                retires_at: None,
+                is_active: true,
+            }]
+        );
+    });
+}
@@ -5,15 +5,21 @@ use frame_support::weights::Weight;
 /// Weight functions needed for pallet-bioauth.
 pub trait WeightInfo {
     /// A function to calculate required weights for authenticate call.
-    fn authenticate(authentications: u32, nonces: u32) -> Weight;
+    fn authenticate(signatures: u32) -> Weight;
     /// A function to calculate required weights for `set_robonode_public_key` call.
     fn set_robonode_public_key(authentications: u32) -> Weight;
+    /// A function to calculate required weights for `add_robonode_public_key` call.
+    fn add_robonode_public_key() -> Weight;
+    /// A function to calculate required weights for `retire_robonode_public_key` call.
+    fn retire_robonode_public_key() -> Weight;
+    /// A function to calculate required weights for `set_robonode_signatures_threshold` call.
+    fn set_robonode_signatures_threshold() -> Weight;
     /// A function to calculate required weights for `on_initialize` hook.
-    fn on_initialize(authentications: u32) -> Weight;
+    fn on_initialize(authentications: u32, nonces: u32) -> Weight;
 }
 
 impl WeightInfo for () {
-    fn authenticate(_authentications: u32, _nonces: u32) -> Weight {
+    fn authenticate(_signatures: u32) -> Weight {
         Weight::zero()
     }
 
@@ -21,7 +27,19 @@ impl WeightInfo for () {
         Weight::zero()
     }
 
-    fn on_initialize(_authentications: u32) -> Weight {
+    fn add_robonode_public_key() -> Weight {
+        Weight::zero()
+    }
+
+    fn retire_robonode_public_key() -> Weight {
+        Weight::zero()
+    }
+
+    fn set_robonode_signatures_threshold() -> Weight {
+        Weight::zero()
+    }
+
+    fn on_initialize(_authentications: u32, _nonces: u32) -> Weight {
         Weight::zero()
     }
 }
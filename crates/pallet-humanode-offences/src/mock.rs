@@ -127,14 +127,14 @@ impl MaxEncodedLen for MockVerifier {
 
 pub struct MockAuthTicketConverter;
 
-impl pallet_bioauth::TryConvert<MockOpaqueAuthTicket, pallet_bioauth::AuthTicket<AccountId>>
+impl pallet_bioauth::TryConvert<MockOpaqueAuthTicket, pallet_bioauth::AuthTicket<AccountId, u64>>
     for MockAuthTicketConverter
 {
     type Error = DeriveError;
 
     fn try_convert(
         _value: MockOpaqueAuthTicket,
-    ) -> Result<pallet_bioauth::AuthTicket<AccountId>, Self::Error> {
+    ) -> Result<pallet_bioauth::AuthTicket<AccountId, u64>, Self::Error> {
         panic!("should be unused in tests")
     }
 }
@@ -180,9 +180,14 @@ impl pallet_bioauth::Config for Test {
     type DisplayMoment = MockDisplayMoment;
     type CurrentMoment = MockCurrentMoment;
     type AuthenticationsExpireAfter = ConstU64<10>;
+    type AuthTicketsExpireAfter = ConstU64<10>;
+    type AuthTicketsMaxClockDrift = ConstU64<1>;
+    type AuthenticationsRenewalWindow = ConstU64<1>;
     type WeightInfo = ();
     type MaxAuthentications = ConstU32<5>;
     type MaxNonces = ConstU32<5>;
+    type MaxNonceExpirationsPerBlock = ConstU32<5>;
+    type MaxRobonodePublicKeys = ConstU32<2>;
     type BeforeAuthHook = ();
     type AfterAuthHook = ();
     type DeauthenticationReason = DeauthenticationReason;
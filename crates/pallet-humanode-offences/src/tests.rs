@@ -3,11 +3,7 @@
 // Allow simple integer arithmetic in tests.
 #![allow(clippy::arithmetic_side_effects)]
 
-use frame_support::{
-    traits::{OnFinalize, OnInitialize},
-    BoundedVec,
-};
-use sp_core::ConstU32;
+use frame_support::traits::{OnFinalize, OnInitialize};
 
 use crate::{mock::*, *};
 
@@ -187,7 +183,7 @@ fn offence_report_both_cases_works() {
 }
 
 #[test]
-fn offence_not_slash_new_authentication() {
+fn offence_report_slash_renewed_authentication() {
     new_test_ext().execute_with_ext(|_| {
         // Check test preconditions.
         assert_eq!(<Total<Test>>::get(), None);
@@ -213,16 +209,19 @@ fn offence_not_slash_new_authentication() {
         let offenders_ctx = MockOffence::offenders_context();
         offenders_ctx
             .expect()
-            .times(2)
+            .times(1)
             .return_const(offenders.clone());
 
         // Block 0 -> 1.
         switch_block();
 
+        // Model that the authentication has been renewed by the same validator.
+        <pallet_bioauth::ActiveAuthentications<Test>>::insert(1, 2000);
+
         // Report offence.
         HumanodeOffences::report_offence(vec![], MockOffence {}).unwrap();
 
-        // Assert state changes that first authentication has been slashed.
+        // Assert state changes that the renewed authentication has been slashed.
         assert_eq!(<Total<Test>>::get(), Some(1));
         assert!(Bioauth::active_authentications().is_empty());
         assert_eq!(Bootnodes::bootnodes(), vec![42]);
@@ -230,32 +229,15 @@ fn offence_not_slash_new_authentication() {
             kind: MOCKED_OFFENCE_KIND,
             offenders,
         }));
-
-        // Block 1 -> 2.
-        switch_block();
-
-        // Model that bioauth has passed again by the same validator.
-        <pallet_bioauth::ActiveAuthentications<Test>>::put(
-            BoundedVec::<_, ConstU32<5>>::try_from(vec![pallet_bioauth::Authentication {
-                public_key: 1,
-                expires_at: 2000,
-            }])
-            .unwrap(),
-        );
-
-        // Report offence again.
-        HumanodeOffences::report_offence(vec![], MockOffence {}).unwrap();
-
-        // Assert state changes that new authentication has not been slashed.
-        assert_eq!(<Total<Test>>::get(), Some(1));
-        assert_eq!(
-            Bioauth::active_authentications(),
-            vec![pallet_bioauth::Authentication {
-                public_key: 1,
-                expires_at: 2000,
-            }]
-        );
-        assert_eq!(Bootnodes::bootnodes(), vec![42]);
+        System::assert_has_event(RuntimeEvent::Bioauth(
+            pallet_bioauth::Event::AuthenticationsRemoved {
+                removed: vec![pallet_bioauth::Authentication {
+                    public_key: 1,
+                    expires_at: 2000,
+                }],
+                reason: DeauthenticationReason::Offence,
+            },
+        ));
 
         // Assert mock invocations.
         offenders_ctx.checkpoint();
@@ -253,7 +253,6 @@ impl<T: Config> Pallet<T> {
         let banned_accounts = <BannedAccounts<T>>::get();
 
         let bioauth_active_authentications = <pallet_bioauth::Pallet<T>>::active_authentications()
-            .into_inner()
             .into_iter()
             .take(T::MaxBioauthValidators::get().try_into().unwrap())
             .filter_map(move |authentication| {
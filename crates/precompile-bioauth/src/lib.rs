@@ -71,9 +71,7 @@ where
             }
         })?;
 
-        let is_authenticated = pallet_bioauth::ActiveAuthentications::<T>::get()
-            .iter()
-            .any(|active_authentication| active_authentication.public_key == account_id);
+        let is_authenticated = pallet_bioauth::Pallet::<T>::is_authenticated(&account_id);
 
         Ok(succeed(
             EvmDataWriter::new().write(is_authenticated).build(),
@@ -44,7 +44,7 @@ pub type ValidatorPublicKey = [u8; 32];
 
 #[derive(PartialEq, Eq, Default, Clone, Encode, Decode, Hash, Debug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct MockOpaqueAuthTicket(pub AuthTicket<ValidatorPublicKey>);
+pub struct MockOpaqueAuthTicket(pub AuthTicket<ValidatorPublicKey, UnixMilliseconds>);
 
 impl AsRef<[u8]> for MockOpaqueAuthTicket {
     fn as_ref(&self) -> &[u8] {
@@ -60,12 +60,14 @@ impl From<Vec<u8>> for MockOpaqueAuthTicket {
 
 pub struct MockAuthTicketConverter;
 
-impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey>> for MockAuthTicketConverter {
+impl TryConvert<MockOpaqueAuthTicket, AuthTicket<ValidatorPublicKey, UnixMilliseconds>>
+    for MockAuthTicketConverter
+{
     type Error = DeriveError;
 
     fn try_convert(
         value: MockOpaqueAuthTicket,
-    ) -> Result<AuthTicket<ValidatorPublicKey>, Self::Error> {
+    ) -> Result<AuthTicket<ValidatorPublicKey, UnixMilliseconds>, Self::Error> {
         Ok(value.0)
     }
 }
@@ -94,19 +96,9 @@ impl MaxEncodedLen for MockVerifier {
 pub struct MockValidatorSetUpdater;
 
 impl pallet_bioauth::ValidatorSetUpdater<ValidatorPublicKey> for MockValidatorSetUpdater {
-    fn update_validators_set<'a, I: Iterator<Item = &'a ValidatorPublicKey> + 'a>(
-        _validator_public_keys: I,
-    ) where
-        ValidatorPublicKey: 'a,
-    {
-    }
+    fn update_validators_set<I: Iterator<Item = ValidatorPublicKey>>(_validator_public_keys: I) {}
 
-    fn init_validators_set<'a, I: Iterator<Item = &'a ValidatorPublicKey> + 'a>(
-        _validator_public_keys: I,
-    ) where
-        ValidatorPublicKey: 'a,
-    {
-    }
+    fn init_validators_set<I: Iterator<Item = ValidatorPublicKey>>(_validator_public_keys: I) {}
 }
 
 pub struct MockCurrentMomentProvider;
@@ -148,8 +140,13 @@ const TIMESTAMP_SECOND: UnixMilliseconds = 1000;
 const TIMESTAMP_MINUTE: UnixMilliseconds = 60 * TIMESTAMP_SECOND;
 
 pub const AUTHENTICATIONS_EXPIRE_AFTER: UnixMilliseconds = TIMESTAMP_MINUTE;
+pub const AUTH_TICKETS_EXPIRE_AFTER: UnixMilliseconds = 10 * TIMESTAMP_MINUTE;
+pub const AUTH_TICKETS_MAX_CLOCK_DRIFT: UnixMilliseconds = TIMESTAMP_MINUTE;
+pub const AUTHENTICATIONS_RENEWAL_WINDOW: UnixMilliseconds = 10 * TIMESTAMP_SECOND;
 pub const MAX_AUTHENTICATIONS: u32 = 512;
 pub const MAX_NONCES: u32 = 512;
+pub const MAX_NONCE_EXPIRATIONS_PER_BLOCK: u32 = 64;
+pub const MAX_ROBONODE_PUBLIC_KEYS: u32 = 4;
 
 pub struct DisplayMoment;
 
@@ -177,9 +174,14 @@ impl pallet_bioauth::Config for Test {
     type DisplayMoment = DisplayMoment;
     type CurrentMoment = MockCurrentMomentProvider;
     type AuthenticationsExpireAfter = ConstU64<AUTHENTICATIONS_EXPIRE_AFTER>;
+    type AuthTicketsExpireAfter = ConstU64<AUTH_TICKETS_EXPIRE_AFTER>;
+    type AuthTicketsMaxClockDrift = ConstU64<AUTH_TICKETS_MAX_CLOCK_DRIFT>;
+    type AuthenticationsRenewalWindow = ConstU64<AUTHENTICATIONS_RENEWAL_WINDOW>;
     type WeightInfo = ();
     type MaxAuthentications = ConstU32<MAX_AUTHENTICATIONS>;
     type MaxNonces = ConstU32<MAX_NONCES>;
+    type MaxNonceExpirationsPerBlock = ConstU32<MAX_NONCE_EXPIRATIONS_PER_BLOCK>;
+    type MaxRobonodePublicKeys = ConstU32<MAX_ROBONODE_PUBLIC_KEYS>;
     type BeforeAuthHook = ();
     type AfterAuthHook = ();
     type DeauthenticationReason = ();
@@ -1,23 +1,8 @@
-use frame_support::{traits::ConstU32, BoundedVec};
 use pallet_evm::ExitSucceed;
 use precompile_utils::{Bytes, EvmDataWriter};
 
 use crate::{mock::*, *};
 
-type TestAuthentication = pallet_bioauth::Authentication<
-    <Test as pallet_bioauth::Config>::ValidatorPublicKey,
-    <Test as pallet_bioauth::Config>::Moment,
->;
-
-fn make_bounded_authentications(
-    authentications: Vec<pallet_bioauth::Authentication<ValidatorPublicKey, UnixMilliseconds>>,
-) -> BoundedVec<
-    pallet_bioauth::Authentication<ValidatorPublicKey, UnixMilliseconds>,
-    ConstU32<MAX_AUTHENTICATIONS>,
-> {
-    BoundedVec::<_, ConstU32<MAX_AUTHENTICATIONS>>::try_from(authentications).unwrap()
-}
-
 #[test]
 fn test_empty_selector() {
     new_test_ext().execute_with(|| {
@@ -86,12 +71,7 @@ fn test_is_authenticated_true() {
             .write(sp_core::H256::from(sample_key))
             .build();
 
-        pallet_bioauth::ActiveAuthentications::<Test>::put(make_bounded_authentications(vec![
-            TestAuthentication {
-                public_key: sample_key,
-                expires_at: 1,
-            },
-        ]));
+        pallet_bioauth::ActiveAuthentications::<Test>::insert(sample_key, 1);
 
         let mut mock_handle = MockPrecompileHandle::new();
         mock_handle.expect_record_cost().returning(|_| Ok(()));
@@ -116,8 +96,6 @@ fn test_is_authenticated_false() {
             .write(sp_core::H256::from([0; 32]))
             .build();
 
-        pallet_bioauth::ActiveAuthentications::<Test>::put(make_bounded_authentications(vec![]));
-
         let mut mock_handle = MockPrecompileHandle::new();
         mock_handle.expect_record_cost().returning(|_| Ok(()));
         mock_handle.expect_input().return_const(input);
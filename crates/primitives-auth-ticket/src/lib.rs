@@ -1,14 +1,38 @@
 //! Plain and opaque Auth Tickets.
+//!
+//! The opaque auth tickets are versioned: they start with the [`VERSIONED_MARKER`] followed by
+//! the encoding version and the SCALE-encoded [`AuthTicket`].
+//! The legacy auth tickets, issued before the versioning was introduced, are just
+//! the SCALE-encoded [`LegacyAuthTicket`], and lack the issuance moment.
+//!
+//! The runtime only accepts the versioned auth tickets, while the older runtimes only accept
+//! the legacy ones, so the robonode has to keep issuing the legacy auth tickets until
+//! the runtime upgrade is enacted. The upgrade order is:
+//!
+//! 1. Upgrade the robonode, with the legacy auth tickets issuance enabled.
+//! 2. Enact the runtime upgrade; the legacy auth tickets issued but not yet submitted by then
+//!    are rejected, and have to be requested again.
+//! 3. Disable the legacy auth tickets issuance at the robonode.
 
 // Either generate code at standard mode, or `no_std`, based on the `std` feature presence.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_std::prelude::*;
 
+/// The first byte of the versioned opaque auth ticket.
+///
+/// The legacy auth tickets start with the SCALE compact-encoded length of the public key,
+/// and no valid compact-encoded length starts with this byte, so the versioned auth tickets
+/// are always distinguishable from the legacy ones.
+pub const VERSIONED_MARKER: u8 = 0xff;
+
+/// The current version of the opaque auth ticket encoding.
+pub const CURRENT_VERSION: u8 = 1;
+
 /// The one-time ticket to authenticate in the network.
 #[derive(Debug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -25,22 +49,83 @@ pub struct AuthTicket {
     /// Nonce is supposed to be unique among all of the authentication attempts,
     /// or at the very least - all authentication attempts for a particular public key.
     pub authentication_nonce: Vec<u8>,
+    /// The moment (in UNIX milliseconds) at which the robonode has issued this ticket.
+    /// The ticket is only accepted by the network for a limited amount of time after this moment.
+    pub issued_at: u64,
+}
+
+/// The one-time ticket to authenticate in the network, in the legacy encoding without
+/// the issuance moment.
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct LegacyAuthTicket {
+    /// The public key that matched with the provided FaceTec 3D FaceScan.
+    pub public_key: Vec<u8>,
+    /// Opaque one-time use value.
+    pub authentication_nonce: Vec<u8>,
+}
+
+/// An error that can occur while decoding the opaque auth ticket.
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The auth ticket is in the legacy encoding, that does not carry the issuance moment.
+    Legacy,
+    /// The auth ticket encoding version is not supported.
+    UnsupportedVersion(u8),
+    /// The auth ticket could not be decoded.
+    Codec(codec::Error),
 }
 
 impl TryFrom<&OpaqueAuthTicket> for AuthTicket {
-    type Error = codec::Error;
+    type Error = DecodeError;
 
     fn try_from(value: &OpaqueAuthTicket) -> Result<Self, Self::Error> {
-        Self::decode(&mut &*value.0)
+        match value.0.as_slice() {
+            [VERSIONED_MARKER, CURRENT_VERSION, payload @ ..] => {
+                Self::decode_all(&mut &*payload).map_err(DecodeError::Codec)
+            }
+            [VERSIONED_MARKER, version, ..] => Err(DecodeError::UnsupportedVersion(*version)),
+            _ => match LegacyAuthTicket::decode_all(&mut &*value.0) {
+                Ok(_) => Err(DecodeError::Legacy),
+                Err(err) => Err(DecodeError::Codec(err)),
+            },
+        }
     }
 }
 
 impl From<&AuthTicket> for OpaqueAuthTicket {
     fn from(val: &AuthTicket) -> Self {
+        let mut buf = Vec::with_capacity(val.size_hint().saturating_add(2));
+        buf.push(VERSIONED_MARKER);
+        buf.push(CURRENT_VERSION);
+        val.encode_to(&mut buf);
+        Self(buf)
+    }
+}
+
+impl TryFrom<&OpaqueAuthTicket> for LegacyAuthTicket {
+    type Error = codec::Error;
+
+    fn try_from(value: &OpaqueAuthTicket) -> Result<Self, Self::Error> {
+        Self::decode_all(&mut &*value.0)
+    }
+}
+
+impl From<&LegacyAuthTicket> for OpaqueAuthTicket {
+    fn from(val: &LegacyAuthTicket) -> Self {
         Self(val.encode())
     }
 }
 
+impl From<&AuthTicket> for LegacyAuthTicket {
+    fn from(val: &AuthTicket) -> Self {
+        Self {
+            public_key: val.public_key.clone(),
+            authentication_nonce: val.authentication_nonce.clone(),
+        }
+    }
+}
+
 impl AsRef<[u8]> for OpaqueAuthTicket {
     fn as_ref(&self) -> &[u8] {
         self.0.as_slice()
@@ -64,3 +149,83 @@ impl From<OpaqueAuthTicket> for Vec<u8> {
         val.0
     }
 }
+
+/// Get the current moment in UNIX milliseconds, as used for the [`AuthTicket::issued_at`].
+#[cfg(feature = "std")]
+pub fn current_unix_milliseconds() -> u64 {
+    let since_unix_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time must be after the unix epoch");
+    // u64 is big enough for this overflow to be practically impossible.
+    since_unix_epoch.as_millis().try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth_ticket() -> AuthTicket {
+        AuthTicket {
+            public_key: vec![1; 32],
+            authentication_nonce: vec![2; 16],
+            issued_at: 1_609_459_200_000,
+        }
+    }
+
+    #[test]
+    fn versioned_round_trip() {
+        let opaque_auth_ticket = OpaqueAuthTicket::from(&auth_ticket());
+
+        assert_eq!(
+            &opaque_auth_ticket.0[..2],
+            &[VERSIONED_MARKER, CURRENT_VERSION]
+        );
+        assert_eq!(
+            AuthTicket::try_from(&opaque_auth_ticket).unwrap(),
+            auth_ticket()
+        );
+    }
+
+    #[test]
+    fn legacy_is_detected() {
+        let opaque_auth_ticket = OpaqueAuthTicket::from(&LegacyAuthTicket::from(&auth_ticket()));
+
+        assert!(matches!(
+            AuthTicket::try_from(&opaque_auth_ticket),
+            Err(DecodeError::Legacy)
+        ));
+        assert_eq!(
+            LegacyAuthTicket::try_from(&opaque_auth_ticket).unwrap(),
+            LegacyAuthTicket {
+                public_key: vec![1; 32],
+                authentication_nonce: vec![2; 16],
+            }
+        );
+    }
+
+    #[test]
+    fn versioned_is_not_legacy() {
+        let opaque_auth_ticket = OpaqueAuthTicket::from(&auth_ticket());
+
+        assert!(LegacyAuthTicket::try_from(&opaque_auth_ticket).is_err());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut opaque_auth_ticket = OpaqueAuthTicket::from(&auth_ticket());
+        opaque_auth_ticket.0[1] = CURRENT_VERSION + 1;
+
+        assert!(matches!(
+            AuthTicket::try_from(&opaque_auth_ticket),
+            Err(DecodeError::UnsupportedVersion(version)) if version == CURRENT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn garbage() {
+        assert!(matches!(
+            AuthTicket::try_from(&OpaqueAuthTicket(b"ticket".to_vec())),
+            Err(DecodeError::Codec(_))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+//! Plain and opaque Enrollment Receipts.
+
+// Either generate code at standard mode, or `no_std`, based on the `std` feature presence.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_std::prelude::*;
+
+/// The prefix every opaque enrollment receipt starts with.
+///
+/// The receipts are signed with the same robonode key as the auth tickets, so the prefix
+/// separates the two: a signed receipt can never be decoded into an auth ticket with a valid
+/// public key, and thus can't be used to authenticate.
+pub const OPAQUE_ENROLLMENT_RECEIPT_PREFIX: &[u8] = b"humanode-enrollment-receipt:";
+
+/// The robonode-signed evidence of a successful enrollment.
+#[derive(Debug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(transparent))]
+pub struct OpaqueEnrollmentReceipt(pub Vec<u8>);
+
+/// The robonode-signed evidence of a successful enrollment.
+#[derive(Debug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct EnrollmentReceipt {
+    /// The public key that has been enrolled.
+    pub public_key: Vec<u8>,
+    /// The moment (in UNIX milliseconds) at which the robonode has enrolled the public key.
+    pub enrolled_at: u64,
+    /// The public key of the robonode that has issued this receipt, identifying the key
+    /// the receipt signature is to be verified with.
+    pub robonode_key_id: Vec<u8>,
+}
+
+impl TryFrom<&OpaqueEnrollmentReceipt> for EnrollmentReceipt {
+    type Error = codec::Error;
+
+    fn try_from(value: &OpaqueEnrollmentReceipt) -> Result<Self, Self::Error> {
+        let encoded = value
+            .0
+            .strip_prefix(OPAQUE_ENROLLMENT_RECEIPT_PREFIX)
+            .ok_or("enrollment receipt prefix mismatch")?;
+        Self::decode(&mut &*encoded)
+    }
+}
+
+impl From<&EnrollmentReceipt> for OpaqueEnrollmentReceipt {
+    fn from(val: &EnrollmentReceipt) -> Self {
+        let mut opaque = OPAQUE_ENROLLMENT_RECEIPT_PREFIX.to_vec();
+        val.encode_to(&mut opaque);
+        Self(opaque)
+    }
+}
+
+impl AsRef<[u8]> for OpaqueEnrollmentReceipt {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+impl From<Vec<u8>> for OpaqueEnrollmentReceipt {
+    fn from(val: Vec<u8>) -> Self {
+        Self(val)
+    }
+}
+
+impl From<Box<[u8]>> for OpaqueEnrollmentReceipt {
+    fn from(val: Box<[u8]>) -> Self {
+        Self(val.into())
+    }
+}
+
+impl From<OpaqueEnrollmentReceipt> for Vec<u8> {
+    fn from(val: OpaqueEnrollmentReceipt) -> Self {
+        val.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opaque_round_trip() {
+        let receipt = EnrollmentReceipt {
+            public_key: vec![1; 32],
+            enrolled_at: 1_700_000_000_000,
+            robonode_key_id: vec![2; 32],
+        };
+
+        let opaque = OpaqueEnrollmentReceipt::from(&receipt);
+        assert!(opaque.0.starts_with(OPAQUE_ENROLLMENT_RECEIPT_PREFIX));
+        assert_eq!(EnrollmentReceipt::try_from(&opaque).unwrap(), receipt);
+    }
+
+    #[test]
+    fn rejects_missing_prefix() {
+        let receipt = EnrollmentReceipt {
+            public_key: vec![1; 32],
+            enrolled_at: 1_700_000_000_000,
+            robonode_key_id: vec![2; 32],
+        };
+
+        let opaque = OpaqueEnrollmentReceipt(receipt.encode());
+        assert!(EnrollmentReceipt::try_from(&opaque).is_err());
+    }
+}
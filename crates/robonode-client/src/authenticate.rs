@@ -33,6 +33,13 @@ pub struct AuthenticateRequest<'a> {
     /// The signature of the liveness data, proving the possession of the
     /// private key by the issuer of this request.
     pub liveness_data_signature: &'a [u8],
+    /// The opaque auth ticket issued by another robonode to co-sign instead of issuing a new
+    /// one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_ticket: Option<&'a [u8]>,
+    /// The signature of the auth ticket to co-sign by the robonode that has issued it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_ticket_signature: Option<&'a [u8]>,
 }
 
 /// Input data for the authenticate request.
@@ -80,6 +87,16 @@ pub enum AuthenticateError {
     /// the public key that the person enrolled with don't match.
     #[error("signature invalid")]
     SignatureInvalid(ScanResultBlob),
+    /// The auth ticket to co-sign was not issued to the person that matched with the provided
+    /// FaceScan.
+    ///
+    /// The error data from robonode doesn't contain scan result blob.
+    #[error("auth ticket mismatch")]
+    AuthTicketMismatchNoBlob,
+    /// The auth ticket to co-sign was not issued to the person that matched with the provided
+    /// FaceScan.
+    #[error("auth ticket mismatch")]
+    AuthTicketMismatch(ScanResultBlob),
     /// A logic internal error occurred on the server end.
     ///
     /// The error data from robonode doesn't contain scan result blob.
@@ -120,6 +137,10 @@ impl AuthenticateError {
                 None => Self::SignatureInvalidNoBlob,
                 Some(scan_result_blob) => Self::SignatureInvalid(scan_result_blob),
             },
+            "AUTHENTICATE_AUTH_TICKET_MISMATCH" => match scan_result_blob {
+                None => Self::AuthTicketMismatchNoBlob,
+                Some(scan_result_blob) => Self::AuthTicketMismatch(scan_result_blob),
+            },
             "LOGIC_INTERNAL_ERROR" => match scan_result_blob {
                 None => Self::LogicInternalNoBlob,
                 Some(scan_result_blob) => Self::LogicInternal(scan_result_blob),
@@ -147,6 +168,28 @@ mod tests {
         let actual_request = serde_json::to_value(&AuthenticateRequest {
             liveness_data: &[1, 2, 3],
             liveness_data_signature: &[4, 5, 6],
+            auth_ticket: None,
+            auth_ticket_signature: None,
+        })
+        .unwrap();
+
+        assert_eq!(expected_request, actual_request);
+    }
+
+    #[test]
+    fn request_serialization_with_auth_ticket() {
+        let expected_request = serde_json::json!({
+            "livenessData": [1, 2, 3],
+            "livenessDataSignature": [4, 5, 6],
+            "authTicket": [7, 8, 9],
+            "authTicketSignature": [10, 11, 12],
+        });
+
+        let actual_request = serde_json::to_value(&AuthenticateRequest {
+            liveness_data: &[1, 2, 3],
+            liveness_data_signature: &[4, 5, 6],
+            auth_ticket: Some(&[7, 8, 9]),
+            auth_ticket_signature: Some(&[10, 11, 12]),
         })
         .unwrap();
 
@@ -197,6 +240,8 @@ mod tests {
         let sample_request = AuthenticateRequest {
             liveness_data: b"dummy liveness data",
             liveness_data_signature: b"123",
+            auth_ticket: None,
+            auth_ticket_signature: None,
         };
         let sample_response = serde_json::json!({
             "authTicket": b"456",
@@ -229,6 +274,8 @@ mod tests {
         let sample_request = AuthenticateRequest {
             liveness_data: b"dummy liveness data",
             liveness_data_signature: b"123",
+            auth_ticket: None,
+            auth_ticket_signature: None,
         };
         let sample_response = serde_json::json!({
             "authTicket": b"456",
@@ -296,6 +343,8 @@ mod tests {
             let sample_request = AuthenticateRequest {
                 liveness_data: b"dummy liveness data",
                 liveness_data_signature: b"123",
+                auth_ticket: None,
+                auth_ticket_signature: None,
             };
 
             let response = ResponseTemplate::new(http_code).set_body_json(mkerr(error_code, None));
@@ -362,6 +411,18 @@ mod tests {
                 ResponseIncludesBlob::Yes,
                 AuthenticateError::SignatureInvalid("scan result blob".to_owned()),
             ),
+            (
+                StatusCode::FORBIDDEN,
+                "AUTHENTICATE_AUTH_TICKET_MISMATCH",
+                ResponseIncludesBlob::No,
+                AuthenticateError::AuthTicketMismatchNoBlob,
+            ),
+            (
+                StatusCode::FORBIDDEN,
+                "AUTHENTICATE_AUTH_TICKET_MISMATCH",
+                ResponseIncludesBlob::Yes,
+                AuthenticateError::AuthTicketMismatch("scan result blob".to_owned()),
+            ),
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "LOGIC_INTERNAL_ERROR",
@@ -388,6 +449,8 @@ mod tests {
             let sample_request = AuthenticateRequest {
                 liveness_data: b"dummy liveness data",
                 liveness_data_signature: b"123",
+                auth_ticket: None,
+                auth_ticket_signature: None,
             };
 
             let response_scan_result_blob = match response_includes_blob {
@@ -422,6 +485,8 @@ mod tests {
         let sample_request = AuthenticateRequest {
             liveness_data: b"dummy liveness data",
             liveness_data_signature: b"123",
+            auth_ticket: None,
+            auth_ticket_signature: None,
         };
         let sample_response = "Some error text";
 
@@ -1,6 +1,9 @@
 //! Client API for the Humanode's Bioauth Robonode.
 
+pub use primitives_enrollment_receipt::EnrollmentReceipt;
+use primitives_enrollment_receipt::OpaqueEnrollmentReceipt;
 use reqwest::StatusCode;
+use robonode_crypto::Verifier;
 use serde::{Deserialize, Serialize};
 
 use crate::{error_response::ErrorResponse, Client, Error, ScanResultBlob};
@@ -15,6 +18,8 @@ impl Client {
         let res = self.reqwest.post(url).json(&req).send().await?;
         match res.status() {
             StatusCode::CREATED if res.content_length() == Some(0) => Ok(EnrollResponse {
+                enrollment_receipt: None,
+                enrollment_receipt_signature: None,
                 scan_result_blob: None,
             }),
             StatusCode::CREATED => Ok(res.json().await?),
@@ -44,11 +49,75 @@ pub struct EnrollRequest<'a> {
 #[derive(Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct EnrollResponse {
+    /// An opaque enrollment receipt, the robonode-signed evidence of this enrollment.
+    ///
+    /// Absent if the robonode doesn't issue the enrollment receipts.
+    pub enrollment_receipt: Option<Box<[u8]>>,
+    /// The robonode signature for this opaque enrollment receipt.
+    pub enrollment_receipt_signature: Option<Box<[u8]>>,
     /// Scan result blob.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scan_result_blob: Option<ScanResultBlob>,
 }
 
+impl EnrollResponse {
+    /// Verify the enrollment receipt was signed by the robonode with the given public key,
+    /// and return the decoded receipt.
+    pub fn verify_enrollment_receipt(
+        &self,
+        robonode_public_key: &[u8],
+    ) -> Result<EnrollmentReceipt, VerifyEnrollmentReceiptError> {
+        let (Some(enrollment_receipt), Some(enrollment_receipt_signature)) = (
+            self.enrollment_receipt.as_deref(),
+            self.enrollment_receipt_signature.as_deref(),
+        ) else {
+            return Err(VerifyEnrollmentReceiptError::MissingReceipt);
+        };
+
+        let robonode_public_key = robonode_crypto::PublicKey::try_from(robonode_public_key)
+            .map_err(|_| VerifyEnrollmentReceiptError::InvalidRobonodePublicKey)?;
+        let signature = robonode_crypto::Signature::try_from(enrollment_receipt_signature)
+            .map_err(|_| VerifyEnrollmentReceiptError::InvalidSignature)?;
+        robonode_public_key
+            .verify(enrollment_receipt, &signature)
+            .map_err(|_| VerifyEnrollmentReceiptError::SignatureMismatch)?;
+
+        let enrollment_receipt =
+            EnrollmentReceipt::try_from(&OpaqueEnrollmentReceipt(enrollment_receipt.to_vec()))
+                .map_err(VerifyEnrollmentReceiptError::InvalidReceipt)?;
+
+        // The receipt must have been issued with the very key it is verified with.
+        if enrollment_receipt.robonode_key_id != robonode_public_key.as_bytes() {
+            return Err(VerifyEnrollmentReceiptError::RobonodeKeyMismatch);
+        }
+
+        Ok(enrollment_receipt)
+    }
+}
+
+/// The enrollment receipt verification error.
+#[derive(Error, Debug)]
+pub enum VerifyEnrollmentReceiptError {
+    /// The robonode has not issued the enrollment receipt.
+    #[error("enrollment receipt is missing")]
+    MissingReceipt,
+    /// The robonode public key is invalid.
+    #[error("invalid robonode public key")]
+    InvalidRobonodePublicKey,
+    /// The enrollment receipt signature is malformed.
+    #[error("invalid enrollment receipt signature")]
+    InvalidSignature,
+    /// The enrollment receipt signature doesn't match the robonode public key.
+    #[error("enrollment receipt signature mismatch")]
+    SignatureMismatch,
+    /// The enrollment receipt could not be decoded.
+    #[error("invalid enrollment receipt: {0}")]
+    InvalidReceipt(<EnrollmentReceipt as TryFrom<&'static OpaqueEnrollmentReceipt>>::Error),
+    /// The enrollment receipt was issued for a different robonode key.
+    #[error("enrollment receipt robonode key mismatch")]
+    RobonodeKeyMismatch,
+}
+
 /// The enroll-specific error condition.
 #[derive(Error, Debug, PartialEq)]
 
@@ -128,6 +197,7 @@ impl EnrollError {
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use robonode_crypto::Signer;
     use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
 
     use super::*;
@@ -204,6 +274,126 @@ mod tests {
         client.enroll(sample_request).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn mock_success_with_receipt() {
+        let mock_server = MockServer::start().await;
+
+        let robonode_signing_key = robonode_crypto::SigningKey::from_bytes(&[7; 32]);
+        let robonode_public_key = robonode_signing_key.verifying_key().to_bytes();
+        let enrollment_receipt = OpaqueEnrollmentReceipt::from(&EnrollmentReceipt {
+            public_key: b"123".to_vec(),
+            enrolled_at: 1_700_000_000_000,
+            robonode_key_id: robonode_public_key.to_vec(),
+        });
+        let enrollment_receipt_signature = robonode_signing_key.sign(enrollment_receipt.as_ref());
+
+        let sample_request = EnrollRequest {
+            liveness_data: b"dummy liveness data",
+            public_key: b"123",
+            liveness_data_signature: b"signature",
+        };
+        let sample_response = serde_json::json!({
+            "enrollmentReceipt": enrollment_receipt.0,
+            "enrollmentReceiptSignature": enrollment_receipt_signature.to_bytes().to_vec(),
+            "scanResultBlob": "blob".to_owned(),
+        });
+
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/enroll"))
+            .and(matchers::body_json(&sample_request))
+            .respond_with(ResponseTemplate::new(201).set_body_json(&sample_response))
+            .mount(&mock_server)
+            .await;
+
+        let client = Client {
+            base_url: mock_server.uri(),
+            reqwest: reqwest::Client::new(),
+        };
+
+        let response = client.enroll(sample_request).await.unwrap();
+        let receipt = response
+            .verify_enrollment_receipt(&robonode_public_key)
+            .unwrap();
+        assert_eq!(receipt.public_key, b"123".to_vec());
+        assert_eq!(receipt.enrolled_at, 1_700_000_000_000);
+
+        let other_public_key = robonode_crypto::SigningKey::from_bytes(&[8; 32])
+            .verifying_key()
+            .to_bytes();
+        assert_matches!(
+            response.verify_enrollment_receipt(&other_public_key),
+            Err(VerifyEnrollmentReceiptError::SignatureMismatch)
+        );
+    }
+
+    #[test]
+    fn verify_enrollment_receipt_errors() {
+        let robonode_signing_key = robonode_crypto::SigningKey::from_bytes(&[7; 32]);
+        let robonode_public_key = robonode_signing_key.verifying_key().to_bytes();
+        let sign = |enrollment_receipt: &[u8]| EnrollResponse {
+            enrollment_receipt: Some(enrollment_receipt.into()),
+            enrollment_receipt_signature: Some(
+                robonode_signing_key
+                    .sign(enrollment_receipt)
+                    .to_bytes()
+                    .to_vec()
+                    .into(),
+            ),
+            scan_result_blob: None,
+        };
+
+        let missing = EnrollResponse {
+            enrollment_receipt: None,
+            enrollment_receipt_signature: None,
+            scan_result_blob: None,
+        };
+        assert_matches!(
+            missing.verify_enrollment_receipt(&robonode_public_key),
+            Err(VerifyEnrollmentReceiptError::MissingReceipt)
+        );
+
+        let valid = sign(
+            OpaqueEnrollmentReceipt::from(&EnrollmentReceipt {
+                public_key: b"123".to_vec(),
+                enrolled_at: 1,
+                robonode_key_id: robonode_public_key.to_vec(),
+            })
+            .as_ref(),
+        );
+        assert_matches!(
+            valid.verify_enrollment_receipt(b"short"),
+            Err(VerifyEnrollmentReceiptError::InvalidRobonodePublicKey)
+        );
+
+        let malformed_signature = EnrollResponse {
+            enrollment_receipt_signature: Some(b"short".to_vec().into()),
+            ..sign(b"receipt")
+        };
+        assert_matches!(
+            malformed_signature.verify_enrollment_receipt(&robonode_public_key),
+            Err(VerifyEnrollmentReceiptError::InvalidSignature)
+        );
+
+        let undecodable = sign(b"receipt");
+        assert_matches!(
+            undecodable.verify_enrollment_receipt(&robonode_public_key),
+            Err(VerifyEnrollmentReceiptError::InvalidReceipt(_))
+        );
+
+        let other_key = sign(
+            OpaqueEnrollmentReceipt::from(&EnrollmentReceipt {
+                public_key: b"123".to_vec(),
+                enrolled_at: 1,
+                robonode_key_id: b"other robonode".to_vec(),
+            })
+            .as_ref(),
+        );
+        assert_matches!(
+            other_key.verify_enrollment_receipt(&robonode_public_key),
+            Err(VerifyEnrollmentReceiptError::RobonodeKeyMismatch)
+        );
+    }
+
     #[tokio::test]
     async fn mock_error_response_before_2023_05() {
         let cases = [
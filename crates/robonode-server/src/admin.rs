@@ -0,0 +1,265 @@
+//! The admin API access control and audit log.
+//!
+//! The admin API is authenticated with a static bearer token. Every admin action is recorded
+//! at an append-only audit log, one JSON entry per line, before it is conducted and once again
+//! when it completes; the action is refused if it can't be recorded.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    net::IpAddr,
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::logic::{
+    common::current_unix_milliseconds, op_admin_delete, op_admin_lookup, op_admin_migrate,
+    op_admin_reconcile,
+};
+
+/// The admin API state.
+pub struct Admin {
+    /// The bearer token the admin requests must be authenticated with.
+    token: String,
+    /// The audit log to record the admin actions at.
+    audit_log: AuditLog,
+}
+
+impl Admin {
+    /// Create the admin API state.
+    pub fn new(token: String, audit_log: AuditLog) -> Self {
+        Self { token, audit_log }
+    }
+
+    /// Check the `Authorization` header value carries the admin bearer token.
+    pub fn authorize(&self, authorization: Option<&str>) -> bool {
+        let Some(token) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+            return false;
+        };
+        constant_time_eq(token.trim().as_bytes(), self.token.as_bytes())
+    }
+
+    /// Record the entry at the audit log.
+    pub fn audit(&self, entry: &AuditEntry) -> Result<(), AuditLogError> {
+        self.audit_log.append(entry)
+    }
+}
+
+/// Compare the secrets without revealing the position of the first mismatch through timing.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// An admin action, as seen by the audit log.
+pub trait Action {
+    /// The name of the action.
+    const NAME: &'static str;
+
+    /// The public key the action is conducted on.
+    fn public_key(&self) -> &[u8];
+
+    /// The new public key the action assigns, if any.
+    fn new_public_key(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+impl Action for op_admin_lookup::Request {
+    const NAME: &'static str = "admin_lookup";
+
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+impl Action for op_admin_delete::Request {
+    const NAME: &'static str = "admin_delete";
+
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+impl Action for op_admin_migrate::Request {
+    const NAME: &'static str = "admin_migrate";
+
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    fn new_public_key(&self) -> Option<&[u8]> {
+        Some(&self.new_public_key)
+    }
+}
+
+impl Action for op_admin_reconcile::Request {
+    const NAME: &'static str = "admin_reconcile";
+
+    /// The reconciliation is not conducted on a particular public key.
+    fn public_key(&self) -> &[u8] {
+        &[]
+    }
+}
+
+/// The stage of the admin action an audit log entry is recorded at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Stage {
+    /// The action has been requested, and is about to be conducted.
+    Requested,
+    /// The action has succeeded.
+    Succeeded,
+    /// The action has failed.
+    Failed,
+}
+
+/// An audit log entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// The moment the entry was recorded at, in UNIX milliseconds.
+    pub at: u64,
+    /// The name of the action.
+    pub action: String,
+    /// The stage of the action.
+    pub stage: Stage,
+    /// The hex-encoded public key the action is conducted on.
+    pub public_key: String,
+    /// The hex-encoded new public key the action assigns, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_public_key: Option<String>,
+    /// The reason for the action given by the admin.
+    pub reason: String,
+    /// The IP of the client that requested the action.
+    pub client_ip: Option<IpAddr>,
+    /// The error code the action has failed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+impl AuditEntry {
+    /// Prepare the entry for the requested action.
+    pub fn requested<A: Action>(action: &A, reason: String, client_ip: Option<IpAddr>) -> Self {
+        Self {
+            at: current_unix_milliseconds(),
+            action: A::NAME.to_owned(),
+            stage: Stage::Requested,
+            public_key: hex::encode(action.public_key()),
+            new_public_key: action.new_public_key().map(hex::encode),
+            reason,
+            client_ip,
+            error_code: None,
+        }
+    }
+
+    /// Prepare the entry for the completion of the requested action, with the error code
+    /// if it has failed.
+    pub fn completed(&self, error_code: Option<&str>) -> Self {
+        Self {
+            at: current_unix_milliseconds(),
+            stage: if error_code.is_some() {
+                Stage::Failed
+            } else {
+                Stage::Succeeded
+            },
+            error_code: error_code.map(ToOwned::to_owned),
+            ..self.clone()
+        }
+    }
+}
+
+/// The audit log errors.
+#[derive(Debug, thiserror::Error)]
+pub enum AuditLogError {
+    /// An IO error.
+    #[error("audit log IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// An entry could not be encoded.
+    #[error("unable to encode the audit log entry: {0}")]
+    Encode(serde_json::Error),
+}
+
+/// The append-only audit log file.
+pub struct AuditLog {
+    /// The log file, opened for appending.
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open the audit log at the given path, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, AuditLogError> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Check the audit log at the given path can be opened, without creating it.
+    pub fn check(path: impl AsRef<Path>) -> Result<(), AuditLogError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(crate::store::check_parent_dir(path)?);
+        }
+        OpenOptions::new().append(true).open(path)?;
+        Ok(())
+    }
+
+    /// Write the entry to the log and sync it to the disk.
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), AuditLogError> {
+        let mut line = serde_json::to_string(entry).map_err(AuditLogError::Encode)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorizes_bearer_token() {
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let admin = Admin::new("secret token".to_owned(), AuditLog::open(&path).unwrap());
+
+        assert!(admin.authorize(Some("Bearer secret token")));
+        assert!(!admin.authorize(Some("Bearer secret tokem")));
+        assert!(!admin.authorize(Some("Bearer secret")));
+        assert!(!admin.authorize(Some("Basic secret token")));
+        assert!(!admin.authorize(Some("secret token")));
+        assert!(!admin.authorize(None));
+    }
+
+    #[test]
+    fn appends_audit_entries() {
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+
+        let requested = AuditEntry::requested(
+            &op_admin_migrate::Request {
+                public_key: vec![1],
+                new_public_key: vec![2],
+            },
+            "lost key".to_owned(),
+            Some([127, 0, 0, 1].into()),
+        );
+        let completed = requested.completed(Some("ADMIN_NOT_ENROLLED"));
+
+        AuditLog::open(&path).unwrap().append(&requested).unwrap();
+        AuditLog::open(&path).unwrap().append(&completed).unwrap();
+
+        let entries: Vec<AuditEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries, vec![requested, completed.clone()]);
+        assert_eq!(completed.action, "admin_migrate");
+        assert_eq!(completed.stage, Stage::Failed);
+        assert_eq!(completed.public_key, "01");
+        assert_eq!(completed.new_public_key.as_deref(), Some("02"));
+    }
+}
@@ -0,0 +1,142 @@
+//! The robonode admin command line tool, conducting the actions via the robonode admin API.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use serde::Deserialize;
+
+/// The command line interface.
+#[derive(clap::Parser)]
+#[command(about = "Humanode's Bioauth Robonode admin tool")]
+struct Cli {
+    /// The base URL of the robonode.
+    #[arg(long, env = "ROBONODE_URL", default_value = "http://127.0.0.1:3033")]
+    url: String,
+
+    /// The path to the file with the admin API bearer token.
+    #[arg(long, env = "ADMIN_TOKEN_FILE")]
+    token_file: PathBuf,
+
+    /// The reason for the action, to be recorded at the audit log.
+    #[arg(long)]
+    reason: String,
+
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The admin commands.
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Report the discrepancies between the FaceTec 3D-DB group and the enrollments recorded by
+    /// the robonode.
+    Reconcile {
+        /// Delete the orphans and the half-finished enrollments from the 3D-DB group.
+        #[arg(long)]
+        cleanup: bool,
+    },
+}
+
+/// The reconciliation report.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReconcileReport {
+    /// The 3D-DB group entries that must not be there.
+    orphans: Vec<String>,
+    /// The enrollments that have been started, but not known to be completed.
+    half_finished: Vec<HalfFinishedEnrollment>,
+    /// The 3D-DB group entries the robonode has no record of.
+    unrecorded: Vec<String>,
+    /// The temporary 3D-DB entries of the authentications.
+    #[serde(default)]
+    tmp_entries: Vec<String>,
+    /// The 3D-DB identifiers that have been cleaned up.
+    cleaned_up: Vec<String>,
+}
+
+/// An enrollment that has been started, but not known to be completed.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HalfFinishedEnrollment {
+    /// The 3D-DB identifier of the person.
+    identifier: String,
+    /// Whether the person is enrolled at the 3D-DB group.
+    in_group: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let cli = Cli::parse();
+
+    let token = std::fs::read_to_string(&cli.token_file)?;
+
+    match cli.command {
+        Command::Reconcile { cleanup } => {
+            let body = serde_json::json!({
+                "cleanup": cleanup,
+                "reason": cli.reason,
+            });
+            let res = reqwest::Client::new()
+                .post(format!("{}/admin/reconcile", cli.url.trim_end_matches('/')))
+                .bearer_auth(token.trim())
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&body)?)
+                .send()
+                .await?;
+
+            let status = res.status();
+            let bytes = res.bytes().await?;
+            if !status.is_success() {
+                return Err(format!(
+                    "robonode responded with {status}: {}",
+                    String::from_utf8_lossy(&bytes)
+                )
+                .into());
+            }
+
+            let report: ReconcileReport = serde_json::from_slice(&bytes)?;
+            print_report(&report);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the reconciliation report.
+fn print_report(report: &ReconcileReport) {
+    println!("Orphans: {}", report.orphans.len());
+    for identifier in &report.orphans {
+        println!("  {identifier}");
+    }
+
+    println!("Half-finished enrollments: {}", report.half_finished.len());
+    for enrollment in &report.half_finished {
+        let location = if enrollment.in_group {
+            "in the group"
+        } else {
+            "not in the group"
+        };
+        println!("  {} ({location})", enrollment.identifier);
+    }
+
+    println!("Unrecorded entries: {}", report.unrecorded.len());
+    for identifier in &report.unrecorded {
+        println!("  {identifier}");
+    }
+
+    println!(
+        "Temporary entries (outside of the group): {}",
+        report.tmp_entries.len()
+    );
+    for identifier in &report.tmp_entries {
+        println!("  {identifier}");
+    }
+
+    if !report.cleaned_up.is_empty() {
+        println!("Cleaned up: {}", report.cleaned_up.len());
+        for identifier in &report.cleaned_up {
+            println!("  {identifier}");
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! A local stub of the remote signer, for running the robonode with the `remote` signer backend
+//! in development.
+//!
+//! The key is kept in the process memory, so it must never be used in production.
+
+use std::sync::Arc;
+
+use clap::Parser;
+
+/// The command line interface.
+#[derive(clap::Parser)]
+#[command(about = "A local stub of the robonode remote signer")]
+struct Cli {
+    /// The address to listen at.
+    #[arg(long, env = "ADDR", default_value = "127.0.0.1:3034")]
+    addr: std::net::SocketAddr,
+
+    /// The hex-encoded secret key to sign with.
+    #[arg(long, env = "ROBONODE_SECRET_KEY", hide_env_values = true)]
+    secret_key: String,
+
+    /// The path to the file with the bearer token to authenticate the requests with.
+    #[arg(long, env = "REMOTE_SIGNER_TOKEN_FILE")]
+    token_file: std::path::PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let cli = Cli::parse();
+
+    let mut secret_key = robonode_crypto::SecretKey::default();
+    hex::decode_to_slice(cli.secret_key.trim(), &mut secret_key)?;
+    let signing_key = robonode_crypto::SigningKey::from_bytes(&secret_key);
+
+    let token = std::fs::read_to_string(&cli.token_file)?.trim().to_owned();
+    if token.is_empty() {
+        return Err("the token must not be empty".into());
+    }
+
+    let filter =
+        robonode_server::signer::remote_stub::filter(Arc::new(signing_key), Arc::new(token));
+    let (addr, server) = warp::serve(filter).bind_with_graceful_shutdown(cli.addr, async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install CTRL+C signal handler");
+    });
+
+    println!("Listening on http://{addr}");
+
+    server.await;
+    Ok(())
+}
@@ -2,9 +2,13 @@
 
 use warp::hyper::StatusCode;
 
-use crate::logic::{
-    op_authenticate, op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
-    op_get_public_key, ScanResultBlob,
+use crate::{
+    logic::{
+        op_admin_delete, op_admin_lookup, op_admin_migrate, op_admin_reconcile, op_authenticate,
+        op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
+        op_get_public_key, ScanResultBlob,
+    },
+    rate_limit,
 };
 
 /// A logic error.
@@ -20,6 +24,32 @@ pub struct Logic {
 
 impl warp::reject::Reject for Logic {}
 
+impl warp::reject::Reject for rate_limit::Limited {}
+
+/// An admin API error, occurring before the admin action is conducted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admin {
+    /// The request does not carry the valid admin bearer token.
+    Unauthorized,
+    /// The reason for the admin action is not given.
+    ReasonRequired,
+    /// The admin action could not be recorded at the audit log.
+    AuditLogFailed,
+}
+
+impl warp::reject::Reject for Admin {}
+
+impl Admin {
+    /// The HTTP status code and the error code to serve the error response with.
+    pub fn status_and_code(self) -> (StatusCode, &'static str) {
+        match self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "ADMIN_UNAUTHORIZED"),
+            Self::ReasonRequired => (StatusCode::BAD_REQUEST, "ADMIN_REASON_REQUIRED"),
+            Self::AuditLogFailed => (StatusCode::INTERNAL_SERVER_ERROR, "ADMIN_AUDIT_LOG_FAILED"),
+        }
+    }
+}
+
 impl Logic {
     /// Create a new [`Logic`] error.
     pub const fn new(
@@ -75,7 +105,9 @@ impl From<op_enroll::Error> for Logic {
             | op_enroll::Error::InternalErrorDbSearch(_, scan_result_blob)
             | op_enroll::Error::InternalErrorDbSearchUnsuccessful(scan_result_blob)
             | op_enroll::Error::InternalErrorDbEnroll(_, scan_result_blob)
-            | op_enroll::Error::InternalErrorDbEnrollUnsuccessful(scan_result_blob) => {
+            | op_enroll::Error::InternalErrorDbEnrollUnsuccessful(scan_result_blob)
+            | op_enroll::Error::InternalErrorEnrollmentReceiptSigningFailed(scan_result_blob)
+            | op_enroll::Error::InternalErrorEnrollmentPersistingFailed(_, scan_result_blob) => {
                 internal_logic(Some(scan_result_blob))
             }
             op_enroll::Error::InternalErrorEnrollment(_)
@@ -107,6 +139,11 @@ impl From<op_authenticate::Error> for Logic {
                 "AUTHENTICATE_SIGNATURE_INVALID",
                 Some(scan_result_blob),
             ),
+            op_authenticate::Error::AuthTicketMismatch(scan_result_blob) => Self::new(
+                StatusCode::FORBIDDEN,
+                "AUTHENTICATE_AUTH_TICKET_MISMATCH",
+                Some(scan_result_blob),
+            ),
             op_authenticate::Error::InternalErrorEnrollmentUnsuccessful(scan_result_blob)
             | op_authenticate::Error::InternalErrorDbSearch(_, scan_result_blob)
             | op_authenticate::Error::InternalErrorDbSearchUnsuccessful(scan_result_blob)
@@ -114,10 +151,15 @@ impl From<op_authenticate::Error> for Logic {
             | op_authenticate::Error::InternalErrorInvalidPublicKeyHex(scan_result_blob)
             | op_authenticate::Error::InternalErrorInvalidPublicKey(scan_result_blob)
             | op_authenticate::Error::InternalErrorSignatureVerificationFailed(scan_result_blob)
-            | op_authenticate::Error::InternalErrorAuthTicketSigningFailed(scan_result_blob) => {
-                internal_logic(Some(scan_result_blob))
+            | op_authenticate::Error::InternalErrorAuthTicketSigningFailed(scan_result_blob)
+            | op_authenticate::Error::InternalErrorAuthTicketPersistingFailed(
+                _,
+                scan_result_blob,
+            ) => internal_logic(Some(scan_result_blob)),
+            op_authenticate::Error::InternalErrorEnrollment(_)
+            | op_authenticate::Error::InternalErrorSequencePersistingFailed(_) => {
+                internal_logic(None)
             }
-            op_authenticate::Error::InternalErrorEnrollment(_) => internal_logic(None),
         }
     }
 }
@@ -144,3 +186,79 @@ impl From<op_get_public_key::Error> for Logic {
         match err {}
     }
 }
+
+impl From<op_admin_lookup::Error> for Logic {
+    fn from(err: op_admin_lookup::Error) -> Self {
+        match err {
+            op_admin_lookup::Error::InvalidPublicKey => {
+                Self::new(StatusCode::BAD_REQUEST, "ADMIN_INVALID_PUBLIC_KEY", None)
+            }
+            op_admin_lookup::Error::InternalErrorDbSearch(_)
+            | op_admin_lookup::Error::InternalErrorDbSearchUnsuccessful => internal_logic(None),
+        }
+    }
+}
+
+impl From<op_admin_delete::Error> for Logic {
+    fn from(err: op_admin_delete::Error) -> Self {
+        match err {
+            op_admin_delete::Error::InvalidPublicKey => {
+                Self::new(StatusCode::BAD_REQUEST, "ADMIN_INVALID_PUBLIC_KEY", None)
+            }
+            op_admin_delete::Error::NotEnrolled => {
+                Self::new(StatusCode::NOT_FOUND, "ADMIN_NOT_ENROLLED", None)
+            }
+            op_admin_delete::Error::InternalErrorDbSearch(_)
+            | op_admin_delete::Error::InternalErrorDbSearchUnsuccessful
+            | op_admin_delete::Error::InternalErrorDbDelete(_)
+            | op_admin_delete::Error::InternalErrorDbDeleteUnsuccessful
+            | op_admin_delete::Error::InternalErrorDeletionPersistingFailed(_) => {
+                internal_logic(None)
+            }
+        }
+    }
+}
+
+impl From<op_admin_reconcile::Error> for Logic {
+    fn from(err: op_admin_reconcile::Error) -> Self {
+        match err {
+            op_admin_reconcile::Error::InternalErrorStoreUnavailable
+            | op_admin_reconcile::Error::InternalErrorDbList(_)
+            | op_admin_reconcile::Error::InternalErrorDbListUnsuccessful
+            | op_admin_reconcile::Error::InternalErrorDbDelete(_)
+            | op_admin_reconcile::Error::InternalErrorDbDeleteUnsuccessful
+            | op_admin_reconcile::Error::InternalErrorDeletionPersistingFailed(_) => {
+                internal_logic(None)
+            }
+        }
+    }
+}
+
+impl From<op_admin_migrate::Error> for Logic {
+    fn from(err: op_admin_migrate::Error) -> Self {
+        match err {
+            op_admin_migrate::Error::InvalidPublicKey => {
+                Self::new(StatusCode::BAD_REQUEST, "ADMIN_INVALID_PUBLIC_KEY", None)
+            }
+            op_admin_migrate::Error::InvalidNewPublicKey => Self::new(
+                StatusCode::BAD_REQUEST,
+                "ADMIN_INVALID_NEW_PUBLIC_KEY",
+                None,
+            ),
+            op_admin_migrate::Error::NotEnrolled => {
+                Self::new(StatusCode::NOT_FOUND, "ADMIN_NOT_ENROLLED", None)
+            }
+            op_admin_migrate::Error::NewPublicKeyAlreadyUsed => Self::new(
+                StatusCode::CONFLICT,
+                "ADMIN_NEW_PUBLIC_KEY_ALREADY_USED",
+                None,
+            ),
+            op_admin_migrate::Error::InternalErrorDbSearch(_)
+            | op_admin_migrate::Error::InternalErrorDbSearchUnsuccessful
+            | op_admin_migrate::Error::InternalErrorStoreUnavailable
+            | op_admin_migrate::Error::InternalErrorMigrationPersistingFailed(_) => {
+                internal_logic(None)
+            }
+        }
+    }
+}
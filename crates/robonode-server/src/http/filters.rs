@@ -1,16 +1,23 @@
 //! Filters, essentially how [`warp`] implements routes and middlewares.
 
-use std::sync::Arc;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
 
 use serde::Serialize;
 use warp::Filter;
 
 use crate::{
+    admin::Admin,
     http::{error, handlers},
     logic::{
-        op_authenticate, op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
-        op_get_public_key, LogicOp,
+        op_admin_delete, op_admin_lookup, op_admin_migrate, op_admin_reconcile, op_authenticate,
+        op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
+        op_get_public_key, op_probe_facetec, LogicOp,
     },
+    metrics::{Metrics, OutcomeLabel},
+    rate_limit::{FaceScanRejection, RateLimiter},
 };
 
 /// Json body content length limit in bytes.
@@ -36,48 +43,172 @@ where
     warp::body::content_length_limit(JSON_BODY_LENGTH_LIMIT).and(warp::body::json::<T>())
 }
 
-/// The root mount point with all the routes.
+/// Extract the client IP.
+fn client_ip(
+    trust_x_forwarded_for: bool,
+) -> impl Filter<Extract = (Option<IpAddr>,), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .map(
+            move |remote: Option<SocketAddr>, forwarded_for: Option<String>| {
+                let forwarded_for = forwarded_for
+                    .filter(|_| trust_x_forwarded_for)
+                    // The last address is the one appended by the reverse proxy, the preceding
+                    // ones are provided by the client and can't be trusted.
+                    .and_then(|forwarded_for| {
+                        forwarded_for.rsplit(',').next()?.trim().parse().ok()
+                    });
+                forwarded_for.or(remote.map(|remote| remote.ip()))
+            },
+        )
+}
+
+/// Take a request from the client IP rate limit quota.
+fn rate_limit_client_ip(
+    rate_limiter: Arc<RateLimiter>,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    client_ip(rate_limiter.trust_x_forwarded_for())
+        .and(with_arc(rate_limiter))
+        .and_then(handlers::rate_limit_client_ip)
+        .untuple_one()
+}
+
+/// Authorize the admin request.
+fn admin_authorize(
+    admin: Option<Arc<Admin>>,
+) -> impl Filter<Extract = (Arc<Admin>,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |authorization| handlers::admin_authorize(admin.clone(), authorization))
+}
+
+/// The root mount point with all the public routes.
+///
+/// The admin routes are only served if the admin API state is provided.
+/// The metrics and the health probes are served separately, see [`internal`].
 pub fn root<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
+    admin: Option<Arc<Admin>>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
 where
-    L: LogicOp<op_authenticate::Request>
+    L: LogicOp<op_admin_delete::Request>
+        + LogicOp<op_admin_lookup::Request>
+        + LogicOp<op_admin_migrate::Request>
+        + LogicOp<op_admin_reconcile::Request>
+        + LogicOp<op_authenticate::Request>
         + LogicOp<op_enroll::Request>
         + LogicOp<op_get_facetec_device_sdk_params::Request>
         + LogicOp<op_get_facetec_session_token::Request>
         + LogicOp<op_get_public_key::Request>
         + Send
         + Sync,
-    <L as LogicOp<op_enroll::Request>>::Error: Into<error::Logic>,
+    <L as LogicOp<op_enroll::Request>>::Error:
+        Into<error::Logic> + OutcomeLabel + FaceScanRejection,
     <L as LogicOp<op_enroll::Request>>::Response: Serialize,
-    <L as LogicOp<op_authenticate::Request>>::Error: Into<error::Logic>,
+    <L as LogicOp<op_authenticate::Request>>::Error:
+        Into<error::Logic> + OutcomeLabel + FaceScanRejection,
     <L as LogicOp<op_authenticate::Request>>::Response: Serialize,
-    <L as LogicOp<op_get_facetec_device_sdk_params::Request>>::Error: Into<error::Logic>,
+    <L as LogicOp<op_get_facetec_device_sdk_params::Request>>::Error:
+        Into<error::Logic> + OutcomeLabel,
     <L as LogicOp<op_get_facetec_device_sdk_params::Request>>::Response: Serialize,
-    <L as LogicOp<op_get_facetec_session_token::Request>>::Error: Into<error::Logic>,
+    <L as LogicOp<op_get_facetec_session_token::Request>>::Error: Into<error::Logic> + OutcomeLabel,
     <L as LogicOp<op_get_facetec_session_token::Request>>::Response: Serialize,
-    <L as LogicOp<op_get_public_key::Request>>::Error: Into<error::Logic>,
+    <L as LogicOp<op_get_public_key::Request>>::Error: Into<error::Logic> + OutcomeLabel,
     <L as LogicOp<op_get_public_key::Request>>::Response: Serialize,
+    <L as LogicOp<op_admin_lookup::Request>>::Error: Into<error::Logic> + OutcomeLabel,
+    <L as LogicOp<op_admin_lookup::Request>>::Response: Serialize,
+    <L as LogicOp<op_admin_delete::Request>>::Error: Into<error::Logic> + OutcomeLabel,
+    <L as LogicOp<op_admin_delete::Request>>::Response: Serialize,
+    <L as LogicOp<op_admin_migrate::Request>>::Error: Into<error::Logic> + OutcomeLabel,
+    <L as LogicOp<op_admin_migrate::Request>>::Response: Serialize,
+    <L as LogicOp<op_admin_reconcile::Request>>::Error: Into<error::Logic> + OutcomeLabel,
+    <L as LogicOp<op_admin_reconcile::Request>>::Response: Serialize,
+{
+    let trust_x_forwarded_for = rate_limiter.trust_x_forwarded_for();
+    enroll(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        Arc::clone(&rate_limiter),
+    )
+    .or(authenticate(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        Arc::clone(&rate_limiter),
+    ))
+    .or(get_facetec_session_token(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        rate_limiter,
+    ))
+    .or(get_facetec_device_sdk_params(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+    ))
+    .or(get_public_key(Arc::clone(&logic), Arc::clone(&metrics)))
+    .or(admin_lookup(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        admin.clone(),
+        trust_x_forwarded_for,
+    ))
+    .or(admin_delete(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        admin.clone(),
+        trust_x_forwarded_for,
+    ))
+    .or(admin_migrate(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        admin.clone(),
+        trust_x_forwarded_for,
+    ))
+    .or(admin_reconcile(
+        logic,
+        metrics,
+        admin,
+        trust_x_forwarded_for,
+    ))
+}
+
+/// The internal mount point with the metrics and the health probes, not meant to be exposed
+/// to the public.
+pub fn internal<L>(
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<
+            op_probe_facetec::Request,
+            Response = op_probe_facetec::Response,
+            Error = op_probe_facetec::Error,
+        > + Send
+        + Sync,
 {
-    enroll(Arc::clone(&logic))
-        .or(authenticate(Arc::clone(&logic)))
-        .or(get_facetec_session_token(Arc::clone(&logic)))
-        .or(get_facetec_device_sdk_params(Arc::clone(&logic)))
-        .or(get_public_key(logic))
+    healthz(Arc::clone(&logic))
+        .or(readyz(logic))
+        .or(get_metrics(metrics))
 }
 
 /// POST /enroll with JSON body.
 fn enroll<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
 where
     L: LogicOp<op_enroll::Request> + Send + Sync,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel + FaceScanRejection,
     L::Response: Serialize,
 {
     warp::path!("enroll")
         .and(warp::post())
+        .and(rate_limit_client_ip(Arc::clone(&rate_limiter)))
         .and(with_arc(logic))
+        .and(with_arc(metrics))
+        .and(client_ip(rate_limiter.trust_x_forwarded_for()))
+        .and(with_arc(rate_limiter))
         .and(json_body::<op_enroll::Request>())
         .and_then(handlers::enroll)
 }
@@ -85,15 +216,21 @@ where
 /// POST /authenticate with JSON body.
 fn authenticate<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
 where
     L: LogicOp<op_authenticate::Request> + Send + Sync,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel + FaceScanRejection,
     L::Response: Serialize,
 {
     warp::path!("authenticate")
         .and(warp::post())
+        .and(rate_limit_client_ip(Arc::clone(&rate_limiter)))
         .and(with_arc(logic))
+        .and(with_arc(metrics))
+        .and(client_ip(rate_limiter.trust_x_forwarded_for()))
+        .and(with_arc(rate_limiter))
         .and(json_body::<op_authenticate::Request>())
         .and_then(handlers::authenticate)
 }
@@ -101,44 +238,186 @@ where
 /// GET /facetec-session-token.
 fn get_facetec_session_token<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
 where
     L: LogicOp<op_get_facetec_session_token::Request> + Send + Sync,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
     L::Response: Serialize,
 {
     warp::path!("facetec-session-token")
         .and(warp::get())
+        .and(rate_limit_client_ip(rate_limiter))
         .and(with_arc(logic))
+        .and(with_arc(metrics))
         .and_then(handlers::get_facetec_session_token)
 }
 
 /// GET /facetec-device-sdk-params.
 fn get_facetec_device_sdk_params<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
 where
     L: LogicOp<op_get_facetec_device_sdk_params::Request> + Send + Sync,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
     L::Response: Serialize,
 {
     warp::path!("facetec-device-sdk-params")
         .and(warp::get())
         .and(with_arc(logic))
+        .and(with_arc(metrics))
         .and_then(handlers::get_facetec_device_sdk_params)
 }
 
 /// GET /public-key.
 fn get_public_key<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
 where
     L: LogicOp<op_get_public_key::Request> + Send + Sync,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
     L::Response: Serialize,
 {
     warp::path!("public-key")
         .and(warp::get())
         .and(with_arc(logic))
+        .and(with_arc(metrics))
         .and_then(handlers::get_public_key)
 }
+
+/// GET /healthz.
+fn healthz<L>(
+    logic: Arc<L>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<
+            op_probe_facetec::Request,
+            Response = op_probe_facetec::Response,
+            Error = op_probe_facetec::Error,
+        > + Send
+        + Sync,
+{
+    warp::path!("healthz")
+        .and(warp::get())
+        .and(with_arc(logic))
+        .and_then(handlers::healthz)
+}
+
+/// GET /readyz.
+fn readyz<L>(
+    logic: Arc<L>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<
+            op_probe_facetec::Request,
+            Response = op_probe_facetec::Response,
+            Error = op_probe_facetec::Error,
+        > + Send
+        + Sync,
+{
+    warp::path!("readyz")
+        .and(warp::get())
+        .and(with_arc(logic))
+        .and_then(handlers::readyz)
+}
+
+/// POST /admin/lookup with JSON body.
+fn admin_lookup<L>(
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    admin: Option<Arc<Admin>>,
+    trust_x_forwarded_for: bool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<op_admin_lookup::Request> + Send + Sync,
+    L::Error: Into<error::Logic> + OutcomeLabel,
+    L::Response: Serialize,
+{
+    warp::path!("admin" / "lookup")
+        .and(warp::post())
+        .and(admin_authorize(admin))
+        .and(with_arc(logic))
+        .and(with_arc(metrics))
+        .and(client_ip(trust_x_forwarded_for))
+        .and(json_body::<handlers::AdminInput<op_admin_lookup::Request>>())
+        .and_then(handlers::admin::<L, op_admin_lookup::Request>)
+}
+
+/// POST /admin/delete with JSON body.
+fn admin_delete<L>(
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    admin: Option<Arc<Admin>>,
+    trust_x_forwarded_for: bool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<op_admin_delete::Request> + Send + Sync,
+    L::Error: Into<error::Logic> + OutcomeLabel,
+    L::Response: Serialize,
+{
+    warp::path!("admin" / "delete")
+        .and(warp::post())
+        .and(admin_authorize(admin))
+        .and(with_arc(logic))
+        .and(with_arc(metrics))
+        .and(client_ip(trust_x_forwarded_for))
+        .and(json_body::<handlers::AdminInput<op_admin_delete::Request>>())
+        .and_then(handlers::admin::<L, op_admin_delete::Request>)
+}
+
+/// POST /admin/migrate with JSON body.
+fn admin_migrate<L>(
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    admin: Option<Arc<Admin>>,
+    trust_x_forwarded_for: bool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<op_admin_migrate::Request> + Send + Sync,
+    L::Error: Into<error::Logic> + OutcomeLabel,
+    L::Response: Serialize,
+{
+    warp::path!("admin" / "migrate")
+        .and(warp::post())
+        .and(admin_authorize(admin))
+        .and(with_arc(logic))
+        .and(with_arc(metrics))
+        .and(client_ip(trust_x_forwarded_for))
+        .and(json_body::<handlers::AdminInput<op_admin_migrate::Request>>())
+        .and_then(handlers::admin::<L, op_admin_migrate::Request>)
+}
+
+/// POST /admin/reconcile with JSON body.
+fn admin_reconcile<L>(
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    admin: Option<Arc<Admin>>,
+    trust_x_forwarded_for: bool,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    L: LogicOp<op_admin_reconcile::Request> + Send + Sync,
+    L::Error: Into<error::Logic> + OutcomeLabel,
+    L::Response: Serialize,
+{
+    warp::path!("admin" / "reconcile")
+        .and(warp::post())
+        .and(admin_authorize(admin))
+        .and(with_arc(logic))
+        .and(with_arc(metrics))
+        .and(client_ip(trust_x_forwarded_for))
+        .and(json_body::<handlers::AdminInput<op_admin_reconcile::Request>>())
+        .and_then(handlers::admin::<L, op_admin_reconcile::Request>)
+}
+
+/// GET /metrics.
+fn get_metrics(
+    metrics: Arc<Metrics>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("metrics")
+        .and(warp::get())
+        .and(with_arc(metrics))
+        .and_then(handlers::get_metrics)
+}
@@ -1,28 +1,135 @@
 //! Handlers, the HTTP transport coupling for the internal logic.
 
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use warp::hyper::StatusCode;
 use warp::Reply;
 
 use super::error;
-use crate::logic::{
-    op_authenticate, op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
-    op_get_public_key, LogicOp,
+use crate::{
+    admin::{self, Admin, AuditEntry},
+    logic::{
+        op_authenticate, op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
+        op_get_public_key, op_probe_facetec, LogicOp,
+    },
+    metrics::{Metrics, OutcomeLabel},
+    rate_limit::{FaceScanRejection, FailureKey, RateLimiter},
 };
 
+/// Take a request from the client IP rate limit quota.
+pub async fn rate_limit_client_ip(
+    client_ip: Option<IpAddr>,
+    rate_limiter: Arc<RateLimiter>,
+) -> Result<(), warp::Rejection> {
+    rate_limiter
+        .check_ip(client_ip)
+        .map_err(warp::reject::custom)
+}
+
+/// The admin operation input, the operation request along with the reason for the action.
+#[derive(Debug, Deserialize)]
+pub struct AdminInput<R> {
+    /// The operation request.
+    #[serde(flatten)]
+    pub request: R,
+    /// The reason for the action, to be recorded at the audit log.
+    pub reason: String,
+}
+
+/// Authorize the admin request, rejecting it as an unknown call if the admin API is disabled.
+pub async fn admin_authorize(
+    admin: Option<Arc<Admin>>,
+    authorization: Option<String>,
+) -> Result<Arc<Admin>, warp::Rejection> {
+    let admin = admin.ok_or_else(warp::reject::not_found)?;
+    if !admin.authorize(authorization.as_deref()) {
+        return Err(warp::reject::custom(error::Admin::Unauthorized));
+    }
+    Ok(admin)
+}
+
+/// Admin operations HTTP transport coupling, recording the operation at the audit log.
+pub async fn admin<L, R>(
+    admin: Arc<Admin>,
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    client_ip: Option<IpAddr>,
+    input: AdminInput<R>,
+) -> Result<impl warp::Reply, warp::Rejection>
+where
+    R: admin::Action + Send,
+    L: LogicOp<R>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
+    L::Response: Serialize,
+{
+    let AdminInput { request, reason } = input;
+    if reason.trim().is_empty() {
+        return Err(warp::reject::custom(error::Admin::ReasonRequired));
+    }
+
+    let requested = AuditEntry::requested(&request, reason, client_ip);
+    if let Err(err) = admin.audit(&requested) {
+        tracing::error!(
+            message = "Unable to record the admin action at the audit log",
+            ?err,
+            ?requested
+        );
+        return Err(warp::reject::custom(error::Admin::AuditLogFailed));
+    }
+
+    let res = logic.call(request).await;
+    metrics.observe_operation(R::NAME, &res);
+    let res = res.map_err(Into::<error::Logic>::into);
+
+    // The action has already been conducted at this point, so the failure to record
+    // the outcome is not reported to the client.
+    let completed = requested.completed(res.as_ref().err().map(|err| err.error_code));
+    if let Err(err) = admin.audit(&completed) {
+        tracing::error!(
+            message = "Unable to record the admin action outcome at the audit log",
+            ?err,
+            ?completed
+        );
+    }
+    let res = res?;
+
+    let reply = warp::reply::json(&res);
+    let reply = warp::reply::with_status(reply, StatusCode::OK);
+    Ok(reply.into_response())
+}
+
 /// Enroll operation HTTP transport coupling.
 pub async fn enroll<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
+    client_ip: Option<IpAddr>,
     input: op_enroll::Request,
 ) -> Result<impl warp::Reply, warp::Rejection>
 where
     L: LogicOp<op_enroll::Request>,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel + FaceScanRejection,
     L::Response: Serialize,
 {
-    let res = logic.call(input).await.map_err(Into::into)?;
+    rate_limiter
+        .check_public_key(&input.public_key)
+        .map_err(warp::reject::custom)?;
+    let failure_keys: Vec<_> = client_ip
+        .map(FailureKey::Ip)
+        .into_iter()
+        .chain([FailureKey::PublicKey(input.public_key.clone())])
+        .collect();
+    rate_limiter
+        .check_face_scan_rejections(&failure_keys)
+        .map_err(warp::reject::custom)?;
+
+    let res = logic.call(input).await;
+    metrics.observe_operation("enroll", &res);
+    if matches!(&res, Err(err) if err.is_face_scan_rejection()) {
+        rate_limiter.record_face_scan_rejection(&failure_keys);
+    }
+    let res = res.map_err(Into::into)?;
 
     let reply = warp::reply::json(&res);
     let reply = warp::reply::with_status(reply, StatusCode::CREATED);
@@ -32,14 +139,29 @@ where
 /// Authenticate operation HTTP transport coupling.
 pub async fn authenticate<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
+    rate_limiter: Arc<RateLimiter>,
+    client_ip: Option<IpAddr>,
     input: op_authenticate::Request,
 ) -> Result<impl warp::Reply, warp::Rejection>
 where
     L: LogicOp<op_authenticate::Request>,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel + FaceScanRejection,
     L::Response: Serialize,
 {
-    let res = logic.call(input).await.map_err(Into::into)?;
+    // The public key is not known until the person is found, so the rejected FaceScans are
+    // only counted per client IP.
+    let failure_keys: Vec<_> = client_ip.map(FailureKey::Ip).into_iter().collect();
+    rate_limiter
+        .check_face_scan_rejections(&failure_keys)
+        .map_err(warp::reject::custom)?;
+
+    let res = logic.call(input).await;
+    metrics.observe_operation("authenticate", &res);
+    if matches!(&res, Err(err) if err.is_face_scan_rejection()) {
+        rate_limiter.record_face_scan_rejection(&failure_keys);
+    }
+    let res = res.map_err(Into::into)?;
 
     let reply = warp::reply::json(&res);
     let reply = warp::reply::with_status(reply, StatusCode::OK);
@@ -49,16 +171,16 @@ where
 /// Get FaceTec Session Token operation HTTP transport coupling.
 pub async fn get_facetec_session_token<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
 ) -> Result<impl warp::Reply, warp::Rejection>
 where
     L: LogicOp<op_get_facetec_session_token::Request>,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
     L::Response: Serialize,
 {
-    let res = logic
-        .call(op_get_facetec_session_token::Request {})
-        .await
-        .map_err(Into::into)?;
+    let res = logic.call(op_get_facetec_session_token::Request {}).await;
+    metrics.observe_operation("get_facetec_session_token", &res);
+    let res = res.map_err(Into::into)?;
 
     let reply = warp::reply::json(&res);
     let reply = warp::reply::with_status(reply, StatusCode::OK);
@@ -68,16 +190,18 @@ where
 /// Get FaceTec Device SDK Params operation HTTP transport coupling.
 pub async fn get_facetec_device_sdk_params<L>(
     logic: Arc<L>,
+    metrics: Arc<Metrics>,
 ) -> Result<impl warp::Reply, warp::Rejection>
 where
     L: LogicOp<op_get_facetec_device_sdk_params::Request>,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
     L::Response: Serialize,
 {
     let res = logic
         .call(op_get_facetec_device_sdk_params::Request {})
-        .await
-        .map_err(Into::into)?;
+        .await;
+    metrics.observe_operation("get_facetec_device_sdk_params", &res);
+    let res = res.map_err(Into::into)?;
 
     let reply = warp::reply::json(&res);
     let reply = warp::reply::with_status(reply, StatusCode::OK);
@@ -85,18 +209,74 @@ where
 }
 
 /// Get the robonode public key.
-pub async fn get_public_key<L>(logic: Arc<L>) -> Result<impl warp::Reply, warp::Rejection>
+pub async fn get_public_key<L>(
+    logic: Arc<L>,
+    metrics: Arc<Metrics>,
+) -> Result<impl warp::Reply, warp::Rejection>
 where
     L: LogicOp<op_get_public_key::Request>,
-    L::Error: Into<error::Logic>,
+    L::Error: Into<error::Logic> + OutcomeLabel,
     L::Response: Serialize,
 {
-    let res = logic
-        .call(op_get_public_key::Request)
-        .await
-        .map_err(Into::into)?;
+    let res = logic.call(op_get_public_key::Request).await;
+    metrics.observe_operation("get_public_key", &res);
+    let res = res.map_err(Into::into)?;
 
     let reply = warp::reply::json(&res);
     let reply = warp::reply::with_status(reply, StatusCode::OK);
     Ok(reply.into_response())
 }
+
+/// Liveness probe, succeeds if the FaceTec Server is reachable.
+pub async fn healthz<L>(logic: Arc<L>) -> Result<impl warp::Reply, warp::Rejection>
+where
+    L: LogicOp<
+        op_probe_facetec::Request,
+        Response = op_probe_facetec::Response,
+        Error = op_probe_facetec::Error,
+    >,
+{
+    let healthy = match logic.call(op_probe_facetec::Request).await {
+        Ok(op_probe_facetec::Response) | Err(op_probe_facetec::Error::NotReady) => true,
+        Err(op_probe_facetec::Error::Unreachable(_) | op_probe_facetec::Error::Timeout) => false,
+    };
+    Ok(probe_reply(healthy))
+}
+
+/// Readiness probe, succeeds if the FaceTec Server is ready to serve the requests.
+pub async fn readyz<L>(logic: Arc<L>) -> Result<impl warp::Reply, warp::Rejection>
+where
+    L: LogicOp<
+        op_probe_facetec::Request,
+        Response = op_probe_facetec::Response,
+        Error = op_probe_facetec::Error,
+    >,
+{
+    let ready = logic.call(op_probe_facetec::Request).await.is_ok();
+    Ok(probe_reply(ready))
+}
+
+/// Prepare the probe reply.
+fn probe_reply(ok: bool) -> warp::reply::Response {
+    let (status, status_code) = if ok {
+        ("ok", StatusCode::OK)
+    } else {
+        ("unavailable", StatusCode::SERVICE_UNAVAILABLE)
+    };
+    let reply = warp::reply::json(&serde_json::json!({ "status": status }));
+    let reply = warp::reply::with_status(reply, status_code);
+    reply.into_response()
+}
+
+/// Serve the metrics in the Prometheus text format.
+pub async fn get_metrics(metrics: Arc<Metrics>) -> Result<impl warp::Reply, warp::Rejection> {
+    let reply = match metrics.encode() {
+        Ok(encoded) => warp::reply::with_header(encoded, "content-type", prometheus::TEXT_FORMAT)
+            .into_response(),
+        Err(err) => {
+            tracing::error!(message = "Unable to encode the metrics", ?err);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    };
+    Ok(reply)
+}
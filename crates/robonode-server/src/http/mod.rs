@@ -8,4 +8,4 @@ pub mod rejection;
 #[cfg(test)]
 mod tests;
 
-pub use filters::root;
+pub use filters::{internal, root};
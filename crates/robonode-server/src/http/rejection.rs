@@ -1,10 +1,16 @@
 //! Rejection handling logic.
 
+use std::time::Duration;
+
 use serde::Serialize;
-use warp::{hyper::StatusCode, Reply};
+use warp::{
+    http::{header::RETRY_AFTER, HeaderValue},
+    hyper::StatusCode,
+    Reply,
+};
 
 use super::error;
-use crate::logic::ScanResultBlob;
+use crate::{logic::ScanResultBlob, rate_limit};
 
 /// Error response shape that we can return for the error body.
 #[derive(Debug, Serialize)]
@@ -19,22 +25,54 @@ pub(super) struct ErrorResponse {
 
 /// This function receives a `Rejection` and generates an error response.
 pub async fn handle(err: warp::reject::Rejection) -> Result<impl Reply, std::convert::Infallible> {
-    let (status_code, error_code, scan_result_blob) =
+    let (status_code, error_code, scan_result_blob, retry_after) =
         if let Some(logic_error) = err.find::<error::Logic>() {
             (
                 logic_error.status_code,
                 logic_error.error_code,
                 logic_error.scan_result_blob.clone(),
+                None,
+            )
+        } else if let Some(admin_error) = err.find::<error::Admin>() {
+            let (status_code, error_code) = admin_error.status_and_code();
+            (status_code, error_code, None, None)
+        } else if let Some(limited) = err.find::<rate_limit::Limited>() {
+            let error_code = match limited.kind {
+                rate_limit::LimitKind::Rate => "RATE_LIMITED",
+                rate_limit::LimitKind::FailedAttempts => "TOO_MANY_FAILED_ATTEMPTS",
+            };
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                error_code,
+                None,
+                Some(limited.retry_after),
             )
         } else {
-            (StatusCode::NOT_IMPLEMENTED, "UNKNOWN_CALL", None)
+            (StatusCode::NOT_IMPLEMENTED, "UNKNOWN_CALL", None, None)
         };
 
     let json = warp::reply::json(&ErrorResponse {
         error_code,
         scan_result_blob,
     });
-    Ok(warp::reply::with_status(json, status_code))
+    let mut response = warp::reply::with_status(json, status_code).into_response();
+    if let Some(retry_after) = retry_after {
+        response.headers_mut().insert(
+            RETRY_AFTER,
+            HeaderValue::from(retry_after_secs(retry_after)),
+        );
+    }
+    Ok(response)
+}
+
+/// The `Retry-After` header value, the number of whole seconds to wait, rounded up.
+fn retry_after_secs(retry_after: Duration) -> u64 {
+    let secs = retry_after.as_secs();
+    if retry_after.subsec_nanos() > 0 {
+        secs.saturating_add(1)
+    } else {
+        secs
+    }
 }
 
 #[cfg(test)]
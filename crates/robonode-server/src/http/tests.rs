@@ -1,21 +1,26 @@
 // Allow simple integer arithmetic in tests.
 #![allow(clippy::arithmetic_side_effects)]
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use facetec_api_client::ServerError;
 use mockall::predicate::*;
 use mockall::*;
 use primitives_auth_ticket::OpaqueAuthTicket;
+use primitives_enrollment_receipt::OpaqueEnrollmentReceipt;
 use primitives_liveness_data::OpaqueLivenessData;
 use warp::{hyper::StatusCode, Filter, Reply};
 
 use crate::{
-    http::{rejection, root},
+    admin::{self, Admin, AuditEntry, AuditLog},
+    http::{internal, rejection, root},
     logic::{
-        op_authenticate, op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
-        op_get_public_key, LogicOp, ScanResultBlob,
+        op_admin_delete, op_admin_lookup, op_admin_migrate, op_admin_reconcile, op_authenticate,
+        op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token,
+        op_get_public_key, op_probe_facetec, LogicOp, ScanResultBlob,
     },
+    metrics::Metrics,
+    rate_limit::{self, RateLimiter},
 };
 
 mock! {
@@ -25,6 +30,11 @@ mock! {
         fn get_facetec_session_token(&self, req: op_get_facetec_session_token::Request) -> Result<op_get_facetec_session_token::Response, op_get_facetec_session_token::Error>;
         fn get_facetec_device_sdk_params(&self, req: op_get_facetec_device_sdk_params::Request) -> Result<op_get_facetec_device_sdk_params::Response, op_get_facetec_device_sdk_params::Error>;
         fn get_public_key(&self, req: op_get_public_key::Request) -> Result<op_get_public_key::Response, op_get_public_key::Error>;
+        fn probe_facetec(&self, req: op_probe_facetec::Request) -> Result<op_probe_facetec::Response, op_probe_facetec::Error>;
+        fn admin_lookup(&self, req: op_admin_lookup::Request) -> Result<op_admin_lookup::Response, op_admin_lookup::Error>;
+        fn admin_delete(&self, req: op_admin_delete::Request) -> Result<op_admin_delete::Response, op_admin_delete::Error>;
+        fn admin_migrate(&self, req: op_admin_migrate::Request) -> Result<op_admin_migrate::Response, op_admin_migrate::Error>;
+        fn admin_reconcile(&self, req: op_admin_reconcile::Request) -> Result<op_admin_reconcile::Response, op_admin_reconcile::Error>;
     }
 }
 
@@ -180,6 +190,46 @@ impl_Logic!(
     get_public_key
 );
 
+impl_Logic!(
+    MockLogic,
+    op_probe_facetec::Request,
+    op_probe_facetec::Response,
+    op_probe_facetec::Error,
+    probe_facetec
+);
+
+impl_Logic!(
+    MockLogic,
+    op_admin_lookup::Request,
+    op_admin_lookup::Response,
+    op_admin_lookup::Error,
+    admin_lookup
+);
+
+impl_Logic!(
+    MockLogic,
+    op_admin_delete::Request,
+    op_admin_delete::Response,
+    op_admin_delete::Error,
+    admin_delete
+);
+
+impl_Logic!(
+    MockLogic,
+    op_admin_migrate::Request,
+    op_admin_migrate::Response,
+    op_admin_migrate::Error,
+    admin_migrate
+);
+
+impl_Logic!(
+    MockLogic,
+    op_admin_reconcile::Request,
+    op_admin_reconcile::Response,
+    op_admin_reconcile::Error,
+    admin_reconcile
+);
+
 async fn expect_error_body_response(
     status_code: StatusCode,
     error_code: &'static str,
@@ -196,7 +246,45 @@ async fn expect_error_body_response(
 fn root_with_error_handler(
     logic: MockLogic,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
-    root(Arc::new(logic)).recover(rejection::handle)
+    root_with_rate_limit(logic, Default::default())
+}
+
+fn root_with_rate_limit(
+    logic: MockLogic,
+    rate_limit: rate_limit::Config,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    root(
+        Arc::new(logic),
+        Arc::new(Metrics::new().unwrap()),
+        Arc::new(RateLimiter::new(rate_limit)),
+        None,
+    )
+    .recover(rejection::handle)
+}
+
+fn internal_with_error_handler(
+    logic: MockLogic,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    internal(Arc::new(logic), Arc::new(Metrics::new().unwrap())).recover(rejection::handle)
+}
+
+/// The admin API bearer token used in tests.
+const ADMIN_TOKEN: &str = "0123456789abcdef0123456789abcdef";
+
+fn root_with_admin(
+    logic: MockLogic,
+    audit_log_path: &std::path::Path,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    root(
+        Arc::new(logic),
+        Arc::new(Metrics::new().unwrap()),
+        Arc::new(RateLimiter::new(Default::default())),
+        Some(Arc::new(Admin::new(
+            ADMIN_TOKEN.to_owned(),
+            AuditLog::open(audit_log_path).unwrap(),
+        ))),
+    )
+    .recover(rejection::handle)
 }
 
 /// Possible response variants we can expect in trivial success tests.
@@ -217,9 +305,15 @@ trivial_success_tests! [
             liveness_data_signature: b"signature".to_vec(),
         },
         mocked_call = expect_enroll,
-        injected_response = op_enroll::Response {scan_result_blob: "scan result blob".to_owned()},
+        injected_response = op_enroll::Response {
+            enrollment_receipt: OpaqueEnrollmentReceipt(b"receipt".to_vec()),
+            enrollment_receipt_signature: b"signature".to_vec(),
+            scan_result_blob: "scan result blob".to_owned(),
+        },
         expected_status = StatusCode::CREATED,
         expected_response = SuccessResponse::Json(serde_json::json!({
+            "enrollmentReceipt": b"receipt".to_vec(),
+            "enrollmentReceiptSignature": b"signature".to_vec(),
             "scanResultBlob": "scan result blob",
         })),
     },
@@ -232,6 +326,8 @@ trivial_success_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_response = op_authenticate::Response {
@@ -546,6 +642,42 @@ trivial_error_tests! [
         expected_scan_result_blob = Some("scan result blob".to_owned()),
     },
 
+    /// This test verifies getting expected HTTP response
+    /// during failed enrollment request with InternalErrorEnrollmentReceiptSigningFailed error.
+    {
+        test_name = enroll_error_internal_enrollment_receipt_signing_failed,
+        method = "POST",
+        path = "/enroll",
+        input = op_enroll::Request {
+            public_key: b"key".to_vec(),
+            liveness_data: OpaqueLivenessData(b"data".to_vec()),
+            liveness_data_signature: b"signature".to_vec(),
+        },
+        mocked_call = expect_enroll,
+        injected_error = op_enroll::Error::InternalErrorEnrollmentReceiptSigningFailed("scan result blob".to_owned()),
+        expected_status = StatusCode::INTERNAL_SERVER_ERROR,
+        expected_code = "LOGIC_INTERNAL_ERROR",
+        expected_scan_result_blob = Some("scan result blob".to_owned()),
+    },
+
+    /// This test verifies getting expected HTTP response
+    /// during failed enrollment request with InternalErrorEnrollmentPersistingFailed error.
+    {
+        test_name = enroll_error_internal_enrollment_persisting_failed,
+        method = "POST",
+        path = "/enroll",
+        input = op_enroll::Request {
+            public_key: b"key".to_vec(),
+            liveness_data: OpaqueLivenessData(b"data".to_vec()),
+            liveness_data_signature: b"signature".to_vec(),
+        },
+        mocked_call = expect_enroll,
+        injected_error = op_enroll::Error::InternalErrorEnrollmentPersistingFailed(crate::store::Error::DuplicateNonce, "scan result blob".to_owned()),
+        expected_status = StatusCode::INTERNAL_SERVER_ERROR,
+        expected_code = "LOGIC_INTERNAL_ERROR",
+        expected_scan_result_blob = Some("scan result blob".to_owned()),
+    },
+
     /// This test verifies getting expected HTTP response
     /// during failed authentication request with InvalidLivenessData error.
     {
@@ -555,6 +687,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InvalidLivenessData(codec::Error::from("invalid_data")),
@@ -572,6 +706,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::FaceScanRejected("scan result blob".to_owned()),
@@ -589,6 +725,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::PersonNotFound("scan result blob".to_owned()),
@@ -606,6 +744,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::SignatureInvalid("scan result blob".to_owned()),
@@ -614,6 +754,25 @@ trivial_error_tests! [
         expected_scan_result_blob = Some("scan result blob".to_owned()),
     },
 
+    /// This test verifies getting expected HTTP response
+    /// during failed authentication request with AuthTicketMismatch error.
+    {
+        test_name = authenticate_error_auth_ticket_mismatch,
+        method = "POST",
+        path = "/authenticate",
+        input = op_authenticate::Request {
+            liveness_data: OpaqueLivenessData(b"data".to_vec()),
+            liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: Some(OpaqueAuthTicket(b"ticket".to_vec())),
+            auth_ticket_signature: None,
+        },
+        mocked_call = expect_authenticate,
+        injected_error = op_authenticate::Error::AuthTicketMismatch("scan result blob".to_owned()),
+        expected_status = StatusCode::FORBIDDEN,
+        expected_code = "AUTHENTICATE_AUTH_TICKET_MISMATCH",
+        expected_scan_result_blob = Some("scan result blob".to_owned()),
+    },
+
     /// This test verifies getting expected HTTP response
     /// during failed authentication request with InternalErrorEnrollment error.
     {
@@ -623,6 +782,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorEnrollment(facetec_api_client::Error::Server(
@@ -644,6 +805,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorEnrollmentUnsuccessful("scan result blob".to_owned()),
@@ -661,6 +824,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorDbSearch(facetec_api_client::Error::Server(
@@ -682,6 +847,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorDbSearchUnsuccessful("scan result blob".to_owned()),
@@ -699,6 +866,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorDbSearchMatchLevelMismatch("scan result blob".to_owned()),
@@ -716,6 +885,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorInvalidPublicKeyHex("scan result blob".to_owned()),
@@ -733,6 +904,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorInvalidPublicKey("scan result blob".to_owned()),
@@ -750,6 +923,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorSignatureVerificationFailed("scan result blob".to_owned()),
@@ -767,6 +942,8 @@ trivial_error_tests! [
         input = op_authenticate::Request {
             liveness_data: OpaqueLivenessData(b"data".to_vec()),
             liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         },
         mocked_call = expect_authenticate,
         injected_error = op_authenticate::Error::InternalErrorAuthTicketSigningFailed("scan result blob".to_owned()),
@@ -775,6 +952,44 @@ trivial_error_tests! [
         expected_scan_result_blob = Some("scan result blob".to_owned()),
     },
 
+    /// This test verifies getting expected HTTP response
+    /// during failed authentication request with InternalErrorSequencePersistingFailed error.
+    {
+        test_name = authenticate_error_internal_sequence_persisting_failed,
+        method = "POST",
+        path = "/authenticate",
+        input = op_authenticate::Request {
+            liveness_data: OpaqueLivenessData(b"data".to_vec()),
+            liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
+        },
+        mocked_call = expect_authenticate,
+        injected_error = op_authenticate::Error::InternalErrorSequencePersistingFailed(crate::store::Error::NonMonotonicSequence { value: 1, last: 1 }),
+        expected_status = StatusCode::INTERNAL_SERVER_ERROR,
+        expected_code = "LOGIC_INTERNAL_ERROR",
+        expected_scan_result_blob = None,
+    },
+
+    /// This test verifies getting expected HTTP response
+    /// during failed authentication request with InternalErrorAuthTicketPersistingFailed error.
+    {
+        test_name = authenticate_error_internal_auth_ticket_persisting_failed,
+        method = "POST",
+        path = "/authenticate",
+        input = op_authenticate::Request {
+            liveness_data: OpaqueLivenessData(b"data".to_vec()),
+            liveness_data_signature: b"signature".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
+        },
+        mocked_call = expect_authenticate,
+        injected_error = op_authenticate::Error::InternalErrorAuthTicketPersistingFailed(crate::store::Error::DuplicateNonce, "scan result blob".to_owned()),
+        expected_status = StatusCode::INTERNAL_SERVER_ERROR,
+        expected_code = "LOGIC_INTERNAL_ERROR",
+        expected_scan_result_blob = Some("scan result blob".to_owned()),
+    },
+
     /// This test verifies getting expected HTTP response during
     /// failed get_facetec_session_token request with internal error.
     {
@@ -789,3 +1004,617 @@ trivial_error_tests! [
         expected_scan_result_blob = None,
     },
 ];
+
+/// Call the probe at the given path with the given probe result injected, and return
+/// the response status and body.
+async fn probe(
+    path: &str,
+    probe_result: fn() -> Result<op_probe_facetec::Response, op_probe_facetec::Error>,
+) -> (StatusCode, serde_json::Value) {
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_probe_facetec()
+        .returning(move |_| probe_result());
+
+    let filter = internal_with_error_handler(mock_logic);
+
+    let res = warp::test::request()
+        .method("GET")
+        .path(path)
+        .reply(&filter)
+        .await;
+
+    (res.status(), serde_json::from_slice(res.body()).unwrap())
+}
+
+/// This test verifies the liveness and readiness probes succeed when the FaceTec Server
+/// is ready.
+#[tokio::test]
+async fn probes_facetec_ready() {
+    let ready = || Ok(op_probe_facetec::Response);
+    let ok = serde_json::json!({ "status": "ok" });
+
+    assert_eq!(probe("/healthz", ready).await, (StatusCode::OK, ok.clone()));
+    assert_eq!(probe("/readyz", ready).await, (StatusCode::OK, ok));
+}
+
+/// This test verifies only the liveness probe succeeds when the FaceTec Server is reachable,
+/// but not ready.
+#[tokio::test]
+async fn probes_facetec_not_ready() {
+    let not_ready = || Err(op_probe_facetec::Error::NotReady);
+
+    assert_eq!(
+        probe("/healthz", not_ready).await,
+        (StatusCode::OK, serde_json::json!({ "status": "ok" }))
+    );
+    assert_eq!(
+        probe("/readyz", not_ready).await,
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            serde_json::json!({ "status": "unavailable" })
+        )
+    );
+}
+
+/// This test verifies both probes fail when the FaceTec Server does not respond.
+#[tokio::test]
+async fn probes_facetec_timeout() {
+    let timeout = || Err(op_probe_facetec::Error::Timeout);
+    let unavailable = serde_json::json!({ "status": "unavailable" });
+
+    assert_eq!(
+        probe("/healthz", timeout).await,
+        (StatusCode::SERVICE_UNAVAILABLE, unavailable.clone())
+    );
+    assert_eq!(
+        probe("/readyz", timeout).await,
+        (StatusCode::SERVICE_UNAVAILABLE, unavailable)
+    );
+}
+
+/// This test verifies the handled operations are counted by the error variant in the metrics.
+#[tokio::test]
+async fn metrics_count_operations() {
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_enroll()
+        .returning(|_| Err(op_enroll::Error::PublicKeyAlreadyUsed));
+
+    let logic = Arc::new(mock_logic);
+    let metrics = Arc::new(Metrics::new().unwrap());
+    let filter = root(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        Arc::new(RateLimiter::new(Default::default())),
+        None,
+    )
+    .recover(rejection::handle);
+    let internal_filter = internal(logic, metrics).recover(rejection::handle);
+
+    for _ in 0..2 {
+        let res = warp::test::request()
+            .method("POST")
+            .path("/enroll")
+            .json(&op_enroll::Request {
+                public_key: b"key".to_vec(),
+                liveness_data: OpaqueLivenessData(b"data".to_vec()),
+                liveness_data_signature: b"signature".to_vec(),
+            })
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+
+    let res = warp::test::request()
+        .method("GET")
+        .path("/metrics")
+        .reply(&internal_filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-type"], prometheus::TEXT_FORMAT);
+    let body = String::from_utf8(res.body().to_vec()).unwrap();
+    assert!(body.contains(
+        r#"robonode_operations_total{operation="enroll",outcome="PublicKeyAlreadyUsed"} 2"#
+    ));
+}
+
+/// This test verifies the metrics and the health probes are not served at the public routes.
+#[tokio::test]
+async fn internal_routes_are_not_public() {
+    let filter = root_with_error_handler(MockLogic::new());
+
+    for path in ["/metrics", "/healthz", "/readyz"] {
+        let res = warp::test::request()
+            .method("GET")
+            .path(path)
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::NOT_IMPLEMENTED, "{path}");
+    }
+}
+
+/// The rate limit settings with the tight quotas, to be hit in tests.
+fn tight_rate_limit() -> rate_limit::Config {
+    rate_limit::Config {
+        per_ip: rate_limit::Quota {
+            burst: 2,
+            per_minute: 1,
+        },
+        per_public_key: rate_limit::Quota {
+            burst: 1,
+            per_minute: 1,
+        },
+        face_scan_rejections: rate_limit::FailureCap {
+            max_failures: 2,
+            window_secs: 60,
+        },
+        ..Default::default()
+    }
+}
+
+/// Prepare the enroll request from the given client address with the given public key.
+fn enroll_request(remote_addr: [u8; 4], public_key: &[u8]) -> warp::test::RequestBuilder {
+    warp::test::request()
+        .method("POST")
+        .path("/enroll")
+        .remote_addr(SocketAddr::from((remote_addr, 12345)))
+        .json(&op_enroll::Request {
+            public_key: public_key.to_vec(),
+            liveness_data: OpaqueLivenessData(b"data".to_vec()),
+            liveness_data_signature: b"signature".to_vec(),
+        })
+}
+
+/// Assert the response is the rate limit error with the given code.
+async fn assert_rate_limited(
+    res: warp::http::Response<warp::hyper::body::Bytes>,
+    code: &'static str,
+) {
+    assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(res.headers().contains_key("retry-after"));
+    assert_eq!(
+        res.body(),
+        &expect_error_body_response(StatusCode::TOO_MANY_REQUESTS, code, None).await
+    );
+}
+
+/// This test verifies the requests are rate limited per client IP.
+#[tokio::test]
+async fn rate_limited_per_ip() {
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_enroll()
+        .times(3)
+        .returning(|_| Err(op_enroll::Error::PublicKeyAlreadyUsed));
+
+    let filter = root_with_rate_limit(mock_logic, tight_rate_limit());
+
+    for public_key in [b"key1", b"key2"] {
+        let res = enroll_request([1, 1, 1, 1], public_key)
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+
+    let res = enroll_request([1, 1, 1, 1], b"key3").reply(&filter).await;
+    assert_rate_limited(res, "RATE_LIMITED").await;
+
+    // Other clients are not affected.
+    let res = enroll_request([2, 2, 2, 2], b"key3").reply(&filter).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+}
+
+/// This test verifies the enrollments are rate limited per public key.
+#[tokio::test]
+async fn enroll_rate_limited_per_public_key() {
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_enroll()
+        .times(2)
+        .returning(|_| Err(op_enroll::Error::PublicKeyAlreadyUsed));
+
+    let filter = root_with_rate_limit(mock_logic, tight_rate_limit());
+
+    let res = enroll_request([1, 1, 1, 1], b"key1").reply(&filter).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let res = enroll_request([2, 2, 2, 2], b"key1").reply(&filter).await;
+    assert_rate_limited(res, "RATE_LIMITED").await;
+
+    let res = enroll_request([2, 2, 2, 2], b"key2").reply(&filter).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+}
+
+/// This test verifies the session tokens are rate limited per client IP, taken from
+/// the `X-Forwarded-For` header when it is trusted.
+#[tokio::test]
+async fn session_token_rate_limited_per_forwarded_ip() {
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_get_facetec_session_token()
+        .times(3)
+        .returning(|_| {
+            Ok(op_get_facetec_session_token::Response {
+                session_token: "token".to_owned(),
+            })
+        });
+
+    let filter = root_with_rate_limit(
+        mock_logic,
+        rate_limit::Config {
+            trust_x_forwarded_for: true,
+            ..tight_rate_limit()
+        },
+    );
+
+    let request = |forwarded_for: &str| {
+        warp::test::request()
+            .method("GET")
+            .path("/facetec-session-token")
+            .remote_addr(SocketAddr::from(([10, 0, 0, 1], 12345)))
+            .header("x-forwarded-for", forwarded_for)
+    };
+
+    for _ in 0..2 {
+        let res = request("6.6.6.6, 1.1.1.1").reply(&filter).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    // The client provided part of the header is ignored.
+    let res = request("7.7.7.7, 1.1.1.1").reply(&filter).await;
+    assert_rate_limited(res, "RATE_LIMITED").await;
+
+    let res = request("2.2.2.2").reply(&filter).await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+/// This test verifies the rejected FaceScans are capped per public key.
+#[tokio::test]
+async fn enroll_face_scan_rejections_capped() {
+    let mut mock_logic = MockLogic::new();
+    mock_logic.expect_enroll().times(2).returning(|_| {
+        Err(op_enroll::Error::FaceScanRejected(
+            "scan result blob".to_owned(),
+        ))
+    });
+
+    let filter = root_with_rate_limit(
+        mock_logic,
+        rate_limit::Config {
+            per_public_key: rate_limit::Quota {
+                burst: 10,
+                per_minute: 10,
+            },
+            ..tight_rate_limit()
+        },
+    );
+
+    for remote_addr in [[1, 1, 1, 1], [2, 2, 2, 2]] {
+        let res = enroll_request(remote_addr, b"key").reply(&filter).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    let res = enroll_request([3, 3, 3, 3], b"key").reply(&filter).await;
+    assert_rate_limited(res, "TOO_MANY_FAILED_ATTEMPTS").await;
+}
+
+/// This test verifies the rejected FaceScans are capped per client IP for the authentication.
+#[tokio::test]
+async fn authenticate_face_scan_rejections_capped() {
+    let mut mock_logic = MockLogic::new();
+    mock_logic.expect_authenticate().times(2).returning(|_| {
+        Err(op_authenticate::Error::FaceScanRejected(
+            "scan result blob".to_owned(),
+        ))
+    });
+
+    let filter = root_with_rate_limit(
+        mock_logic,
+        rate_limit::Config {
+            per_ip: rate_limit::Quota {
+                burst: 10,
+                per_minute: 10,
+            },
+            ..tight_rate_limit()
+        },
+    );
+
+    let request = || {
+        warp::test::request()
+            .method("POST")
+            .path("/authenticate")
+            .remote_addr(SocketAddr::from(([1, 1, 1, 1], 12345)))
+            .json(&op_authenticate::Request {
+                liveness_data: OpaqueLivenessData(b"data".to_vec()),
+                liveness_data_signature: b"signature".to_vec(),
+                auth_ticket: None,
+                auth_ticket_signature: None,
+            })
+    };
+
+    for _ in 0..2 {
+        let res = request().reply(&filter).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    let res = request().reply(&filter).await;
+    assert_rate_limited(res, "TOO_MANY_FAILED_ATTEMPTS").await;
+}
+
+/// Create a fresh audit log file, removed once the returned path is dropped.
+fn audit_log_path() -> tempfile::TempPath {
+    tempfile::NamedTempFile::new().unwrap().into_temp_path()
+}
+
+/// Read the audit log entries.
+fn audit_log_entries(path: &std::path::Path) -> Vec<AuditEntry> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+/// Prepare the admin delete request with the given authorization and reason.
+fn admin_delete_request(authorization: Option<&str>, reason: &str) -> warp::test::RequestBuilder {
+    let request = warp::test::request()
+        .method("POST")
+        .path("/admin/delete")
+        .json(&serde_json::json!({
+            "publicKey": b"key".to_vec(),
+            "reason": reason,
+        }));
+    match authorization {
+        Some(authorization) => request.header("authorization", authorization),
+        None => request,
+    }
+}
+
+/// This test verifies the admin routes are not served unless the admin API is enabled.
+#[tokio::test]
+async fn admin_disabled() {
+    let filter = root_with_error_handler(MockLogic::new());
+
+    let res = admin_delete_request(Some(&format!("Bearer {ADMIN_TOKEN}")), "erasure request")
+        .reply(&filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(
+        res.body(),
+        &expect_error_body_response(StatusCode::NOT_IMPLEMENTED, "UNKNOWN_CALL", None).await
+    );
+}
+
+/// This test verifies the admin requests without the valid bearer token are rejected and
+/// not recorded at the audit log.
+#[tokio::test]
+async fn admin_unauthorized() {
+    let path = audit_log_path();
+    let filter = root_with_admin(MockLogic::new(), &path);
+
+    for authorization in [None, Some("Bearer wrong"), Some(ADMIN_TOKEN)] {
+        let res = admin_delete_request(authorization, "erasure request")
+            .reply(&filter)
+            .await;
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            res.body(),
+            &expect_error_body_response(StatusCode::UNAUTHORIZED, "ADMIN_UNAUTHORIZED", None).await
+        );
+    }
+
+    assert_eq!(audit_log_entries(&path), vec![]);
+}
+
+/// This test verifies the admin requests must give the reason for the action.
+#[tokio::test]
+async fn admin_reason_required() {
+    let path = audit_log_path();
+    let filter = root_with_admin(MockLogic::new(), &path);
+
+    let res = admin_delete_request(Some(&format!("Bearer {ADMIN_TOKEN}")), " ")
+        .reply(&filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        res.body(),
+        &expect_error_body_response(StatusCode::BAD_REQUEST, "ADMIN_REASON_REQUIRED", None).await
+    );
+    assert_eq!(audit_log_entries(&path), vec![]);
+}
+
+/// This test verifies the successful admin action is recorded at the audit log.
+#[tokio::test]
+async fn admin_delete_audited() {
+    let path = audit_log_path();
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_admin_delete()
+        .with(function(|req: &op_admin_delete::Request| {
+            req.public_key == b"key"
+        }))
+        .returning(|_| {
+            Ok(op_admin_delete::Response {
+                identifier: hex::encode(b"key"),
+            })
+        });
+    let filter = root_with_admin(mock_logic, &path);
+
+    let res = admin_delete_request(Some(&format!("Bearer {ADMIN_TOKEN}")), "erasure request")
+        .remote_addr(SocketAddr::from(([1, 1, 1, 1], 12345)))
+        .reply(&filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(res.body()).unwrap(),
+        serde_json::json!({ "identifier": hex::encode(b"key") })
+    );
+
+    let entries = audit_log_entries(&path);
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.action.as_str(), entry.stage))
+            .collect::<Vec<_>>(),
+        vec![
+            ("admin_delete", admin::Stage::Requested),
+            ("admin_delete", admin::Stage::Succeeded),
+        ]
+    );
+    for entry in entries {
+        assert_eq!(entry.public_key, hex::encode(b"key"));
+        assert_eq!(entry.reason, "erasure request");
+        assert_eq!(entry.client_ip, Some([1, 1, 1, 1].into()));
+        assert_eq!(entry.error_code, None);
+    }
+}
+
+/// This test verifies the failed admin action is recorded at the audit log along with
+/// the error code.
+#[tokio::test]
+async fn admin_migrate_failure_audited() {
+    let path = audit_log_path();
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_admin_migrate()
+        .returning(|_| Err(op_admin_migrate::Error::NewPublicKeyAlreadyUsed));
+    let filter = root_with_admin(mock_logic, &path);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/admin/migrate")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .json(&serde_json::json!({
+            "publicKey": b"old".to_vec(),
+            "newPublicKey": b"new".to_vec(),
+            "reason": "lost key",
+        }))
+        .reply(&filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        res.body(),
+        &expect_error_body_response(
+            StatusCode::CONFLICT,
+            "ADMIN_NEW_PUBLIC_KEY_ALREADY_USED",
+            None
+        )
+        .await
+    );
+
+    let entries = audit_log_entries(&path);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].stage, admin::Stage::Failed);
+    assert_eq!(entries[1].new_public_key, Some(hex::encode(b"new")));
+    assert_eq!(
+        entries[1].error_code.as_deref(),
+        Some("ADMIN_NEW_PUBLIC_KEY_ALREADY_USED")
+    );
+}
+
+/// This test verifies the admin reconcile response shape, and that the reconciliation is
+/// recorded at the audit log.
+#[tokio::test]
+async fn admin_reconcile_audited() {
+    let path = audit_log_path();
+    let mut mock_logic = MockLogic::new();
+    mock_logic
+        .expect_admin_reconcile()
+        .with(function(|req: &op_admin_reconcile::Request| req.cleanup))
+        .returning(|_| {
+            Ok(op_admin_reconcile::Response {
+                orphans: vec!["tmp-1".to_owned()],
+                half_finished: vec![op_admin_reconcile::HalfFinishedEnrollment {
+                    identifier: "aa".to_owned(),
+                    in_group: false,
+                }],
+                unrecorded: vec![],
+                tmp_entries: vec!["tmp-1".to_owned(), "tmp-2".to_owned()],
+                cleaned_up: vec!["tmp-1".to_owned(), "aa".to_owned()],
+            })
+        });
+    let filter = root_with_admin(mock_logic, &path);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/admin/reconcile")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .json(&serde_json::json!({
+            "cleanup": true,
+            "reason": "weekly reconciliation",
+        }))
+        .reply(&filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(res.body()).unwrap(),
+        serde_json::json!({
+            "orphans": ["tmp-1"],
+            "halfFinished": [{ "identifier": "aa", "inGroup": false }],
+            "unrecorded": [],
+            "tmpEntries": ["tmp-1", "tmp-2"],
+            "cleanedUp": ["tmp-1", "aa"],
+        })
+    );
+
+    let entries = audit_log_entries(&path);
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.action.as_str(), entry.stage))
+            .collect::<Vec<_>>(),
+        vec![
+            ("admin_reconcile", admin::Stage::Requested),
+            ("admin_reconcile", admin::Stage::Succeeded),
+        ]
+    );
+    assert_eq!(entries[0].public_key, "");
+}
+
+/// This test verifies the admin lookup response shape.
+#[tokio::test]
+async fn admin_lookup_success() {
+    let path = audit_log_path();
+    let mut mock_logic = MockLogic::new();
+    mock_logic.expect_admin_lookup().returning(|_| {
+        Ok(op_admin_lookup::Response {
+            identifier: Some(hex::encode(b"key")),
+            migrated_to: None,
+            enrolled: true,
+            issued_tickets: 2,
+            last_issued_at: Some(1000),
+        })
+    });
+    let filter = root_with_admin(mock_logic, &path);
+
+    let res = warp::test::request()
+        .method("POST")
+        .path("/admin/lookup")
+        .header("authorization", format!("Bearer {ADMIN_TOKEN}"))
+        .json(&serde_json::json!({
+            "publicKey": b"key".to_vec(),
+            "reason": "support ticket",
+        }))
+        .reply(&filter)
+        .await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(res.body()).unwrap(),
+        serde_json::json!({
+            "identifier": hex::encode(b"key"),
+            "migratedTo": null,
+            "enrolled": true,
+            "issuedTickets": 2,
+            "lastIssuedAt": 1000,
+        })
+    );
+}
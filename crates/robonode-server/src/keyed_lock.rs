@@ -0,0 +1,136 @@
+//! Keyed lock implementation.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, PoisonError},
+};
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// A set of locks identified by keys.
+///
+/// The lock for a key is allocated when it is first needed and released when it is no longer
+/// held or awaited, so the set only holds the locks that are in use.
+#[derive(Debug)]
+pub struct KeyedLock<K> {
+    /// The locks in use, by key.
+    locks: std::sync::Mutex<HashMap<K, Arc<Mutex<()>>>>,
+}
+
+impl<K> Default for KeyedLock<K> {
+    fn default() -> Self {
+        Self {
+            locks: Default::default(),
+        }
+    }
+}
+
+impl<K> KeyedLock<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Create a new keyed lock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Acquire the lock for the given key, waiting until it is released if it is held.
+    pub async fn lock(&self, key: K) -> KeyedLockGuard<'_, K> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(PoisonError::into_inner);
+            Arc::clone(locks.entry(key.clone()).or_default())
+        };
+        let guard = Arc::clone(&lock).lock_owned().await;
+        KeyedLockGuard {
+            keyed_lock: self,
+            key,
+            lock,
+            guard: Some(guard),
+        }
+    }
+}
+
+/// The guard of the lock for a particular key, releases the lock when dropped.
+#[derive(Debug)]
+pub struct KeyedLockGuard<'a, K>
+where
+    K: Eq + Hash,
+{
+    /// The keyed lock this guard belongs to.
+    keyed_lock: &'a KeyedLock<K>,
+    /// The key of the held lock.
+    key: K,
+    /// The held lock.
+    lock: Arc<Mutex<()>>,
+    /// The guard of the held lock.
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl<'a, K> Drop for KeyedLockGuard<'a, K>
+where
+    K: Eq + Hash,
+{
+    fn drop(&mut self) {
+        let mut locks = self
+            .keyed_lock
+            .locks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        drop(self.guard.take());
+        // If the lock is only referenced by the set and this guard, nobody else holds or awaits
+        // it; new references are only obtained under the set lock, so it is safe to remove.
+        if Arc::strong_count(&self.lock) <= 2 {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn same_key_is_exclusive() {
+        let keyed_lock = KeyedLock::new();
+
+        let guard = keyed_lock.lock("a").await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), keyed_lock.lock("a"))
+                .await
+                .is_err()
+        );
+        drop(guard);
+
+        let _guard = tokio::time::timeout(Duration::from_secs(5), keyed_lock.lock("a"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn different_keys_are_independent() {
+        let keyed_lock = KeyedLock::new();
+
+        let _guard_a = keyed_lock.lock("a").await;
+        let _guard_b = tokio::time::timeout(Duration::from_secs(5), keyed_lock.lock("b"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn released_locks_are_removed() {
+        let keyed_lock = KeyedLock::new();
+
+        let guard_a = keyed_lock.lock("a").await;
+        let guard_b = keyed_lock.lock("b").await;
+        assert_eq!(keyed_lock.locks.lock().unwrap().len(), 2);
+
+        drop(guard_a);
+        assert_eq!(keyed_lock.locks.lock().unwrap().len(), 1);
+
+        drop(guard_b);
+        assert!(keyed_lock.locks.lock().unwrap().is_empty());
+    }
+}
@@ -2,41 +2,80 @@
 
 use std::{convert::Infallible, marker::PhantomData, sync::Arc};
 
-use http::root;
+use http::{internal, root};
 use tokio::sync::Mutex;
 use warp::Filter;
 
+pub mod admin;
+pub mod config;
 mod http;
+mod keyed_lock;
 mod logging_inspector;
 mod logic;
+mod metrics;
+pub mod rate_limit;
 mod sequence;
+pub mod signer;
+pub mod store;
 mod validator_key;
 
 pub use logging_inspector::LoggingInspector;
-pub use logic::FacetecDeviceSdkParams;
+pub use logic::{FacetecDbParams, FacetecDeviceSdkParams, PublicKeyProvider};
 
-/// Initialize the [`warp::Filter`] implementing the HTTP transport for
-/// the robonode.
+/// Initialize the [`warp::Filter`]s implementing the HTTP transport for
+/// the robonode: the public one, and the internal one with the metrics and the health probes.
+#[allow(clippy::too_many_arguments)]
 pub fn init(
     execution_id: uuid::Uuid,
     facetec_api_client: facetec_api_client::Client<LoggingInspector>,
     facetec_device_sdk_params: FacetecDeviceSdkParams,
-    robonode_signing_key: robonode_crypto::SigningKey,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone {
+    facetec_db_params: FacetecDbParams,
+    signer: signer::Backend,
+    store: Option<store::Store>,
+    rate_limit: rate_limit::Config,
+    admin: Option<admin::Admin>,
+    legacy_auth_tickets: bool,
+    trusted_robonode_public_keys: Vec<robonode_crypto::PublicKey>,
+) -> (
+    impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone,
+    impl Filter<Extract = (impl warp::Reply,), Error = std::convert::Infallible> + Clone,
+) {
+    // Continue the sequence from the last persisted value to keep the nonces monotonic across
+    // restarts.
+    let sequence_init = store.as_ref().map_or(0, store::Store::last_sequence);
+    let metrics = Arc::new(
+        metrics::Metrics::new().expect("registering the metrics at a new registry must succeed"),
+    );
     let logic = logic::Logic {
-        locked: Mutex::new(logic::Locked {
-            sequence: sequence::Sequence::new(0),
-            execution_id,
-            facetec: facetec_api_client,
-            signer: robonode_signing_key,
-            public_key_type: PhantomData::<validator_key::SubstratePublic<sp_core::sr25519::Public>>,
+        sequence_state: Mutex::new(logic::SequenceState {
+            sequence: sequence::Sequence::new(sequence_init),
+            store,
         }),
+        execution_id,
+        facetec: facetec_api_client,
+        signer,
+        public_key_type: PhantomData::<validator_key::SubstratePublic<sp_core::sr25519::Public>>,
+        enrollment_locks: keyed_lock::KeyedLock::new(),
+        db_enrollment_lock: Mutex::new(()),
         facetec_device_sdk_params,
+        facetec_db_params,
+        metrics: Arc::clone(&metrics),
+        legacy_auth_tickets,
+        trusted_robonode_public_keys,
     };
-    let log = warp::log("robonode::api");
-    root(Arc::new(logic))
-        .with(log)
-        .recover(http::rejection::handle)
+    let logic = Arc::new(logic);
+    let public = root(
+        Arc::clone(&logic),
+        Arc::clone(&metrics),
+        Arc::new(rate_limit::RateLimiter::new(rate_limit)),
+        admin.map(Arc::new),
+    )
+    .with(warp::log("robonode::api"))
+    .recover(http::rejection::handle);
+    let internal = internal(logic, metrics)
+        .with(warp::log("robonode::internal"))
+        .recover(http::rejection::handle);
+    (public, internal)
 }
 
 #[async_trait::async_trait]
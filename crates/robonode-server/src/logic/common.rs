@@ -7,7 +7,27 @@
 pub const EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE: &str =
     "An enrollment already exists for this externalDatabaseRefID.";
 
-/// The group name at 3D DB.
+/// This is the error message that FaceTec server returns when the group we operate on
+/// does not exist.
+pub const NO_GROUP_ERROR_MESSAGE: &str =
+    "Tried to search a groupName when that groupName does not exist.";
+
+/// This is the error message that FaceTec server returns when there is no FaceMap stored
+/// under the given `externalDatabaseRefID`.
+pub const NO_ENTRY_ERROR_MESSAGE: &str =
+    "No entry found in the database for this externalDatabaseRefID.";
+
+/// The prefix of the temporary external database IDs the authentication FaceScans are
+/// processed under.
+pub const TMP_EXTERNAL_DATABASE_REF_ID_PREFIX: &str = "tmp-";
+
+/// The default group name at 3D DB.
 pub const DB_GROUP_NAME: &str = "humanode";
-/// The match level to use throughout the code.
+/// The default match level to use throughout the code.
 pub const MATCH_LEVEL: i64 = 10;
+
+/// The maximum difference between the issuance moment of the auth ticket to co-sign and
+/// the current moment, in milliseconds.
+pub const CO_SIGN_MAX_CLOCK_DRIFT: u64 = 5 * 60 * 1000;
+
+pub use primitives_auth_ticket::current_unix_milliseconds;
@@ -2,6 +2,8 @@
 
 use facetec_api_client as ft;
 
+use super::common::NO_GROUP_ERROR_MESSAGE;
+
 /// An enum with all of the meaningful outcomes from the db search result.
 pub enum DbSearchResult {
     /// A usual response.
@@ -20,8 +22,7 @@ pub fn db_search_result_adapter(
     match search_res {
         Ok(res) => DbSearchResult::Response(res),
         Err(ft::Error::Server(ft::ServerError { error_message }))
-            if error_message
-                .starts_with("Tried to search a groupName when that groupName does not exist.") =>
+            if error_message.starts_with(NO_GROUP_ERROR_MESSAGE) =>
         {
             DbSearchResult::NoGroupError
         }
@@ -0,0 +1,113 @@
+//! The resolution of the persons enrolled at the 3D-DB, taking the key migrations into account.
+//!
+//! A person is enrolled at the 3D-DB under the identifier equal to the hex-encoded public key
+//! they enrolled with. When the person is migrated to a new public key, the 3D-DB enrollment is
+//! kept intact, and the migration is recorded at the store instead.
+
+use facetec_api_client as ft;
+
+use super::{
+    common::NO_ENTRY_ERROR_MESSAGE,
+    facetec_utils::{db_search_result_adapter, DbSearchResult},
+    Logic,
+};
+use crate::store::Store;
+
+/// The 3D-DB identifier of the person controlled by the given hex-encoded public key.
+///
+/// Returns `None` if the person enrolled with this public key has been migrated to another one.
+pub fn identifier_of(store: Option<&Store>, public_key_hex: &str) -> Option<String> {
+    if let Some(store) = store {
+        if let Some(identifier) = store.migrated_identifier(public_key_hex) {
+            return Some(identifier.to_owned());
+        }
+        if store.migrated_public_key(public_key_hex).is_some() {
+            return None;
+        }
+    }
+    Some(public_key_hex.to_owned())
+}
+
+/// The hex-encoded public key controlling the person enrolled under the given 3D-DB identifier.
+pub fn public_key_of(store: Option<&Store>, identifier: &str) -> String {
+    store
+        .and_then(|store| store.migrated_public_key(identifier))
+        .unwrap_or(identifier)
+        .to_owned()
+}
+
+/// The outcome of checking whether the identifier is enrolled at the 3D-DB.
+pub enum DbEnrollment {
+    /// The identifier is enrolled at the 3D-DB group.
+    Enrolled,
+    /// The identifier is not enrolled at the 3D-DB group.
+    NotEnrolled,
+    /// The search has failed.
+    Error(ft::Error),
+    /// The search response was unsuccessful.
+    Unsuccessful,
+}
+
+impl<S, PK> Logic<S, PK> {
+    /// Check whether the given identifier is enrolled at the 3D-DB group.
+    pub(super) async fn db_enrollment(&self, identifier: &str) -> DbEnrollment {
+        let search_result = self
+            .metrics
+            .observe_facetec_call(
+                "db_search",
+                self.facetec.db_search(ft::db_search::Request {
+                    external_database_ref_id: identifier,
+                    group_name: &self.facetec_db_params.group_name,
+                    min_match_level: self.facetec_db_params.match_level,
+                }),
+            )
+            .await;
+
+        match db_search_result_adapter(search_result) {
+            DbSearchResult::Response(search_res) if !search_res.success => {
+                DbEnrollment::Unsuccessful
+            }
+            DbSearchResult::Response(search_res) => {
+                if search_res
+                    .results
+                    .iter()
+                    .any(|result| result.identifier == identifier)
+                {
+                    DbEnrollment::Enrolled
+                } else {
+                    DbEnrollment::NotEnrolled
+                }
+            }
+            DbSearchResult::NoGroupError => DbEnrollment::NotEnrolled,
+            // There is no FaceMap stored under this identifier, i.e. nobody has ever enrolled
+            // with it.
+            DbSearchResult::OtherError(ft::Error::Server(ft::ServerError { error_message }))
+                if error_message.starts_with(NO_ENTRY_ERROR_MESSAGE) =>
+            {
+                DbEnrollment::NotEnrolled
+            }
+            DbSearchResult::OtherError(err) => DbEnrollment::Error(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn resolves_migrations() {
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let mut store = Store::open(&path).unwrap();
+        store.record_key_migration("aa", "bb").await.unwrap();
+
+        assert_eq!(identifier_of(Some(&store), "aa"), None);
+        assert_eq!(identifier_of(Some(&store), "bb"), Some("aa".to_owned()));
+        assert_eq!(identifier_of(Some(&store), "cc"), Some("cc".to_owned()));
+        assert_eq!(identifier_of(None, "aa"), Some("aa".to_owned()));
+
+        assert_eq!(public_key_of(Some(&store), "aa"), "bb");
+        assert_eq!(public_key_of(Some(&store), "cc"), "cc");
+        assert_eq!(public_key_of(None, "aa"), "aa");
+    }
+}
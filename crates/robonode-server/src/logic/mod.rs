@@ -1,19 +1,25 @@
 //! Core logic of the system.
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
 use facetec_api_client as ft;
 use tokio::sync::Mutex;
 
-use crate::sequence::Sequence;
+use crate::{keyed_lock::KeyedLock, metrics::Metrics, sequence::Sequence, store::Store};
 
-mod common;
+pub(crate) mod common;
 mod facetec_utils;
+mod key_migration;
+pub mod op_admin_delete;
+pub mod op_admin_lookup;
+pub mod op_admin_migrate;
+pub mod op_admin_reconcile;
 pub mod op_authenticate;
 pub mod op_enroll;
 pub mod op_get_facetec_device_sdk_params;
 pub mod op_get_facetec_session_token;
 pub mod op_get_public_key;
+pub mod op_probe_facetec;
 #[cfg(test)]
 mod tests;
 pub mod traits;
@@ -24,20 +30,12 @@ pub use traits::*;
 pub(crate) type ScanResultBlob = String;
 
 /// The overall generic logic.
+///
+/// The operations run concurrently, and only the portions that actually need serialization are
+/// conducted under the locks.
 pub struct Logic<S, PK> {
-    /// The mutex over the locked portions of the logic.
-    /// This way we're ensuring the operations can only be conducted under
-    /// the lock.
-    pub locked: Mutex<Locked<S, PK>>,
-    /// The FaceTec Device SDK params to expose.
-    pub facetec_device_sdk_params: FacetecDeviceSdkParams,
-}
-
-/// The inner state, to be hidden behind the mutex to ensure we don't have
-/// access to it unless we lock the mutex.
-pub struct Locked<S, PK> {
-    /// The sequence number.
-    pub sequence: Sequence,
+    /// The sequence state, behind the mutex to serialize the sequence values allocation.
+    pub sequence_state: Mutex<SequenceState>,
     /// An execution ID, to be used together with sequence to guarantee uniqueness of the temporary
     /// enrollment external database IDs.
     pub execution_id: uuid::Uuid,
@@ -47,6 +45,35 @@ pub struct Locked<S, PK> {
     pub signer: S,
     /// Public key type to use under the hood.
     pub public_key_type: PhantomData<PK>,
+    /// The per-public-key locks, to serialize the enrollments with the same public key.
+    pub enrollment_locks: KeyedLock<String>,
+    /// The lock over the 3D-DB duplicates search and enrollment, to ensure a person can't be
+    /// enrolled twice by concurrent enrollments.
+    ///
+    /// It has to be global rather than per public key, as the same person enrolling with
+    /// different public keys is exactly what the duplicates search is there to catch.
+    /// The time spent waiting for it is reported at the metrics.
+    pub db_enrollment_lock: Mutex<()>,
+    /// The FaceTec Device SDK params to expose.
+    pub facetec_device_sdk_params: FacetecDeviceSdkParams,
+    /// The FaceTec 3D-DB params to enroll and search the persons with.
+    pub facetec_db_params: FacetecDbParams,
+    /// The metrics to measure the FaceTec Server API calls with.
+    pub metrics: Arc<Metrics>,
+    /// Whether to issue the auth tickets in the legacy encoding, for the chains that have not
+    /// enacted the runtime upgrade accepting the versioned auth tickets yet.
+    pub legacy_auth_tickets: bool,
+    /// The public keys of the robonodes whose auth tickets can be co-signed.
+    pub trusted_robonode_public_keys: Vec<robonode_crypto::PublicKey>,
+}
+
+/// The sequence state, to be hidden behind the mutex to ensure we don't have
+/// access to it unless we lock the mutex.
+pub struct SequenceState {
+    /// The sequence number.
+    pub sequence: Sequence,
+    /// The persistent state store, if the state is to be kept across restarts.
+    pub store: Option<Store>,
 }
 
 /// The FaceTec Device SDK params.
@@ -59,3 +86,21 @@ pub struct FacetecDeviceSdkParams {
     /// The production key.
     pub production_key: Option<String>,
 }
+
+/// The FaceTec 3D-DB params.
+#[derive(Debug, Clone)]
+pub struct FacetecDbParams {
+    /// The group name at 3D-DB.
+    pub group_name: String,
+    /// The minimal match level to consider the FaceScans matching.
+    pub match_level: i64,
+}
+
+impl Default for FacetecDbParams {
+    fn default() -> Self {
+        Self {
+            group_name: common::DB_GROUP_NAME.to_owned(),
+            match_level: common::MATCH_LEVEL,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Admin deletion of the enrollment operation.
+//!
+//! Deletes the person from the 3D-DB, so that they are no longer found at authentication,
+//! and can enroll again with a new public key. The FaceMap stored under the enrollment
+//! external database ref ID is kept by the FaceTec Server, so the public key the person
+//! enrolled with can't be enrolled with again.
+
+use facetec_api_client as ft;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use super::{
+    key_migration::{identifier_of, DbEnrollment},
+    Logic, LogicOp,
+};
+use crate::store;
+
+/// The request of the admin delete operation.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// The public key of the validator to delete the enrollment of.
+    pub public_key: Vec<u8>,
+}
+
+/// The response of the admin delete operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// The 3D-DB identifier of the deleted person.
+    pub identifier: String,
+}
+
+/// Errors for the admin delete operation.
+///
+/// Allow dead code to explicitly control errors data.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    /// The provided public key failed to load because it was invalid.
+    InvalidPublicKey,
+    /// No person enrolled at the 3D-DB is controlled by the public key.
+    NotEnrolled,
+    /// Internal error at 3D-DB search due to the underlying request
+    /// error at the API level.
+    InternalErrorDbSearch(ft::Error),
+    /// Internal error at 3D-DB search due to unsuccessful response.
+    InternalErrorDbSearchUnsuccessful,
+    /// Internal error at 3D-DB delete due to the underlying request
+    /// error at the API level.
+    InternalErrorDbDelete(ft::Error),
+    /// Internal error at 3D-DB delete due to unsuccessful response.
+    InternalErrorDbDeleteUnsuccessful,
+    /// Internal error when persisting the deletion.
+    InternalErrorDeletionPersistingFailed(store::Error),
+}
+
+#[async_trait::async_trait]
+impl<S, PK> LogicOp<Request> for Logic<S, PK>
+where
+    S: Send + Sync + 'static,
+    PK: Send + Sync + for<'a> TryFrom<&'a [u8]> + AsRef<[u8]>,
+{
+    type Response = Response;
+    type Error = Error;
+
+    async fn call(&self, req: Request) -> Result<Self::Response, Self::Error> {
+        let public_key = PK::try_from(&req.public_key).map_err(|_| Error::InvalidPublicKey)?;
+        let public_key_hex = hex::encode(public_key);
+
+        // Serialize with the enrollments and the other admin operations, so that the 3D-DB and
+        // the key migrations don't change under us.
+        let _db_enrollment_guard = self
+            .metrics
+            .observe_db_enrollment_lock_wait("admin_delete", self.db_enrollment_lock.lock())
+            .await;
+
+        let identifier = {
+            let sequence_state = self.sequence_state.lock().await;
+            identifier_of(sequence_state.store.as_ref(), &public_key_hex)
+        };
+        let identifier = identifier.ok_or(Error::NotEnrolled)?;
+
+        match self.db_enrollment(&identifier).await {
+            DbEnrollment::Enrolled => {}
+            DbEnrollment::NotEnrolled => return Err(Error::NotEnrolled),
+            DbEnrollment::Error(err) => return Err(Error::InternalErrorDbSearch(err)),
+            DbEnrollment::Unsuccessful => return Err(Error::InternalErrorDbSearchUnsuccessful),
+        }
+
+        let db_delete_res = self
+            .metrics
+            .observe_facetec_call(
+                "db_delete",
+                self.facetec.db_delete(ft::db_delete::Request {
+                    identifier: &identifier,
+                    group_name: &self.facetec_db_params.group_name,
+                }),
+            )
+            .await
+            .map_err(Error::InternalErrorDbDelete)?;
+
+        trace!(message = "Got FaceTec 3D-DB delete results", ?db_delete_res);
+
+        if !db_delete_res.success {
+            return Err(Error::InternalErrorDbDeleteUnsuccessful);
+        }
+
+        if let Some(store) = self.sequence_state.lock().await.store.as_mut() {
+            store
+                .record_enrollment_deletion(&identifier)
+                .await
+                .map_err(Error::InternalErrorDeletionPersistingFailed)?;
+        }
+
+        Ok(Response { identifier })
+    }
+}
@@ -0,0 +1,101 @@
+//! Admin lookup of the enrollment by public key operation.
+
+use facetec_api_client as ft;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    key_migration::{identifier_of, DbEnrollment},
+    Logic, LogicOp,
+};
+
+/// The request of the admin lookup operation.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// The public key of the validator to look up the enrollment of.
+    pub public_key: Vec<u8>,
+}
+
+/// The response of the admin lookup operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// The 3D-DB identifier of the person controlled by the public key.
+    /// Not set if the person enrolled with this public key has been migrated to another one.
+    pub identifier: Option<String>,
+    /// The hex-encoded public key the person enrolled with this public key has been
+    /// migrated to, if any.
+    pub migrated_to: Option<String>,
+    /// Whether the person is enrolled at the 3D-DB.
+    pub enrolled: bool,
+    /// The number of the auth tickets issued for the public key.
+    pub issued_tickets: usize,
+    /// The moment the last auth ticket was issued for the public key at, in UNIX milliseconds.
+    pub last_issued_at: Option<u64>,
+}
+
+/// Errors for the admin lookup operation.
+///
+/// Allow dead code to explicitly control errors data.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    /// The provided public key failed to load because it was invalid.
+    InvalidPublicKey,
+    /// Internal error at 3D-DB search due to the underlying request
+    /// error at the API level.
+    InternalErrorDbSearch(ft::Error),
+    /// Internal error at 3D-DB search due to unsuccessful response.
+    InternalErrorDbSearchUnsuccessful,
+}
+
+#[async_trait::async_trait]
+impl<S, PK> LogicOp<Request> for Logic<S, PK>
+where
+    S: Send + Sync + 'static,
+    PK: Send + Sync + for<'a> TryFrom<&'a [u8]> + AsRef<[u8]>,
+{
+    type Response = Response;
+    type Error = Error;
+
+    async fn call(&self, req: Request) -> Result<Self::Response, Self::Error> {
+        let public_key = PK::try_from(&req.public_key).map_err(|_| Error::InvalidPublicKey)?;
+        let public_key_hex = hex::encode(public_key);
+
+        let (identifier, migrated_to, issued_tickets, last_issued_at) = {
+            let sequence_state = self.sequence_state.lock().await;
+            let store = sequence_state.store.as_ref();
+            (
+                identifier_of(store, &public_key_hex),
+                store
+                    .and_then(|store| store.migrated_public_key(&public_key_hex))
+                    .map(ToOwned::to_owned),
+                store.map_or(0, |store| store.issued_tickets(&public_key_hex)),
+                store.and_then(|store| {
+                    store
+                        .issuance_history(&public_key_hex)
+                        .last()
+                        .map(|ticket| ticket.issued_at)
+                }),
+            )
+        };
+
+        let enrolled = match &identifier {
+            Some(identifier) => match self.db_enrollment(identifier).await {
+                DbEnrollment::Enrolled => true,
+                DbEnrollment::NotEnrolled => false,
+                DbEnrollment::Error(err) => return Err(Error::InternalErrorDbSearch(err)),
+                DbEnrollment::Unsuccessful => return Err(Error::InternalErrorDbSearchUnsuccessful),
+            },
+            None => false,
+        };
+
+        Ok(Response {
+            identifier,
+            migrated_to,
+            enrolled,
+            issued_tickets,
+            last_issued_at,
+        })
+    }
+}
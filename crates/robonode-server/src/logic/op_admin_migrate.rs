@@ -0,0 +1,145 @@
+//! Admin migration of the enrollment to a new public key operation.
+//!
+//! The person can't be enrolled at the 3D-DB under a new identifier without a fresh FaceScan,
+//! so the 3D-DB enrollment is kept intact, and the new public key the person authenticates
+//! with is recorded at the store.
+
+use facetec_api_client as ft;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    key_migration::{identifier_of, DbEnrollment},
+    Logic, LogicOp,
+};
+use crate::store;
+
+/// The request of the admin migrate operation.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// The public key of the validator currently controlling the enrollment.
+    pub public_key: Vec<u8>,
+    /// The public key of the validator to migrate the enrollment to.
+    pub new_public_key: Vec<u8>,
+}
+
+/// The response of the admin migrate operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// The 3D-DB identifier of the migrated person.
+    pub identifier: String,
+}
+
+/// Errors for the admin migrate operation.
+///
+/// Allow dead code to explicitly control errors data.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    /// The provided public key failed to load because it was invalid.
+    InvalidPublicKey,
+    /// The provided new public key failed to load because it was invalid.
+    InvalidNewPublicKey,
+    /// No person enrolled at the 3D-DB is controlled by the public key.
+    NotEnrolled,
+    /// The new public key is already used by some person.
+    NewPublicKeyAlreadyUsed,
+    /// Internal error at 3D-DB search due to the underlying request
+    /// error at the API level.
+    InternalErrorDbSearch(ft::Error),
+    /// Internal error at 3D-DB search due to unsuccessful response.
+    InternalErrorDbSearchUnsuccessful,
+    /// Internal error due to the state store not being configured, the migrations can't be kept
+    /// without it.
+    InternalErrorStoreUnavailable,
+    /// Internal error when persisting the migration.
+    InternalErrorMigrationPersistingFailed(store::Error),
+}
+
+impl<S, PK> Logic<S, PK> {
+    /// Check whether the given identifier is enrolled at the 3D-DB, for the migration.
+    async fn migration_db_enrollment(&self, identifier: &str) -> Result<bool, Error> {
+        match self.db_enrollment(identifier).await {
+            DbEnrollment::Enrolled => Ok(true),
+            DbEnrollment::NotEnrolled => Ok(false),
+            DbEnrollment::Error(err) => Err(Error::InternalErrorDbSearch(err)),
+            DbEnrollment::Unsuccessful => Err(Error::InternalErrorDbSearchUnsuccessful),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, PK> LogicOp<Request> for Logic<S, PK>
+where
+    S: Send + Sync + 'static,
+    PK: Send + Sync + for<'a> TryFrom<&'a [u8]> + AsRef<[u8]>,
+{
+    type Response = Response;
+    type Error = Error;
+
+    async fn call(&self, req: Request) -> Result<Self::Response, Self::Error> {
+        let public_key = PK::try_from(&req.public_key).map_err(|_| Error::InvalidPublicKey)?;
+        let public_key_hex = hex::encode(public_key);
+        let new_public_key =
+            PK::try_from(&req.new_public_key).map_err(|_| Error::InvalidNewPublicKey)?;
+        let new_public_key_hex = hex::encode(new_public_key);
+
+        if new_public_key_hex == public_key_hex {
+            return Err(Error::NewPublicKeyAlreadyUsed);
+        }
+
+        // Keep the new public key from being enrolled with concurrently, and serialize with
+        // the other admin operations, in the same order the enrollment takes the locks in.
+        let _enrollment_guard = self.enrollment_locks.lock(new_public_key_hex.clone()).await;
+        let _db_enrollment_guard = self
+            .metrics
+            .observe_db_enrollment_lock_wait("admin_migrate", self.db_enrollment_lock.lock())
+            .await;
+
+        let (identifier, new_public_key_identifier) = {
+            let sequence_state = self.sequence_state.lock().await;
+            let store = sequence_state
+                .store
+                .as_ref()
+                .ok_or(Error::InternalErrorStoreUnavailable)?;
+            (
+                identifier_of(Some(store), &public_key_hex),
+                identifier_of(Some(store), &new_public_key_hex),
+            )
+        };
+        let identifier = identifier.ok_or(Error::NotEnrolled)?;
+
+        if !self.migration_db_enrollment(&identifier).await? {
+            return Err(Error::NotEnrolled);
+        }
+
+        // Migrating the person back to the public key they enrolled with is fine, otherwise
+        // the new public key must not control any enrollment.
+        if new_public_key_hex != identifier {
+            let new_public_key_used = match new_public_key_identifier {
+                Some(new_public_key_identifier)
+                    if new_public_key_identifier == new_public_key_hex =>
+                {
+                    self.migration_db_enrollment(&new_public_key_hex).await?
+                }
+                _ => true,
+            };
+            if new_public_key_used {
+                return Err(Error::NewPublicKeyAlreadyUsed);
+            }
+        }
+
+        self.sequence_state
+            .lock()
+            .await
+            .store
+            .as_mut()
+            .ok_or(Error::InternalErrorStoreUnavailable)?
+            .record_key_migration(&identifier, &new_public_key_hex)
+            .await
+            .map_err(Error::InternalErrorMigrationPersistingFailed)?;
+
+        Ok(Response { identifier })
+    }
+}
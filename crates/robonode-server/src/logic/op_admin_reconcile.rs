@@ -0,0 +1,211 @@
+//! Admin reconciliation of the 3D-DB operation.
+//!
+//! Walks the 3D-DB group and the enrollments recorded at the store, and reports
+//! the discrepancies between them:
+//!
+//! - orphans - the group entries that must not be there: the temporary entries of
+//!   the authentications, and the persons that have been deleted;
+//! - half-finished enrollments - the enrollments that have been started, but not known to be
+//!   completed, e.g. due to the 3D-DB enrollment failing midway;
+//! - unrecorded entries - the group entries the store has no record of, e.g. the persons
+//!   enrolled before the enrollments were recorded; these are reported only;
+//! - temporary entries - the entries the authentications have enrolled the FaceScans under,
+//!   as recorded at the store; these are reported only, since the FaceTec Server keeps them
+//!   outside of the groups, and its API offers no way to delete them.
+//!
+//! With the cleanup requested, the orphans and the half-finished enrollments are deleted from
+//! the group, and the half-finished enrollments are recorded as deleted.
+
+use facetec_api_client as ft;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use super::{
+    common::{NO_GROUP_ERROR_MESSAGE, TMP_EXTERNAL_DATABASE_REF_ID_PREFIX},
+    Logic, LogicOp,
+};
+use crate::store::{self, EnrollmentStatus};
+
+/// The request of the admin reconcile operation.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    /// Whether to clean up the found orphans and half-finished enrollments.
+    #[serde(default)]
+    pub cleanup: bool,
+}
+
+/// The response of the admin reconcile operation.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// The 3D-DB group entries that must not be there.
+    pub orphans: Vec<String>,
+    /// The enrollments that have been started, but not known to be completed.
+    pub half_finished: Vec<HalfFinishedEnrollment>,
+    /// The 3D-DB group entries the store has no record of.
+    pub unrecorded: Vec<String>,
+    /// The temporary 3D-DB entries of the authentications.
+    pub tmp_entries: Vec<String>,
+    /// The 3D-DB identifiers that have been cleaned up.
+    pub cleaned_up: Vec<String>,
+}
+
+/// An enrollment that has been started, but not known to be completed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HalfFinishedEnrollment {
+    /// The 3D-DB identifier of the person.
+    pub identifier: String,
+    /// Whether the person is enrolled at the 3D-DB group.
+    pub in_group: bool,
+}
+
+/// Errors for the admin reconcile operation.
+///
+/// Allow dead code to explicitly control errors data.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    /// The enrollments are not recorded since the state store is not set up.
+    InternalErrorStoreUnavailable,
+    /// Internal error at 3D-DB list due to the underlying request
+    /// error at the API level.
+    InternalErrorDbList(ft::Error),
+    /// Internal error at 3D-DB list due to unsuccessful response.
+    InternalErrorDbListUnsuccessful,
+    /// Internal error at 3D-DB delete due to the underlying request
+    /// error at the API level.
+    InternalErrorDbDelete(ft::Error),
+    /// Internal error at 3D-DB delete due to unsuccessful response.
+    InternalErrorDbDeleteUnsuccessful,
+    /// Internal error when persisting the deletion.
+    InternalErrorDeletionPersistingFailed(store::Error),
+}
+
+#[async_trait::async_trait]
+impl<S, PK> LogicOp<Request> for Logic<S, PK>
+where
+    S: Send + Sync + 'static,
+    PK: Send + Sync,
+{
+    type Response = Response;
+    type Error = Error;
+
+    async fn call(&self, req: Request) -> Result<Self::Response, Self::Error> {
+        // Serialize with the enrollments and the other admin operations, so that no enrollment
+        // is in progress while we compare the 3D-DB with the store.
+        let _db_enrollment_guard = self
+            .metrics
+            .observe_db_enrollment_lock_wait("admin_reconcile", self.db_enrollment_lock.lock())
+            .await;
+
+        let list_res = self
+            .metrics
+            .observe_facetec_call(
+                "db_list",
+                self.facetec.db_list(ft::db_list::Request {
+                    group_name: &self.facetec_db_params.group_name,
+                }),
+            )
+            .await;
+
+        let group = match list_res {
+            Ok(list_res) if !list_res.success => {
+                return Err(Error::InternalErrorDbListUnsuccessful)
+            }
+            Ok(list_res) => list_res.identifiers,
+            // The group is created with the first enrollment.
+            Err(ft::Error::Server(ft::ServerError { error_message }))
+                if error_message.starts_with(NO_GROUP_ERROR_MESSAGE) =>
+            {
+                vec![]
+            }
+            Err(err) => return Err(Error::InternalErrorDbList(err)),
+        };
+
+        trace!(message = "Got FaceTec 3D-DB group", entries = group.len());
+
+        let mut res = Response::default();
+        {
+            let sequence_state = self.sequence_state.lock().await;
+            let store = sequence_state
+                .store
+                .as_ref()
+                .ok_or(Error::InternalErrorStoreUnavailable)?;
+
+            for identifier in &group {
+                if identifier.starts_with(TMP_EXTERNAL_DATABASE_REF_ID_PREFIX) {
+                    res.orphans.push(identifier.clone());
+                    continue;
+                }
+                match store.enrollment_status(identifier) {
+                    // The half-finished enrollments are reported below, along with the ones
+                    // missing from the group.
+                    Some(EnrollmentStatus::Completed | EnrollmentStatus::Started) => {}
+                    Some(EnrollmentStatus::Deleted) => res.orphans.push(identifier.clone()),
+                    None => res.unrecorded.push(identifier.clone()),
+                }
+            }
+
+            res.half_finished = store
+                .enrollments()
+                .filter(|(_, status)| *status == EnrollmentStatus::Started)
+                .map(|(identifier, _)| HalfFinishedEnrollment {
+                    identifier: identifier.to_owned(),
+                    in_group: group.iter().any(|entry| entry == identifier),
+                })
+                .collect();
+
+            res.tmp_entries = store.tmp_identifiers().map(str::to_owned).collect();
+        }
+
+        if !req.cleanup {
+            return Ok(res);
+        }
+
+        for identifier in &res.orphans {
+            self.delete_from_group(identifier).await?;
+            res.cleaned_up.push(identifier.clone());
+        }
+
+        for enrollment in &res.half_finished {
+            if enrollment.in_group {
+                self.delete_from_group(&enrollment.identifier).await?;
+            }
+            if let Some(store) = self.sequence_state.lock().await.store.as_mut() {
+                store
+                    .record_enrollment_deletion(&enrollment.identifier)
+                    .await
+                    .map_err(Error::InternalErrorDeletionPersistingFailed)?;
+            }
+            res.cleaned_up.push(enrollment.identifier.clone());
+        }
+
+        Ok(res)
+    }
+}
+
+impl<S, PK> Logic<S, PK> {
+    /// Delete the given identifier from the 3D-DB group.
+    async fn delete_from_group(&self, identifier: &str) -> Result<(), Error> {
+        let db_delete_res = self
+            .metrics
+            .observe_facetec_call(
+                "db_delete",
+                self.facetec.db_delete(ft::db_delete::Request {
+                    identifier,
+                    group_name: &self.facetec_db_params.group_name,
+                }),
+            )
+            .await
+            .map_err(Error::InternalErrorDbDelete)?;
+
+        trace!(message = "Got FaceTec 3D-DB delete results", ?db_delete_res);
+
+        if !db_delete_res.success {
+            return Err(Error::InternalErrorDbDeleteUnsuccessful);
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,23 @@
 //! Authenticate operation.
 
 use facetec_api_client as ft;
-use primitives_auth_ticket::{AuthTicket, OpaqueAuthTicket};
+use primitives_auth_ticket::{AuthTicket, LegacyAuthTicket, OpaqueAuthTicket};
 use primitives_liveness_data::{LivenessData, OpaqueLivenessData};
+use robonode_crypto::Verifier as _;
 use serde::{Deserialize, Serialize};
 use tracing::{error, trace};
 
-use super::{common::*, Logic, LogicOp, ScanResultBlob, Signer, Verifier};
-use crate::logic::facetec_utils::{db_search_result_adapter, DbSearchResult};
+use super::{Logic, LogicOp, ScanResultBlob, Signer, Verifier};
+use crate::{
+    logic::{
+        common::{
+            current_unix_milliseconds, CO_SIGN_MAX_CLOCK_DRIFT, TMP_EXTERNAL_DATABASE_REF_ID_PREFIX,
+        },
+        facetec_utils::{db_search_result_adapter, DbSearchResult},
+        key_migration,
+    },
+    store,
+};
 
 /// The request of the authenticate operation.
 #[derive(Debug, Deserialize, Serialize)]
@@ -18,6 +28,13 @@ pub struct Request {
     /// The signature of the liveness data with the private key of the node.
     /// Proves the possession of the private key by the liveness data bearer.
     pub liveness_data_signature: Vec<u8>,
+    /// The auth ticket issued by another robonode to co-sign instead of issuing a new one.
+    /// Lets the auth ticket collect the signatures of multiple robonodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_ticket: Option<OpaqueAuthTicket>,
+    /// The signature of the auth ticket to co-sign by the robonode that has issued it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_ticket_signature: Option<Vec<u8>>,
 }
 
 /// The response of the authenticate operation.
@@ -55,6 +72,10 @@ pub enum Error {
     /// This means that the user might've provided a signature using different
     /// keypair from what was used for the original enrollment.
     SignatureInvalid(ScanResultBlob),
+    /// The auth ticket to co-sign could not be decoded, was not signed by a trusted robonode,
+    /// was not issued recently, or was issued to a public key other than the one that matched
+    /// with the provided FaceScan.
+    AuthTicketMismatch(ScanResultBlob),
     /// Internal error at server-level enrollment due to the underlying request
     /// error at the API level.
     InternalErrorEnrollment(ft::Error),
@@ -78,6 +99,10 @@ pub enum Error {
     InternalErrorSignatureVerificationFailed(ScanResultBlob),
     /// Internal error when signing auth ticket.
     InternalErrorAuthTicketSigningFailed(ScanResultBlob),
+    /// Internal error when persisting the sequence value.
+    InternalErrorSequencePersistingFailed(store::Error),
+    /// Internal error when persisting the issued auth ticket.
+    InternalErrorAuthTicketPersistingFailed(store::Error, ScanResultBlob),
 }
 
 #[async_trait::async_trait]
@@ -93,24 +118,41 @@ where
         let liveness_data =
             LivenessData::try_from(&req.liveness_data).map_err(Error::InvalidLivenessData)?;
 
-        let mut unlocked = self.locked.lock().await;
-
-        // Bump the sequence counter.
-        unlocked.sequence.inc();
-        let sequence_value = unlocked.sequence.get();
+        let (sequence_value, tmp_external_database_ref_id) = {
+            let mut sequence_state = self.sequence_state.lock().await;
+
+            // Bump the sequence counter.
+            sequence_state.sequence.inc();
+            let sequence_value = sequence_state.sequence.get();
+
+            // Prepare the ID to be used for this temporary FaceScan.
+            let tmp_external_database_ref_id =
+                make_tmp_external_database_ref_id(self.execution_id, sequence_value);
+
+            // Persist the sequence value and the temporary FaceScan ID before using them, so that
+            // the sequence value is never reused after a restart, and the temporary FaceScan can
+            // be reconciled later.
+            if let Some(store) = sequence_state.store.as_mut() {
+                store
+                    .record_authentication_sequence(sequence_value, &tmp_external_database_ref_id)
+                    .await
+                    .map_err(Error::InternalErrorSequencePersistingFailed)?;
+            }
 
-        // Prepare the ID to be used for this temporary FaceScan.
-        let tmp_external_database_ref_id =
-            make_tmp_external_database_ref_id(unlocked.execution_id, sequence_value);
+            (sequence_value, tmp_external_database_ref_id)
+        };
 
-        let enroll_res = unlocked
-            .facetec
-            .enrollment_3d(ft::enrollment3d::Request {
-                external_database_ref_id: &tmp_external_database_ref_id,
-                face_scan: &liveness_data.face_scan,
-                audit_trail_image: &liveness_data.audit_trail_image,
-                low_quality_audit_trail_image: &liveness_data.low_quality_audit_trail_image,
-            })
+        let enroll_res = self
+            .metrics
+            .observe_facetec_call(
+                "enrollment_3d",
+                self.facetec.enrollment_3d(ft::enrollment3d::Request {
+                    external_database_ref_id: &tmp_external_database_ref_id,
+                    face_scan: &liveness_data.face_scan,
+                    audit_trail_image: &liveness_data.audit_trail_image,
+                    low_quality_audit_trail_image: &liveness_data.low_quality_audit_trail_image,
+                }),
+            )
             .await
             .map_err(Error::InternalErrorEnrollment)?;
 
@@ -139,13 +181,16 @@ where
             scan_result_blob, ..
         } = enroll_res;
 
-        let search_result = unlocked
-            .facetec
-            .db_search(ft::db_search::Request {
-                external_database_ref_id: &tmp_external_database_ref_id,
-                group_name: DB_GROUP_NAME,
-                min_match_level: MATCH_LEVEL,
-            })
+        let search_result = self
+            .metrics
+            .observe_facetec_call(
+                "db_search",
+                self.facetec.db_search(ft::db_search::Request {
+                    external_database_ref_id: &tmp_external_database_ref_id,
+                    group_name: &self.facetec_db_params.group_name,
+                    min_match_level: self.facetec_db_params.match_level,
+                }),
+            )
             .await;
 
         let results = match db_search_result_adapter(search_result) {
@@ -171,13 +216,19 @@ where
             return Err(Error::PersonNotFound(scan_result_blob));
         };
 
-        if found.match_level < MATCH_LEVEL {
+        if found.match_level < self.facetec_db_params.match_level {
             return Err(Error::InternalErrorDbSearchMatchLevelMismatch(
                 scan_result_blob,
             ));
         }
 
-        let public_key_bytes = match hex::decode(&found.identifier) {
+        // The person might have been migrated to a new public key since the enrollment.
+        let public_key_hex = {
+            let sequence_state = self.sequence_state.lock().await;
+            key_migration::public_key_of(sequence_state.store.as_ref(), &found.identifier)
+        };
+
+        let public_key_bytes = match hex::decode(public_key_hex) {
             Ok(public_key_bytes) => public_key_bytes,
             Err(_) => return Err(Error::InternalErrorInvalidPublicKeyHex(scan_result_blob)),
         };
@@ -203,22 +254,45 @@ where
             return Err(Error::SignatureInvalid(scan_result_blob));
         }
 
-        // Prepare an authentication nonce from the sequence number.
-        let authentication_nonce = make_authentication_nonce(unlocked.execution_id, sequence_value);
-
-        // Prepare the raw auth ticket.
-        let auth_ticket = AuthTicket {
-            public_key: public_key.into(),
-            authentication_nonce,
+        let (auth_ticket, opaque_auth_ticket) = match req.auth_ticket {
+            // Co-sign the auth ticket issued by a trusted robonode, as long as it is issued to
+            // the same person; the nonce is enforced by the chain.
+            Some(opaque_auth_ticket) => match check_auth_ticket_to_co_sign(
+                &self.trusted_robonode_public_keys,
+                &opaque_auth_ticket,
+                req.auth_ticket_signature.as_deref(),
+                &public_key_bytes,
+                current_unix_milliseconds(),
+            ) {
+                Some(auth_ticket) => (auth_ticket, opaque_auth_ticket),
+                None => return Err(Error::AuthTicketMismatch(scan_result_blob)),
+            },
+            None => {
+                // Prepare an authentication nonce from the sequence number.
+                let authentication_nonce =
+                    make_authentication_nonce(self.execution_id, sequence_value);
+
+                // Prepare the raw auth ticket.
+                let auth_ticket = AuthTicket {
+                    public_key: public_key.into(),
+                    authentication_nonce,
+                    issued_at: current_unix_milliseconds(),
+                };
+
+                // Prepare an opaque auth ticket, get ready for signing.
+                let opaque_auth_ticket = if self.legacy_auth_tickets {
+                    OpaqueAuthTicket::from(&LegacyAuthTicket::from(&auth_ticket))
+                } else {
+                    OpaqueAuthTicket::from(&auth_ticket)
+                };
+
+                (auth_ticket, opaque_auth_ticket)
+            }
         };
 
-        // Prepare an opaque auth ticket, get ready for signing.
-        #[allow(clippy::needless_borrow)]
-        let opaque_auth_ticket = (&auth_ticket).into();
-
         // Sign the auth ticket with our private key, so that later on it's possible to validate
         // this ticket was issues by us.
-        let auth_ticket_signature = match unlocked.signer.sign(&opaque_auth_ticket).await {
+        let auth_ticket_signature = match self.signer.sign(&opaque_auth_ticket).await {
             Ok(auth_ticket_signature) => auth_ticket_signature,
             Err(_) => {
                 return Err(Error::InternalErrorAuthTicketSigningFailed(
@@ -227,6 +301,27 @@ where
             }
         };
 
+        // Persist the issued auth ticket before handing it out, so that there is a record of it.
+        let persist_res = match self.sequence_state.lock().await.store.as_mut() {
+            Some(store) => {
+                store
+                    .record_issued_ticket(store::IssuedTicket {
+                        sequence_value,
+                        public_key: hex::encode(&auth_ticket.public_key),
+                        authentication_nonce: hex::encode(&auth_ticket.authentication_nonce),
+                        issued_at: auth_ticket.issued_at,
+                    })
+                    .await
+            }
+            None => Ok(()),
+        };
+        if let Err(err) = persist_res {
+            return Err(Error::InternalErrorAuthTicketPersistingFailed(
+                err,
+                scan_result_blob,
+            ));
+        }
+
         Ok(Response {
             auth_ticket: opaque_auth_ticket,
             auth_ticket_signature,
@@ -235,9 +330,43 @@ where
     }
 }
 
+/// Check the auth ticket to co-sign: it must be signed by one of the trusted robonodes, issued
+/// within the accepted clock drift from the current moment, and issued to the given public key.
+///
+/// Returns the decoded auth ticket if it can be co-signed.
+fn check_auth_ticket_to_co_sign(
+    trusted_robonode_public_keys: &[robonode_crypto::PublicKey],
+    opaque_auth_ticket: &OpaqueAuthTicket,
+    auth_ticket_signature: Option<&[u8]>,
+    public_key: &[u8],
+    current_moment: u64,
+) -> Option<AuthTicket> {
+    let auth_ticket_signature =
+        robonode_crypto::Signature::from_slice(auth_ticket_signature?).ok()?;
+    let is_trusted = trusted_robonode_public_keys
+        .iter()
+        .any(|robonode_public_key| {
+            robonode_public_key
+                .verify(opaque_auth_ticket.as_ref(), &auth_ticket_signature)
+                .is_ok()
+        });
+    if !is_trusted {
+        return None;
+    }
+
+    let auth_ticket = AuthTicket::try_from(opaque_auth_ticket).ok()?;
+    if auth_ticket.public_key != public_key
+        || auth_ticket.issued_at.abs_diff(current_moment) > CO_SIGN_MAX_CLOCK_DRIFT
+    {
+        return None;
+    }
+
+    Some(auth_ticket)
+}
+
 /// Make an key to store the temporary scan at.
 fn make_tmp_external_database_ref_id(execution_id: uuid::Uuid, sequence_value: u64) -> String {
-    format!("tmp-{execution_id}-{sequence_value}")
+    format!("{TMP_EXTERNAL_DATABASE_REF_ID_PREFIX}{execution_id}-{sequence_value}")
 }
 
 /// Make an authentication nonce.
@@ -1,12 +1,19 @@
 //! Enroll operation.
 
 use facetec_api_client as ft;
+use primitives_enrollment_receipt::{EnrollmentReceipt, OpaqueEnrollmentReceipt};
 use primitives_liveness_data::{LivenessData, OpaqueLivenessData};
 use serde::{Deserialize, Serialize};
 use tracing::{error, trace};
 
-use super::{common::*, Logic, LogicOp, ScanResultBlob, Signer, Verifier};
-use crate::logic::facetec_utils::{db_search_result_adapter, DbSearchResult};
+use super::{
+    common::{current_unix_milliseconds, EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE},
+    Logic, LogicOp, PublicKeyProvider, ScanResultBlob, Signer, Verifier,
+};
+use crate::{
+    logic::facetec_utils::{db_search_result_adapter, DbSearchResult},
+    store,
+};
 
 /// The request for the enroll operation.
 #[derive(Debug, Deserialize, Serialize)]
@@ -25,6 +32,11 @@ pub struct Request {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
+    /// An opaque enrollment receipt, the evidence of this enrollment.
+    pub enrollment_receipt: OpaqueEnrollmentReceipt,
+    /// The signature of the enrollment receipt, signed with the robonode's private key.
+    /// Can be verified with the robonode's public key.
+    pub enrollment_receipt_signature: Vec<u8>,
     /// Scan result blob.
     pub scan_result_blob: ScanResultBlob,
 }
@@ -67,12 +79,16 @@ pub enum Error {
     InternalErrorDbEnrollUnsuccessful(ScanResultBlob),
     /// Internal error at signature verification.
     InternalErrorSignatureVerificationFailed,
+    /// Internal error when signing enrollment receipt.
+    InternalErrorEnrollmentReceiptSigningFailed(ScanResultBlob),
+    /// Internal error when persisting the start of the 3D-DB enrollment.
+    InternalErrorEnrollmentPersistingFailed(store::Error, ScanResultBlob),
 }
 
 #[async_trait::async_trait]
 impl<S, PK> LogicOp<Request> for Logic<S, PK>
 where
-    S: Signer<Vec<u8>> + Send + 'static,
+    S: Signer<Vec<u8>> + PublicKeyProvider + Send + Sync + 'static,
     PK: Send + Sync + for<'a> TryFrom<&'a [u8]> + AsRef<[u8]> + Verifier<Vec<u8>>,
 {
     type Response = Response;
@@ -94,17 +110,30 @@ where
             return Err(Error::SignatureInvalid);
         }
 
-        let public_key_hex = hex::encode(public_key);
-
-        let unlocked = self.locked.lock().await;
-        let enroll_res = unlocked
-            .facetec
-            .enrollment_3d(ft::enrollment3d::Request {
-                external_database_ref_id: &public_key_hex,
-                face_scan: &liveness_data.face_scan,
-                audit_trail_image: &liveness_data.audit_trail_image,
-                low_quality_audit_trail_image: &liveness_data.low_quality_audit_trail_image,
-            })
+        let public_key_hex = hex::encode(&public_key);
+
+        // Serialize the enrollments with the same public key.
+        let _enrollment_guard = self.enrollment_locks.lock(public_key_hex.clone()).await;
+
+        // The public key some person has been migrated to is in use, even though nobody has
+        // enrolled with it.
+        if let Some(store) = self.sequence_state.lock().await.store.as_ref() {
+            if store.migrated_identifier(&public_key_hex).is_some() {
+                return Err(Error::PublicKeyAlreadyUsed);
+            }
+        }
+
+        let enroll_res = self
+            .metrics
+            .observe_facetec_call(
+                "enrollment_3d",
+                self.facetec.enrollment_3d(ft::enrollment3d::Request {
+                    external_database_ref_id: &public_key_hex,
+                    face_scan: &liveness_data.face_scan,
+                    audit_trail_image: &liveness_data.audit_trail_image,
+                    low_quality_audit_trail_image: &liveness_data.low_quality_audit_trail_image,
+                }),
+            )
             .await
             .map_err(|err| match err {
                 ft::Error::Server(server_error)
@@ -139,13 +168,25 @@ where
             scan_result_blob, ..
         } = enroll_res;
 
-        let search_result = unlocked
-            .facetec
-            .db_search(ft::db_search::Request {
-                external_database_ref_id: &public_key_hex,
-                group_name: DB_GROUP_NAME,
-                min_match_level: MATCH_LEVEL,
-            })
+        // Conduct the 3D-DB duplicates search and enrollment under the lock, so that concurrent
+        // enrollments of the same person can't both pass the search before either is enrolled.
+        // The per-public-key lock is not enough here, as the concurrent enrollments of the same
+        // person would come with different public keys.
+        let _db_enrollment_guard = self
+            .metrics
+            .observe_db_enrollment_lock_wait("enroll", self.db_enrollment_lock.lock())
+            .await;
+
+        let search_result = self
+            .metrics
+            .observe_facetec_call(
+                "db_search",
+                self.facetec.db_search(ft::db_search::Request {
+                    external_database_ref_id: &public_key_hex,
+                    group_name: &self.facetec_db_params.group_name,
+                    min_match_level: self.facetec_db_params.match_level,
+                }),
+            )
             .await;
 
         let results = match db_search_result_adapter(search_result) {
@@ -171,12 +212,44 @@ where
             return Err(Error::PersonAlreadyEnrolled(scan_result_blob));
         }
 
-        let db_enroll_res = match unlocked
-            .facetec
-            .db_enroll(ft::db_enroll::Request {
-                external_database_ref_id: &public_key_hex,
-                group_name: DB_GROUP_NAME,
-            })
+        // Sign the receipt before committing the enrollment, so that the enrollment never ends up
+        // conducted without the receipt issued for it.
+        let enrollment_receipt = EnrollmentReceipt {
+            public_key: public_key.as_ref().to_vec(),
+            enrolled_at: current_unix_milliseconds(),
+            robonode_key_id: self.signer.public_key().to_vec(),
+        };
+        let opaque_enrollment_receipt = OpaqueEnrollmentReceipt::from(&enrollment_receipt);
+        let enrollment_receipt_signature = match self.signer.sign(&opaque_enrollment_receipt).await
+        {
+            Ok(enrollment_receipt_signature) => enrollment_receipt_signature,
+            Err(_) => {
+                return Err(Error::InternalErrorEnrollmentReceiptSigningFailed(
+                    scan_result_blob,
+                ))
+            }
+        };
+
+        // Record the enrollment before conducting it, so that an enrollment interrupted midway
+        // can be found and reconciled later.
+        if let Some(store) = self.sequence_state.lock().await.store.as_mut() {
+            if let Err(err) = store.record_enrollment_start(&public_key_hex).await {
+                return Err(Error::InternalErrorEnrollmentPersistingFailed(
+                    err,
+                    scan_result_blob,
+                ));
+            }
+        }
+
+        let db_enroll_res = match self
+            .metrics
+            .observe_facetec_call(
+                "db_enroll",
+                self.facetec.db_enroll(ft::db_enroll::Request {
+                    external_database_ref_id: &public_key_hex,
+                    group_name: &self.facetec_db_params.group_name,
+                }),
+            )
             .await
         {
             Ok(db_enroll_res) => db_enroll_res,
@@ -189,6 +262,22 @@ where
             return Err(Error::InternalErrorDbEnrollUnsuccessful(scan_result_blob));
         }
 
-        Ok(Response { scan_result_blob })
+        // The enrollment has been conducted already, so failing to record its completion must
+        // not fail it; the reconciliation will report it as half-finished instead.
+        if let Some(store) = self.sequence_state.lock().await.store.as_mut() {
+            if let Err(err) = store.record_enrollment_completion(&public_key_hex).await {
+                error!(
+                    message = "Unable to record the enrollment completion",
+                    ?err,
+                    identifier = %public_key_hex
+                );
+            }
+        }
+
+        Ok(Response {
+            enrollment_receipt: opaque_enrollment_receipt,
+            enrollment_receipt_signature,
+            scan_result_blob,
+        })
     }
 }
@@ -29,8 +29,8 @@ pub enum Error {}
 #[async_trait::async_trait]
 impl<S, PK> LogicOp<Request> for Logic<S, PK>
 where
-    S: Signer<Vec<u8>> + Send + 'static,
-    PK: Send + for<'a> TryFrom<&'a [u8]>,
+    S: Signer<Vec<u8>> + Send + Sync + 'static,
+    PK: Send + Sync + for<'a> TryFrom<&'a [u8]>,
 {
     type Response = Response;
     type Error = Error;
@@ -34,18 +34,16 @@ pub enum Error {
 #[async_trait::async_trait]
 impl<S, PK> LogicOp<Request> for Logic<S, PK>
 where
-    S: Signer<Vec<u8>> + Send + 'static,
-    PK: Send + for<'a> TryFrom<&'a [u8]>,
+    S: Signer<Vec<u8>> + Send + Sync + 'static,
+    PK: Send + Sync + for<'a> TryFrom<&'a [u8]>,
 {
     type Response = Response;
     type Error = Error;
 
     async fn call(&self, _req: Request) -> Result<Self::Response, Self::Error> {
-        let unlocked = self.locked.lock().await;
-
-        let res = unlocked
-            .facetec
-            .session_token()
+        let res = self
+            .metrics
+            .observe_facetec_call("session_token", self.facetec.session_token())
             .await
             .map_err(Error::InternalErrorSessionToken)?;
 
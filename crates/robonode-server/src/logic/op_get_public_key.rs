@@ -24,15 +24,14 @@ pub enum Error {}
 #[async_trait::async_trait]
 impl<S, PK> LogicOp<Request> for Logic<S, PK>
 where
-    S: PublicKeyProvider + Send + 'static,
-    PK: Send,
+    S: PublicKeyProvider + Send + Sync + 'static,
+    PK: Send + Sync,
 {
     type Response = Response;
     type Error = Error;
 
     async fn call(&self, _req: Request) -> Result<Self::Response, Self::Error> {
-        let unlocked = self.locked.lock().await;
-        let public_key = unlocked.signer.public_key().to_vec();
+        let public_key = self.signer.public_key().to_vec();
         Ok(Response { public_key })
     }
 }
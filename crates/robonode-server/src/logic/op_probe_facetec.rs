@@ -0,0 +1,61 @@
+//! Probe FaceTec operation.
+
+use std::time::Duration;
+
+use facetec_api_client as ft;
+
+use super::{Logic, LogicOp};
+
+/// The time to wait for the FaceTec Server to respond to the probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The request of the probe facetec operation.
+#[derive(Debug)]
+pub struct Request;
+
+/// The response of the probe facetec operation, the FaceTec Server is ready to serve
+/// the requests.
+#[derive(Debug)]
+pub struct Response;
+
+/// Errors for the probe facetec operation.
+///
+/// Allow dead code to explicitly control errors data.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Error {
+    /// The FaceTec Server could not be reached, or its response could not be understood.
+    Unreachable(ft::Error),
+    /// The FaceTec Server did not respond in time.
+    Timeout,
+    /// The FaceTec Server is reachable, but is unable to serve the requests.
+    NotReady,
+}
+
+#[async_trait::async_trait]
+impl<S, PK> LogicOp<Request> for Logic<S, PK>
+where
+    S: Send + Sync + 'static,
+    PK: Send + Sync,
+{
+    type Response = Response;
+    type Error = Error;
+
+    async fn call(&self, _req: Request) -> Result<Self::Response, Self::Error> {
+        // Obtaining a session token is the cheapest call that involves the FaceTec Server
+        // being fully operational.
+        let res = tokio::time::timeout(
+            PROBE_TIMEOUT,
+            self.metrics
+                .observe_facetec_call("session_token", self.facetec.session_token()),
+        )
+        .await
+        .map_err(|_| Error::Timeout)?;
+
+        match res {
+            Ok(res) if res.success => Ok(Response),
+            Ok(_) | Err(ft::Error::Server(_)) => Err(Error::NotReady),
+            Err(err) => Err(Error::Unreachable(err)),
+        }
+    }
+}
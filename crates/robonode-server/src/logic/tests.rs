@@ -1,12 +1,23 @@
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
 
 use facetec_api_client as ft;
+use primitives_enrollment_receipt::EnrollmentReceipt;
 use primitives_liveness_data::{LivenessData, OpaqueLivenessData};
-use tokio::sync::{Mutex, MutexGuard};
+use tokio::sync::{Barrier, Mutex, MutexGuard, Notify};
 use tracing::{info, trace};
+use warp::Filter;
 
-use super::{Locked, Logic, LogicOp};
-use crate::{logic::common::DB_GROUP_NAME, sequence::Sequence};
+use super::{Logic, LogicOp, SequenceState};
+use crate::{
+    keyed_lock::KeyedLock,
+    logic::common::{
+        current_unix_milliseconds, CO_SIGN_MAX_CLOCK_DRIFT, DB_GROUP_NAME,
+        EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE, NO_ENTRY_ERROR_MESSAGE,
+    },
+    metrics::Metrics,
+    sequence::Sequence,
+    store::Store,
+};
 
 struct TestSigner;
 
@@ -22,6 +33,12 @@ impl super::Signer<Vec<u8>> for TestSigner {
     }
 }
 
+impl super::PublicKeyProvider for TestSigner {
+    fn public_key(&self) -> &[u8] {
+        b"dummy robonode public key"
+    }
+}
+
 struct TestValidatorPublicKey(Vec<u8>);
 
 #[async_trait::async_trait]
@@ -115,6 +132,33 @@ fn public_keys_to_cleanup() -> Vec<&'static [u8]> {
     vec![TEST_PUBLIC_KEY, b"a", b"b"]
 }
 
+/// Make the logic with the test signer and public key.
+fn make_logic(
+    facetec: ft::Client<crate::LoggingInspector>,
+) -> Logic<TestSigner, TestValidatorPublicKey> {
+    Logic {
+        sequence_state: Mutex::new(SequenceState {
+            sequence: Sequence::new(0),
+            store: None,
+        }),
+        execution_id: uuid::Uuid::new_v4(),
+        facetec,
+        signer: TestSigner,
+        public_key_type: PhantomData::<TestValidatorPublicKey>,
+        enrollment_locks: KeyedLock::new(),
+        db_enrollment_lock: Mutex::new(()),
+        facetec_device_sdk_params: crate::FacetecDeviceSdkParams {
+            device_key_identifier: "device_key_identifier".to_owned(),
+            public_face_map_encryption_key: "public_face_map_encryption_key".to_owned(),
+            production_key: None,
+        },
+        facetec_db_params: Default::default(),
+        metrics: Arc::new(Metrics::new().unwrap()),
+        legacy_auth_tickets: false,
+        trusted_robonode_public_keys: Vec::new(),
+    }
+}
+
 async fn setup() -> (
     MutexGuard<'static, ()>,
     TestParams,
@@ -130,6 +174,7 @@ async fn setup() -> (
         device_key_identifier: test_params.facetec_device_key_identifier.clone(),
         injected_ip_address: Some(test_params.facetec_injected_ip_address.clone()),
         response_body_error_inspector: crate::LoggingInspector,
+        policy: Default::default(),
     };
 
     let res = facetec
@@ -152,21 +197,7 @@ async fn setup() -> (
         trace!(message = "3D DB cleanup at the facetec server", ?res);
     }
 
-    let locked = Locked {
-        sequence: Sequence::new(0),
-        execution_id: uuid::Uuid::new_v4(),
-        facetec,
-        signer: TestSigner,
-        public_key_type: PhantomData::<TestValidatorPublicKey>,
-    };
-    let logic = Logic {
-        locked: Mutex::new(locked),
-        facetec_device_sdk_params: crate::FacetecDeviceSdkParams {
-            device_key_identifier: "device_key_identifier".to_owned(),
-            public_face_map_encryption_key: "public_face_map_encryption_key".to_owned(),
-            production_key: None,
-        },
-    };
+    let logic = make_logic(facetec);
 
     (guard, test_params, logic)
 }
@@ -197,6 +228,8 @@ async fn first_authenticate() {
         .call(super::op_authenticate::Request {
             liveness_data: test_params.authenticate_liveness_data,
             liveness_data_signature: b"qwe".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         })
         .await
         .unwrap_err();
@@ -228,6 +261,8 @@ async fn enroll_authenticate() {
         .call(super::op_authenticate::Request {
             liveness_data: test_params.authenticate_liveness_data,
             liveness_data_signature: b"qwe".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
         })
         .await
         .unwrap();
@@ -262,3 +297,818 @@ async fn double_enroll() {
         super::op_enroll::Error::PersonAlreadyEnrolled(_)
     ));
 }
+
+/// The state of the fake FaceTec server.
+#[derive(Default)]
+struct FakeFacetecState {
+    /// The FaceScans enrolled via `/enrollment-3d`, by the external database ref ID.
+    enrollments: HashMap<String, String>,
+    /// The external database ref IDs enrolled into the 3D-DB.
+    db: Vec<String>,
+    /// The error message the `/3d-db/search` requests fail with, if set.
+    db_search_error: Option<String>,
+}
+
+/// A minimal in-process fake of the FaceTec Server, good enough to exercise the concurrency
+/// of the logic.
+///
+/// The persons are identified by the FaceScan value.
+struct FakeFacetec {
+    /// The server state.
+    state: Arc<std::sync::Mutex<FakeFacetecState>>,
+    /// Notified every time the `/enrollment-3d` request arrives.
+    enrollment_3d_arrived: Arc<Notify>,
+    /// The base URL of the server.
+    base_url: String,
+}
+
+impl FakeFacetec {
+    /// Start the server.
+    ///
+    /// If the barrier is provided, the `/enrollment-3d` requests wait at it before proceeding.
+    fn start(enrollment_3d_barrier: Option<Arc<Barrier>>) -> Self {
+        let state = Arc::new(std::sync::Mutex::new(FakeFacetecState::default()));
+        let enrollment_3d_arrived = Arc::new(Notify::new());
+
+        let enrollment_3d = warp::post()
+            .and(warp::path!("enrollment-3d"))
+            .and(warp::body::json())
+            .then({
+                let state = Arc::clone(&state);
+                let enrollment_3d_arrived = Arc::clone(&enrollment_3d_arrived);
+                move |req: serde_json::Value| {
+                    let state = Arc::clone(&state);
+                    let enrollment_3d_arrived = Arc::clone(&enrollment_3d_arrived);
+                    let enrollment_3d_barrier = enrollment_3d_barrier.clone();
+                    async move {
+                        enrollment_3d_arrived.notify_one();
+                        if let Some(barrier) = enrollment_3d_barrier {
+                            barrier.wait().await;
+                        }
+
+                        let id = req["externalDatabaseRefID"].as_str().unwrap().to_owned();
+                        let face_scan = req["faceScan"].as_str().unwrap().to_owned();
+
+                        let mut state = state.lock().unwrap();
+                        if state.enrollments.contains_key(&id) {
+                            return warp::reply::json(&serde_json::json!({
+                                "error": true,
+                                "errorMessage": EXTERNAL_DATABASE_REF_ID_ALREADY_IN_USE_ERROR_MESSAGE,
+                                "success": false,
+                            }));
+                        }
+                        state.enrollments.insert(id.clone(), face_scan);
+
+                        warp::reply::json(&serde_json::json!({
+                            "error": false,
+                            "externalDatabaseRefID": id,
+                            "faceScanSecurityChecks": {
+                                "auditTrailVerificationCheckSucceeded": true,
+                                "faceScanLivenessCheckSucceeded": true,
+                                "replayCheckSucceeded": true,
+                                "sessionTokenCheckSucceeded": true,
+                            },
+                            "scanResultBlob": "scan result blob",
+                            "success": true,
+                        }))
+                    }
+                }
+            });
+
+        let db_search = warp::post()
+            .and(warp::path!("3d-db" / "search"))
+            .and(warp::body::json())
+            .then({
+                let state = Arc::clone(&state);
+                move |req: serde_json::Value| {
+                    let state = Arc::clone(&state);
+                    async move {
+                        // Widen the window for the concurrent requests to interleave.
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+
+                        let id = req["externalDatabaseRefID"].as_str().unwrap();
+
+                        let state = state.lock().unwrap();
+                        if let Some(error_message) = &state.db_search_error {
+                            return warp::reply::json(&serde_json::json!({
+                                "error": true,
+                                "errorMessage": error_message,
+                                "success": false,
+                            }));
+                        }
+                        let Some(face_scan) = state.enrollments.get(id) else {
+                            return warp::reply::json(&serde_json::json!({
+                                "error": true,
+                                "errorMessage": NO_ENTRY_ERROR_MESSAGE,
+                                "success": false,
+                            }));
+                        };
+                        let results = state
+                            .db
+                            .iter()
+                            .filter(|enrolled_id| {
+                                state.enrollments.get(*enrolled_id) == Some(face_scan)
+                            })
+                            .map(|enrolled_id| {
+                                serde_json::json!({
+                                    "identifier": enrolled_id,
+                                    "matchLevel": 10,
+                                })
+                            })
+                            .collect::<Vec<_>>();
+
+                        warp::reply::json(&serde_json::json!({
+                            "error": false,
+                            "results": results,
+                            "success": true,
+                        }))
+                    }
+                }
+            });
+
+        let db_enroll = warp::post()
+            .and(warp::path!("3d-db" / "enroll"))
+            .and(warp::body::json())
+            .then({
+                let state = Arc::clone(&state);
+                move |req: serde_json::Value| {
+                    let state = Arc::clone(&state);
+                    async move {
+                        // Widen the window for the concurrent requests to interleave.
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+
+                        let id = req["externalDatabaseRefID"].as_str().unwrap().to_owned();
+                        state.lock().unwrap().db.push(id);
+
+                        warp::reply::json(&serde_json::json!({
+                            "error": false,
+                            "success": true,
+                        }))
+                    }
+                }
+            });
+
+        let db_delete = warp::post()
+            .and(warp::path!("3d-db" / "delete"))
+            .and(warp::body::json())
+            .map({
+                let state = Arc::clone(&state);
+                move |req: serde_json::Value| {
+                    let id = req["identifier"].as_str().unwrap();
+                    state
+                        .lock()
+                        .unwrap()
+                        .db
+                        .retain(|enrolled_id| enrolled_id != id);
+
+                    warp::reply::json(&serde_json::json!({
+                        "error": false,
+                        "success": true,
+                    }))
+                }
+            });
+
+        let session_token = warp::get().and(warp::path!("session-token")).map(|| {
+            warp::reply::json(&serde_json::json!({
+                "error": false,
+                "sessionToken": "session token",
+                "success": true,
+            }))
+        });
+
+        let (addr, server) = warp::serve(
+            enrollment_3d
+                .or(db_search)
+                .or(db_enroll)
+                .or(db_delete)
+                .or(session_token),
+        )
+        .bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        Self {
+            state,
+            enrollment_3d_arrived,
+            base_url: format!("http://{addr}"),
+        }
+    }
+
+    /// Make a logic that uses this server.
+    fn logic(&self) -> Arc<Logic<TestSigner, TestValidatorPublicKey>> {
+        Arc::new(make_logic(ft::Client {
+            reqwest: reqwest::Client::new(),
+            base_url: self.base_url.clone(),
+            device_key_identifier: "device_key_identifier".to_owned(),
+            injected_ip_address: None,
+            response_body_error_inspector: crate::LoggingInspector,
+            policy: Default::default(),
+        }))
+    }
+
+    /// Make a logic that uses this server and keeps the state at a fresh store.
+    fn logic_with_store(&self) -> Arc<Logic<TestSigner, TestValidatorPublicKey>> {
+        let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        let mut logic = Arc::into_inner(self.logic()).unwrap();
+        // The journal file is removed right after opening, the store keeps using the open file.
+        logic.sequence_state.get_mut().store = Some(Store::open(&path).unwrap());
+        Arc::new(logic)
+    }
+
+    /// The external database ref IDs enrolled into the 3D-DB.
+    fn db(&self) -> Vec<String> {
+        self.state.lock().unwrap().db.clone()
+    }
+
+    /// Make the `/3d-db/search` requests fail with the given error message.
+    fn fail_db_search(&self, error_message: &str) {
+        self.state.lock().unwrap().db_search_error = Some(error_message.to_owned());
+    }
+}
+
+/// Prepare the liveness data of the given person.
+fn liveness_data_of(person: &str) -> OpaqueLivenessData {
+    OpaqueLivenessData::from(&LivenessData {
+        face_scan: person.to_owned(),
+        audit_trail_image: "audit trail image".to_owned(),
+        low_quality_audit_trail_image: "low quality audit trail image".to_owned(),
+    })
+}
+
+/// Run the enroll operations concurrently and collect the results.
+async fn enroll_concurrently(
+    logic: &Arc<Logic<TestSigner, TestValidatorPublicKey>>,
+    requests: Vec<(Vec<u8>, &str)>,
+) -> Vec<Result<super::op_enroll::Response, super::op_enroll::Error>> {
+    let tasks = requests
+        .into_iter()
+        .map(|(public_key, person)| {
+            let logic = Arc::clone(logic);
+            let liveness_data = liveness_data_of(person);
+            tokio::spawn(async move {
+                logic
+                    .call(super::op_enroll::Request {
+                        public_key,
+                        liveness_data,
+                        liveness_data_signature: b"qwe".to_vec(),
+                    })
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        results.push(task.await.unwrap());
+    }
+    results
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_enrollments_of_the_same_person() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic();
+
+    let requests = (0..8u8).map(|i| (vec![i], "person")).collect();
+    let results = enroll_concurrently(&logic, requests).await;
+
+    assert_eq!(results.iter().filter(|res| res.is_ok()).count(), 1);
+    for res in results {
+        assert!(matches!(
+            res,
+            Ok(_) | Err(super::op_enroll::Error::PersonAlreadyEnrolled(_))
+        ));
+    }
+    assert_eq!(facetec.db().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_enrollments_with_the_same_public_key() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic();
+
+    let persons = ["a", "b", "c", "d", "e", "f", "g", "h"];
+    let requests = persons.iter().map(|person| (vec![0], *person)).collect();
+    let results = enroll_concurrently(&logic, requests).await;
+
+    assert_eq!(results.iter().filter(|res| res.is_ok()).count(), 1);
+    for res in results {
+        assert!(matches!(
+            res,
+            Ok(_) | Err(super::op_enroll::Error::PublicKeyAlreadyUsed)
+        ));
+    }
+    assert_eq!(facetec.db(), vec![hex::encode([0u8])]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unrelated_enrollments_proceed_concurrently() {
+    // Neither of the enrollments can pass the liveness check unless both are conducted
+    // at the same time.
+    let facetec = FakeFacetec::start(Some(Arc::new(Barrier::new(2))));
+    let logic = facetec.logic();
+
+    let requests = vec![(vec![1], "a"), (vec![2], "b")];
+    let results = tokio::time::timeout(
+        Duration::from_secs(10),
+        enroll_concurrently(&logic, requests),
+    )
+    .await
+    .expect("enrollments have been serialized");
+
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(facetec.db().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn session_token_is_not_blocked_by_enrollment() {
+    // The enrollment is stuck at the liveness check until the second one arrives, which never
+    // happens.
+    let facetec = FakeFacetec::start(Some(Arc::new(Barrier::new(2))));
+    let logic = facetec.logic();
+
+    let enrollment = tokio::spawn({
+        let logic = Arc::clone(&logic);
+        async move {
+            logic
+                .call(super::op_enroll::Request {
+                    public_key: vec![1],
+                    liveness_data: liveness_data_of("a"),
+                    liveness_data_signature: b"qwe".to_vec(),
+                })
+                .await
+        }
+    });
+    facetec.enrollment_3d_arrived.notified().await;
+
+    let res = tokio::time::timeout(
+        Duration::from_secs(10),
+        logic.call(super::op_get_facetec_session_token::Request),
+    )
+    .await
+    .expect("session token request has been blocked by the enrollment")
+    .unwrap();
+    assert_eq!(res.session_token, "session token");
+
+    enrollment.abort();
+}
+
+/// Enroll the person with the given public key.
+async fn enroll(
+    logic: &Logic<TestSigner, TestValidatorPublicKey>,
+    public_key: &[u8],
+    person: &str,
+) -> Result<super::op_enroll::Response, super::op_enroll::Error> {
+    logic
+        .call(super::op_enroll::Request {
+            public_key: public_key.to_vec(),
+            liveness_data: liveness_data_of(person),
+            liveness_data_signature: b"qwe".to_vec(),
+        })
+        .await
+}
+
+/// Authenticate the person.
+async fn authenticate(
+    logic: &Logic<TestSigner, TestValidatorPublicKey>,
+    person: &str,
+) -> Result<super::op_authenticate::Response, super::op_authenticate::Error> {
+    logic
+        .call(super::op_authenticate::Request {
+            liveness_data: liveness_data_of(person),
+            liveness_data_signature: b"qwe".to_vec(),
+            auth_ticket: None,
+            auth_ticket_signature: None,
+        })
+        .await
+}
+
+/// Look up the enrollment of the given public key.
+async fn admin_lookup(
+    logic: &Logic<TestSigner, TestValidatorPublicKey>,
+    public_key: &[u8],
+) -> super::op_admin_lookup::Response {
+    logic
+        .call(super::op_admin_lookup::Request {
+            public_key: public_key.to_vec(),
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn enroll_issues_receipt() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic();
+
+    let res = enroll(&logic, &[1], "a").await.unwrap();
+
+    let receipt = EnrollmentReceipt::try_from(&res.enrollment_receipt).unwrap();
+    assert_eq!(receipt.public_key, vec![1]);
+    assert_eq!(
+        receipt.robonode_key_id,
+        b"dummy robonode public key".to_vec()
+    );
+    assert!(receipt.enrolled_at > 0);
+    assert_eq!(
+        res.enrollment_receipt_signature,
+        b"dummy signature".to_vec()
+    );
+}
+
+#[tokio::test]
+async fn admin_delete_allows_enrolling_with_a_new_key() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic_with_store();
+
+    enroll(&logic, &[1], "a").await.unwrap();
+    assert!(admin_lookup(&logic, &[1]).await.enrolled);
+
+    let res = logic
+        .call(super::op_admin_delete::Request {
+            public_key: vec![1],
+        })
+        .await
+        .unwrap();
+    assert_eq!(res.identifier, hex::encode([1u8]));
+    assert!(facetec.db().is_empty());
+    assert!(!admin_lookup(&logic, &[1]).await.enrolled);
+
+    assert!(matches!(
+        authenticate(&logic, "a").await,
+        Err(super::op_authenticate::Error::PersonNotFound(_))
+    ));
+    assert!(matches!(
+        logic
+            .call(super::op_admin_delete::Request {
+                public_key: vec![1],
+            })
+            .await,
+        Err(super::op_admin_delete::Error::NotEnrolled)
+    ));
+
+    enroll(&logic, &[2], "a").await.unwrap();
+    assert_eq!(facetec.db(), vec![hex::encode([2u8])]);
+}
+
+#[tokio::test]
+async fn admin_migrate_moves_the_enrollment_to_a_new_key() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic_with_store();
+
+    enroll(&logic, &[1], "a").await.unwrap();
+    enroll(&logic, &[3], "b").await.unwrap();
+
+    let migrate = |public_key: u8, new_public_key: u8| {
+        logic.call(super::op_admin_migrate::Request {
+            public_key: vec![public_key],
+            new_public_key: vec![new_public_key],
+        })
+    };
+
+    assert!(matches!(
+        migrate(1, 3).await,
+        Err(super::op_admin_migrate::Error::NewPublicKeyAlreadyUsed)
+    ));
+    assert!(matches!(
+        migrate(4, 5).await,
+        Err(super::op_admin_migrate::Error::NotEnrolled)
+    ));
+
+    let res = migrate(1, 2).await.unwrap();
+    assert_eq!(res.identifier, hex::encode([1u8]));
+
+    assert_eq!(
+        admin_lookup(&logic, &[1]).await,
+        super::op_admin_lookup::Response {
+            identifier: None,
+            migrated_to: Some(hex::encode([2u8])),
+            enrolled: false,
+            issued_tickets: 0,
+            last_issued_at: None,
+        }
+    );
+
+    authenticate(&logic, "a").await.unwrap();
+    let lookup = admin_lookup(&logic, &[2]).await;
+    assert_eq!(lookup.identifier, Some(hex::encode([1u8])));
+    assert!(lookup.enrolled);
+    assert_eq!(lookup.issued_tickets, 1);
+
+    assert!(matches!(
+        enroll(&logic, &[2], "c").await,
+        Err(super::op_enroll::Error::PublicKeyAlreadyUsed)
+    ));
+
+    // Migrating back to the original key drops the migration.
+    migrate(2, 1).await.unwrap();
+    assert_eq!(
+        admin_lookup(&logic, &[1]).await.identifier,
+        Some(hex::encode([1u8]))
+    );
+    assert_eq!(
+        admin_lookup(&logic, &[2]).await.identifier,
+        Some(hex::encode([2u8]))
+    );
+}
+
+#[tokio::test]
+async fn admin_migrate_requires_the_store() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic();
+
+    enroll(&logic, &[1], "a").await.unwrap();
+
+    assert!(matches!(
+        logic
+            .call(super::op_admin_migrate::Request {
+                public_key: vec![1],
+                new_public_key: vec![2],
+            })
+            .await,
+        Err(super::op_admin_migrate::Error::InternalErrorStoreUnavailable)
+    ));
+}
+
+/// Make a logic that uses a fresh FaceTec Server simulator.
+fn simulated_logic() -> Logic<TestSigner, TestValidatorPublicKey> {
+    simulated_logic_with_state(Default::default())
+}
+
+/// Make a logic that uses the FaceTec Server simulator with the given state.
+fn simulated_logic_with_state(
+    state: devutil_facetec_server::SharedState,
+) -> Logic<TestSigner, TestValidatorPublicKey> {
+    let (addr, server) =
+        warp::serve(devutil_facetec_server::routes(state)).bind_ephemeral(([127, 0, 0, 1], 0));
+    tokio::spawn(server);
+
+    make_logic(ft::Client {
+        reqwest: reqwest::Client::new(),
+        base_url: format!("http://{addr}"),
+        device_key_identifier: "device_key_identifier".to_owned(),
+        injected_ip_address: None,
+        response_body_error_inspector: crate::LoggingInspector,
+        policy: Default::default(),
+    })
+}
+
+#[tokio::test]
+async fn simulated_enroll_authenticate() {
+    let logic = simulated_logic();
+
+    assert!(matches!(
+        authenticate(&logic, "alice#1").await,
+        Err(super::op_authenticate::Error::PersonNotFound(_))
+    ));
+
+    enroll(&logic, b"alice key", "alice#2").await.unwrap();
+
+    let res = authenticate(&logic, "alice#3").await.unwrap();
+    let auth_ticket = primitives_auth_ticket::AuthTicket::try_from(&res.auth_ticket).unwrap();
+    assert_eq!(auth_ticket.public_key, b"alice key".to_vec());
+
+    assert!(matches!(
+        authenticate(&logic, "bob").await,
+        Err(super::op_authenticate::Error::PersonNotFound(_))
+    ));
+    assert!(matches!(
+        authenticate(&logic, "spoof:alice").await,
+        Err(super::op_authenticate::Error::FaceScanRejected(_))
+    ));
+}
+
+#[tokio::test]
+async fn simulated_authenticate_legacy_auth_ticket() {
+    let logic = Logic {
+        legacy_auth_tickets: true,
+        ..simulated_logic()
+    };
+
+    enroll(&logic, b"alice key", "alice#1").await.unwrap();
+
+    let res = authenticate(&logic, "alice#2").await.unwrap();
+    assert!(matches!(
+        primitives_auth_ticket::AuthTicket::try_from(&res.auth_ticket),
+        Err(primitives_auth_ticket::DecodeError::Legacy)
+    ));
+    let auth_ticket = primitives_auth_ticket::LegacyAuthTicket::try_from(&res.auth_ticket).unwrap();
+    assert_eq!(auth_ticket.public_key, b"alice key".to_vec());
+}
+
+#[tokio::test]
+async fn simulated_authenticate_co_sign() {
+    let issuing_robonode_key = robonode_crypto::SigningKey::from_bytes(&[1; 32]);
+    let untrusted_robonode_key = robonode_crypto::SigningKey::from_bytes(&[2; 32]);
+    let co_signing_logic = Logic {
+        trusted_robonode_public_keys: vec![issuing_robonode_key.verifying_key()],
+        ..simulated_logic()
+    };
+
+    enroll(&co_signing_logic, b"alice key", "alice#1")
+        .await
+        .unwrap();
+    enroll(&co_signing_logic, b"bob key", "bob#1")
+        .await
+        .unwrap();
+
+    let issue = |robonode_key: &robonode_crypto::SigningKey, issued_at: u64| {
+        let auth_ticket =
+            primitives_auth_ticket::OpaqueAuthTicket::from(&primitives_auth_ticket::AuthTicket {
+                public_key: b"alice key".to_vec(),
+                authentication_nonce: b"nonce".to_vec(),
+                issued_at,
+            });
+        let auth_ticket_signature =
+            robonode_crypto::Signer::sign(robonode_key, auth_ticket.as_ref()).to_bytes();
+        (auth_ticket, Some(auth_ticket_signature.to_vec()))
+    };
+
+    let co_sign = |person: &'static str,
+                   (auth_ticket, auth_ticket_signature): (
+        primitives_auth_ticket::OpaqueAuthTicket,
+        Option<Vec<u8>>,
+    )| {
+        co_signing_logic.call(super::op_authenticate::Request {
+            liveness_data: liveness_data_of(person),
+            liveness_data_signature: b"qwe".to_vec(),
+            auth_ticket: Some(auth_ticket),
+            auth_ticket_signature,
+        })
+    };
+
+    let now = current_unix_milliseconds();
+    let issued = issue(&issuing_robonode_key, now);
+
+    let co_signed = co_sign("alice#2", issued.clone()).await.unwrap();
+    assert_eq!(co_signed.auth_ticket, issued.0);
+
+    // Issued to another person.
+    assert!(matches!(
+        co_sign("bob#2", issued.clone()).await,
+        Err(super::op_authenticate::Error::AuthTicketMismatch(_))
+    ));
+    // Not signed by the issuing robonode.
+    assert!(matches!(
+        co_sign("alice#3", (issued.0, None)).await,
+        Err(super::op_authenticate::Error::AuthTicketMismatch(_))
+    ));
+    // Signed by an untrusted robonode.
+    assert!(matches!(
+        co_sign("alice#4", issue(&untrusted_robonode_key, now)).await,
+        Err(super::op_authenticate::Error::AuthTicketMismatch(_))
+    ));
+    // Issued outside of the accepted clock drift.
+    assert!(matches!(
+        co_sign(
+            "alice#5",
+            issue(&issuing_robonode_key, now - 2 * CO_SIGN_MAX_CLOCK_DRIFT),
+        )
+        .await,
+        Err(super::op_authenticate::Error::AuthTicketMismatch(_))
+    ));
+    assert!(matches!(
+        co_sign(
+            "alice#6",
+            issue(&issuing_robonode_key, now + 2 * CO_SIGN_MAX_CLOCK_DRIFT),
+        )
+        .await,
+        Err(super::op_authenticate::Error::AuthTicketMismatch(_))
+    ));
+    // Unable to decode.
+    let garbage = primitives_auth_ticket::OpaqueAuthTicket(b"garbage".to_vec());
+    let garbage_signature = robonode_crypto::Signer::sign(&issuing_robonode_key, garbage.as_ref());
+    assert!(matches!(
+        co_sign(
+            "alice#7",
+            (garbage, Some(garbage_signature.to_bytes().to_vec())),
+        )
+        .await,
+        Err(super::op_authenticate::Error::AuthTicketMismatch(_))
+    ));
+}
+
+#[tokio::test]
+async fn simulated_double_enroll() {
+    let logic = simulated_logic();
+
+    enroll(&logic, b"a", "alice#1").await.unwrap();
+
+    assert!(matches!(
+        enroll(&logic, b"b", "alice#2").await,
+        Err(super::op_enroll::Error::PersonAlreadyEnrolled(_))
+    ));
+    assert!(matches!(
+        enroll(&logic, b"a", "bob").await,
+        Err(super::op_enroll::Error::PublicKeyAlreadyUsed)
+    ));
+    assert!(matches!(
+        enroll(&logic, b"c", "spoof:bob").await,
+        Err(super::op_enroll::Error::FaceScanRejected(_))
+    ));
+
+    enroll(&logic, b"c", "bob").await.unwrap();
+}
+
+#[tokio::test]
+async fn simulated_reconcile() {
+    let state = devutil_facetec_server::SharedState::default();
+    let mut logic = simulated_logic_with_state(Arc::clone(&state));
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+    logic.sequence_state.get_mut().store = Some(Store::open(&path).unwrap());
+
+    enroll(&logic, b"a", "alice").await.unwrap();
+    authenticate(&logic, "alice#2").await.unwrap();
+    let tmp_entries = vec![format!("tmp-{}-1", logic.execution_id)];
+
+    {
+        let mut sequence_state = logic.sequence_state.lock().await;
+        let store = sequence_state.store.as_mut().unwrap();
+        // The enrollment that has failed before reaching the group.
+        store.record_enrollment_start("bb").await.unwrap();
+        // The enrollment that has reached the group, but has not been recorded as completed.
+        store.record_enrollment_start("cc").await.unwrap();
+        // The person that has been deleted, but is still in the group.
+        store.record_enrollment_deletion("dd").await.unwrap();
+    }
+    {
+        let mut state = state.lock().unwrap();
+        for (identifier, person) in [
+            ("cc", "carol"),
+            ("dd", "dave"),
+            ("legacy", "eve"),
+            ("tmp-1", "frank"),
+        ] {
+            state.enrollment_3d(identifier, person).unwrap();
+            state.db_enroll(identifier, DB_GROUP_NAME).unwrap();
+        }
+    }
+
+    let report = logic
+        .call(super::op_admin_reconcile::Request { cleanup: false })
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        super::op_admin_reconcile::Response {
+            orphans: vec!["dd".to_owned(), "tmp-1".to_owned()],
+            half_finished: vec![
+                super::op_admin_reconcile::HalfFinishedEnrollment {
+                    identifier: "bb".to_owned(),
+                    in_group: false,
+                },
+                super::op_admin_reconcile::HalfFinishedEnrollment {
+                    identifier: "cc".to_owned(),
+                    in_group: true,
+                },
+            ],
+            unrecorded: vec!["legacy".to_owned()],
+            tmp_entries: tmp_entries.clone(),
+            cleaned_up: vec![],
+        }
+    );
+    assert_eq!(state.lock().unwrap().group(DB_GROUP_NAME).len(), 5);
+
+    let res = logic
+        .call(super::op_admin_reconcile::Request { cleanup: true })
+        .await
+        .unwrap();
+    assert_eq!(res.cleaned_up, vec!["dd", "tmp-1", "bb", "cc"]);
+    assert_eq!(
+        state.lock().unwrap().group(DB_GROUP_NAME),
+        vec![hex::encode(b"a"), "legacy".to_owned()]
+    );
+
+    let report = logic
+        .call(super::op_admin_reconcile::Request { cleanup: false })
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        super::op_admin_reconcile::Response {
+            unrecorded: vec!["legacy".to_owned()],
+            tmp_entries,
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn admin_delete_fails_on_unexpected_db_search_error() {
+    let facetec = FakeFacetec::start(None);
+    let logic = facetec.logic_with_store();
+
+    enroll(&logic, &[1], "a").await.unwrap();
+    facetec.fail_db_search("An unexpected server error.");
+
+    assert!(matches!(
+        logic
+            .call(super::op_admin_delete::Request {
+                public_key: vec![1],
+            })
+            .await,
+        Err(super::op_admin_delete::Error::InternalErrorDbSearch(
+            ft::Error::Server(_)
+        ))
+    ));
+    assert_eq!(facetec.db(), vec![hex::encode([1u8])]);
+}
@@ -2,7 +2,9 @@
 
 use std::env::VarError;
 
-use tracing::info;
+use clap::Parser;
+use robonode_server::{config::Signer, signer, PublicKeyProvider};
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -10,44 +12,125 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     logger.with_colors(true);
     logger.init()?;
 
-    let addr: std::net::SocketAddr = env("ADDR")?;
-    let facetec_server_url = env("FACETEC_SERVER_URL")?;
-    let facetec_device_key_identifier: String = env("FACETEC_DEVICE_KEY_IDENTIFIER")?;
-    let facetec_public_face_map_encryption_key = env("FACETEC_PUBLIC_FACE_MAP_ENCRYPTION_KEY")?;
-    let facetec_production_key: Option<String> = maybe_env("FACETEC_PRODUCTION_KEY")?;
-    let robonode_secret_key_string: String = env("ROBONODE_SECRET_KEY")?;
+    let cli = robonode_server::config::Cli::parse();
+    let check_config = cli.check_config;
+    let config = robonode_server::config::Config::load(cli)?;
 
-    let mut robonode_secret_key_bytes = robonode_crypto::SecretKey::default();
-    hex::decode_to_slice(robonode_secret_key_string, &mut robonode_secret_key_bytes)?;
-    let robonode_signing_key = robonode_crypto::SigningKey::from_bytes(&robonode_secret_key_bytes);
+    if let Some(tls) = &config.tls {
+        tls.check()?;
+    }
+
+    let signer = match config.signer {
+        Signer::Local(signing_key) => signer::Backend::Local(signing_key),
+        Signer::Pkcs11(params) => signer::Backend::Pkcs11(
+            tokio::task::spawn_blocking(move || signer::pkcs11::Pkcs11Signer::open(params))
+                .await??,
+        ),
+        Signer::Remote(params) => {
+            signer::Backend::Remote(signer::remote::RemoteSigner::connect(params).await?)
+        }
+    };
+    info!(
+        "Using the {} signer backend, robonode public key is {}",
+        signer.name(),
+        hex::encode(signer.public_key())
+    );
 
     let facetec_api_client = facetec_api_client::Client {
-        base_url: facetec_server_url,
+        base_url: config.facetec_server_url,
         reqwest: reqwest::Client::new(),
-        device_key_identifier: facetec_device_key_identifier.clone(),
+        device_key_identifier: config
+            .facetec_device_sdk_params
+            .device_key_identifier
+            .clone(),
         injected_ip_address: None,
         response_body_error_inspector: robonode_server::LoggingInspector,
+        policy: config.facetec_policy.to_policy(),
     };
-    let face_tec_device_sdk_params = robonode_server::FacetecDeviceSdkParams {
-        device_key_identifier: facetec_device_key_identifier,
-        public_face_map_encryption_key: facetec_public_face_map_encryption_key,
-        production_key: facetec_production_key,
+    let execution_id = uuid::Uuid::new_v4();
+
+    // Check the files without creating or modifying them.
+    if check_config {
+        if let Some(state_file) = &config.state_file {
+            robonode_server::store::Store::check(state_file)?;
+        }
+        if let Some(admin) = &config.admin {
+            robonode_server::admin::AuditLog::check(&admin.audit_log_file)?;
+        }
+        info!("The configuration is valid");
+        return Ok(());
+    }
+
+    let store = match config.state_file {
+        Some(state_file) => {
+            let store = robonode_server::store::Store::open(&state_file)?;
+            info!(
+                "Loaded the state from {}, last sequence value is {}",
+                state_file.display(),
+                store.last_sequence()
+            );
+            Some(store)
+        }
+        None => None,
     };
 
-    let execution_id = uuid::Uuid::new_v4();
+    let admin = match config.admin {
+        Some(admin) => {
+            let audit_log = robonode_server::admin::AuditLog::open(&admin.audit_log_file)?;
+            info!(
+                "Admin API is enabled, recording the audit log at {}",
+                admin.audit_log_file.display()
+            );
+            Some(robonode_server::admin::Admin::new(admin.token, audit_log))
+        }
+        None => None,
+    };
 
-    let root_filter = robonode_server::init(
+    if config.legacy_auth_tickets {
+        warn!("Issuing the auth tickets in the legacy encoding");
+    }
+
+    let (root_filter, internal_filter) = robonode_server::init(
         execution_id,
         facetec_api_client,
-        face_tec_device_sdk_params,
-        robonode_signing_key,
+        config.facetec_device_sdk_params,
+        config.facetec_db_params,
+        signer,
+        store,
+        config.rate_limit,
+        admin,
+        config.legacy_auth_tickets,
+        config.trusted_robonode_public_keys,
     );
-    let (addr, server) =
-        warp::serve(root_filter).bind_with_graceful_shutdown(addr, shutdown_signal());
 
-    info!("Listening on http://{}", addr);
+    if let Some(internal_addr) = config.internal_addr {
+        let (addr, server) = warp::serve(internal_filter)
+            .bind_with_graceful_shutdown(internal_addr, shutdown_signal());
+        info!(
+            "Serving the metrics and the health probes at http://{}",
+            addr
+        );
+        tokio::spawn(server);
+    }
+
+    match config.tls {
+        Some(tls) => {
+            let (addr, server) = warp::serve(root_filter)
+                .tls()
+                .cert_path(tls.cert_file)
+                .key_path(tls.key_file)
+                .bind_with_graceful_shutdown(config.addr, shutdown_signal());
+            info!("Listening on https://{}", addr);
+            server.await;
+        }
+        None => {
+            let (addr, server) = warp::serve(root_filter)
+                .bind_with_graceful_shutdown(config.addr, shutdown_signal());
+            info!("Listening on http://{}", addr);
+            server.await;
+        }
+    }
 
-    server.await;
     Ok(())
 }
 
@@ -0,0 +1,304 @@
+//! Prometheus metrics.
+
+use std::{future::Future, time::Instant};
+
+use facetec_api_client as ft;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+
+use crate::logic::{
+    op_admin_delete, op_admin_lookup, op_admin_migrate, op_admin_reconcile, op_authenticate,
+    op_enroll, op_get_facetec_device_sdk_params, op_get_facetec_session_token, op_get_public_key,
+};
+
+/// The outcome label value for the successful calls.
+const OUTCOME_OK: &str = "ok";
+
+/// The outcome label value for the failed FaceTec Server API calls.
+const OUTCOME_ERROR: &str = "error";
+
+/// The robonode metrics.
+#[derive(Debug)]
+pub struct Metrics {
+    /// The registry the metrics are registered at.
+    registry: Registry,
+    /// The number of the handled operations, by operation and outcome.
+    operations: IntCounterVec,
+    /// The duration of the FaceTec Server API calls, by call and outcome.
+    facetec_call_duration: HistogramVec,
+    /// The time spent waiting for the 3D-DB enrollment lock, by operation.
+    db_enrollment_lock_wait_duration: HistogramVec,
+}
+
+impl Metrics {
+    /// Create the metrics and register them at a new registry.
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new();
+
+        let operations = IntCounterVec::new(
+            Opts::new(
+                "robonode_operations_total",
+                "Number of the handled operations, by operation and outcome",
+            ),
+            &["operation", "outcome"],
+        )?;
+        registry.register(Box::new(operations.clone()))?;
+
+        let facetec_call_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "robonode_facetec_call_duration_seconds",
+                "Duration of the FaceTec Server API calls, by call and outcome",
+            )
+            .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+            &["call", "outcome"],
+        )?;
+        registry.register(Box::new(facetec_call_duration.clone()))?;
+
+        let db_enrollment_lock_wait_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "robonode_db_enrollment_lock_wait_duration_seconds",
+                "Time spent waiting for the 3D-DB enrollment lock, by operation",
+            )
+            .buckets(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+            &["operation"],
+        )?;
+        registry.register(Box::new(db_enrollment_lock_wait_duration.clone()))?;
+
+        Ok(Self {
+            registry,
+            operations,
+            facetec_call_duration,
+            db_enrollment_lock_wait_duration,
+        })
+    }
+
+    /// Count the handled operation with its outcome.
+    pub fn observe_operation<T, E>(&self, operation: &str, res: &Result<T, E>)
+    where
+        E: OutcomeLabel,
+    {
+        let outcome = match res {
+            Ok(_) => OUTCOME_OK,
+            Err(err) => err.outcome_label(),
+        };
+        self.operations
+            .with_label_values(&[operation, outcome])
+            .inc();
+    }
+
+    /// Conduct the FaceTec Server API call and measure its duration.
+    pub async fn observe_facetec_call<T>(
+        &self,
+        call: &str,
+        fut: impl Future<Output = Result<T, ft::Error>>,
+    ) -> Result<T, ft::Error> {
+        let started_at = Instant::now();
+        let res = fut.await;
+        let outcome = if res.is_ok() {
+            OUTCOME_OK
+        } else {
+            OUTCOME_ERROR
+        };
+        self.facetec_call_duration
+            .with_label_values(&[call, outcome])
+            .observe(started_at.elapsed().as_secs_f64());
+        res
+    }
+
+    /// Acquire the 3D-DB enrollment lock and measure how long it took.
+    pub async fn observe_db_enrollment_lock_wait<T>(
+        &self,
+        operation: &str,
+        fut: impl Future<Output = T>,
+    ) -> T {
+        let started_at = Instant::now();
+        let guard = fut.await;
+        self.db_enrollment_lock_wait_duration
+            .with_label_values(&[operation])
+            .observe(started_at.elapsed().as_secs_f64());
+        guard
+    }
+
+    /// Encode the metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<Vec<u8>, prometheus::Error> {
+        let mut buf = Vec::new();
+        prometheus::TextEncoder::new().encode(&self.registry.gather(), &mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// The value of the outcome label for the failed operations.
+pub trait OutcomeLabel {
+    /// The outcome label value, the name of the error variant.
+    fn outcome_label(&self) -> &'static str;
+}
+
+impl OutcomeLabel for op_enroll::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InvalidPublicKey => "InvalidPublicKey",
+            Self::InvalidLivenessData(_) => "InvalidLivenessData",
+            Self::SignatureInvalid => "SignatureInvalid",
+            Self::FaceScanRejected(_) => "FaceScanRejected",
+            Self::PublicKeyAlreadyUsed => "PublicKeyAlreadyUsed",
+            Self::PersonAlreadyEnrolled(_) => "PersonAlreadyEnrolled",
+            Self::InternalErrorEnrollment(_) => "InternalErrorEnrollment",
+            Self::InternalErrorEnrollmentUnsuccessful(_) => "InternalErrorEnrollmentUnsuccessful",
+            Self::InternalErrorDbSearch(..) => "InternalErrorDbSearch",
+            Self::InternalErrorDbSearchUnsuccessful(_) => "InternalErrorDbSearchUnsuccessful",
+            Self::InternalErrorDbEnroll(..) => "InternalErrorDbEnroll",
+            Self::InternalErrorDbEnrollUnsuccessful(_) => "InternalErrorDbEnrollUnsuccessful",
+            Self::InternalErrorSignatureVerificationFailed => {
+                "InternalErrorSignatureVerificationFailed"
+            }
+            Self::InternalErrorEnrollmentReceiptSigningFailed(_) => {
+                "InternalErrorEnrollmentReceiptSigningFailed"
+            }
+            Self::InternalErrorEnrollmentPersistingFailed(..) => {
+                "InternalErrorEnrollmentPersistingFailed"
+            }
+        }
+    }
+}
+
+impl OutcomeLabel for op_authenticate::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InvalidLivenessData(_) => "InvalidLivenessData",
+            Self::FaceScanRejected(_) => "FaceScanRejected",
+            Self::PersonNotFound(_) => "PersonNotFound",
+            Self::SignatureInvalid(_) => "SignatureInvalid",
+            Self::AuthTicketMismatch(_) => "AuthTicketMismatch",
+            Self::InternalErrorEnrollment(_) => "InternalErrorEnrollment",
+            Self::InternalErrorEnrollmentUnsuccessful(_) => "InternalErrorEnrollmentUnsuccessful",
+            Self::InternalErrorDbSearch(..) => "InternalErrorDbSearch",
+            Self::InternalErrorDbSearchUnsuccessful(_) => "InternalErrorDbSearchUnsuccessful",
+            Self::InternalErrorDbSearchMatchLevelMismatch(_) => {
+                "InternalErrorDbSearchMatchLevelMismatch"
+            }
+            Self::InternalErrorInvalidPublicKeyHex(_) => "InternalErrorInvalidPublicKeyHex",
+            Self::InternalErrorInvalidPublicKey(_) => "InternalErrorInvalidPublicKey",
+            Self::InternalErrorSignatureVerificationFailed(_) => {
+                "InternalErrorSignatureVerificationFailed"
+            }
+            Self::InternalErrorAuthTicketSigningFailed(_) => "InternalErrorAuthTicketSigningFailed",
+            Self::InternalErrorSequencePersistingFailed(_) => {
+                "InternalErrorSequencePersistingFailed"
+            }
+            Self::InternalErrorAuthTicketPersistingFailed(..) => {
+                "InternalErrorAuthTicketPersistingFailed"
+            }
+        }
+    }
+}
+
+impl OutcomeLabel for op_admin_lookup::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InvalidPublicKey => "InvalidPublicKey",
+            Self::InternalErrorDbSearch(_) => "InternalErrorDbSearch",
+            Self::InternalErrorDbSearchUnsuccessful => "InternalErrorDbSearchUnsuccessful",
+        }
+    }
+}
+
+impl OutcomeLabel for op_admin_delete::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InvalidPublicKey => "InvalidPublicKey",
+            Self::NotEnrolled => "NotEnrolled",
+            Self::InternalErrorDbSearch(_) => "InternalErrorDbSearch",
+            Self::InternalErrorDbSearchUnsuccessful => "InternalErrorDbSearchUnsuccessful",
+            Self::InternalErrorDbDelete(_) => "InternalErrorDbDelete",
+            Self::InternalErrorDbDeleteUnsuccessful => "InternalErrorDbDeleteUnsuccessful",
+            Self::InternalErrorDeletionPersistingFailed(_) => {
+                "InternalErrorDeletionPersistingFailed"
+            }
+        }
+    }
+}
+
+impl OutcomeLabel for op_admin_reconcile::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InternalErrorStoreUnavailable => "InternalErrorStoreUnavailable",
+            Self::InternalErrorDbList(_) => "InternalErrorDbList",
+            Self::InternalErrorDbListUnsuccessful => "InternalErrorDbListUnsuccessful",
+            Self::InternalErrorDbDelete(_) => "InternalErrorDbDelete",
+            Self::InternalErrorDbDeleteUnsuccessful => "InternalErrorDbDeleteUnsuccessful",
+            Self::InternalErrorDeletionPersistingFailed(_) => {
+                "InternalErrorDeletionPersistingFailed"
+            }
+        }
+    }
+}
+
+impl OutcomeLabel for op_admin_migrate::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InvalidPublicKey => "InvalidPublicKey",
+            Self::InvalidNewPublicKey => "InvalidNewPublicKey",
+            Self::NotEnrolled => "NotEnrolled",
+            Self::NewPublicKeyAlreadyUsed => "NewPublicKeyAlreadyUsed",
+            Self::InternalErrorDbSearch(_) => "InternalErrorDbSearch",
+            Self::InternalErrorDbSearchUnsuccessful => "InternalErrorDbSearchUnsuccessful",
+            Self::InternalErrorStoreUnavailable => "InternalErrorStoreUnavailable",
+            Self::InternalErrorMigrationPersistingFailed(_) => {
+                "InternalErrorMigrationPersistingFailed"
+            }
+        }
+    }
+}
+
+impl OutcomeLabel for op_get_facetec_session_token::Error {
+    fn outcome_label(&self) -> &'static str {
+        match self {
+            Self::InternalErrorSessionToken(_) => "InternalErrorSessionToken",
+            Self::InternalErrorSessionTokenUnsuccessful => "InternalErrorSessionTokenUnsuccessful",
+        }
+    }
+}
+
+impl OutcomeLabel for op_get_facetec_device_sdk_params::Error {
+    fn outcome_label(&self) -> &'static str {
+        match *self {}
+    }
+}
+
+impl OutcomeLabel for op_get_public_key::Error {
+    fn outcome_label(&self) -> &'static str {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn encodes_observations() {
+        let metrics = Metrics::new().unwrap();
+
+        metrics.observe_operation("enroll", &Ok::<_, op_enroll::Error>(()));
+        metrics.observe_operation::<(), _>("enroll", &Err(op_enroll::Error::PublicKeyAlreadyUsed));
+        metrics.observe_operation::<(), _>("enroll", &Err(op_enroll::Error::PublicKeyAlreadyUsed));
+        metrics
+            .observe_facetec_call("session_token", async { Ok(()) })
+            .await
+            .unwrap();
+        metrics
+            .observe_db_enrollment_lock_wait("enroll", async {})
+            .await;
+
+        let encoded = String::from_utf8(metrics.encode().unwrap()).unwrap();
+        assert!(encoded.contains(r#"robonode_operations_total{operation="enroll",outcome="ok"} 1"#));
+        assert!(encoded.contains(
+            r#"robonode_operations_total{operation="enroll",outcome="PublicKeyAlreadyUsed"} 2"#
+        ));
+        assert!(encoded.contains(
+            r#"robonode_facetec_call_duration_seconds_count{call="session_token",outcome="ok"} 1"#
+        ));
+        assert!(encoded.contains(
+            r#"robonode_db_enrollment_lock_wait_duration_seconds_count{operation="enroll"} 1"#
+        ));
+    }
+}
@@ -0,0 +1,410 @@
+//! Rate limiting of the robonode operations.
+//!
+//! The request rates are limited per client IP and per public key with token buckets
+//! (implemented as GCRA), and the number of the rejected FaceScans is capped per key within
+//! a fixed window, so that a single client can't hammer the FaceTec Server with the liveness
+//! attempts.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    net::IpAddr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use crate::logic::{op_authenticate, op_enroll};
+
+/// The number of the tracked keys, exceeding which triggers the cleanup of the stale entries.
+const CLEANUP_THRESHOLD: usize = 10_000;
+
+/// The token bucket quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Quota {
+    /// The number of requests allowed in a burst.
+    pub burst: u32,
+    /// The number of requests allowed per minute in the long run.
+    pub per_minute: u32,
+}
+
+/// The cap on the failed attempts within a fixed window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FailureCap {
+    /// The number of the failed attempts allowed within the window.
+    pub max_failures: u32,
+    /// The window length, in seconds.
+    pub window_secs: u64,
+}
+
+/// The rate limiting settings.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Whether the rate limiting is enabled.
+    pub enabled: bool,
+    /// Whether to identify the clients by the last address of the `X-Forwarded-For` header,
+    /// as appended by the reverse proxy the robonode runs behind.
+    pub trust_x_forwarded_for: bool,
+    /// The quota per client IP, for all the rate limited operations.
+    pub per_ip: Quota,
+    /// The quota per public key, for the enrollments.
+    pub per_public_key: Quota,
+    /// The cap on the rejected FaceScans per client IP and per public key, if it is known.
+    pub face_scan_rejections: FailureCap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            trust_x_forwarded_for: false,
+            per_ip: Quota {
+                burst: 30,
+                per_minute: 60,
+            },
+            per_public_key: Quota {
+                burst: 5,
+                per_minute: 10,
+            },
+            face_scan_rejections: FailureCap {
+                max_failures: 10,
+                window_secs: 3600,
+            },
+        }
+    }
+}
+
+impl Config {
+    /// Validate the settings.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, quota) in [
+            ("per ip", &self.per_ip),
+            ("per public key", &self.per_public_key),
+        ] {
+            if quota.burst == 0 || quota.per_minute == 0 {
+                return Err(format!("the {name} quota must allow at least one request"));
+            }
+        }
+        if self.face_scan_rejections.window_secs == 0 {
+            return Err("the face scan rejections window must not be empty".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// The kind of the exceeded limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// The request rate is exceeded.
+    Rate,
+    /// The number of the failed attempts is exceeded.
+    FailedAttempts,
+}
+
+/// The limit has been exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limited {
+    /// The kind of the exceeded limit.
+    pub kind: LimitKind,
+    /// The time after which the request may be retried.
+    pub retry_after: Duration,
+}
+
+/// The key to count the rejected FaceScans by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FailureKey {
+    /// The public key the operation is conducted for.
+    PublicKey(Vec<u8>),
+    /// The client IP.
+    Ip(IpAddr),
+}
+
+/// Tells whether the operation error is the FaceScan rejection.
+pub trait FaceScanRejection {
+    /// Whether the error is the FaceScan rejection.
+    fn is_face_scan_rejection(&self) -> bool;
+}
+
+impl FaceScanRejection for op_enroll::Error {
+    fn is_face_scan_rejection(&self) -> bool {
+        matches!(self, Self::FaceScanRejected(_))
+    }
+}
+
+impl FaceScanRejection for op_authenticate::Error {
+    fn is_face_scan_rejection(&self) -> bool {
+        matches!(self, Self::FaceScanRejected(_))
+    }
+}
+
+/// The rate limiter for the robonode operations.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// The settings.
+    config: Config,
+    /// The per client IP buckets.
+    per_ip: Buckets<IpAddr>,
+    /// The per public key buckets.
+    per_public_key: Buckets<Vec<u8>>,
+    /// The rejected FaceScans counters.
+    face_scan_rejections: FailureCounters<FailureKey>,
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter with the given settings.
+    pub fn new(config: Config) -> Self {
+        Self {
+            per_ip: Buckets::new(config.per_ip),
+            per_public_key: Buckets::new(config.per_public_key),
+            face_scan_rejections: FailureCounters::new(config.face_scan_rejections),
+            config,
+        }
+    }
+
+    /// Whether to identify the clients by the `X-Forwarded-For` header.
+    pub fn trust_x_forwarded_for(&self) -> bool {
+        self.config.trust_x_forwarded_for
+    }
+
+    /// Take a request from the client IP quota.
+    ///
+    /// The requests with unknown client IP are not limited.
+    pub fn check_ip(&self, ip: Option<IpAddr>) -> Result<(), Limited> {
+        match ip {
+            Some(ip) if self.config.enabled => self.per_ip.take(ip, Instant::now()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Take a request from the public key quota.
+    pub fn check_public_key(&self, public_key: &[u8]) -> Result<(), Limited> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        self.per_public_key
+            .take(public_key.to_vec(), Instant::now())
+    }
+
+    /// Check the number of the rejected FaceScans is under the cap for each of the keys.
+    pub fn check_face_scan_rejections(&self, keys: &[FailureKey]) -> Result<(), Limited> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let now = Instant::now();
+        keys.iter()
+            .try_for_each(|key| self.face_scan_rejections.check(key, now))
+    }
+
+    /// Count the rejected FaceScan for each of the keys.
+    pub fn record_face_scan_rejection(&self, keys: &[FailureKey]) {
+        if !self.config.enabled {
+            return;
+        }
+        let now = Instant::now();
+        for key in keys {
+            self.face_scan_rejections.record(key.clone(), now);
+        }
+    }
+}
+
+/// Token buckets by key, implemented as GCRA: for each key the theoretical arrival time of
+/// the next request is tracked, and the request is allowed if it is not too far ahead.
+#[derive(Debug)]
+struct Buckets<K> {
+    /// The time to replenish a single token.
+    emission_interval: Duration,
+    /// How far ahead the theoretical arrival time is allowed to be, i.e. the burst.
+    tolerance: Duration,
+    /// The theoretical arrival times, by key.
+    entries: Mutex<HashMap<K, Instant>>,
+}
+
+impl<K: Eq + Hash> Buckets<K> {
+    /// Create the buckets with the given quota.
+    fn new(quota: Quota) -> Self {
+        let emission_interval = Duration::from_secs(60)
+            .checked_div(quota.per_minute)
+            .unwrap_or(Duration::MAX);
+        Self {
+            emission_interval,
+            tolerance: emission_interval.saturating_mul(quota.burst.saturating_sub(1)),
+            entries: Default::default(),
+        }
+    }
+
+    /// Take a token from the bucket of the given key.
+    fn take(&self, key: K, now: Instant) -> Result<(), Limited> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        cleanup(&mut entries, |tat| *tat > now);
+
+        let tat = entries.get(&key).copied().unwrap_or(now).max(now);
+        let ahead = tat.saturating_duration_since(now);
+        if ahead > self.tolerance {
+            return Err(Limited {
+                kind: LimitKind::Rate,
+                retry_after: ahead.saturating_sub(self.tolerance),
+            });
+        }
+
+        entries.insert(key, tat.checked_add(self.emission_interval).unwrap_or(tat));
+        Ok(())
+    }
+}
+
+/// The failed attempts counters by key, within the fixed windows.
+#[derive(Debug)]
+struct FailureCounters<K> {
+    /// The number of the failed attempts allowed within the window.
+    max_failures: u32,
+    /// The window length.
+    window: Duration,
+    /// The window ends and the failed attempts counts, by key.
+    entries: Mutex<HashMap<K, (Instant, u32)>>,
+}
+
+impl<K: Eq + Hash> FailureCounters<K> {
+    /// Create the counters with the given cap.
+    fn new(cap: FailureCap) -> Self {
+        Self {
+            max_failures: cap.max_failures,
+            window: Duration::from_secs(cap.window_secs),
+            entries: Default::default(),
+        }
+    }
+
+    /// Check the number of the failed attempts for the given key is under the cap.
+    fn check(&self, key: &K, now: Instant) -> Result<(), Limited> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        match entries.get(key) {
+            Some((window_end, failures)) if *window_end > now && *failures >= self.max_failures => {
+                Err(Limited {
+                    kind: LimitKind::FailedAttempts,
+                    retry_after: window_end.saturating_duration_since(now),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Count the failed attempt for the given key.
+    fn record(&self, key: K, now: Instant) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        cleanup(&mut entries, |(window_end, _)| *window_end > now);
+
+        let entry = entries.entry(key).or_insert((now, 0));
+        if entry.0 <= now {
+            *entry = (now.checked_add(self.window).unwrap_or(now), 0);
+        }
+        entry.1 = entry.1.saturating_add(1);
+    }
+}
+
+/// Drop the stale entries if there are too many of them.
+fn cleanup<K, V>(entries: &mut HashMap<K, V>, mut is_live: impl FnMut(&V) -> bool) {
+    if entries.len() < CLEANUP_THRESHOLD {
+        return;
+    }
+    entries.retain(|_, value| is_live(value));
+}
+
+#[cfg(test)]
+// Allow simple time arithmetic in tests.
+#[allow(clippy::arithmetic_side_effects)]
+mod tests {
+    use super::*;
+
+    const QUOTA: Quota = Quota {
+        burst: 2,
+        per_minute: 6,
+    };
+
+    #[test]
+    fn buckets_allow_bursts_and_replenish() {
+        let buckets = Buckets::new(QUOTA);
+        let now = Instant::now();
+
+        assert_eq!(buckets.take("a", now), Ok(()));
+        assert_eq!(buckets.take("a", now), Ok(()));
+        assert_eq!(
+            buckets.take("a", now),
+            Err(Limited {
+                kind: LimitKind::Rate,
+                retry_after: Duration::from_secs(10),
+            })
+        );
+
+        // Other keys are not affected.
+        assert_eq!(buckets.take("b", now), Ok(()));
+
+        // A single token is replenished every 10 seconds.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(buckets.take("a", later), Ok(()));
+        assert!(buckets.take("a", later).is_err());
+
+        // The full burst is available after the idle period.
+        let much_later = later + Duration::from_secs(60);
+        assert_eq!(buckets.take("a", much_later), Ok(()));
+        assert_eq!(buckets.take("a", much_later), Ok(()));
+        assert!(buckets.take("a", much_later).is_err());
+    }
+
+    #[test]
+    fn failures_are_capped_within_the_window() {
+        let counters = FailureCounters::new(FailureCap {
+            max_failures: 2,
+            window_secs: 60,
+        });
+        let now = Instant::now();
+
+        counters.record("a", now);
+        assert_eq!(counters.check(&"a", now), Ok(()));
+        counters.record("a", now + Duration::from_secs(10));
+        assert_eq!(
+            counters.check(&"a", now + Duration::from_secs(20)),
+            Err(Limited {
+                kind: LimitKind::FailedAttempts,
+                retry_after: Duration::from_secs(40),
+            })
+        );
+        assert_eq!(counters.check(&"b", now), Ok(()));
+
+        // The counter is reset when the window ends.
+        assert_eq!(counters.check(&"a", now + Duration::from_secs(60)), Ok(()));
+        counters.record("a", now + Duration::from_secs(60));
+        assert_eq!(counters.check(&"a", now + Duration::from_secs(60)), Ok(()));
+    }
+
+    #[test]
+    fn disabled_limiter_allows_everything() {
+        let rate_limiter = RateLimiter::new(Config {
+            enabled: false,
+            per_ip: Quota {
+                burst: 1,
+                per_minute: 1,
+            },
+            ..Default::default()
+        });
+        let ip = Some(IpAddr::from([127, 0, 0, 1]));
+
+        assert_eq!(rate_limiter.check_ip(ip), Ok(()));
+        assert_eq!(rate_limiter.check_ip(ip), Ok(()));
+    }
+
+    #[test]
+    fn validates_quotas() {
+        assert!(Config::default().validate().is_ok());
+        assert!(Config {
+            per_public_key: Quota {
+                burst: 0,
+                per_minute: 1,
+            },
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
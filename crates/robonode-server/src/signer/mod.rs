@@ -0,0 +1,75 @@
+//! The signer backends.
+//!
+//! The robonode key can be kept in the process memory, at a PKCS#11 token (i.e. an HSM),
+//! or at a remote signer; the backend is selected at startup.
+
+use crate::logic::{PublicKeyProvider, Signer};
+
+pub mod pkcs11;
+pub mod remote;
+pub mod remote_stub;
+
+/// The signer backend selected at startup.
+pub enum Backend {
+    /// The robonode key is kept in the process memory.
+    Local(robonode_crypto::SigningKey),
+    /// The robonode key is kept at a PKCS#11 token.
+    Pkcs11(pkcs11::Pkcs11Signer),
+    /// The robonode key is kept at a remote signer.
+    Remote(remote::RemoteSigner),
+}
+
+impl Backend {
+    /// The name of the backend, for logging.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Local(_) => "local",
+            Self::Pkcs11(_) => "pkcs11",
+            Self::Remote(_) => "remote",
+        }
+    }
+}
+
+/// The signer backend errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The PKCS#11 signer error.
+    #[error(transparent)]
+    Pkcs11(#[from] pkcs11::Error),
+    /// The remote signer error.
+    #[error(transparent)]
+    Remote(#[from] remote::Error),
+}
+
+#[async_trait::async_trait]
+impl Signer<Vec<u8>> for Backend {
+    type Error = Error;
+
+    async fn sign<'a, D>(&self, data: D) -> Result<Vec<u8>, Self::Error>
+    where
+        D: AsRef<[u8]> + Send + 'a,
+    {
+        let res = match self {
+            Self::Local(signing_key) => match signing_key.sign(data).await {
+                Ok(signature) => Ok(signature),
+                Err(err) => match err {},
+            },
+            Self::Pkcs11(signer) => signer.sign(data).await.map_err(Error::Pkcs11),
+            Self::Remote(signer) => signer.sign(data).await.map_err(Error::Remote),
+        };
+        if let Err(err) = &res {
+            tracing::error!(message = "Signing failed", signer = self.name(), ?err);
+        }
+        res
+    }
+}
+
+impl PublicKeyProvider for Backend {
+    fn public_key(&self) -> &[u8] {
+        match self {
+            Self::Local(signing_key) => signing_key.public_key(),
+            Self::Pkcs11(signer) => signer.public_key(),
+            Self::Remote(signer) => signer.public_key(),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! The PKCS#11 signer backend.
+//!
+//! The robonode key is an Ed25519 key pair kept at a PKCS#11 token, the private key never
+//! leaves the token. The signing calls are blocking, so they are conducted at the blocking
+//! thread pool.
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    types::AuthPin,
+};
+
+/// The DER tag of the octet string, the PKCS#11 tokens wrap the EC point into.
+const DER_OCTET_STRING_TAG: u8 = 0x04;
+
+/// The Ed25519 public key length.
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// The PKCS#11 signer params.
+#[derive(Clone)]
+pub struct Params {
+    /// The path to the PKCS#11 module (the shared library) to load.
+    pub module: PathBuf,
+    /// The label of the token holding the key.
+    pub token_label: String,
+    /// The label of the key pair.
+    pub key_label: String,
+    /// The user PIN of the token.
+    pub pin: String,
+}
+
+impl std::fmt::Debug for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Params")
+            .field("module", &self.module)
+            .field("token_label", &self.token_label)
+            .field("key_label", &self.key_label)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The PKCS#11 signer errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The PKCS#11 call failed.
+    #[error("the PKCS#11 call failed: {0}")]
+    Pkcs11(#[from] cryptoki::error::Error),
+    /// The token with the given label was not found.
+    #[error("the PKCS#11 token {0:?} was not found")]
+    TokenNotFound(String),
+    /// The key with the given label was not found, or is ambiguous.
+    #[error("exactly one PKCS#11 {class} with label {label:?} must be present, found {found}")]
+    KeyNotFound {
+        /// The class of the key object.
+        class: &'static str,
+        /// The label of the key.
+        label: String,
+        /// The number of the matching objects.
+        found: usize,
+    },
+    /// The public key at the token is not an Ed25519 public key.
+    #[error("the PKCS#11 public key is not a valid Ed25519 public key")]
+    InvalidPublicKey,
+    /// The signature produced by the token does not match the public key.
+    #[error("the PKCS#11 token produced an invalid signature")]
+    InvalidSignature,
+    /// The blocking signing task failed.
+    #[error("the PKCS#11 signing task failed: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+/// The signer backed by a PKCS#11 token.
+pub struct Pkcs11Signer {
+    /// The state shared with the blocking signing tasks.
+    inner: Arc<Inner>,
+}
+
+/// The state of the PKCS#11 signer.
+struct Inner {
+    /// The logged in session, along with the handle of the private key.
+    ///
+    /// The sessions can't be used concurrently, so the access is serialized.
+    session: Mutex<(Session, ObjectHandle)>,
+    /// The public key of the key pair.
+    public_key: robonode_crypto::PublicKey,
+}
+
+impl Pkcs11Signer {
+    /// Load the PKCS#11 module, log in to the token and find the key pair.
+    ///
+    /// This call is blocking.
+    pub fn open(params: Params) -> Result<Self, Error> {
+        let pkcs11 = Pkcs11::new(&params.module)?;
+        pkcs11.initialize(CInitializeArgs::OsThreads)?;
+
+        let mut slot = None;
+        for candidate in pkcs11.get_slots_with_token()? {
+            if pkcs11.get_token_info(candidate)?.label() == params.token_label {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.ok_or_else(|| Error::TokenNotFound(params.token_label.clone()))?;
+
+        let session = pkcs11.open_ro_session(slot)?;
+        session.login(UserType::User, Some(&AuthPin::new(params.pin)))?;
+
+        let find_key = |class: ObjectClass, class_name: &'static str| -> Result<_, Error> {
+            let found = session.find_objects(&[
+                Attribute::Class(class),
+                Attribute::KeyType(KeyType::EC_EDWARDS),
+                Attribute::Label(params.key_label.clone().into_bytes()),
+            ])?;
+            match found.as_slice() {
+                [handle] => Ok(*handle),
+                _ => Err(Error::KeyNotFound {
+                    class: class_name,
+                    label: params.key_label.clone(),
+                    found: found.len(),
+                }),
+            }
+        };
+        let private_key = find_key(ObjectClass::PRIVATE_KEY, "private key")?;
+        let public_key = find_key(ObjectClass::PUBLIC_KEY, "public key")?;
+
+        let ec_point = session
+            .get_attributes(public_key, &[AttributeType::EcPoint])?
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                Attribute::EcPoint(ec_point) => Some(ec_point),
+                _ => None,
+            })
+            .ok_or(Error::InvalidPublicKey)?;
+        let public_key = parse_ec_point(&ec_point)?;
+
+        let inner = Inner {
+            session: Mutex::new((session, private_key)),
+            public_key,
+        };
+
+        // Make sure the private key at the token matches the public key before using it.
+        inner.sign(b"robonode pkcs11 self-check")?;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Sign the data at the token and verify the signature.
+    pub async fn sign(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+        let data = data.as_ref().to_vec();
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || inner.sign(&data)).await?
+    }
+
+    /// The public key of the key pair.
+    pub fn public_key(&self) -> &[u8] {
+        self.inner.public_key.as_bytes()
+    }
+}
+
+impl Inner {
+    /// Sign the data at the token and verify the signature, blocking the current thread.
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let signature = {
+            let session = self.session.lock().unwrap_or_else(PoisonError::into_inner);
+            let (session, private_key) = &*session;
+            session.sign(&Mechanism::Eddsa, *private_key, data)?
+        };
+
+        let parsed_signature = robonode_crypto::Signature::from_slice(&signature)
+            .map_err(|_| Error::InvalidSignature)?;
+        self.public_key
+            .verify_strict(data, &parsed_signature)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(signature)
+    }
+}
+
+/// Parse the Ed25519 public key from the `CKA_EC_POINT` value.
+///
+/// The tokens report the point either DER-encoded as an octet string, or raw.
+fn parse_ec_point(ec_point: &[u8]) -> Result<robonode_crypto::PublicKey, Error> {
+    let raw = match ec_point {
+        raw if raw.len() == PUBLIC_KEY_LEN => raw,
+        [DER_OCTET_STRING_TAG, len, raw @ ..] if usize::from(*len) == raw.len() => raw,
+        _ => return Err(Error::InvalidPublicKey),
+    };
+    let raw: &[u8; PUBLIC_KEY_LEN] = raw.try_into().map_err(|_| Error::InvalidPublicKey)?;
+    robonode_crypto::PublicKey::from_bytes(raw).map_err(|_| Error::InvalidPublicKey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ec_point() {
+        let public_key = robonode_crypto::SigningKey::from_bytes(&[7; 32]).verifying_key();
+
+        let mut der = vec![DER_OCTET_STRING_TAG, 32];
+        der.extend_from_slice(public_key.as_bytes());
+        assert_eq!(parse_ec_point(&der).unwrap(), public_key);
+        assert_eq!(parse_ec_point(public_key.as_bytes()).unwrap(), public_key);
+
+        assert!(matches!(
+            parse_ec_point(&der[..20]),
+            Err(Error::InvalidPublicKey)
+        ));
+    }
+
+    /// Sign with the key at SoftHSM, prepared with something like:
+    ///
+    /// ```sh
+    /// softhsm2-util --init-token --free --label robonode --pin 1234 --so-pin 1234
+    /// pkcs11-tool --module "$PKCS11_MODULE" --token-label robonode --login --pin 1234 \
+    ///   --keypairgen --key-type EC:edwards25519 --label robonode
+    /// ```
+    #[tokio::test]
+    #[ignore = "requires SoftHSM"]
+    async fn signs_with_softhsm() {
+        let params = Params {
+            module: std::env::var("PKCS11_MODULE")
+                .unwrap_or_else(|_| "/usr/lib/softhsm/libsofthsm2.so".to_owned())
+                .into(),
+            token_label: std::env::var("PKCS11_TOKEN_LABEL")
+                .unwrap_or_else(|_| "robonode".to_owned()),
+            key_label: std::env::var("PKCS11_KEY_LABEL").unwrap_or_else(|_| "robonode".to_owned()),
+            pin: std::env::var("PKCS11_PIN").unwrap_or_else(|_| "1234".to_owned()),
+        };
+
+        let signer = tokio::task::spawn_blocking(move || Pkcs11Signer::open(params))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let signature = signer.sign(b"data").await.unwrap();
+        let signature = robonode_crypto::Signature::from_slice(&signature).unwrap();
+        robonode_crypto::PublicKey::from_bytes(signer.public_key().try_into().unwrap())
+            .unwrap()
+            .verify_strict(b"data", &signature)
+            .unwrap();
+    }
+}
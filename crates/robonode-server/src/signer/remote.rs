@@ -0,0 +1,270 @@
+//! The remote signer backend.
+//!
+//! The remote signer keeps the robonode key and serves a simple HTTP API:
+//!
+//! - `GET /public-key` responds with the hex-encoded public key;
+//! - `POST /sign` signs the hex-encoded data and responds with the hex-encoded signature.
+//!
+//! The requests are authenticated with a static bearer token in the `Authorization` header.
+//!
+//! The signatures are verified against the public key before use, so a misbehaving remote
+//! signer can't make the robonode hand out invalid signatures.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The time to wait for the remote signer to respond.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The response of the public key endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyResponse {
+    /// The hex-encoded public key.
+    pub public_key: String,
+}
+
+/// The request of the sign endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequest {
+    /// The hex-encoded data to sign.
+    pub data: String,
+}
+
+/// The response of the sign endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignResponse {
+    /// The hex-encoded signature.
+    pub signature: String,
+}
+
+/// The remote signer params.
+#[derive(Debug, Clone)]
+pub struct Params {
+    /// The base URL of the remote signer API.
+    pub url: reqwest::Url,
+    /// The bearer token to authenticate the requests with.
+    pub token: String,
+}
+
+/// The remote signer errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The remote signer URL can't be used as a base URL.
+    #[error("the remote signer URL is invalid: {0}")]
+    InvalidUrl(String),
+    /// The request to the remote signer failed.
+    #[error("the remote signer request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    /// The remote signer responded with an invalid public key.
+    #[error("the remote signer responded with an invalid public key")]
+    InvalidPublicKey,
+    /// The remote signer responded with a signature that does not match the public key.
+    #[error("the remote signer responded with an invalid signature")]
+    InvalidSignature,
+}
+
+/// The signer backed by a remote signer.
+#[derive(Debug)]
+pub struct RemoteSigner {
+    /// The HTTP client.
+    client: reqwest::Client,
+    /// The URL of the sign endpoint.
+    sign_url: reqwest::Url,
+    /// The bearer token to authenticate the requests with.
+    token: String,
+    /// The public key of the remote signer.
+    public_key: robonode_crypto::PublicKey,
+}
+
+impl RemoteSigner {
+    /// Connect to the remote signer and obtain its public key.
+    pub async fn connect(params: Params) -> Result<Self, Error> {
+        let mut base_url = params.url;
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let endpoint_url = |path: &str| {
+            base_url
+                .join(path)
+                .map_err(|err| Error::InvalidUrl(err.to_string()))
+        };
+        let public_key_url = endpoint_url("public-key")?;
+        let sign_url = endpoint_url("sign")?;
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+
+        let res: PublicKeyResponse = client
+            .get(public_key_url)
+            .bearer_auth(&params.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut public_key = [0u8; 32];
+        hex::decode_to_slice(res.public_key, &mut public_key)
+            .map_err(|_| Error::InvalidPublicKey)?;
+        let public_key = robonode_crypto::PublicKey::from_bytes(&public_key)
+            .map_err(|_| Error::InvalidPublicKey)?;
+
+        Ok(Self {
+            client,
+            sign_url,
+            token: params.token,
+            public_key,
+        })
+    }
+
+    /// Sign the data at the remote signer and verify the signature.
+    pub async fn sign(&self, data: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+        let data = data.as_ref();
+
+        let res: SignResponse = self
+            .client
+            .post(self.sign_url.clone())
+            .bearer_auth(&self.token)
+            .json(&SignRequest {
+                data: hex::encode(data),
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let signature = hex::decode(res.signature).map_err(|_| Error::InvalidSignature)?;
+        let parsed_signature = robonode_crypto::Signature::from_slice(&signature)
+            .map_err(|_| Error::InvalidSignature)?;
+        self.public_key
+            .verify_strict(data, &parsed_signature)
+            .map_err(|_| Error::InvalidSignature)?;
+
+        Ok(signature)
+    }
+
+    /// The public key of the remote signer.
+    pub fn public_key(&self) -> &[u8] {
+        self.public_key.as_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use warp::{filters::BoxedFilter, Filter};
+
+    use super::*;
+    use crate::signer::remote_stub;
+
+    /// Serve the filter at an ephemeral port and return the base URL.
+    fn serve<R>(filter: BoxedFilter<(R,)>) -> reqwest::Url
+    where
+        R: warp::Reply + 'static,
+    {
+        let (addr, server) = warp::serve(filter).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{addr}/signer").parse().unwrap()
+    }
+
+    /// The bearer token the stub authenticates the requests with.
+    const TOKEN: &str = "remote signer token";
+
+    fn signing_key() -> robonode_crypto::SigningKey {
+        robonode_crypto::SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn params(url: reqwest::Url) -> Params {
+        Params {
+            url,
+            token: TOKEN.to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_via_stub() {
+        let signing_key = signing_key();
+        let url = serve(
+            warp::path("signer")
+                .and(remote_stub::filter(
+                    Arc::new(signing_key.clone()),
+                    Arc::new(TOKEN.to_owned()),
+                ))
+                .boxed(),
+        );
+
+        let signer = RemoteSigner::connect(params(url)).await.unwrap();
+        assert_eq!(signer.public_key(), signing_key.verifying_key().as_bytes());
+
+        let signature = signer.sign(b"data").await.unwrap();
+        let expected_signature = robonode_crypto::Signer::sign(&signing_key, b"data");
+        assert_eq!(signature, expected_signature.to_vec());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_signatures() {
+        let signing_key = signing_key();
+        let public_key = warp::get()
+            .and(warp::path!("signer" / "public-key"))
+            .map(move || {
+                warp::reply::json(&PublicKeyResponse {
+                    public_key: hex::encode(signing_key.verifying_key().as_bytes()),
+                })
+            });
+        let sign = warp::post().and(warp::path!("signer" / "sign")).map(|| {
+            warp::reply::json(&SignResponse {
+                signature: hex::encode([0u8; 64]),
+            })
+        });
+        let url = serve(public_key.or(sign).unify().boxed());
+
+        let signer = RemoteSigner::connect(params(url)).await.unwrap();
+        assert!(matches!(
+            signer.sign(b"data").await,
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_public_key() {
+        let public_key = warp::path!("signer" / "public-key").map(|| {
+            warp::reply::json(&PublicKeyResponse {
+                public_key: "qwe".to_owned(),
+            })
+        });
+        let url = serve(public_key.boxed());
+
+        assert!(matches!(
+            RemoteSigner::connect(params(url)).await,
+            Err(Error::InvalidPublicKey)
+        ));
+    }
+
+    #[tokio::test]
+    async fn stub_rejects_invalid_token() {
+        let url = serve(
+            warp::path("signer")
+                .and(remote_stub::filter(
+                    Arc::new(signing_key()),
+                    Arc::new(TOKEN.to_owned()),
+                ))
+                .boxed(),
+        );
+
+        let params = Params {
+            url,
+            token: "invalid token".to_owned(),
+        };
+        let Err(Error::Request(err)) = RemoteSigner::connect(params).await else {
+            panic!("the connection with an invalid token must fail");
+        };
+        assert_eq!(err.status(), Some(reqwest::StatusCode::UNAUTHORIZED));
+    }
+}
@@ -0,0 +1,81 @@
+//! A local stub of the remote signer.
+//!
+//! Serves the remote signer API with the key kept in the process memory, for development and
+//! testing; it must never be used in production.
+
+use std::sync::Arc;
+
+use warp::{http::StatusCode, Filter};
+
+use super::remote::{PublicKeyResponse, SignRequest, SignResponse};
+use crate::admin::constant_time_eq;
+
+/// The [`warp::Filter`] serving the remote signer API with the given key, to the requests
+/// authenticated with the given bearer token.
+pub fn filter(
+    signing_key: Arc<robonode_crypto::SigningKey>,
+    token: Arc<String>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let authorized = warp::header::optional::<String>("authorization").map(
+        move |authorization: Option<String>| {
+            authorization
+                .as_deref()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(|value| constant_time_eq(value.as_bytes(), token.as_bytes()))
+                .unwrap_or(false)
+        },
+    );
+
+    let public_key = warp::get()
+        .and(warp::path!("public-key"))
+        .and(authorized.clone())
+        .map({
+            let signing_key = Arc::clone(&signing_key);
+            move |authorized| {
+                if !authorized {
+                    return unauthorized();
+                }
+                warp::reply::with_status(
+                    warp::reply::json(&PublicKeyResponse {
+                        public_key: hex::encode(signing_key.verifying_key().as_bytes()),
+                    }),
+                    StatusCode::OK,
+                )
+            }
+        });
+
+    let sign = warp::post()
+        .and(warp::path!("sign"))
+        .and(authorized)
+        .and(warp::body::json())
+        .map(move |authorized, req: SignRequest| {
+            if !authorized {
+                return unauthorized();
+            }
+            match hex::decode(req.data) {
+                Ok(data) => {
+                    let signature = robonode_crypto::Signer::sign(&*signing_key, &data);
+                    warp::reply::with_status(
+                        warp::reply::json(&SignResponse {
+                            signature: hex::encode(signature.to_bytes()),
+                        }),
+                        StatusCode::OK,
+                    )
+                }
+                Err(_) => warp::reply::with_status(
+                    warp::reply::json(&serde_json::json!({ "error": "the data is not valid hex" })),
+                    StatusCode::BAD_REQUEST,
+                ),
+            }
+        });
+
+    public_key.or(sign).unify()
+}
+
+/// The reply to the requests without a valid bearer token.
+fn unauthorized() -> warp::reply::WithStatus<warp::reply::Json> {
+    warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({ "error": "unauthorized" })),
+        StatusCode::UNAUTHORIZED,
+    )
+}
@@ -0,0 +1,813 @@
+//! Persistent robonode state.
+//!
+//! The state is kept in an append-only journal file, one JSON record per line.
+//! Each record is synced to the disk before the corresponding value is used, so the state
+//! survives crashes; an incomplete trailing record, left by a crash in the middle of a write,
+//! is discarded when the journal is opened.
+//! The journal is written to at the blocking threads, so that the async runtime is not blocked
+//! while the records are synced to the disk.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of the issued auth tickets kept in memory per public key.
+///
+/// The older auth tickets are dropped from the loaded state, but are kept in the journal.
+pub const MAX_ISSUANCE_HISTORY_LEN: usize = 64;
+
+/// A record of an issued auth ticket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedTicket {
+    /// The sequence value the auth ticket was issued at.
+    pub sequence_value: u64,
+    /// The hex-encoded public key the auth ticket was issued for.
+    pub public_key: String,
+    /// The hex-encoded authentication nonce of the auth ticket.
+    pub authentication_nonce: String,
+    /// The moment the auth ticket was issued at, in UNIX milliseconds.
+    pub issued_at: u64,
+}
+
+/// The status of an enrollment into the 3D-DB, as recorded by the robonode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EnrollmentStatus {
+    /// The enrollment into the 3D-DB group has been started, but not known to be completed.
+    Started,
+    /// The person has been enrolled into the 3D-DB group.
+    Completed,
+    /// The person has been deleted from the 3D-DB group.
+    Deleted,
+}
+
+/// A journal record.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Record {
+    /// The sequence has been advanced to the given value.
+    Sequence {
+        /// The new sequence value.
+        value: u64,
+        /// The temporary 3D-DB identifier the authentication FaceScan is processed under at
+        /// this sequence value, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tmp_identifier: Option<String>,
+    },
+    /// An auth ticket has been issued.
+    TicketIssued(IssuedTicket),
+    /// The person enrolled at the 3D-DB under the given identifier has been migrated to
+    /// a new public key.
+    KeyMigrated {
+        /// The 3D-DB identifier of the person, the hex-encoded public key they enrolled with.
+        identifier: String,
+        /// The hex-encoded public key the person now authenticates with.
+        public_key: String,
+    },
+    /// The person enrolled at the 3D-DB under the given identifier has been deleted from it.
+    EnrollmentDeleted {
+        /// The 3D-DB identifier of the person.
+        identifier: String,
+    },
+    /// The enrollment of the person into the 3D-DB under the given identifier has been started.
+    EnrollmentStarted {
+        /// The 3D-DB identifier of the person.
+        identifier: String,
+    },
+    /// The enrollment of the person into the 3D-DB under the given identifier has been
+    /// completed.
+    EnrollmentCompleted {
+        /// The 3D-DB identifier of the person.
+        identifier: String,
+    },
+}
+
+/// The store errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// An IO error.
+    #[error("state store IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// A complete journal record could not be decoded.
+    #[error("state store journal record at line {line} is corrupted: {source}")]
+    CorruptedRecord {
+        /// The line number of the corrupted record, starting from 1.
+        line: usize,
+        /// The decoding error.
+        source: serde_json::Error,
+    },
+    /// A record could not be encoded.
+    #[error("unable to encode the state store record: {0}")]
+    Encode(serde_json::Error),
+    /// The authentication nonce has already been issued.
+    #[error("the authentication nonce has already been issued")]
+    DuplicateNonce,
+    /// The sequence value does not exceed the last recorded one.
+    #[error("the sequence value {value} does not exceed the last recorded value {last}")]
+    NonMonotonicSequence {
+        /// The sequence value attempted to be recorded.
+        value: u64,
+        /// The last recorded sequence value.
+        last: u64,
+    },
+    /// A failed write could not be rolled back, so the journal might end with a partial record.
+    #[error("the state store journal might be corrupted by a failed write, restart to recover")]
+    Poisoned,
+}
+
+/// The auth tickets issued for a public key.
+#[derive(Debug, Default)]
+struct IssuanceHistory {
+    /// The total number of the issued auth tickets.
+    total: usize,
+    /// The last [`MAX_ISSUANCE_HISTORY_LEN`] issued auth tickets, in the order of issuance.
+    recent: VecDeque<IssuedTicket>,
+}
+
+/// The persistent state of the robonode.
+#[derive(Debug)]
+pub struct Store {
+    /// The journal file, opened for appending.
+    journal: File,
+    /// Whether a failed write could not be rolled back; no more records are written then.
+    poisoned: bool,
+    /// The last recorded sequence value.
+    last_sequence: u64,
+    /// The authentication nonces of the issued auth tickets kept in the issuance history.
+    issued_nonces: HashSet<String>,
+    /// The issued auth tickets, by the hex-encoded public key.
+    issuance_history: HashMap<String, IssuanceHistory>,
+    /// The hex-encoded public keys of the migrated persons, by the 3D-DB identifier.
+    migrated_public_keys: HashMap<String, String>,
+    /// The 3D-DB identifiers of the migrated persons, by the hex-encoded public key.
+    migrated_identifiers: HashMap<String, String>,
+    /// The statuses of the recorded enrollments, by the 3D-DB identifier.
+    enrollments: BTreeMap<String, EnrollmentStatus>,
+    /// The temporary 3D-DB identifiers the authentication FaceScans have been processed under.
+    tmp_identifiers: BTreeSet<String>,
+}
+
+impl Store {
+    /// Open the store at the given path, creating it if it doesn't exist, and load the state.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let journal = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let (store, complete_len) = Self::load(journal)?;
+
+        // Drop the incomplete trailing record, if any, so that the new records are appended
+        // cleanly.
+        if store.journal.metadata()?.len() > complete_len {
+            store.journal.set_len(complete_len)?;
+            store.journal.sync_all()?;
+        }
+
+        Ok(store)
+    }
+
+    /// Check the store at the given path can be opened, without creating or modifying it.
+    pub fn check(path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(check_parent_dir(path)?);
+        }
+
+        let journal = OpenOptions::new().read(true).append(true).open(path)?;
+        Self::load(journal)?;
+        Ok(())
+    }
+
+    /// Load the state from the journal, returning it along with the length of the complete
+    /// records in the journal.
+    fn load(mut journal: File) -> Result<(Self, u64), Error> {
+        let mut store = Self {
+            journal: journal.try_clone()?,
+            poisoned: false,
+            last_sequence: 0,
+            issued_nonces: HashSet::new(),
+            issuance_history: HashMap::new(),
+            migrated_public_keys: HashMap::new(),
+            migrated_identifiers: HashMap::new(),
+            enrollments: BTreeMap::new(),
+            tmp_identifiers: BTreeSet::new(),
+        };
+
+        journal.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(&mut journal);
+        let mut complete_len: u64 = 0;
+        let mut line = String::new();
+        let mut line_number: usize = 0;
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            // The trailing record without the line end has not been completely written.
+            if !line.ends_with('\n') {
+                break;
+            }
+            line_number = line_number.saturating_add(1);
+
+            let record = serde_json::from_str(&line).map_err(|source| Error::CorruptedRecord {
+                line: line_number,
+                source,
+            })?;
+            store.apply(record);
+
+            // usize is never wider than u64 on the supported platforms.
+            complete_len = complete_len.saturating_add(read.try_into().unwrap());
+        }
+
+        Ok((store, complete_len))
+    }
+
+    /// The last recorded sequence value, zero if none was recorded.
+    pub fn last_sequence(&self) -> u64 {
+        self.last_sequence
+    }
+
+    /// Durably record the new sequence value.
+    ///
+    /// Must be called before the sequence value is used, so that it is never reused after
+    /// a restart.
+    pub async fn record_sequence(&mut self, value: u64) -> Result<(), Error> {
+        self.append_sequence(value, None).await
+    }
+
+    /// Durably record the new sequence value, along with the temporary 3D-DB identifier
+    /// the authentication FaceScan is going to be processed under.
+    ///
+    /// Must be called before the sequence value and the identifier are used, so that there is
+    /// a record of every temporary entry that might have reached the 3D-DB.
+    pub async fn record_authentication_sequence(
+        &mut self,
+        value: u64,
+        tmp_identifier: &str,
+    ) -> Result<(), Error> {
+        self.append_sequence(value, Some(tmp_identifier.to_owned()))
+            .await
+    }
+
+    /// Durably record the issued auth ticket.
+    ///
+    /// Must be called before the auth ticket is handed out, so that there is a record of every
+    /// auth ticket the robonode has signed.
+    pub async fn record_issued_ticket(&mut self, ticket: IssuedTicket) -> Result<(), Error> {
+        if self.issued_nonces.contains(&ticket.authentication_nonce) {
+            return Err(Error::DuplicateNonce);
+        }
+        self.append(Record::TicketIssued(ticket)).await
+    }
+
+    /// The last auth tickets issued for the given hex-encoded public key, in the order of
+    /// issuance, at most [`MAX_ISSUANCE_HISTORY_LEN`] of them.
+    pub fn issuance_history(&self, public_key: &str) -> impl Iterator<Item = &IssuedTicket> {
+        self.issuance_history
+            .get(public_key)
+            .into_iter()
+            .flat_map(|history| history.recent.iter())
+    }
+
+    /// The total number of the auth tickets issued for the given hex-encoded public key.
+    pub fn issued_tickets(&self, public_key: &str) -> usize {
+        self.issuance_history
+            .get(public_key)
+            .map_or(0, |history| history.total)
+    }
+
+    /// Durably record the migration of the person enrolled under the given 3D-DB identifier
+    /// to the new hex-encoded public key.
+    ///
+    /// Migrating the person back to the public key they enrolled with drops the migration.
+    pub async fn record_key_migration(
+        &mut self,
+        identifier: &str,
+        public_key: &str,
+    ) -> Result<(), Error> {
+        self.append(Record::KeyMigrated {
+            identifier: identifier.to_owned(),
+            public_key: public_key.to_owned(),
+        })
+        .await
+    }
+
+    /// Durably record the deletion of the person enrolled under the given 3D-DB identifier.
+    pub async fn record_enrollment_deletion(&mut self, identifier: &str) -> Result<(), Error> {
+        self.append(Record::EnrollmentDeleted {
+            identifier: identifier.to_owned(),
+        })
+        .await
+    }
+
+    /// Durably record the start of the enrollment into the 3D-DB under the given identifier.
+    ///
+    /// Must be called before the 3D-DB enrollment is conducted, so that there is a record of
+    /// every enrollment that might have reached the 3D-DB.
+    pub async fn record_enrollment_start(&mut self, identifier: &str) -> Result<(), Error> {
+        self.append(Record::EnrollmentStarted {
+            identifier: identifier.to_owned(),
+        })
+        .await
+    }
+
+    /// Durably record the completion of the enrollment into the 3D-DB under the given
+    /// identifier.
+    pub async fn record_enrollment_completion(&mut self, identifier: &str) -> Result<(), Error> {
+        self.append(Record::EnrollmentCompleted {
+            identifier: identifier.to_owned(),
+        })
+        .await
+    }
+
+    /// The recorded statuses of the enrollments, by the 3D-DB identifier, in the identifiers
+    /// order.
+    pub fn enrollments(&self) -> impl Iterator<Item = (&str, EnrollmentStatus)> {
+        self.enrollments
+            .iter()
+            .map(|(identifier, status)| (identifier.as_str(), *status))
+    }
+
+    /// The recorded temporary 3D-DB identifiers of the authentications, in the identifiers
+    /// order.
+    pub fn tmp_identifiers(&self) -> impl Iterator<Item = &str> {
+        self.tmp_identifiers.iter().map(String::as_str)
+    }
+
+    /// The recorded status of the enrollment under the given 3D-DB identifier, if any.
+    pub fn enrollment_status(&self, identifier: &str) -> Option<EnrollmentStatus> {
+        self.enrollments.get(identifier).copied()
+    }
+
+    /// The hex-encoded public key the person enrolled under the given 3D-DB identifier has been
+    /// migrated to, if any.
+    pub fn migrated_public_key(&self, identifier: &str) -> Option<&str> {
+        self.migrated_public_keys
+            .get(identifier)
+            .map(String::as_str)
+    }
+
+    /// The 3D-DB identifier of the person migrated to the given hex-encoded public key, if any.
+    pub fn migrated_identifier(&self, public_key: &str) -> Option<&str> {
+        self.migrated_identifiers
+            .get(public_key)
+            .map(String::as_str)
+    }
+
+    /// Write the record to the journal, sync it to the disk and apply it to the loaded state.
+    ///
+    /// A failed write is rolled back, so that the next record is not appended to a partial one.
+    async fn append(&mut self, record: Record) -> Result<(), Error> {
+        if self.poisoned {
+            return Err(Error::Poisoned);
+        }
+
+        let mut line = serde_json::to_string(&record).map_err(Error::Encode)?;
+        line.push('\n');
+
+        let mut journal = self.journal.try_clone()?;
+        let written = tokio::task::spawn_blocking(move || write_durably(&mut journal, &line)).await;
+        match written {
+            Ok(Ok(())) => {}
+            Ok(Err(WriteFailure::RolledBack(err))) => return Err(err.into()),
+            Ok(Err(WriteFailure::NotRolledBack(err))) => {
+                self.poisoned = true;
+                return Err(err.into());
+            }
+            // The write might have been interrupted midway.
+            Err(_) => {
+                self.poisoned = true;
+                return Err(Error::Poisoned);
+            }
+        }
+
+        self.apply(record);
+        Ok(())
+    }
+
+    /// Record the new sequence value, ensuring it is greater than the last one.
+    async fn append_sequence(
+        &mut self,
+        value: u64,
+        tmp_identifier: Option<String>,
+    ) -> Result<(), Error> {
+        if value <= self.last_sequence {
+            return Err(Error::NonMonotonicSequence {
+                value,
+                last: self.last_sequence,
+            });
+        }
+        self.append(Record::Sequence {
+            value,
+            tmp_identifier,
+        })
+        .await
+    }
+
+    /// Apply the record to the loaded state.
+    fn apply(&mut self, record: Record) {
+        match record {
+            Record::Sequence {
+                value,
+                tmp_identifier,
+            } => {
+                self.last_sequence = self.last_sequence.max(value);
+                self.tmp_identifiers.extend(tmp_identifier);
+            }
+            Record::TicketIssued(ticket) => {
+                self.issued_nonces
+                    .insert(ticket.authentication_nonce.clone());
+                let history = self
+                    .issuance_history
+                    .entry(ticket.public_key.clone())
+                    .or_default();
+                history.total = history.total.saturating_add(1);
+                history.recent.push_back(ticket);
+                if history.recent.len() > MAX_ISSUANCE_HISTORY_LEN {
+                    if let Some(dropped) = history.recent.pop_front() {
+                        self.issued_nonces.remove(&dropped.authentication_nonce);
+                    }
+                }
+            }
+            Record::KeyMigrated {
+                identifier,
+                public_key,
+            } => {
+                self.forget_migration(&identifier);
+                if identifier != public_key {
+                    self.migrated_identifiers
+                        .insert(public_key.clone(), identifier.clone());
+                    self.migrated_public_keys.insert(identifier, public_key);
+                }
+            }
+            Record::EnrollmentDeleted { identifier } => {
+                self.forget_migration(&identifier);
+                self.enrollments
+                    .insert(identifier, EnrollmentStatus::Deleted);
+            }
+            Record::EnrollmentStarted { identifier } => {
+                self.enrollments
+                    .insert(identifier, EnrollmentStatus::Started);
+            }
+            Record::EnrollmentCompleted { identifier } => {
+                self.enrollments
+                    .insert(identifier, EnrollmentStatus::Completed);
+            }
+        }
+    }
+
+    /// Drop the migration of the person enrolled under the given 3D-DB identifier, if any.
+    fn forget_migration(&mut self, identifier: &str) {
+        if let Some(public_key) = self.migrated_public_keys.remove(identifier) {
+            self.migrated_identifiers.remove(&public_key);
+        }
+    }
+}
+
+/// Check the directory the file at the given path would be created at exists.
+pub(crate) fn check_parent_dir(path: &Path) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if !parent.metadata()?.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} is not a directory", parent.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Truncate the journal back to the given length.
+fn rollback(journal: &File, len: u64) -> std::io::Result<()> {
+    journal.set_len(len)?;
+    journal.sync_all()
+}
+
+/// A failed journal write.
+enum WriteFailure {
+    /// The write has failed, and has been rolled back.
+    RolledBack(std::io::Error),
+    /// The write has failed, and could not be rolled back, so the journal might end with
+    /// a partial record.
+    NotRolledBack(std::io::Error),
+}
+
+/// Write the line to the end of the journal and sync it to the disk, truncating the journal
+/// back if that fails.
+fn write_durably(journal: &mut File, line: &str) -> Result<(), WriteFailure> {
+    let len = journal.metadata().map_err(WriteFailure::RolledBack)?.len();
+    let written = journal
+        .write_all(line.as_bytes())
+        .and_then(|()| journal.sync_data());
+    if let Err(err) = written {
+        return Err(match rollback(journal, len) {
+            Ok(()) => WriteFailure::RolledBack(err),
+            Err(_) => WriteFailure::NotRolledBack(err),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a fresh journal file, removed once the returned path is dropped.
+    fn journal_path() -> tempfile::TempPath {
+        tempfile::NamedTempFile::new().unwrap().into_temp_path()
+    }
+
+    fn ticket(sequence_value: u64, public_key: &str) -> IssuedTicket {
+        IssuedTicket {
+            sequence_value,
+            public_key: public_key.to_owned(),
+            authentication_nonce: format!("nonce-{sequence_value}"),
+            issued_at: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn state_survives_reopening() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 0);
+        store.record_sequence(1).await.unwrap();
+        store.record_issued_ticket(ticket(1, "aa")).await.unwrap();
+        store.record_sequence(2).await.unwrap();
+        store.record_issued_ticket(ticket(2, "bb")).await.unwrap();
+        store.record_sequence(3).await.unwrap();
+        store.record_issued_ticket(ticket(3, "aa")).await.unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 3);
+        assert_eq!(
+            store.issuance_history("aa").collect::<Vec<_>>(),
+            vec![&ticket(1, "aa"), &ticket(3, "aa")]
+        );
+        assert_eq!(
+            store.issuance_history("bb").collect::<Vec<_>>(),
+            vec![&ticket(2, "bb")]
+        );
+        assert_eq!(store.issuance_history("cc").count(), 0);
+        assert_eq!(store.issued_tickets("aa"), 2);
+        assert_eq!(store.issued_tickets("cc"), 0);
+    }
+
+    #[tokio::test]
+    async fn issuance_history_is_bounded() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        let count: u64 = (MAX_ISSUANCE_HISTORY_LEN + 1).try_into().unwrap();
+        for sequence_value in 1..=count {
+            store
+                .record_issued_ticket(ticket(sequence_value, "aa"))
+                .await
+                .unwrap();
+        }
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.issued_tickets("aa"), MAX_ISSUANCE_HISTORY_LEN + 1);
+        assert_eq!(store.issued_nonces.len(), MAX_ISSUANCE_HISTORY_LEN);
+        let history = store.issuance_history("aa").collect::<Vec<_>>();
+        assert_eq!(history.len(), MAX_ISSUANCE_HISTORY_LEN);
+        assert_eq!(history.first(), Some(&&ticket(2, "aa")));
+        assert_eq!(history.last(), Some(&&ticket(count, "aa")));
+    }
+
+    #[tokio::test]
+    async fn sequence_must_be_monotonic() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_sequence(5).await.unwrap();
+        assert!(matches!(
+            store.record_sequence(5).await,
+            Err(Error::NonMonotonicSequence { value: 5, last: 5 })
+        ));
+        assert!(matches!(
+            store.record_sequence(4).await,
+            Err(Error::NonMonotonicSequence { value: 4, last: 5 })
+        ));
+        drop(store);
+
+        let mut store = Store::open(&path).unwrap();
+        assert!(matches!(
+            store.record_sequence(5).await,
+            Err(Error::NonMonotonicSequence { value: 5, last: 5 })
+        ));
+        store.record_sequence(6).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn nonces_must_be_unique() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_issued_ticket(ticket(1, "aa")).await.unwrap();
+        drop(store);
+
+        let mut store = Store::open(&path).unwrap();
+        assert!(matches!(
+            store.record_issued_ticket(ticket(1, "bb")).await,
+            Err(Error::DuplicateNonce)
+        ));
+        assert_eq!(store.issuance_history("bb").count(), 0);
+    }
+
+    #[tokio::test]
+    async fn incomplete_trailing_record_is_discarded() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_sequence(1).await.unwrap();
+        drop(store);
+
+        // Simulate a crash in the middle of a write.
+        let mut journal = OpenOptions::new().append(true).open(&path).unwrap();
+        journal.write_all(br#"{"type":"sequence","val"#).unwrap();
+        drop(journal);
+
+        let mut store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 1);
+        store.record_sequence(2).await.unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_write_is_rolled_back() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_sequence(1).await.unwrap();
+        let len = store.journal.metadata().unwrap().len();
+
+        // Simulate a write that has failed in the middle.
+        let mut journal = OpenOptions::new().append(true).open(&path).unwrap();
+        journal.write_all(br#"{"type":"sequence","val"#).unwrap();
+        drop(journal);
+
+        rollback(&store.journal, len).unwrap();
+        assert!(!store.poisoned);
+        store.record_sequence(2).await.unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_rollback_poisons_the_store() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_sequence(1).await.unwrap();
+
+        // Make both the writes and the rollback fail.
+        store.journal = File::open(&path).unwrap();
+        assert!(matches!(store.record_sequence(2).await, Err(Error::Io(_))));
+        assert!(store.poisoned);
+        assert_eq!(store.last_sequence(), 1);
+
+        // Ensure no more records are written, even if the journal is writable again.
+        store.journal = OpenOptions::new().append(true).open(&path).unwrap();
+        assert!(matches!(
+            store.record_sequence(3).await,
+            Err(Error::Poisoned)
+        ));
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 1);
+    }
+
+    #[tokio::test]
+    async fn key_migrations_survive_reopening() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_key_migration("aa", "bb").await.unwrap();
+        store.record_key_migration("aa", "cc").await.unwrap();
+        store.record_key_migration("dd", "ee").await.unwrap();
+        store.record_key_migration("ff", "11").await.unwrap();
+        store.record_enrollment_deletion("dd").await.unwrap();
+        store.record_key_migration("ff", "ff").await.unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.migrated_public_key("aa"), Some("cc"));
+        assert_eq!(store.migrated_identifier("cc"), Some("aa"));
+        assert_eq!(store.migrated_identifier("bb"), None);
+        assert_eq!(store.migrated_public_key("dd"), None);
+        assert_eq!(store.migrated_identifier("ee"), None);
+        assert_eq!(store.migrated_public_key("ff"), None);
+        assert_eq!(store.migrated_identifier("11"), None);
+    }
+
+    #[tokio::test]
+    async fn check_does_not_modify_the_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.jsonl");
+
+        Store::check(&path).unwrap();
+        assert!(!path.exists());
+        assert!(Store::check(dir.path().join("missing").join("state.jsonl")).is_err());
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_sequence(1).await.unwrap();
+        drop(store);
+        let mut journal = OpenOptions::new().append(true).open(&path).unwrap();
+        journal.write_all(br#"{"type":"sequence","val"#).unwrap();
+        drop(journal);
+        let len = std::fs::metadata(&path).unwrap().len();
+
+        Store::check(&path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+    }
+
+    #[test]
+    fn corrupted_record_is_reported() {
+        let path = journal_path();
+        std::fs::write(&path, "{\"type\":\"sequence\",\"value\":1}\nnot a record\n").unwrap();
+
+        assert!(matches!(
+            Store::open(&path),
+            Err(Error::CorruptedRecord { line: 2, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn enrollments_survive_reopening() {
+        let path = journal_path();
+
+        let mut store = Store::open(&path).unwrap();
+        store.record_enrollment_start("aa").await.unwrap();
+        store.record_enrollment_completion("aa").await.unwrap();
+        store.record_enrollment_start("bb").await.unwrap();
+        store.record_enrollment_start("cc").await.unwrap();
+        store.record_enrollment_completion("cc").await.unwrap();
+        store.record_enrollment_deletion("cc").await.unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(
+            store.enrollments().collect::<Vec<_>>(),
+            vec![
+                ("aa", EnrollmentStatus::Completed),
+                ("bb", EnrollmentStatus::Started),
+                ("cc", EnrollmentStatus::Deleted),
+            ]
+        );
+        assert_eq!(
+            store.enrollment_status("bb"),
+            Some(EnrollmentStatus::Started)
+        );
+        assert_eq!(store.enrollment_status("dd"), None);
+    }
+
+    #[tokio::test]
+    async fn tmp_identifiers_survive_reopening() {
+        let path = journal_path();
+        std::fs::write(&path, "{\"type\":\"sequence\",\"value\":1}\n").unwrap();
+
+        let mut store = Store::open(&path).unwrap();
+        store
+            .record_authentication_sequence(2, "tmp-b")
+            .await
+            .unwrap();
+        store.record_sequence(3).await.unwrap();
+        assert!(matches!(
+            store.record_authentication_sequence(3, "tmp-c").await,
+            Err(Error::NonMonotonicSequence { value: 3, last: 3 })
+        ));
+        store
+            .record_authentication_sequence(4, "tmp-a")
+            .await
+            .unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.last_sequence(), 4);
+        assert_eq!(
+            store.tmp_identifiers().collect::<Vec<_>>(),
+            vec!["tmp-a", "tmp-b"]
+        );
+    }
+}